@@ -0,0 +1,238 @@
+use std::fmt::Display;
+
+use crate::{
+    color,
+    runner::{InputKind, InputLayout},
+    Color,
+};
+
+/// The known correct answers of one day, usually created with [`answers!`](crate::answers!).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub year: u32,
+    pub day: u32,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Declares the known answers of a day, e.g. `answers!(2023, 17, part1 = 1039, part2 = 1201)`.
+/// Either part can be left out while it isn't solved yet.
+#[macro_export]
+macro_rules! answers {
+    (@part) => {
+        None
+    };
+    (@part $answer:expr) => {
+        Some($answer.to_string())
+    };
+    ($year:expr, $day:expr $(, part1 = $p1:expr)? $(, part2 = $p2:expr)? $(,)?) => {
+        $crate::answers::Answers {
+            year: $year,
+            day: $day,
+            part1: $crate::answers!(@part $($p1)?),
+            part2: $crate::answers!(@part $($p2)?),
+        }
+    };
+}
+
+type Solver<'a> = Box<dyn Fn(&str) -> (String, String) + 'a>;
+
+/// The days to check with [`verify_all`], usually created with
+/// [`Runner::registry`](crate::runner::Runner::registry).
+#[derive(Default)]
+pub struct Registry<'a> {
+    days: Vec<(Answers, Solver<'a>)>,
+}
+impl<'a> Registry<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a day with a function returning both answers for an input.
+    pub fn add<A: Display, B: Display>(
+        &mut self,
+        answers: Answers,
+        solve: impl Fn(&str) -> (A, B) + 'a,
+    ) {
+        let solve = move |input: &str| {
+            let (a, b) = solve(input);
+            (a.to_string(), b.to_string())
+        };
+        self.days.push((answers, Box::new(solve)));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// The real input is missing but the fixture matched its sidecar answers.
+    SamplePass,
+    /// The parts that didn't match as `(part, expected, actual)`.
+    Fail(Vec<(u8, String, String)>),
+    /// The sidecar answers of the fixture couldn't be read.
+    BadSidecar(String),
+    /// There is neither a real input nor a fixture with answers.
+    MissingInput,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub days: Vec<(u32, u32, Status)>,
+}
+impl VerifyReport {
+    /// Whether no day failed. Days without input don't count as failures.
+    pub fn all_passed(&self) -> bool {
+        self.days
+            .iter()
+            .all(|(_, _, status)| !matches!(status, Status::Fail(_) | Status::BadSidecar(_)))
+    }
+}
+impl Display for VerifyReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let colored = color::enabled();
+        for (year, day, status) in &self.days {
+            write!(f, "{year} day {day:>2}  ")?;
+            match status {
+                Status::Pass => writeln!(f, "{}", Color::Green.paint("pass").when(colored))?,
+                Status::SamplePass => writeln!(
+                    f,
+                    "{} {}",
+                    Color::Green.paint("pass").when(colored),
+                    Color::Yellow.paint("on SAMPLE").when(colored)
+                )?,
+                Status::BadSidecar(message) => {
+                    writeln!(f, "{} {message}", Color::Red.paint("error").when(colored))?
+                }
+                Status::MissingInput => {
+                    writeln!(f, "{}", Color::Yellow.paint("missing input").when(colored))?
+                }
+                Status::Fail(parts) => {
+                    write!(f, "{}", Color::Red.paint("fail").when(colored))?;
+                    for (part, expected, actual) in parts {
+                        write!(f, "  part {part}: expected {expected}, got {actual}")?;
+                    }
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs every registered day on its real input in `layout` and compares the results to the
+/// registered answers. Days without a real input fall back to the fixture, like the runner
+/// does, and are checked against its sidecar answers instead. Meant for an ignored test that is
+/// run with `cargo test -- --ignored` after refactoring shared helpers, usually through
+/// [`Runner::verify_all`](crate::runner::Runner::verify_all).
+pub fn verify_all(layout: &InputLayout, registry: &Registry) -> VerifyReport {
+    let days = registry
+        .days
+        .iter()
+        .map(|(answers, solve)| {
+            let status = match layout.resolve(answers.day, InputKind::Real) {
+                Err(_) => Status::MissingInput,
+                Ok(input) if !input.sample => check(answers, solve(&input.text), Status::Pass),
+                Ok(input) => match layout.expected_answers_for_fixture(answers.day) {
+                    Err(err) => Status::BadSidecar(err.to_string()),
+                    Ok(None) => Status::MissingInput,
+                    Ok(Some(expected)) => check(&expected, solve(&input.text), Status::SamplePass),
+                },
+            };
+            (answers.year, answers.day, status)
+        })
+        .collect();
+    VerifyReport { days }
+}
+
+/// `pass` if every known answer matches, otherwise the parts that don't.
+fn check(expected: &Answers, (part1, part2): (String, String), pass: Status) -> Status {
+    let failed: Vec<(u8, String, String)> =
+        [(1, &expected.part1, part1), (2, &expected.part2, part2)]
+            .into_iter()
+            .filter_map(|(part, expected, actual)| {
+                let expected = expected.as_ref()?;
+                (*expected != actual).then(|| (part, expected.clone(), actual))
+            })
+            .collect();
+    if failed.is_empty() {
+        pass
+    } else {
+        Status::Fail(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn line_count(input: &str) -> (usize, usize) {
+        let lines = input.lines().count();
+        (lines, lines * 2)
+    }
+
+    #[test]
+    fn verify_registry() {
+        let dir = std::env::temp_dir().join(format!("aoch-answers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "a\nb\nc\n").unwrap();
+        fs::write(dir.join("day02.txt"), "a\n").unwrap();
+
+        let mut registry = Registry::new();
+        registry.add(answers!(2023, 1, part1 = 3, part2 = 6), line_count);
+        registry.add(answers!(2023, 2, part1 = 1, part2 = "3"), line_count);
+        registry.add(answers!(2023, 3, part1 = 0), line_count);
+        registry.add(answers!(2023, 4), |_| ("", ""));
+        let report = verify_all(&InputLayout::new().input_dir(&dir), &registry);
+        assert_eq!(
+            report.days,
+            [
+                (2023, 1, Status::Pass),
+                (2023, 2, Status::Fail(vec![(2, "3".into(), "2".into())])),
+                (2023, 3, Status::MissingInput),
+                (2023, 4, Status::MissingInput),
+            ]
+        );
+        assert!(!report.all_passed());
+        assert!(report.to_string().contains("part 2: expected 3, got 2"));
+
+        // missing real inputs fall back to the fixtures, checked against their sidecars
+        let fixtures = dir.join("fixtures");
+        fs::create_dir_all(&fixtures).unwrap();
+        fs::write(fixtures.join("day03.txt"), "x\n").unwrap();
+        fs::write(
+            fixtures.join("day03.answers.json"),
+            r#"{"part1": 1, "part2": 2}"#,
+        )
+        .unwrap();
+        fs::write(fixtures.join("day04.txt"), "x\n").unwrap();
+        let layout = InputLayout::new().input_dir(&dir).fixture_dir(&fixtures);
+        let statuses = |registry: &Registry| -> Vec<Status> {
+            let report = verify_all(&layout, registry);
+            report
+                .days
+                .into_iter()
+                .map(|(_, _, status)| status)
+                .collect()
+        };
+        assert_eq!(
+            statuses(&registry)[2..],
+            [Status::SamplePass, Status::MissingInput]
+        );
+        fs::write(fixtures.join("day04.answers.json"), "{").unwrap();
+        let report = verify_all(&layout, &registry);
+        assert!(
+            matches!(&report.days[3].2, Status::BadSidecar(m) if m.contains("day04.answers.json"))
+        );
+        assert!(report.to_string().contains("pass on SAMPLE"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn partial_answers() {
+        let answers = answers!(2022, 5, part1 = "CMZ");
+        assert_eq!(answers.part1.as_deref(), Some("CMZ"));
+        assert_eq!(answers.part2, None);
+    }
+}
@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+
+/// The relative tolerance [`assert_approx_eq!`](crate::assert_approx_eq!) uses by default.
+pub const DEFAULT_REL: f64 = 1e-9;
+/// The absolute tolerance [`assert_approx_eq!`](crate::assert_approx_eq!) uses by default,
+/// which decides for values close to zero where any relative tolerance is too strict.
+pub const DEFAULT_ABS: f64 = 1e-12;
+
+/// Whether `a` and `b` differ by at most `abs` or by at most `rel` times the larger magnitude.
+/// Equal infinities are equal, NaN is never equal to anything.
+pub fn approx_eq(a: f64, b: f64, rel: f64, abs: f64) -> bool {
+    if a == b {
+        return true;
+    }
+    let diff = (a - b).abs();
+    diff <= abs || diff <= rel * a.abs().max(b.abs())
+}
+
+/// Orders the values, `Equal` if they are [`approx_eq`]. Panics if either is NaN.
+pub fn approx_cmp(a: f64, b: f64, rel: f64, abs: f64) -> Ordering {
+    if approx_eq(a, b, rel, abs) {
+        Ordering::Equal
+    } else {
+        a.partial_cmp(&b)
+            .unwrap_or_else(|| panic!("can't compare {a} and {b}"))
+    }
+}
+
+/// Rounds to the given number of decimal places, negative ones round to tens, hundreds, ...
+pub fn round_to(a: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (a * scale).round() / scale
+}
+
+/// Asserts that two floats are [`approx_eq`](crate::approx::approx_eq), with the
+/// [`DEFAULT_REL`](crate::approx::DEFAULT_REL) and
+/// [`DEFAULT_ABS`](crate::approx::DEFAULT_ABS) tolerances unless both are given, e.g.
+/// `assert_approx_eq!(area, 12.5)` or `assert_approx_eq!(t, 3.0, 1e-6, 1e-9)`.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_approx_eq!(
+            $left,
+            $right,
+            $crate::approx::DEFAULT_REL,
+            $crate::approx::DEFAULT_ABS
+        )
+    };
+    ($left:expr, $right:expr, $rel:expr, $abs:expr $(,)?) => {{
+        let (left, right, rel, abs): (f64, f64, f64, f64) = ($left, $right, $rel, $abs);
+        if !$crate::approx::approx_eq(left, right, rel, abs) {
+            panic!(
+                "assertion `left ≈ right` failed\n  left: {left}\n right: {right}\n  diff: {:e} (rel {rel:e}, abs {abs:e})",
+                (left - right).abs()
+            );
+        }
+    }};
+}
+
+/// Vector operations on `[x, y]` float pairs.
+pub trait FloatVec2: Sized + Copy {
+    fn dot(self, other: Self) -> f64;
+    /// The z component of the cross product, `|a| |b| sin(angle)`.
+    fn cross_z(self, other: Self) -> f64;
+    fn length(self) -> f64;
+    /// The vector scaled to length 1, the zero vector stays zero.
+    #[must_use]
+    fn normalized(self) -> Self;
+}
+impl FloatVec2 for [f64; 2] {
+    fn dot(self, other: Self) -> f64 {
+        self[0] * other[0] + self[1] * other[1]
+    }
+
+    fn cross_z(self, other: Self) -> f64 {
+        self[0] * other[1] - self[1] * other[0]
+    }
+
+    fn length(self) -> f64 {
+        self[0].hypot(self[1])
+    }
+
+    fn normalized(self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            self
+        } else {
+            self.map(|c| c / length)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerances() {
+        assert!(approx_eq(1e9, 1e9 + 0.5, 1e-9, 0.0));
+        assert!(!approx_eq(1e9, 1e9 + 2.0, 1e-9, 0.0));
+        // close to zero only the absolute tolerance helps
+        assert!(!approx_eq(1e-15, -1e-15, 1e-9, 0.0));
+        assert!(approx_eq(1e-15, -1e-15, 1e-9, 1e-12));
+        assert!(!approx_eq(0.0, 1e-300, 0.5, 0.0));
+        assert!(approx_eq(f64::INFINITY, f64::INFINITY, 0.0, 0.0));
+        assert!(!approx_eq(f64::NAN, f64::NAN, 1.0, 1.0));
+        assert_eq!(
+            approx_cmp(0.1 + 0.2, 0.3, DEFAULT_REL, DEFAULT_ABS),
+            Ordering::Equal
+        );
+        assert_eq!(
+            approx_cmp(0.3, 0.31, DEFAULT_REL, DEFAULT_ABS),
+            Ordering::Less
+        );
+        assert_eq!(approx_cmp(-1e-3, -2e-3, 0.0, 1e-4), Ordering::Greater);
+        assert_eq!(round_to(2.675_1, 2), 2.68);
+        assert_eq!(round_to(-1234.5, -2), -1200.0);
+        crate::assert_approx_eq!(0.1 + 0.2, 0.3);
+        crate::assert_approx_eq!(100.0, 100.5, 0.01, 0.0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion `left ≈ right` failed\n  left: 1\n right: 1.5\n  diff: 5e-1"
+    )]
+    fn assert_message() {
+        crate::assert_approx_eq!(1.0, 1.5);
+    }
+
+    #[test]
+    fn vectors() {
+        let v = [3.0, 4.0];
+        assert_eq!(v.length(), 5.0);
+        assert_eq!(v.normalized(), [0.6, 0.8]);
+        assert_eq!([0.0, 0.0].normalized(), [0.0, 0.0]);
+        assert_eq!(v.dot([-4.0, 3.0]), 0.0);
+        assert_eq!([1.0, 0.0].cross_z([0.0, 2.0]), 2.0);
+        assert_eq!(v.cross_z(v), 0.0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+fn parse_cells<const N: usize>(s: &str) -> [bool; N] {
+    let cells: Vec<bool> = s
+        .chars()
+        .map(|c| match c {
+            '#' => true,
+            '.' => false,
+            _ => panic!("invalid cell {c:?} in {s:?}"),
+        })
+        .collect();
+    cells
+        .try_into()
+        .unwrap_or_else(|_| panic!("expected {N} cells in {s:?}"))
+}
+
+/// Parses a rule line like `#.#.# => #` into the neighborhood and the resulting state.
+pub fn parse_rule(line: &str) -> ([bool; 5], bool) {
+    let (pattern, result) = line
+        .trim()
+        .split_once(" => ")
+        .unwrap_or_else(|| panic!("missing '=>' in rule {line:?}"));
+    let [result] = parse_cells(result);
+    (parse_cells(pattern), result)
+}
+
+/// The neighborhoods that produce a live cell. Rules producing an empty cell are dropped.
+pub fn parse_rules(s: &str) -> HashSet<[bool; 5]> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_rule)
+        .filter_map(|(pattern, live)| live.then_some(pattern))
+        .collect()
+}
+
+/// A one dimensional automaton on an unbounded line, storing its live cells sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Line1D {
+    live: Vec<i64>,
+}
+impl Line1D {
+    /// Cells from a pattern like `#..#.#`, the first character being position 0.
+    pub fn from_pattern(s: &str) -> Self {
+        let s = s.trim();
+        let s = s.strip_prefix("initial state: ").unwrap_or(s);
+        Self {
+            live: s
+                .chars()
+                .enumerate()
+                .filter_map(|(i, c)| match c {
+                    '#' => Some(i as i64),
+                    '.' => None,
+                    _ => panic!("invalid cell {c:?} in {s:?}"),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn live(&self) -> &[i64] {
+        &self.live
+    }
+
+    /// The first and last live position.
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        Some((*self.live.first()?, *self.live.last()?))
+    }
+
+    pub fn sum_of_positions(&self) -> i64 {
+        self.live.iter().sum()
+    }
+
+    pub fn step(&mut self, rules: &HashSet<[bool; 5]>) {
+        assert!(
+            !rules.contains(&[false; 5]),
+            "empty neighborhoods can't become live on an unbounded line"
+        );
+        let Some((min, max)) = self.bounds() else {
+            return;
+        };
+        let mut next = Vec::new();
+        // index of the first live cell at or after the left edge of the window
+        let mut start = 0;
+        for pos in min - 2..=max + 2 {
+            while start < self.live.len() && self.live[start] < pos - 2 {
+                start += 1;
+            }
+            let mut pattern = [false; 5];
+            for &cell in self.live[start..].iter().take_while(|&&c| c <= pos + 2) {
+                pattern[(cell - pos + 2) as usize] = true;
+            }
+            if rules.contains(&pattern) {
+                next.push(pos);
+            }
+        }
+        self.live = next;
+    }
+
+    /// Runs `n` generations. Once a generation is the previous one shifted, every following one
+    /// is shifted the same way, so the remaining generations are skipped.
+    pub fn advance_generations(&mut self, rules: &HashSet<[bool; 5]>, n: u64) {
+        for done in 0..n {
+            let prev = self.live.clone();
+            self.step(rules);
+            let shift = match (prev.first(), self.live.first()) {
+                (Some(a), Some(b)) => b - a,
+                _ => 0,
+            };
+            let translated = prev.len() == self.live.len()
+                && prev.iter().zip(&self.live).all(|(a, b)| b - a == shift);
+            if translated {
+                let remaining = (n - done - 1) as i64;
+                for pos in &mut self.live {
+                    *pos += shift * remaining;
+                }
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POTS: &str = "initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #";
+
+    fn pots() -> (Line1D, HashSet<[bool; 5]>) {
+        let (initial, rules) = POTS.split_once("\n\n").unwrap();
+        (Line1D::from_pattern(initial), parse_rules(rules))
+    }
+
+    #[test]
+    fn plant_pots() {
+        let (mut line, rules) = pots();
+        for _ in 0..20 {
+            line.step(&rules);
+        }
+        assert_eq!(line.sum_of_positions(), 325);
+        assert_eq!(line.bounds(), Some((-2, 34)));
+        let (mut skipped, _) = pots();
+        skipped.advance_generations(&rules, 20);
+        assert_eq!(skipped, line);
+    }
+
+    #[test]
+    fn glider_shortcut() {
+        // single cells and pairs move right by one cell per generation, other patterns take a
+        // few generations to settle
+        let rules = parse_rules(
+            ".##.. => #
+##... => #
+.#... => #
+#.... => .
+#.#.. => #
+..#.# => #",
+        );
+        for (start, n) in [("##", 1000), ("#.#", 1000), ("##...#", 57), ("#", 3)] {
+            let mut direct = Line1D::from_pattern(start);
+            for _ in 0..n {
+                direct.step(&rules);
+            }
+            let mut skipped = Line1D::from_pattern(start);
+            skipped.advance_generations(&rules, n);
+            assert_eq!(skipped, direct, "{start}");
+        }
+        let mut pair = Line1D::from_pattern("##");
+        pair.advance_generations(&rules, 1_000_000_000);
+        assert_eq!(pair.live(), [1_000_000_000, 1_000_000_001]);
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The current year in UTC, using the days-to-civil conversion from Howard Hinnant's date
+/// algorithms.
+fn current_year() -> u32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before 1970")
+        .as_secs();
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let month = (5 * doy + 2) / 153;
+    let year = yoe + era * 400 + (month >= 10) as i64;
+    year as u32
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: new-day <day> [year]";
+    let day: u32 = args.next().and_then(|d| d.parse().ok()).expect(usage);
+    assert!((1..=25).contains(&day), "day has to be between 1 and 25");
+    let year = args
+        .next()
+        .map_or_else(current_year, |y| y.parse().expect(usage));
+    match aoch::scaffold::scaffold(Path::new("."), day, year) {
+        Ok(path) => println!("created {}", path.display()),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
+}
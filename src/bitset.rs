@@ -0,0 +1,415 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    hash::Hash,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign},
+};
+
+/// A set of the bit positions `0..64`, cheap to copy and hash for use in search states.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet64(pub u64);
+impl BitSet64 {
+    pub const EMPTY: Self = Self(0);
+
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// The set `{0, 1, ..., n - 1}`.
+    pub fn full(n: u32) -> Self {
+        assert!(n <= 64, "a BitSet64 can't hold {n} elements");
+        Self(if n == 64 { u64::MAX } else { (1 << n) - 1 })
+    }
+
+    /// Returns whether the bit was newly inserted. Panics for bits from 64 on.
+    pub fn insert(&mut self, bit: u32) -> bool {
+        assert!(bit < 64, "bit {bit} doesn't fit into a BitSet64");
+        let had = self.contains(bit);
+        self.0 |= 1 << bit;
+        !had
+    }
+
+    /// Returns whether the bit was present. Panics for bits from 64 on.
+    pub fn remove(&mut self, bit: u32) -> bool {
+        assert!(bit < 64, "bit {bit} doesn't fit into a BitSet64");
+        let had = self.contains(bit);
+        self.0 &= !(1 << bit);
+        had
+    }
+
+    pub fn with(mut self, bit: u32) -> Self {
+        self.insert(bit);
+        self
+    }
+
+    pub fn contains(&self, bit: u32) -> bool {
+        bit < 64 && self.0 >> bit & 1 == 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_subset(&self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn is_disjoint(&self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// The members in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> {
+        let mut rest = self.0;
+        std::iter::from_fn(move || {
+            (rest != 0).then(|| {
+                let bit = rest.trailing_zeros();
+                rest &= rest - 1;
+                bit
+            })
+        })
+    }
+
+    /// All subsets of this set including the empty set and the set itself, in descending order
+    /// of their bits.
+    pub fn subsets(&self) -> impl Iterator<Item = Self> {
+        let full = self.0;
+        let mut next = Some(full);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = (current != 0).then(|| (current - 1) & full);
+            Some(Self(current))
+        })
+    }
+}
+
+impl FromIterator<u32> for BitSet64 {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::EMPTY, Self::with)
+    }
+}
+
+impl Display for BitSet64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, bit) in self.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{bit}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+macro_rules! set_ops {
+    ($($trait: ident $f: ident $assign_trait: ident $assign_f: ident |$a: ident, $b: ident| $e: expr;)*) => {
+        $(
+            impl $trait for BitSet64 {
+                type Output = Self;
+                fn $f(self, $b: Self) -> Self {
+                    let $a = self;
+                    Self($e)
+                }
+            }
+            impl $assign_trait for BitSet64 {
+                fn $assign_f(&mut self, other: Self) {
+                    *self = $trait::$f(*self, other);
+                }
+            }
+        )*
+    };
+}
+set_ops! {
+    BitOr bitor BitOrAssign bitor_assign |a, b| a.0 | b.0;
+    BitAnd bitand BitAndAssign bitand_assign |a, b| a.0 & b.0;
+    Sub sub SubAssign sub_assign |a, b| a.0 & !b.0;
+}
+
+/// The best combined score of two disjoint sets in `scores`. A set is only paired with itself
+/// when it is empty.
+pub fn disjoint_pairs_max(scores: &HashMap<BitSet64, u64>) -> u64 {
+    let mut sorted: Vec<(BitSet64, u64)> =
+        scores.iter().map(|(&set, &score)| (set, score)).collect();
+    sorted.sort_unstable_by_key(|&(_, score)| std::cmp::Reverse(score));
+    let mut best = 0;
+    for (i, &(a, score_a)) in sorted.iter().enumerate() {
+        if score_a * 2 < best {
+            break;
+        }
+        for &(b, score_b) in &sorted[i..] {
+            if score_a + score_b <= best {
+                break;
+            }
+            if a.is_disjoint(b) {
+                best = score_a + score_b;
+            }
+        }
+    }
+    best
+}
+
+/// The best combined score of `k` agents working on pairwise disjoint sets, given the best
+/// score of a single agent for each set. An agent may also do nothing for a score of 0. The
+/// best score within every subset is spread over its supersets first (a sum-over-subsets max
+/// transform), so the last agent is a lookup per set. Each agent before that takes up to
+/// `3^n` steps for a universe of `n` bits, at most 24 are supported.
+pub fn best_partition_scores(scores: &HashMap<BitSet64, u64>, k: usize) -> u64 {
+    assert!(k > 0, "there has to be at least one agent");
+    let universe = scores.keys().fold(BitSet64::EMPTY, |all, &set| all | set);
+    let n = 64 - universe.0.leading_zeros();
+    assert!(n <= 24, "a universe of {n} bits is too large to partition");
+    let full = (1u64 << n) - 1;
+    // the best total of the agents so far using only the bits of the index
+    let mut best = vec![0; 1 << n];
+    for (&set, &score) in scores {
+        best[set.0 as usize] = best[set.0 as usize].max(score);
+    }
+    for bit in 0..n {
+        for mask in 0..best.len() {
+            if mask & 1 << bit != 0 {
+                best[mask] = best[mask].max(best[mask ^ 1 << bit]);
+            }
+        }
+    }
+    if k == 1 {
+        return best[full as usize];
+    }
+    for _ in 2..k {
+        let mut next = best.clone();
+        for (&set, &score) in scores {
+            let rest = full & !set.0;
+            let mut others = rest;
+            loop {
+                let mask = (set.0 | others) as usize;
+                next[mask] = next[mask].max(score + best[others as usize]);
+                if others == 0 {
+                    break;
+                }
+                others = (others - 1) & rest;
+            }
+        }
+        best = next;
+    }
+    scores
+        .iter()
+        .map(|(&set, &score)| score + best[(full & !set.0) as usize])
+        .max()
+        .unwrap_or(0)
+}
+
+/// Assigns bit positions to labels in order of first use, so sets of labels can be stored as
+/// [`BitSet64`]s.
+#[derive(Debug, Clone)]
+pub struct BitLabels<K> {
+    labels: Vec<K>,
+    bits: HashMap<K, u32>,
+}
+impl<K> Default for BitLabels<K> {
+    fn default() -> Self {
+        Self {
+            labels: Vec::new(),
+            bits: HashMap::new(),
+        }
+    }
+}
+impl<K: Hash + Eq + Clone> BitLabels<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the bit of the label, assigning the next free one if it's new.
+    pub fn bit(&mut self, label: K) -> u32 {
+        if let Some(&bit) = self.bits.get(&label) {
+            return bit;
+        }
+        let bit = self.labels.len() as u32;
+        assert!(bit < 64, "more than 64 labels");
+        self.labels.push(label.clone());
+        self.bits.insert(label, bit);
+        bit
+    }
+
+    pub fn get(&self, label: &K) -> Option<u32> {
+        self.bits.get(label).copied()
+    }
+
+    pub fn label(&self, bit: u32) -> &K {
+        &self.labels[bit as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The set of all labels assigned so far.
+    pub fn all(&self) -> BitSet64 {
+        BitSet64::full(self.labels.len() as u32)
+    }
+
+    pub fn set_of(&mut self, labels: impl IntoIterator<Item = K>) -> BitSet64 {
+        labels.into_iter().map(|label| self.bit(label)).collect()
+    }
+
+    pub fn labels_of(&self, set: BitSet64) -> impl Iterator<Item = &K> {
+        set.iter().map(|bit| self.label(bit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+
+    use super::*;
+
+    #[test]
+    fn set_ops() {
+        let a: BitSet64 = [1, 3, 5].into_iter().collect();
+        let b: BitSet64 = [3, 4].into_iter().collect();
+        assert_eq!((a | b).to_string(), "{1, 3, 4, 5}");
+        assert_eq!((a & b).to_string(), "{3}");
+        assert_eq!((a - b).to_string(), "{1, 5}");
+        assert!((a & b).is_subset(b));
+        assert_eq!(a.subsets().count(), 8);
+        assert!(a.subsets().all(|s| s.is_subset(a)));
+
+        let mut labels = BitLabels::new();
+        let set = labels.set_of(["DD", "BB", "DD"]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(labels.labels_of(set).collect::<Vec<_>>(), [&"DD", &"BB"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit 64 doesn't fit into a BitSet64")]
+    fn bit_out_of_range() {
+        let mut set = BitSet64::EMPTY.with(63);
+        assert!(!set.contains(64));
+        set.remove(64);
+    }
+
+    const VALVES: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+    /// The best released pressure for every set of opened valves.
+    fn pressure_by_opened(minutes: u64) -> HashMap<BitSet64, u64> {
+        let mut rates = HashMap::new();
+        let mut tunnels = HashMap::new();
+        for line in VALVES.lines() {
+            let name = &line[6..8];
+            let (rate, rest) = line["Valve AA has flow rate=".len()..]
+                .split_once(';')
+                .unwrap();
+            let (_, targets) = rest.split_once("valve").unwrap();
+            let targets: Vec<&str> = targets
+                .trim_start_matches('s')
+                .split(',')
+                .map(str::trim)
+                .collect();
+            rates.insert(name, rate.parse::<u64>().unwrap());
+            tunnels.insert(name, targets);
+        }
+        let distances = |from: &'static str| {
+            let mut dist = HashMap::from([(from, 0)]);
+            let mut queue = VecDeque::from([from]);
+            while let Some(valve) = queue.pop_front() {
+                for &next in &tunnels[valve] {
+                    if !dist.contains_key(next) {
+                        dist.insert(next, dist[valve] + 1);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            dist
+        };
+        let mut labels = BitLabels::new();
+        let useful: Vec<&str> = rates.keys().copied().filter(|v| rates[v] > 0).collect();
+        let all = labels.set_of(useful.iter().copied());
+        let dist: HashMap<&str, HashMap<&str, u64>> =
+            rates.keys().map(|&v| (v, distances(v))).collect();
+
+        let mut best = HashMap::new();
+        let mut stack = vec![("AA", minutes, BitSet64::EMPTY, 0)];
+        while let Some((at, left, opened, released)) = stack.pop() {
+            let entry = best.entry(opened).or_insert(0);
+            *entry = released.max(*entry);
+            for bit in (all - opened).iter() {
+                let next = *labels.label(bit);
+                let cost = dist[at][next] + 1;
+                if cost < left {
+                    let left = left - cost;
+                    stack.push((next, left, opened.with(bit), released + left * rates[next]));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn valves() {
+        let alone = pressure_by_opened(30);
+        assert_eq!(alone.values().max(), Some(&1651));
+        assert_eq!(disjoint_pairs_max(&pressure_by_opened(26)), 1707);
+        assert_eq!(best_partition_scores(&pressure_by_opened(26), 2), 1707);
+    }
+
+    #[test]
+    fn partitions_match_brute_force() {
+        let mut rng = crate::Pcg32::new(11);
+        for _ in 0..50 {
+            let mut scores = HashMap::from([(BitSet64::EMPTY, 0)]);
+            for _ in 0..rng.index(60) + 1 {
+                scores.insert(BitSet64(rng.below(1 << 10) as u64), rng.below(1000) as u64);
+            }
+            assert_eq!(
+                best_partition_scores(&scores, 2),
+                disjoint_pairs_max(&scores)
+            );
+            let sets: Vec<(BitSet64, u64)> = scores.iter().map(|(&s, &v)| (s, v)).collect();
+            let mut brute3 = 0;
+            for &(a, sa) in &sets {
+                for &(b, sb) in sets.iter().filter(|(b, _)| a.is_disjoint(*b)) {
+                    for &(_, sc) in sets.iter().filter(|(c, _)| c.is_disjoint(a | b)) {
+                        brute3 = brute3.max(sa + sb + sc);
+                    }
+                }
+            }
+            assert_eq!(best_partition_scores(&scores, 3), brute3);
+            assert_eq!(
+                best_partition_scores(&scores, 1),
+                *scores.values().max().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn three_agents() {
+        // three jobs that each need one of three disjoint pairs, and a greedy trap
+        let set = |bits: &[u32]| bits.iter().copied().collect::<BitSet64>();
+        let scores = HashMap::from([
+            (set(&[0, 1]), 5),
+            (set(&[2, 3]), 5),
+            (set(&[4, 5]), 5),
+            (set(&[1, 2, 3, 4]), 12),
+        ]);
+        assert_eq!(best_partition_scores(&scores, 3), 15);
+        assert_eq!(best_partition_scores(&scores, 2), 12);
+        assert_eq!(best_partition_scores(&scores, 4), 15);
+        assert_eq!(best_partition_scores(&HashMap::new(), 2), 0);
+    }
+}
@@ -0,0 +1,164 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BracketResult {
+    Ok,
+    /// A closing bracket at byte `index` didn't match. `expected` is the closer of the innermost
+    /// open pair, `None` if nothing was open.
+    Corrupted {
+        index: usize,
+        found: char,
+        expected: Option<char>,
+    },
+    /// The input ended with open pairs. `stack` holds the missing closers in the order they have
+    /// to be appended.
+    Incomplete {
+        stack: Vec<char>,
+    },
+}
+
+/// Matches brackets in `s` using `(open, close)` pairs. Characters that aren't part of any pair
+/// are skipped.
+pub fn check_brackets(s: &str, pairs: &[(char, char)]) -> BracketResult {
+    let mut open: Vec<char> = Vec::new();
+    for (index, c) in s.char_indices() {
+        if let Some(&(_, close)) = pairs.iter().find(|&&(o, _)| o == c) {
+            open.push(close);
+        } else if pairs.iter().any(|&(_, close)| close == c) {
+            let expected = open.pop();
+            if expected != Some(c) {
+                return BracketResult::Corrupted {
+                    index,
+                    found: c,
+                    expected,
+                };
+            }
+        }
+    }
+    if open.is_empty() {
+        BracketResult::Ok
+    } else {
+        open.reverse();
+        BracketResult::Incomplete { stack: open }
+    }
+}
+
+fn score_of(table: &[(char, u64)], c: char) -> u64 {
+    table
+        .iter()
+        .find(|&&(k, _)| k == c)
+        .unwrap_or_else(|| panic!("no score for {c:?}"))
+        .1
+}
+
+/// Sums the `table` score of the offending character of every corrupted line.
+pub fn corruption_score(results: &[BracketResult], table: &[(char, u64)]) -> u64 {
+    results
+        .iter()
+        .filter_map(|r| match r {
+            BracketResult::Corrupted { found, .. } => Some(score_of(table, *found)),
+            _ => None,
+        })
+        .sum()
+}
+
+/// For every incomplete line the score of its completion, where each missing closer multiplies
+/// the running score by `base` and adds its `table` score. Other lines are skipped.
+pub fn completion_scores(results: &[BracketResult], table: &[(char, u64)], base: u64) -> Vec<u64> {
+    results
+        .iter()
+        .filter_map(|r| match r {
+            BracketResult::Incomplete { stack } => Some(
+                stack
+                    .iter()
+                    .fold(0, |score, &c| score * base + score_of(table, c)),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The middle one of the [`completion_scores`], `None` without incomplete lines. For an even
+/// count the upper middle is returned.
+pub fn middle_completion_score(
+    results: &[BracketResult],
+    table: &[(char, u64)],
+    base: u64,
+) -> Option<u64> {
+    let mut scores = completion_scores(results, table, base);
+    if scores.is_empty() {
+        return None;
+    }
+    let mid = scores.len() / 2;
+    Some(*scores.select_nth_unstable(mid).1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+    const SUBSYSTEM: &str = "[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]";
+
+    #[test]
+    fn syntax_scoring() {
+        let results: Vec<_> = SUBSYSTEM
+            .lines()
+            .map(|l| check_brackets(l, &PAIRS))
+            .collect();
+        let corrupt = [(')', 3), (']', 57), ('}', 1197), ('>', 25137)];
+        assert_eq!(corruption_score(&results, &corrupt), 26397);
+        let complete = [(')', 1), (']', 2), ('}', 3), ('>', 4)];
+        assert_eq!(
+            completion_scores(&results, &complete, 5),
+            [288957, 5566, 1480781, 995444, 294]
+        );
+        assert_eq!(
+            middle_completion_score(&results, &complete, 5),
+            Some(288957)
+        );
+        assert_eq!(
+            results[2],
+            BracketResult::Corrupted {
+                index: 12,
+                found: '}',
+                expected: Some(']')
+            }
+        );
+    }
+
+    #[test]
+    fn nesting() {
+        assert_eq!(check_brackets("a(b[c]d)e", &PAIRS), BracketResult::Ok);
+        assert_eq!(
+            check_brackets("([)]", &PAIRS),
+            BracketResult::Corrupted {
+                index: 2,
+                found: ')',
+                expected: Some(']')
+            }
+        );
+        assert_eq!(
+            check_brackets("())", &PAIRS),
+            BracketResult::Corrupted {
+                index: 2,
+                found: ')',
+                expected: None
+            }
+        );
+        assert_eq!(
+            check_brackets("{[<", &PAIRS),
+            BracketResult::Incomplete {
+                stack: vec!['>', ']', '}']
+            }
+        );
+    }
+}
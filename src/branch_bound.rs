@@ -0,0 +1,524 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::Canonicalize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SearchStats {
+    pub expanded: u64,
+    /// Nodes skipped because their upper bound couldn't beat the best value found so far.
+    pub pruned: u64,
+    /// Nodes skipped because their key was seen before.
+    pub transposed: u64,
+}
+
+type StateFn<'a, S, R> = Box<dyn Fn(&S) -> R + 'a>;
+
+/// States that can be saved in a checkpoint of a [`BranchAndBound`] search.
+pub trait CheckpointState: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    /// `None` for bytes that aren't a valid state.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+/// Where and how often a search saves its progress. The fingerprint identifies the problem,
+/// so that a checkpoint of a different input is never resumed.
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    pub every: Duration,
+    pub fingerprint: u64,
+}
+
+struct Checkpoints<S> {
+    config: CheckpointConfig,
+    encode: fn(&S, &mut Vec<u8>),
+    last: Instant,
+}
+
+/// The search as it was when the checkpoint was taken.
+struct Saved<S> {
+    best: u64,
+    stats: SearchStats,
+    seen: HashSet<u64>,
+    stack: Vec<S>,
+}
+
+const MAGIC: &[u8; 8] = b"AOCHBNB1";
+
+/// 64-bit FNV-1a, which is plenty to notice a checkpoint that was cut off or garbled.
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn take<'b>(bytes: &mut &'b [u8], n: usize) -> Result<&'b [u8], String> {
+    if bytes.len() < n {
+        return Err("it ends early".to_owned());
+    }
+    let (taken, rest) = bytes.split_at(n);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn take_u64(bytes: &mut &[u8]) -> Result<u64, String> {
+    Ok(u64::from_le_bytes(take(bytes, 8)?.try_into().unwrap()))
+}
+
+fn encode_checkpoint<S>(
+    fingerprint: u64,
+    (best, stats): (u64, SearchStats),
+    seen: &HashSet<u64>,
+    stack: &[S],
+    encode: impl Fn(&S, &mut Vec<u8>),
+) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    for n in [
+        fingerprint,
+        best,
+        stats.expanded,
+        stats.pruned,
+        stats.transposed,
+    ] {
+        out.extend(n.to_le_bytes());
+    }
+    out.extend((seen.len() as u64).to_le_bytes());
+    for key in seen {
+        out.extend(key.to_le_bytes());
+    }
+    out.extend((stack.len() as u64).to_le_bytes());
+    let mut state = Vec::new();
+    for s in stack {
+        state.clear();
+        encode(s, &mut state);
+        out.extend((state.len() as u64).to_le_bytes());
+        out.extend(&state);
+    }
+    out.extend(checksum(&out).to_le_bytes());
+    out
+}
+
+fn decode_checkpoint<S: CheckpointState>(
+    bytes: &[u8],
+    fingerprint: u64,
+) -> Result<Saved<S>, String> {
+    let Some((mut bytes, sum)) = bytes.split_last_chunk::<8>() else {
+        return Err("it is too short".to_owned());
+    };
+    if checksum(bytes) != u64::from_le_bytes(*sum) {
+        return Err("the checksum doesn't match, it is probably incomplete".to_owned());
+    }
+    if take(&mut bytes, 8)? != MAGIC {
+        return Err("it isn't a search checkpoint".to_owned());
+    }
+    if take_u64(&mut bytes)? != fingerprint {
+        return Err("it belongs to a different problem".to_owned());
+    }
+    let best = take_u64(&mut bytes)?;
+    let stats = SearchStats {
+        expanded: take_u64(&mut bytes)?,
+        pruned: take_u64(&mut bytes)?,
+        transposed: take_u64(&mut bytes)?,
+    };
+    let seen = (0..take_u64(&mut bytes)?)
+        .map(|_| take_u64(&mut bytes))
+        .collect::<Result<_, _>>()?;
+    let stack = (0..take_u64(&mut bytes)?)
+        .map(|_| {
+            let len = take_u64(&mut bytes)? as usize;
+            S::decode(take(&mut bytes, len)?).ok_or_else(|| "a state is invalid".to_owned())
+        })
+        .collect::<Result<_, _>>()?;
+    if !bytes.is_empty() {
+        return Err("there is data after the end".to_owned());
+    }
+    Ok(Saved {
+        best,
+        stats,
+        seen,
+        stack,
+    })
+}
+
+/// Depth-first branch and bound maximizing `value` over all nodes reachable from the initial
+/// state. `upper_bound` has to be at least the value of the node and all its descendants.
+pub struct BranchAndBound<'a, S> {
+    children: StateFn<'a, S, Vec<S>>,
+    value: StateFn<'a, S, u64>,
+    upper_bound: StateFn<'a, S, u64>,
+    key: Option<StateFn<'a, S, u64>>,
+    checkpoints: Option<Checkpoints<S>>,
+    pub stats: SearchStats,
+}
+impl<'a, S> BranchAndBound<'a, S> {
+    pub fn new(
+        children: impl Fn(&S) -> Vec<S> + 'a,
+        value: impl Fn(&S) -> u64 + 'a,
+        upper_bound: impl Fn(&S) -> u64 + 'a,
+    ) -> Self {
+        Self {
+            children: Box::new(children),
+            value: Box::new(value),
+            upper_bound: Box::new(upper_bound),
+            key: None,
+            checkpoints: None,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Skips states whose `key` was seen before. Keys have to identify states exactly, a
+    /// collision drops a state from the search.
+    pub fn with_transpositions(mut self, key: impl Fn(&S) -> u64 + 'a) -> Self {
+        self.key = Some(Box::new(key));
+        self
+    }
+
+    /// Transpositions keyed by [`Canonicalize::canonical_key`], so symmetric states are only
+    /// explored once.
+    pub fn with_canonical_keys(self) -> Self
+    where
+        S: Canonicalize + 'a,
+    {
+        self.with_transpositions(S::canonical_key)
+    }
+
+    /// Saves the search to `config.path` whenever `config.every` has passed, checked once per
+    /// node. The file is replaced atomically, so a crash leaves the previous checkpoint.
+    pub fn with_checkpoints(mut self, config: CheckpointConfig) -> Self
+    where
+        S: CheckpointState,
+    {
+        self.checkpoints = Some(Checkpoints {
+            config,
+            encode: S::encode,
+            last: Instant::now(),
+        });
+        self
+    }
+
+    /// The best value, statistics accumulate in [`BranchAndBound::stats`] across calls.
+    pub fn solve(&mut self, initial: S) -> u64 {
+        let best = (self.value)(&initial);
+        self.run(Saved {
+            best,
+            stats: self.stats,
+            seen: HashSet::new(),
+            stack: vec![initial],
+        })
+    }
+
+    /// Continues the search saved at `path` with the statistics it had at that point. Starts
+    /// from `initial` if there is no checkpoint yet, and warns before doing the same if the
+    /// checkpoint is damaged or its fingerprint doesn't match.
+    pub fn resume_from(&mut self, path: impl AsRef<Path>, fingerprint: u64, initial: S) -> u64
+    where
+        S: CheckpointState,
+    {
+        let path = path.as_ref();
+        let saved = match fs::read(path) {
+            Ok(bytes) => decode_checkpoint(&bytes, fingerprint),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return self.solve(initial),
+            Err(err) => Err(err.to_string()),
+        };
+        match saved {
+            Ok(saved) => self.run(saved),
+            Err(reason) => {
+                eprintln!("warning: ignoring checkpoint {}: {reason}", path.display());
+                self.solve(initial)
+            }
+        }
+    }
+
+    fn save(&mut self, best: u64, seen: &HashSet<u64>, stack: &[S]) {
+        let Some(checkpoints) = &mut self.checkpoints else {
+            return;
+        };
+        if checkpoints.last.elapsed() < checkpoints.config.every {
+            return;
+        }
+        let bytes = encode_checkpoint(
+            checkpoints.config.fingerprint,
+            (best, self.stats),
+            seen,
+            stack,
+            checkpoints.encode,
+        );
+        let path = &checkpoints.config.path;
+        let partial = path.with_extension("partial");
+        if let Err(err) = fs::write(&partial, bytes).and_then(|()| fs::rename(&partial, path)) {
+            eprintln!(
+                "warning: couldn't save checkpoint {}: {err}",
+                path.display()
+            );
+        }
+        checkpoints.last = Instant::now();
+    }
+
+    fn run(&mut self, saved: Saved<S>) -> u64 {
+        let Saved {
+            mut best,
+            stats,
+            mut seen,
+            mut stack,
+        } = saved;
+        self.stats = stats;
+        loop {
+            self.save(best, &seen, &stack);
+            let Some(state) = stack.pop() else {
+                break;
+            };
+            if let Some(key) = &self.key {
+                if !seen.insert(key(&state)) {
+                    self.stats.transposed += 1;
+                    continue;
+                }
+            }
+            if (self.upper_bound)(&state) <= best {
+                self.stats.pruned += 1;
+                continue;
+            }
+            self.stats.expanded += 1;
+            let mut children = (self.children)(&state);
+            for child in &children {
+                best = best.max((self.value)(child));
+            }
+            // explore the first child first
+            children.reverse();
+            stack.extend(children);
+        }
+        best
+    }
+}
+
+pub fn branch_and_bound<S>(
+    initial: S,
+    children: impl Fn(&S) -> Vec<S>,
+    value: impl Fn(&S) -> u64,
+    upper_bound: impl Fn(&S) -> u64,
+) -> u64 {
+    BranchAndBound::new(children, value, upper_bound).solve(initial)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        hash::{DefaultHasher, Hash, Hasher},
+        panic::{catch_unwind, AssertUnwindSafe},
+    };
+
+    use super::*;
+    use crate::Pcg32;
+
+    #[derive(Clone, Hash)]
+    struct Mining {
+        time: u32,
+        robots: [u32; 4],
+        stock: [u32; 4],
+    }
+
+    /// `costs[robot][resource]` for ore, clay, obsidian and geode robots.
+    fn max_geodes(costs: [[u32; 4]; 4], time: u32) -> (u64, SearchStats) {
+        let max_useful: [u32; 4] =
+            std::array::from_fn(|r| costs.iter().map(|c| c[r]).max().unwrap());
+        let children = |s: &Mining| {
+            let mut out = Vec::new();
+            for robot in (0..4).rev() {
+                if robot != 3 && s.robots[robot] >= max_useful[robot] {
+                    continue;
+                }
+                let mut wait = 0;
+                let affordable = (0..4).all(|r| {
+                    let missing = costs[robot][r].saturating_sub(s.stock[r]);
+                    if missing == 0 {
+                        return true;
+                    }
+                    if s.robots[r] == 0 {
+                        return false;
+                    }
+                    wait = wait.max(missing.div_ceil(s.robots[r]));
+                    true
+                });
+                if !affordable || wait + 1 >= s.time {
+                    continue;
+                }
+                let mut next = Mining {
+                    time: s.time - (wait + 1),
+                    robots: s.robots,
+                    stock: std::array::from_fn(|r| {
+                        s.stock[r] + s.robots[r] * (wait + 1) - costs[robot][r]
+                    }),
+                };
+                next.robots[robot] += 1;
+                out.push(next);
+            }
+            out
+        };
+        let value = |s: &Mining| (s.stock[3] + s.robots[3] * s.time) as u64;
+        let bound = |s: &Mining| value(s) + (s.time * s.time.saturating_sub(1) / 2) as u64;
+        let mut search = BranchAndBound::new(children, value, bound).with_transpositions(|s| {
+            let mut hasher = DefaultHasher::new();
+            s.hash(&mut hasher);
+            hasher.finish()
+        });
+        let initial = Mining {
+            time,
+            robots: [1, 0, 0, 0],
+            stock: [0; 4],
+        };
+        (search.solve(initial), search.stats)
+    }
+
+    #[test]
+    fn geode_blueprints() {
+        let blueprints = [
+            [[4, 0, 0, 0], [2, 0, 0, 0], [3, 14, 0, 0], [2, 0, 7, 0]],
+            [[2, 0, 0, 0], [3, 0, 0, 0], [3, 8, 0, 0], [3, 0, 12, 0]],
+        ];
+        let geodes = blueprints.map(|b| max_geodes(b, 24));
+        assert_eq!(geodes.map(|(g, _)| g), [9, 12]);
+        let quality: u64 = (1..).zip(geodes).map(|(id, (g, _))| id * g).sum();
+        assert_eq!(quality, 33);
+        assert!(geodes.iter().all(|(_, stats)| stats.pruned > 0));
+    }
+
+    #[test]
+    fn pruning_keeps_optimum() {
+        let mut rng = Pcg32::new(19);
+        for _ in 0..100 {
+            let n = rng.index(10) + 1;
+            let items: Vec<(u64, u64)> = (0..n)
+                .map(|_| (rng.below(10) as u64 + 1, rng.below(20) as u64))
+                .collect();
+            let capacity = rng.below(30) as u64;
+            // (next item, weight, value)
+            let children = |&(i, weight, value): &(usize, u64, u64)| {
+                let Some(&(w, v)) = items.get(i) else {
+                    return Vec::new();
+                };
+                let mut out = vec![(i + 1, weight, value)];
+                if weight + w <= capacity {
+                    out.push((i + 1, weight + w, value + v));
+                }
+                out
+            };
+            let value = |s: &(usize, u64, u64)| s.2;
+            let exhaustive = branch_and_bound((0, 0, 0), children, value, |_| u64::MAX);
+            let bound = |&(i, _, value): &(usize, u64, u64)| {
+                value + items[i.min(n)..].iter().map(|&(_, v)| v).sum::<u64>()
+            };
+            assert_eq!(
+                branch_and_bound((0, 0, 0), children, value, bound),
+                exhaustive
+            );
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Packing {
+        next: usize,
+        weight: u64,
+        value: u64,
+    }
+    impl CheckpointState for Packing {
+        fn encode(&self, out: &mut Vec<u8>) {
+            for n in [self.next as u64, self.weight, self.value] {
+                out.extend(n.to_le_bytes());
+            }
+        }
+
+        fn decode(bytes: &[u8]) -> Option<Self> {
+            let n: Vec<u64> = bytes
+                .chunks_exact(8)
+                .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+                .collect();
+            match n[..] {
+                [next, weight, value] if bytes.len() == 24 => Some(Packing {
+                    next: next as usize,
+                    weight,
+                    value,
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    /// A knapsack search that panics once `budget` nodes were expanded.
+    fn packing<'a>(items: &'a [(u64, u64)], budget: &'a Cell<u64>) -> BranchAndBound<'a, Packing> {
+        const CAPACITY: u64 = 60;
+        let children = move |s: &Packing| {
+            let left = budget.get();
+            assert!(left > 0, "interrupted");
+            budget.set(left - 1);
+            let Some(&(w, v)) = items.get(s.next) else {
+                return Vec::new();
+            };
+            let skip = Packing {
+                next: s.next + 1,
+                ..*s
+            };
+            let mut out = vec![skip];
+            if s.weight + w <= CAPACITY {
+                out.push(Packing {
+                    next: s.next + 1,
+                    weight: s.weight + w,
+                    value: s.value + v,
+                });
+            }
+            out
+        };
+        let bound = |s: &Packing| s.value + items[s.next..].iter().map(|&(_, v)| v).sum::<u64>();
+        BranchAndBound::new(children, |s| s.value, bound)
+            .with_transpositions(|s| (s.next as u64) << 40 | s.weight << 20 | s.value)
+    }
+
+    #[test]
+    fn checkpoint_and_resume() {
+        let mut rng = Pcg32::new(285);
+        let items: Vec<(u64, u64)> = (0..12)
+            .map(|_| (rng.below(15) as u64 + 1, rng.below(40) as u64))
+            .collect();
+        let initial = Packing {
+            next: 0,
+            weight: 0,
+            value: 0,
+        };
+        let unlimited = Cell::new(u64::MAX);
+        let mut full = packing(&items, &unlimited);
+        let best = full.solve(initial);
+        assert!(full.stats.expanded > 100);
+
+        let path = std::env::temp_dir().join(format!("aoch-checkpoint-{}", std::process::id()));
+        let config = CheckpointConfig {
+            path: path.clone(),
+            every: Duration::ZERO,
+            fingerprint: 285,
+        };
+        let budget = Cell::new(full.stats.expanded / 2);
+        let mut interrupted = packing(&items, &budget).with_checkpoints(config);
+        let crashed = catch_unwind(AssertUnwindSafe(|| interrupted.solve(initial)));
+        assert!(crashed.is_err());
+
+        let resume = |fingerprint: u64| {
+            let calls = Cell::new(u64::MAX);
+            let mut search = packing(&items, &calls);
+            let value = search.resume_from(&path, fingerprint, initial);
+            (value, search.stats, u64::MAX - calls.get())
+        };
+        let (value, stats, expanded) = resume(285);
+        assert_eq!((value, stats), (best, full.stats));
+        assert_eq!(expanded, full.stats.expanded - full.stats.expanded / 2);
+        // a different problem or a cut off file start over
+        assert_eq!(resume(1), (best, full.stats, full.stats.expanded));
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        assert_eq!(resume(285), (best, full.stats, full.stats.expanded));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(resume(285), (best, full.stats, full.stats.expanded));
+    }
+}
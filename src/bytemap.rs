@@ -0,0 +1,219 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+/// A map from `u8` keys backed by an array with one slot per byte, as a faster replacement for
+/// a `HashMap<u8, V>` in hot loops. Iteration is in ascending key order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteMap<V> {
+    slots: Box<[Option<V>; 256]>,
+    len: usize,
+}
+impl<V> Default for ByteMap<V> {
+    fn default() -> Self {
+        Self {
+            slots: Box::new(std::array::from_fn(|_| None)),
+            len: 0,
+        }
+    }
+}
+impl<V> ByteMap<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the previous value of the key.
+    pub fn insert(&mut self, key: u8, value: V) -> Option<V> {
+        let old = self.slots[key as usize].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: u8) -> Option<V> {
+        let old = self.slots[key as usize].take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    pub fn get(&self, key: u8) -> Option<&V> {
+        self.slots[key as usize].as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: u8) -> Option<&mut V> {
+        self.slots[key as usize].as_mut()
+    }
+
+    pub fn get_or_insert_with(&mut self, key: u8, f: impl FnOnce() -> V) -> &mut V {
+        let slot = &mut self.slots[key as usize];
+        if slot.is_none() {
+            self.len += 1;
+        }
+        slot.get_or_insert_with(f)
+    }
+
+    pub fn contains_key(&self, key: u8) -> bool {
+        self.slots[key as usize].is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &V)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(key, value)| Some((key as u8, value.as_ref()?)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = u8> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.slots.iter().flatten()
+    }
+}
+impl<V> FromIterator<(u8, V)> for ByteMap<V> {
+    fn from_iter<I: IntoIterator<Item = (u8, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+/// A set of bytes stored as a 256-bit mask.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteSet([u64; 4]);
+impl ByteSet {
+    pub const EMPTY: Self = Self([0; 4]);
+
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Returns whether the byte was newly inserted.
+    pub fn insert(&mut self, b: u8) -> bool {
+        let had = self.contains(b);
+        self.0[b as usize / 64] |= 1 << (b % 64);
+        !had
+    }
+
+    /// Returns whether the byte was present.
+    pub fn remove(&mut self, b: u8) -> bool {
+        let had = self.contains(b);
+        self.0[b as usize / 64] &= !(1 << (b % 64));
+        had
+    }
+
+    pub fn contains(&self, b: u8) -> bool {
+        self.0[b as usize / 64] >> (b % 64) & 1 == 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn is_subset(&self, other: Self) -> bool {
+        (*self - other).is_empty()
+    }
+
+    /// The members in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=255).filter(|&b| self.contains(b))
+    }
+}
+impl FromIterator<u8> for ByteSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for b in iter {
+            set.insert(b);
+        }
+        set
+    }
+}
+impl BitOr for ByteSet {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] | other.0[i]))
+    }
+}
+impl BitAnd for ByteSet {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+}
+impl Sub for ByteSet {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & !other.0[i]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use crate::Pcg32;
+
+    use super::*;
+
+    #[test]
+    fn parity_with_std() {
+        let mut rng = Pcg32::new(26);
+        let mut map = ByteMap::new();
+        let mut reference = BTreeMap::new();
+        let mut set = ByteSet::new();
+        let mut reference_set = BTreeSet::new();
+        for i in 0..5000u32 {
+            // a small key range makes collisions and removals of present keys common
+            let key = rng.below(40) as u8 * 6;
+            match rng.below(4) {
+                0 => assert_eq!(map.insert(key, i), reference.insert(key, i)),
+                1 => assert_eq!(map.remove(key), reference.remove(&key)),
+                2 => {
+                    *map.get_or_insert_with(key, || 0) += 1;
+                    *reference.entry(key).or_insert(0) += 1;
+                }
+                _ => assert_eq!(map.get(key), reference.get(&key)),
+            }
+            assert_eq!(map.len(), reference.len());
+            if rng.chance(0.5) {
+                assert_eq!(set.insert(key), reference_set.insert(key));
+            } else {
+                assert_eq!(set.remove(key), reference_set.remove(&key));
+            }
+        }
+        assert_eq!(
+            map.iter().map(|(k, &v)| (k, v)).collect::<Vec<_>>(),
+            reference.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(set.len(), reference_set.len());
+        assert!(set.iter().eq(reference_set));
+    }
+
+    #[test]
+    fn set_ops() {
+        let a: ByteSet = b"hello".iter().copied().collect();
+        let b: ByteSet = b"world".iter().copied().collect();
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), b"lo");
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), b"dehlorw");
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), b"eh");
+        assert!((a & b).is_subset(a));
+        let map: ByteMap<u32> = [(200, 1), (3, 2), (100, 3)].into_iter().collect();
+        assert_eq!(map.keys().collect::<Vec<_>>(), [3, 100, 200]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::Grid;
+
+/// States that can be reduced to a key shared by all states that are equivalent under some
+/// symmetry, so searches only explore one of them.
+pub trait Canonicalize {
+    fn canonical_key(&self) -> u64;
+}
+
+fn hash_grid<T: Hash>(grid: &Grid<T>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (grid.width(), grid.height()).hash(&mut hasher);
+    for cell in grid.rows().flatten() {
+        cell.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hashes the lexicographically smallest of the 8 rotations and reflections of the grid.
+pub fn canonical_under_grid_symmetries<T: Clone + Ord + Hash>(grid: &Grid<T>) -> u64 {
+    let mut orientations = Vec::with_capacity(8);
+    let mut current = grid.clone();
+    for _ in 0..4 {
+        orientations.push(current.clone().flip_horizontal());
+        let next = current.clone().rotate_cw();
+        orientations.push(current);
+        current = next;
+    }
+    let min = orientations
+        .iter()
+        .min_by(|a, b| {
+            (a.width(), a.height())
+                .cmp(&(b.width(), b.height()))
+                .then_with(|| a.rows().flatten().cmp(b.rows().flatten()))
+        })
+        .unwrap();
+    hash_grid(min)
+}
+
+/// Hashes groups of interchangeable items, for example the positions of identical agents. The
+/// order within a group doesn't matter, the order of the groups does.
+pub fn canonical_under_permutation<T: Clone + Ord + Hash>(groups: &[&[T]]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for group in groups {
+        let mut sorted = group.to_vec();
+        sorted.sort_unstable();
+        sorted.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BranchAndBound, Vec2i};
+
+    #[test]
+    fn symmetric_grids() {
+        let grid = Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let key = canonical_under_grid_symmetries(&grid);
+        assert_eq!(
+            canonical_under_grid_symmetries(&grid.clone().flip_horizontal()),
+            key
+        );
+        assert_eq!(
+            canonical_under_grid_symmetries(&grid.clone().rotate_ccw()),
+            key
+        );
+        assert_eq!(
+            canonical_under_grid_symmetries(&grid.clone().transpose()),
+            key
+        );
+        let others = [
+            Grid::from_nested(vec![vec![1, 2, 3], vec![4, 6, 5]]),
+            Grid::from_nested(vec![vec![1, 2, 3, 4, 5, 6]]),
+            Grid::from_nested(vec![vec![2, 1, 3], vec![4, 5, 6]]),
+        ];
+        for other in others {
+            assert_ne!(canonical_under_grid_symmetries(&other), key);
+        }
+        assert_eq!(
+            canonical_under_permutation(&[&[3, 1, 2], &[7]]),
+            canonical_under_permutation(&[&[1, 2, 3], &[7]])
+        );
+        assert_ne!(
+            canonical_under_permutation(&[&[1, 2], &[3]]),
+            canonical_under_permutation(&[&[1], &[2, 3]])
+        );
+    }
+
+    #[derive(Clone)]
+    struct Board(Grid<bool>);
+    impl Canonicalize for Board {
+        fn canonical_key(&self) -> u64 {
+            canonical_under_grid_symmetries(&self.0)
+        }
+    }
+
+    #[test]
+    fn canonical_search() {
+        // place up to 3 pieces on a 3x3 board
+        let children = |b: &Board| {
+            if b.0.count(&true) == 3 {
+                return Vec::new();
+            }
+            b.0.positions()
+                .filter(|&pos| !b.0[pos])
+                .map(|pos| {
+                    let mut next = b.clone();
+                    next.0[pos] = true;
+                    next
+                })
+                .collect()
+        };
+        let value = |b: &Board| {
+            let corners = [(0, 0), (2, 0), (0, 2), (2, 2)];
+            corners
+                .iter()
+                .filter(|&&(x, y)| b.0[Vec2i::new(x, y)])
+                .count() as u64
+        };
+        let initial = Board(Grid::new(3, 3, false));
+        let mut exact = BranchAndBound::new(children, value, |_| u64::MAX)
+            .with_transpositions(|b: &Board| hash_grid(&b.0));
+        let mut canonical =
+            BranchAndBound::new(children, value, |_| u64::MAX).with_canonical_keys();
+        assert_eq!(exact.solve(initial.clone()), 3);
+        assert_eq!(canonical.solve(initial), 3);
+        assert_eq!(exact.stats.expanded, 1 + 9 + 36 + 84);
+        assert!(canonical.stats.expanded < exact.stats.expanded / 4);
+    }
+}
@@ -0,0 +1,150 @@
+/// What [`ArrayChunks`] does with items left over at the end that don't fill a whole chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Remainder {
+    /// Leftover items are a bug in the input, panics with their count.
+    Panic,
+    /// Leftover items are dropped.
+    Ignore,
+}
+
+/// A whole chunk, or the items left over at the end.
+type Chunk<T, const N: usize> = Result<[T; N], Vec<T>>;
+
+/// An iterator adapter yielding `N` items at a time as arrays.
+pub struct ArrayChunks<I: Iterator, const N: usize> {
+    iter: I,
+    remainder: Remainder,
+}
+
+/// Groups the items into chunks of `N`. Panics by default if the items don't divide evenly, see
+/// [`ArrayChunks::on_remainder`] and [`ArrayChunks::with_partial`] for the alternatives.
+pub fn chunks_exact_array<I: IntoIterator, const N: usize>(it: I) -> ArrayChunks<I::IntoIter, N> {
+    assert!(N > 0, "chunks have to hold at least one item");
+    ArrayChunks {
+        iter: it.into_iter(),
+        remainder: Remainder::Panic,
+    }
+}
+
+impl<I: Iterator, const N: usize> ArrayChunks<I, N> {
+    pub fn on_remainder(mut self, remainder: Remainder) -> Self {
+        self.remainder = remainder;
+        self
+    }
+
+    /// Yields whole chunks as `Ok` and the leftover items, if there are any, as a final `Err`.
+    pub fn with_partial(mut self) -> impl Iterator<Item = Chunk<I::Item, N>> {
+        std::iter::from_fn(move || self.next_chunk())
+    }
+
+    fn next_chunk(&mut self) -> Option<Chunk<I::Item, N>> {
+        let items: Vec<I::Item> = self.iter.by_ref().take(N).collect();
+        match items.len() {
+            0 => None,
+            len if len == N => Some(Ok(items
+                .try_into()
+                .unwrap_or_else(|_| unreachable!("the length was checked")))),
+            _ => Some(Err(items)),
+        }
+    }
+}
+
+impl<I: Iterator, const N: usize> Iterator for ArrayChunks<I, N> {
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_chunk()? {
+            Ok(chunk) => Some(chunk),
+            Err(partial) => match self.remainder {
+                Remainder::Panic => panic!("{} items left over after chunks of {N}", partial.len()),
+                Remainder::Ignore => None,
+            },
+        }
+    }
+}
+
+/// Reads the values column by column, every `N` values down a column making one output row.
+/// With triangles listed in columns of three, this turns them into a row per triangle. Panics
+/// if the row count isn't a multiple of `N`.
+pub fn columns_to_rows<T: Clone, const N: usize>(rows: &[[T; N]]) -> Vec<[T; N]> {
+    assert!(
+        rows.len().is_multiple_of(N),
+        "{} rows don't split into groups of {N}",
+        rows.len()
+    );
+    (0..N)
+        .flat_map(|col| {
+            rows.chunks(N)
+                .map(move |group| std::array::from_fn(|i| group[i][col].clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rucksack_badges() {
+        let rucksacks = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+        let priorities: u32 = chunks_exact_array(rucksacks.lines())
+            .map(|[a, b, c]: [&str; 3]| {
+                let badge = a
+                    .bytes()
+                    .find(|&x| b.contains(x as char) && c.contains(x as char));
+                match badge.unwrap() {
+                    x @ b'a'..=b'z' => (x - b'a' + 1) as u32,
+                    x => (x - b'A' + 27) as u32,
+                }
+            })
+            .sum();
+        assert_eq!(priorities, 70);
+    }
+
+    #[test]
+    fn triangle_columns() {
+        let rows: Vec<[u32; 3]> = "101 301 501
+102 302 502
+103 303 503
+201 401 601
+202 402 602
+203 403 603"
+            .lines()
+            .map(|line| crate::ints_n::<3>(line).map(|n| n as u32))
+            .collect();
+        let triangles = columns_to_rows(&rows);
+        assert_eq!(triangles.len(), 6);
+        assert_eq!(triangles[0], [101, 102, 103]);
+        assert_eq!(triangles[1], [201, 202, 203]);
+        assert_eq!(triangles[5], [601, 602, 603]);
+        let possible = triangles
+            .iter()
+            .filter(|&&[a, b, c]| a + b > c && a + c > b && b + c > a)
+            .count();
+        assert_eq!(possible, 6);
+    }
+
+    #[test]
+    fn remainders() {
+        let pairs: Vec<[u8; 2]> = chunks_exact_array(1..=5)
+            .on_remainder(Remainder::Ignore)
+            .collect();
+        assert_eq!(pairs, [[1, 2], [3, 4]]);
+        let chunks: Vec<_> = chunks_exact_array::<_, 2>(1..=5).with_partial().collect();
+        assert_eq!(chunks, [Ok([1, 2]), Ok([3, 4]), Err(vec![5])]);
+        let chunks: Vec<_> = chunks_exact_array::<_, 2>(1..=4).with_partial().collect();
+        assert_eq!(chunks, [Ok([1, 2]), Ok([3, 4])]);
+        assert_eq!(chunks_exact_array::<_, 3>(0..0).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 items left over after chunks of 3")]
+    fn remainder_panics() {
+        chunks_exact_array::<_, 3>(0..8).for_each(drop);
+    }
+}
@@ -0,0 +1,405 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+use crate::{Interner, Sym};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    And,
+    Or,
+    Xor,
+    Not,
+    LShift,
+    RShift,
+}
+impl Op {
+    /// Shifting by the full width or more shifts every bit out and yields 0.
+    fn apply(self, a: u64, b: u64) -> u64 {
+        let shift = |f: fn(u64, u32) -> Option<u64>| {
+            u32::try_from(b).ok().and_then(|b| f(a, b)).unwrap_or(0)
+        };
+        match self {
+            Self::And => a & b,
+            Self::Or => a | b,
+            Self::Xor => a ^ b,
+            Self::Not => !a,
+            Self::LShift => shift(u64::checked_shl),
+            Self::RShift => shift(u64::checked_shr),
+        }
+    }
+}
+
+/// The keywords recognized by [`WireCircuit::parse`].
+pub const DEFAULT_OPS: &[(&str, Op)] = &[
+    ("AND", Op::And),
+    ("OR", Op::Or),
+    ("XOR", Op::Xor),
+    ("NOT", Op::Not),
+    ("LSHIFT", Op::LShift),
+    ("RSHIFT", Op::RShift),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Lit(u64),
+    Wire(Sym),
+}
+impl Operand {
+    fn parse(s: &str, names: &mut Interner) -> Self {
+        match s.parse() {
+            Ok(value) => Self::Lit(value),
+            Err(_) => Self::Wire(names.intern(s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Value(Operand),
+    Unary(Op, Operand),
+    Binary(Op, Operand, Operand),
+}
+impl Expr {
+    fn operands(&self) -> impl Iterator<Item = &Operand> {
+        let (a, b) = match self {
+            Self::Value(a) | Self::Unary(_, a) => (a, None),
+            Self::Binary(_, a, b) => (a, Some(b)),
+        };
+        std::iter::once(a).chain(b)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    UnknownWire(String),
+    /// The wires forming the cycle, starting and ending with the same wire.
+    Cycle(Vec<String>),
+}
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownWire(name) => write!(f, "unknown wire {name:?}"),
+            Self::Cycle(wires) => write!(f, "cycle between wires {}", wires.join(" -> ")),
+        }
+    }
+}
+impl std::error::Error for CircuitError {}
+
+/// A set of named wires, each driven by an expression of other wires. Values are computed
+/// lazily and memoized until the circuit is modified.
+#[derive(Debug, Clone, Default)]
+pub struct WireCircuit {
+    names: Interner,
+    wires: HashMap<Sym, Expr>,
+    mask: u64,
+    cache: RefCell<HashMap<Sym, u64>>,
+}
+impl WireCircuit {
+    /// Parses lines like `x AND y -> z`, `NOT x -> h`, `123 -> x` or `x00: 1` with the
+    /// [`DEFAULT_OPS`]. Blank lines are ignored.
+    pub fn parse(s: &str) -> Self {
+        Self::parse_with(s, DEFAULT_OPS)
+    }
+
+    pub fn parse_with(s: &str, ops: &[(&str, Op)]) -> Self {
+        let op = |name: &str| {
+            ops.iter()
+                .find(|(keyword, _)| *keyword == name)
+                .unwrap_or_else(|| panic!("unknown operation {name:?}"))
+                .1
+        };
+        let mut names = Interner::new();
+        let mut wires = HashMap::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (expr, name) = if let Some((expr, name)) = line.split_once("->") {
+                (expr, name)
+            } else if let Some((name, value)) = line.split_once(':') {
+                (value, name)
+            } else {
+                panic!("invalid wire definition {line:?}")
+            };
+            let parts: Vec<&str> = expr.split_whitespace().collect();
+            let expr = match parts[..] {
+                [a] => Expr::Value(Operand::parse(a, &mut names)),
+                [o, a] => Expr::Unary(op(o), Operand::parse(a, &mut names)),
+                [a, o, b] => Expr::Binary(
+                    op(o),
+                    Operand::parse(a, &mut names),
+                    Operand::parse(b, &mut names),
+                ),
+                _ => panic!("invalid wire expression in {line:?}"),
+            };
+            wires.insert(names.intern(name.trim()), expr);
+        }
+        Self {
+            names,
+            wires,
+            mask: u64::MAX,
+            cache: RefCell::default(),
+        }
+    }
+
+    /// Restricts all wire values to the lowest `bits` bits, as needed for `NOT` on 16-bit wires.
+    pub fn with_width(mut self, bits: u32) -> Self {
+        self.mask = if bits >= 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        self.cache.get_mut().clear();
+        self
+    }
+
+    pub fn value_of(&self, name: &str) -> u64 {
+        self.try_value_of(name)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_value_of(&self, name: &str) -> Result<u64, CircuitError> {
+        let sym = self
+            .sym(name)
+            .ok_or_else(|| CircuitError::UnknownWire(name.to_owned()))?;
+        self.try_value_of_sym(sym)
+    }
+
+    /// The symbol of a wire or operand named `name`, for repeated lookups without hashing
+    /// strings.
+    pub fn sym(&self, name: &str) -> Option<Sym> {
+        self.names.get(name)
+    }
+
+    pub fn resolve(&self, sym: Sym) -> &str {
+        self.names.resolve(sym)
+    }
+
+    pub fn try_value_of_sym(&self, name: Sym) -> Result<u64, CircuitError> {
+        if let Some(&value) = self.cache.borrow().get(&name) {
+            return Ok(value);
+        }
+        let mut cache = self.cache.borrow_mut();
+        // iterative post-order evaluation, the stack doubles as the current dependency chain
+        let mut stack: Vec<(Sym, bool)> = vec![(name, false)];
+        let mut chain: Vec<Sym> = Vec::new();
+        while let Some((wire, ready)) = stack.pop() {
+            let expr = self
+                .wires
+                .get(&wire)
+                .ok_or_else(|| CircuitError::UnknownWire(self.resolve(wire).to_owned()))?;
+            if ready {
+                chain.pop();
+                let value = |operand: &Operand| match operand {
+                    Operand::Lit(value) => *value,
+                    Operand::Wire(name) => cache[name],
+                };
+                let result = match expr {
+                    Expr::Value(a) => value(a),
+                    Expr::Unary(op, a) => op.apply(value(a), 0),
+                    Expr::Binary(op, a, b) => op.apply(value(a), value(b)),
+                } & self.mask;
+                cache.insert(wire, result);
+                continue;
+            }
+            if cache.contains_key(&wire) {
+                continue;
+            }
+            if let Some(i) = chain.iter().position(|&w| w == wire) {
+                let mut cycle: Vec<String> = chain[i..]
+                    .iter()
+                    .map(|&w| self.resolve(w).to_owned())
+                    .collect();
+                cycle.push(self.resolve(wire).to_owned());
+                return Err(CircuitError::Cycle(cycle));
+            }
+            chain.push(wire);
+            stack.push((wire, true));
+            for operand in expr.operands() {
+                if let Operand::Wire(dep) = operand {
+                    if !cache.contains_key(dep) {
+                        stack.push((*dep, false));
+                    }
+                }
+            }
+        }
+        Ok(cache[&name])
+    }
+
+    /// Replaces the expression driving `name` by a constant and invalidates all cached values.
+    pub fn override_wire(&mut self, name: &str, value: u64) {
+        let sym = self.names.intern(name);
+        self.wires.insert(sym, Expr::Value(Operand::Lit(value)));
+        self.cache.get_mut().clear();
+    }
+
+    pub fn expr(&self, name: &str) -> Option<&Expr> {
+        self.wires.get(&self.sym(name)?)
+    }
+
+    pub fn wires(&self) -> impl Iterator<Item = (&str, &Expr)> {
+        self.wires
+            .iter()
+            .map(|(&name, expr)| (self.resolve(name), expr))
+    }
+
+    pub fn wires_with_prefix(&self, prefix: &str) -> Vec<&str> {
+        let mut wires: Vec<&str> = self
+            .wires
+            .keys()
+            .map(|&name| self.resolve(name))
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+        wires.sort_unstable();
+        wires
+    }
+
+    /// Assembles a binary number from the wires `{prefix}00`, `{prefix}01`, ... where the wire
+    /// with the lowest number is the least significant bit.
+    pub fn number_from_bits(&self, prefix: &str) -> u64 {
+        self.wires_with_prefix(prefix)
+            .into_iter()
+            .filter_map(|name| {
+                let bit: u32 = name[prefix.len()..].parse().ok()?;
+                Some((bit, self.value_of(name)))
+            })
+            .fold(0, |n, (bit, value)| n | (value & 1) << bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wire_values() {
+        let circuit = WireCircuit::parse(
+            "123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i",
+        )
+        .with_width(16);
+        let expected = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, value) in expected {
+            assert_eq!(circuit.value_of(wire), value, "wire {wire}");
+        }
+    }
+
+    #[test]
+    fn override_invalidates() {
+        let mut circuit = WireCircuit::parse("3 -> a\na LSHIFT 1 -> b\nb -> c");
+        assert_eq!(circuit.value_of("c"), 6);
+        circuit.override_wire("a", 5);
+        assert_eq!(circuit.value_of("c"), 10);
+        let c = circuit.sym("c").unwrap();
+        assert_eq!(circuit.try_value_of_sym(c), Ok(10));
+        assert_eq!(circuit.resolve(c), "c");
+    }
+
+    #[test]
+    fn wide_shifts() {
+        let circuit = WireCircuit::parse(
+            "5 -> a\na LSHIFT 64 -> b\na RSHIFT 100 -> c\na LSHIFT 99999999999 -> d\na LSHIFT 63 -> e",
+        );
+        assert_eq!(circuit.try_value_of("b"), Ok(0));
+        assert_eq!(circuit.try_value_of("c"), Ok(0));
+        assert_eq!(circuit.try_value_of("d"), Ok(0));
+        assert_eq!(circuit.try_value_of("e"), Ok(1 << 63));
+    }
+
+    #[test]
+    fn cycle_is_named() {
+        let circuit = WireCircuit::parse("b -> a\nc AND 1 -> b\na -> c\nc -> d");
+        assert_eq!(
+            circuit.try_value_of("d"),
+            Err(CircuitError::Cycle(vec![
+                "c".to_owned(),
+                "a".to_owned(),
+                "b".to_owned(),
+                "c".to_owned(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn gate_output_number() {
+        let small = WireCircuit::parse(
+            "x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02",
+        );
+        assert_eq!(small.number_from_bits("z"), 4);
+
+        let large = WireCircuit::parse(
+            "x00: 1
+x01: 0
+x02: 1
+x03: 1
+x04: 0
+y00: 1
+y01: 1
+y02: 1
+y03: 1
+y04: 1
+
+ntg XOR fgs -> mjb
+y02 OR x01 -> tnw
+kwq OR kpj -> z05
+x00 OR x03 -> fst
+tgd XOR rvg -> z01
+vdt OR tnw -> bfw
+bfw AND frj -> z10
+ffh OR nrd -> bqk
+y00 AND y03 -> djm
+y03 OR y00 -> psh
+bqk OR frj -> z08
+tnw OR fst -> frj
+gnj AND tgd -> z11
+bfw XOR mjb -> z00
+x03 OR x00 -> vdt
+gnj AND wpb -> z02
+x04 AND y00 -> kjc
+djm OR pbc -> qhw
+nrd AND vdt -> hwm
+kjc AND fst -> rvg
+y04 OR y02 -> fgs
+y01 AND x02 -> pbc
+ntg OR kjc -> kwq
+psh XOR fgs -> tgd
+qhw XOR tgd -> z09
+pbc OR djm -> kpj
+x03 XOR y03 -> ffh
+x00 XOR y04 -> ntg
+bfw OR bqk -> z06
+nrd XOR fgs -> wpb
+frj XOR qhw -> z04
+bqk OR frj -> z07
+y03 OR x01 -> nrd
+hwm AND bqk -> z03
+tgd XOR rvg -> z12
+tnw OR pbc -> gnj",
+        );
+        assert_eq!(large.number_from_bits("z"), 2024);
+        assert_eq!(
+            large.wires_with_prefix("x"),
+            ["x00", "x01", "x02", "x03", "x04"]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use vecm::Vec2i;
+
+use crate::Rect;
+
+/// Claims are counted in a grid when their bounds have at most this many cells.
+const DENSE_LIMIT: usize = 1 << 22;
+
+/// Parses a claim like `#1 @ 1,3: 4x4` into its id and the claimed rectangle.
+pub fn parse_claim(line: &str) -> (usize, Rect) {
+    let [id, x, y, w, h] = crate::ints_n::<5>(line);
+    assert!(
+        id >= 0 && w > 0 && h > 0,
+        "expected a claim like \"#1 @ 1,3: 4x4\" but found {line:?}"
+    );
+    let origin = Vec2i::new(x as i32, y as i32);
+    (id as usize, Rect::from_size(origin, w as usize, h as usize))
+}
+
+/// The number of cells covered by at least two claims. Counts in a grid when the claims are
+/// close together and sweeps over the distinct edge coordinates otherwise.
+pub fn claim_overlap_cells(claims: &[(usize, Rect)]) -> usize {
+    let Some(bounds) = bounds(claims) else {
+        return 0;
+    };
+    if bounds.area() <= DENSE_LIMIT {
+        dense_overlap(claims, bounds)
+    } else {
+        sweep_overlap(claims)
+    }
+}
+
+fn bounds(claims: &[(usize, Rect)]) -> Option<Rect> {
+    claims.iter().map(|&(_, rect)| rect).reduce(|a, b| {
+        Rect::new(
+            Vec2i::new(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+            Vec2i::new(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+        )
+    })
+}
+
+/// Counts with a 2D difference array over `bounds`, so each claim costs four updates.
+fn dense_overlap(claims: &[(usize, Rect)], bounds: Rect) -> usize {
+    let (w, h) = (bounds.width() + 1, bounds.height() + 1);
+    let mut diff = vec![0i32; w * h];
+    for (_, rect) in claims {
+        let offset = rect.min - bounds.min;
+        let (x0, y0) = (offset.x as usize, offset.y as usize);
+        let (x1, y1) = (x0 + rect.width(), y0 + rect.height());
+        diff[y0 * w + x0] += 1;
+        diff[y0 * w + x1] -= 1;
+        diff[y1 * w + x0] -= 1;
+        diff[y1 * w + x1] += 1;
+    }
+    for y in 0..h {
+        for x in 1..w {
+            diff[y * w + x] += diff[y * w + x - 1];
+        }
+    }
+    for y in 1..h {
+        for x in 0..w {
+            diff[y * w + x] += diff[(y - 1) * w + x];
+        }
+    }
+    diff.iter().filter(|&&count| count >= 2).count()
+}
+
+/// Sweeps over vertical slabs between the distinct left and right edges. Within a slab, the
+/// covered length is found by sweeping over the edges of the claims crossing it.
+fn sweep_overlap(claims: &[(usize, Rect)]) -> usize {
+    let mut xs: Vec<i64> = claims
+        .iter()
+        .flat_map(|(_, r)| [r.min.x as i64, r.max.x as i64 + 1])
+        .collect();
+    xs.sort_unstable();
+    xs.dedup();
+    let mut edges = Vec::new();
+    let mut total = 0;
+    for slab in xs.windows(2) {
+        edges.clear();
+        for (_, r) in claims {
+            if (r.min.x as i64) <= slab[0] && slab[0] <= r.max.x as i64 {
+                edges.push((r.min.y as i64, 1));
+                edges.push((r.max.y as i64 + 1, -1));
+            }
+        }
+        edges.sort_unstable();
+        let mut covering = 0;
+        let mut length = 0;
+        for pair in edges.windows(2) {
+            covering += pair[0].1;
+            if covering >= 2 {
+                length += pair[1].0 - pair[0].0;
+            }
+        }
+        total += length * (slab[1] - slab[0]);
+    }
+    total as usize
+}
+
+/// The ids of the claims that don't overlap any other claim, in the order they were given.
+/// Claims are compared sorted by their left edge, so only claims that overlap horizontally
+/// are ever checked against each other.
+pub fn non_overlapping_claims(claims: &[(usize, Rect)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..claims.len()).collect();
+    order.sort_by_key(|&i| claims[i].1.min.x);
+    let mut overlaps = vec![false; claims.len()];
+    for (k, &i) in order.iter().enumerate() {
+        let rect = claims[i].1;
+        for &j in order[k + 1..]
+            .iter()
+            .take_while(|&&j| claims[j].1.min.x <= rect.max.x)
+        {
+            if rect.intersect(&claims[j].1).is_some() {
+                overlaps[i] = true;
+                overlaps[j] = true;
+            }
+        }
+    }
+    claims
+        .iter()
+        .zip(overlaps)
+        .filter(|(_, overlaps)| !overlaps)
+        .map(|(&(id, _), _)| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    #[test]
+    fn fabric() {
+        let claims: Vec<(usize, Rect)> = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"
+            .lines()
+            .map(parse_claim)
+            .collect();
+        assert_eq!(claims[2], (3, Rect::from_size(Vec2i::new(5, 5), 2, 2)));
+        assert_eq!(claim_overlap_cells(&claims), 4);
+        assert_eq!(sweep_overlap(&claims), 4);
+        assert_eq!(non_overlapping_claims(&claims), [3]);
+        assert_eq!(claim_overlap_cells(&[]), 0);
+    }
+
+    #[test]
+    fn dense_and_sweep_agree() {
+        let mut rng = Pcg32::new(286);
+        for _ in 0..50 {
+            let claims: Vec<(usize, Rect)> = (1..=rng.index(30) + 1)
+                .map(|id| {
+                    let origin = Vec2i::new(rng.below(40) as i32 - 5, rng.below(40) as i32 - 5);
+                    let size = (rng.index(12) + 1, rng.index(12) + 1);
+                    (id, Rect::from_size(origin, size.0, size.1))
+                })
+                .collect();
+            let bounds = bounds(&claims).unwrap();
+            let brute = bounds
+                .positions()
+                .filter(|&p| claims.iter().filter(|(_, r)| r.contains(p)).count() >= 2)
+                .count();
+            assert_eq!(dense_overlap(&claims, bounds), brute);
+            assert_eq!(sweep_overlap(&claims), brute);
+            let intact: Vec<usize> = claims
+                .iter()
+                .filter(|(id, a)| {
+                    claims
+                        .iter()
+                        .all(|(other, b)| other == id || a.intersect(b).is_none())
+                })
+                .map(|&(id, _)| id)
+                .collect();
+            assert_eq!(non_overlapping_claims(&claims), intact);
+        }
+        // far apart claims go through the sweep
+        let far = [
+            (1, Rect::from_size(Vec2i::new(0, 0), 3, 3)),
+            (2, Rect::from_size(Vec2i::new(1, 1), 3, 3)),
+            (3, Rect::from_size(Vec2i::new(1_000_000, 1_000_000), 2, 2)),
+        ];
+        assert_eq!(claim_overlap_cells(&far), 4);
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    fmt::{self, Display},
+    io::IsTerminal,
+    sync::OnceLock,
+};
+
+/// Whether colored output is wanted by default: stdout has to be a terminal and `NO_COLOR`
+/// must not be set to a non-empty value. Computed once per process.
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        !no_color && std::io::stdout().is_terminal()
+    })
+}
+
+/// Bold text without a color.
+pub fn bold<T: Display>(text: T) -> Painted<T> {
+    Painted {
+        color: None,
+        bold: true,
+        enabled: true,
+        text,
+    }
+}
+
+/// A terminal foreground color, written as an ANSI escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+impl Color {
+    /// The color used for cells without any highlighting.
+    pub const DEFAULT: Self = Self::Rgb(192, 192, 192);
+
+    pub fn paint<T: Display>(self, text: T) -> Painted<T> {
+        Painted {
+            color: Some(self),
+            bold: false,
+            enabled: true,
+            text,
+        }
+    }
+
+    pub fn paint_bold<T: Display>(self, text: T) -> Painted<T> {
+        Painted {
+            color: Some(self),
+            bold: true,
+            enabled: true,
+            text,
+        }
+    }
+
+    fn write_escape(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+            Self::Rgb(r, g, b) => return write!(f, "\x1b[38;2;{r};{g};{b}m"),
+        };
+        write!(f, "\x1b[{code}m")
+    }
+}
+
+pub struct Painted<T> {
+    color: Option<Color>,
+    bold: bool,
+    enabled: bool,
+    text: T,
+}
+impl<T> Painted<T> {
+    /// Writes only the plain text unless `enabled`.
+    pub fn when(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.enabled {
+            return write!(f, "{}", self.text);
+        }
+        if self.bold {
+            write!(f, "\x1b[1m")?;
+        }
+        if let Some(color) = self.color {
+            color.write_escape(f)?;
+        }
+        write!(f, "{}\x1b[0m", self.text)
+    }
+}
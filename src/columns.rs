@@ -0,0 +1,113 @@
+use std::fmt::Display;
+
+use crate::Counter;
+
+/// Parses two whitespace separated integer columns.
+pub fn pair_columns(s: &str) -> (Vec<i64>, Vec<i64>) {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match crate::ints(line)[..] {
+            [a, b] => (a, b),
+            _ => panic!("expected two columns in line {line:?}"),
+        })
+        .unzip()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnsError {
+    LengthMismatch {
+        left: usize,
+        right: usize,
+    },
+    /// The result doesn't fit into an `i64`.
+    Overflow,
+}
+impl Display for ColumnsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LengthMismatch { left, right } => {
+                write!(f, "lists have different lengths: {left} and {right}")
+            }
+            Self::Overflow => write!(f, "total distance overflows i64"),
+        }
+    }
+}
+impl std::error::Error for ColumnsError {}
+
+/// Sorts copies of both lists and sums the absolute differences of the paired values.
+/// Differences are computed as unsigned values, so extreme inputs can't overflow midway.
+pub fn total_sorted_distance(a: &[i64], b: &[i64]) -> Result<i64, ColumnsError> {
+    if a.len() != b.len() {
+        return Err(ColumnsError::LengthMismatch {
+            left: a.len(),
+            right: b.len(),
+        });
+    }
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort_unstable();
+    b.sort_unstable();
+    a.iter()
+        .zip(&b)
+        .try_fold(0u64, |total, (x, y)| total.checked_add(x.abs_diff(*y)))
+        .and_then(|total| i64::try_from(total).ok())
+        .ok_or(ColumnsError::Overflow)
+}
+
+/// Sums each element of `a` multiplied by the number of times it occurs in `b`. `None` if the
+/// score overflows an `i64`.
+pub fn similarity_score(a: &[i64], b: &[i64]) -> Option<i64> {
+    let counts: Counter<i64> = b.iter().copied().collect();
+    a.iter().try_fold(0i64, |score, x| {
+        let count = i64::try_from(counts.get(x)).ok()?;
+        score.checked_add(x.checked_mul(count)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+    #[test]
+    fn historian_lists() {
+        let (a, b) = pair_columns(SAMPLE);
+        assert_eq!(total_sorted_distance(&a, &b), Ok(11));
+        assert_eq!(similarity_score(&a, &b), Some(31));
+    }
+
+    #[test]
+    fn length_mismatch() {
+        assert_eq!(
+            total_sorted_distance(&[1, 2, 3], &[1]),
+            Err(ColumnsError::LengthMismatch { left: 3, right: 1 })
+        );
+    }
+
+    #[test]
+    fn distance_overflow() {
+        assert_eq!(
+            total_sorted_distance(&[i64::MIN], &[i64::MAX]),
+            Err(ColumnsError::Overflow)
+        );
+        assert_eq!(
+            total_sorted_distance(&[i64::MIN, i64::MIN], &[i64::MAX, i64::MAX]),
+            Err(ColumnsError::Overflow)
+        );
+        assert_eq!(total_sorted_distance(&[0, 0], &[i64::MAX, 0]), Ok(i64::MAX));
+    }
+
+    #[test]
+    fn score_overflow() {
+        assert_eq!(similarity_score(&[i64::MAX], &[i64::MAX]), Some(i64::MAX));
+        assert_eq!(similarity_score(&[i64::MAX], &[i64::MAX, i64::MAX]), None);
+        assert_eq!(similarity_score(&[i64::MIN, -1], &[i64::MIN, -1]), None);
+        assert_eq!(similarity_score(&[-5, 7], &[]), Some(0));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, AddAssign, Index},
+};
+
+/// How often each item occurs. Items that aren't counted have a count of 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, u64>,
+}
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: u64) {
+        if n != 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+        }
+    }
+
+    /// Removes up to `n` occurrences and returns how many there were to remove. Items that
+    /// reach 0 are dropped.
+    pub fn remove_n(&mut self, item: &T, n: u64) -> u64 {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    pub fn get(&self, item: &T) -> u64 {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The items with their counts in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u64)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// All items from the highest count to the lowest, equal counts in ascending item order.
+    pub fn most_common(&self) -> Vec<(T, u64)>
+    where
+        T: Ord + Clone,
+    {
+        let mut items: Vec<(T, u64)> = self
+            .counts
+            .iter()
+            .map(|(item, &count)| (item.clone(), count))
+            .collect();
+        items.sort_unstable_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        items
+    }
+
+    /// The item with the highest count, the smallest one if several have it.
+    pub fn max(&self) -> Option<(&T, u64)>
+    where
+        T: Ord,
+    {
+        self.iter()
+            .min_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)))
+    }
+
+    /// The item with the lowest count, the smallest one if several have it.
+    pub fn min(&self) -> Option<(&T, u64)>
+    where
+        T: Ord,
+    {
+        self.iter()
+            .min_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)))
+    }
+}
+
+impl<T: Eq + Hash> Index<&T> for Counter<T> {
+    type Output = u64;
+
+    fn index(&self, item: &T) -> &u64 {
+        self.counts.get(item).unwrap_or(&0)
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+impl<T: Eq + Hash> Extend<(T, u64)> for Counter<T> {
+    fn extend<I: IntoIterator<Item = (T, u64)>>(&mut self, iter: I) {
+        for (item, n) in iter {
+            self.add_n(item, n);
+        }
+    }
+}
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+/// Counts of the same item are added up.
+impl<T: Eq + Hash> FromIterator<(T, u64)> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = (T, u64)>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.extend(other.counts);
+    }
+}
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging() {
+        let mut a: Counter<char> = "NNCB".chars().collect();
+        let b: Counter<char> = "CHB".chars().collect();
+        assert_eq!(a[&'N'], 2);
+        assert_eq!(a[&'H'], 0);
+        a += b.clone();
+        assert_eq!(a.get(&'C'), 2);
+        assert_eq!(a.total(), 7);
+        let c = a.clone() + b;
+        assert_eq!(c[&'B'], 3);
+        assert_eq!(c.len(), 4);
+        assert_eq!(a.remove_n(&'N', 5), 2);
+        assert_eq!(a.remove_n(&'X', 1), 0);
+        assert_eq!(a.len(), 3);
+    }
+
+    #[test]
+    fn most_common_ties() {
+        let cards: Counter<char> = "KTJJTQ".chars().collect();
+        assert_eq!(
+            cards.most_common(),
+            [('J', 2), ('T', 2), ('K', 1), ('Q', 1)]
+        );
+        assert_eq!(cards.max(), Some((&'J', 2)));
+        assert_eq!(cards.min(), Some((&'K', 1)));
+        assert_eq!(Counter::<char>::new().max(), None);
+    }
+
+    #[test]
+    fn lanternfish_pairs() {
+        // populations by timer, far beyond u32 after enough days
+        let mut fish: Counter<u8> = [(3, 2), (4, 1), (1, 1), (2, 1)].into_iter().collect();
+        for _ in 0..256 {
+            fish = fish
+                .iter()
+                .flat_map(|(&timer, n)| match timer {
+                    0 => vec![(6, n), (8, n)],
+                    t => vec![(t - 1, n)],
+                })
+                .collect();
+        }
+        assert_eq!(fish.total(), 26984457539);
+        let pairs: Counter<&str> = [("NN", 3), ("NC", 1), ("NN", 2)].into_iter().collect();
+        assert_eq!(pairs[&"NN"], 5);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::Grid;
+
+pub const CRT_WIDTH: usize = 40;
+pub const CRT_HEIGHT: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClockedInstr {
+    Noop,
+    Addx(i64),
+}
+impl ClockedInstr {
+    pub fn parse(s: &str) -> Self {
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            ["noop"] => Self::Noop,
+            ["addx", v] => Self::Addx(crate::int(v)),
+            _ => panic!("invalid instruction {s:?}"),
+        }
+    }
+
+    pub fn cycles(self) -> usize {
+        match self {
+            Self::Noop => 1,
+            Self::Addx(_) => 2,
+        }
+    }
+}
+
+type CycleCallback<'a> = Box<dyn FnMut(usize, i64) + 'a>;
+
+/// A single register machine where each instruction takes a fixed number of cycles and its
+/// effect is only applied after its last cycle.
+pub struct ClockedVm<'a> {
+    program: Vec<ClockedInstr>,
+    callbacks: Vec<CycleCallback<'a>>,
+}
+impl<'a> ClockedVm<'a> {
+    pub fn new(program: Vec<ClockedInstr>) -> Self {
+        Self {
+            program,
+            callbacks: Vec::new(),
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        Self::new(
+            s.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(ClockedInstr::parse)
+                .collect(),
+        )
+    }
+
+    /// Registers a callback that is called with the 1-based cycle number and the value of the
+    /// register *during* that cycle.
+    pub fn on_cycle(&mut self, f: impl FnMut(usize, i64) + 'a) {
+        self.callbacks.push(Box::new(f));
+    }
+
+    /// Runs the program, calling all registered callbacks, and returns the final register value.
+    pub fn run(&mut self) -> i64 {
+        let mut x = 1;
+        for (cycle, during) in cycles(&self.program) {
+            for callback in &mut self.callbacks {
+                callback(cycle, during);
+            }
+            x = during;
+        }
+        match self.program.last() {
+            Some(ClockedInstr::Addx(v)) => x + v,
+            _ => x,
+        }
+    }
+
+    /// Iterates over (cycle, register value during the cycle) for every cycle of the program.
+    pub fn cycles(&self) -> impl Iterator<Item = (usize, i64)> + '_ {
+        cycles(&self.program)
+    }
+
+    pub fn signal_strength_sum(&self, at_cycles: &[usize]) -> i64 {
+        self.cycles()
+            .filter(|(cycle, _)| at_cycles.contains(cycle))
+            .map(|(cycle, x)| cycle as i64 * x)
+            .sum()
+    }
+
+    /// Draws one pixel per cycle, row by row, lighting it when the 3 pixel wide sprite centered
+    /// on the register value covers the pixel's column.
+    pub fn render_crt(&self) -> Grid<bool> {
+        let mut rows = vec![vec![false; CRT_WIDTH]; CRT_HEIGHT];
+        for (cycle, x) in self.cycles().take(CRT_WIDTH * CRT_HEIGHT) {
+            let col = (cycle - 1) % CRT_WIDTH;
+            rows[(cycle - 1) / CRT_WIDTH][col] = (x - col as i64).abs() <= 1;
+        }
+        Grid::from_nested(rows)
+    }
+
+    /// Renders the screen and reads the displayed letters.
+    pub fn read_crt(&self) -> String {
+        crate::ocr(&self.render_crt())
+    }
+}
+
+fn cycles(program: &[ClockedInstr]) -> impl Iterator<Item = (usize, i64)> + '_ {
+    let mut x = 1;
+    program
+        .iter()
+        .flat_map(move |&instr| {
+            let during = x;
+            if let ClockedInstr::Addx(v) = instr {
+                x += v;
+            }
+            std::iter::repeat_n(during, instr.cycles())
+        })
+        .enumerate()
+        .map(|(i, x)| (i + 1, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "addx 15\naddx -11\naddx 6\naddx -3\naddx 5\naddx -1\naddx -8\naddx 13
+addx 4\nnoop\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1
+addx -35\naddx 1\naddx 24\naddx -19\naddx 1\naddx 16\naddx -11\nnoop\nnoop\naddx 21\naddx -15
+noop\nnoop\naddx -3\naddx 9\naddx 1\naddx -3\naddx 8\naddx 1\naddx 5\nnoop\nnoop\nnoop\nnoop
+noop\naddx -36\nnoop\naddx 1\naddx 7\nnoop\nnoop\nnoop\naddx 2\naddx 6\nnoop\nnoop\nnoop\nnoop
+noop\naddx 1\nnoop\nnoop\naddx 7\naddx 1\nnoop\naddx -13\naddx 13\naddx 7\nnoop\naddx 1
+addx -33\nnoop\nnoop\nnoop\naddx 2\nnoop\nnoop\nnoop\naddx 8\nnoop\naddx -1\naddx 2\naddx 1
+noop\naddx 17\naddx -9\naddx 1\naddx 1\naddx -3\naddx 11\nnoop\nnoop\naddx 1\nnoop\naddx 1
+noop\nnoop\naddx -13\naddx -19\naddx 1\naddx 3\naddx 26\naddx -30\naddx 12\naddx -1\naddx 3
+addx 1\nnoop\nnoop\nnoop\naddx -9\naddx 18\naddx 1\naddx 2\nnoop\nnoop\naddx 9\nnoop\nnoop
+noop\naddx -1\naddx 2\naddx -37\naddx 1\naddx 3\nnoop\naddx 15\naddx -21\naddx 22\naddx -6
+addx 1\nnoop\naddx 2\naddx 1\nnoop\naddx -10\nnoop\nnoop\naddx 20\naddx 1\naddx 2\naddx 2
+addx -6\naddx -11\nnoop\nnoop\nnoop";
+
+    #[test]
+    fn signal_strength() {
+        let vm = ClockedVm::parse(SAMPLE);
+        assert_eq!(vm.signal_strength_sum(&[20, 60, 100, 140, 180, 220]), 13140);
+    }
+
+    #[test]
+    fn callbacks_see_every_cycle() {
+        let mut seen = Vec::new();
+        let mut vm = ClockedVm::parse("noop\naddx 3\naddx -5");
+        vm.on_cycle(|cycle, x| seen.push((cycle, x)));
+        assert_eq!(vm.run(), -1);
+        drop(vm);
+        assert_eq!(seen, [(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn render_sample() {
+        let rendered = ClockedVm::parse(SAMPLE)
+            .render_crt()
+            .map(|lit| if lit { '#' } else { '.' });
+        let expected = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+        assert_eq!(rendered, Grid::from_str_chars(expected));
+    }
+}
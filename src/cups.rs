@@ -0,0 +1,104 @@
+/// A circle of labeled cups stored as a successor array, `next[label]` being the label
+/// clockwise of `label`. Labels start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuccessorRing {
+    next: Vec<u32>,
+    current: u32,
+    max_label: u32,
+}
+impl SuccessorRing {
+    /// Builds the circle in the given order, optionally followed by all labels after the
+    /// largest given one up to `extend_to`. The first label is the current cup.
+    pub fn from_labels(labels: &[u32], extend_to: Option<u32>) -> Self {
+        assert!(!labels.is_empty(), "there has to be at least one cup");
+        let given_max = *labels.iter().max().unwrap();
+        let max_label = extend_to.unwrap_or(given_max).max(given_max);
+        let mut next = vec![0; max_label as usize + 1];
+        let order = labels.iter().copied().chain(given_max + 1..=max_label);
+        let mut prev = None;
+        for label in order {
+            assert!(label > 0, "cup labels start at 1");
+            if let Some(prev) = prev {
+                next[prev as usize] = label;
+            }
+            prev = Some(label);
+        }
+        next[prev.unwrap() as usize] = labels[0];
+        Self {
+            next,
+            current: labels[0],
+            max_label,
+        }
+    }
+
+    /// Picks up the three cups after `current` and places them after the destination: the
+    /// next lower label that wasn't picked up, wrapping around to `max_label`. Returns the new
+    /// current cup.
+    pub fn do_cups_move(&mut self, current: u32, max_label: u32) -> u32 {
+        let a = self.next[current as usize];
+        let b = self.next[a as usize];
+        let c = self.next[b as usize];
+        let mut dest = current;
+        loop {
+            dest = if dest == 1 { max_label } else { dest - 1 };
+            if dest != a && dest != b && dest != c {
+                break;
+            }
+        }
+        self.next[current as usize] = self.next[c as usize];
+        self.next[c as usize] = self.next[dest as usize];
+        self.next[dest as usize] = a;
+        self.next[current as usize]
+    }
+
+    /// Runs `moves` moves from the current cup.
+    pub fn run(&mut self, moves: usize) {
+        for _ in 0..moves {
+            self.current = self.do_cups_move(self.current, self.max_label);
+        }
+    }
+
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// The `count` labels clockwise after `label`.
+    pub fn after(&self, label: u32, count: usize) -> Vec<u32> {
+        let mut labels = Vec::with_capacity(count);
+        let mut at = label;
+        for _ in 0..count {
+            at = self.next[at as usize];
+            labels.push(at);
+        }
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: [u32; 9] = [3, 8, 9, 1, 2, 5, 4, 6, 7];
+
+    fn order(ring: &SuccessorRing) -> String {
+        ring.after(1, 8).iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn sample_moves() {
+        let mut ring = SuccessorRing::from_labels(&SAMPLE, None);
+        ring.run(10);
+        assert_eq!(order(&ring), "92658374");
+        ring.run(90);
+        assert_eq!(order(&ring), "67384529");
+    }
+
+    #[test]
+    #[ignore = "ten million moves"]
+    fn million_cups() {
+        let mut ring = SuccessorRing::from_labels(&SAMPLE, Some(1_000_000));
+        ring.run(10_000_000);
+        let product: u64 = ring.after(1, 2).iter().map(|&l| l as u64).product();
+        assert_eq!(product, 149245887792);
+    }
+}
@@ -0,0 +1,119 @@
+/// The differences between adjacent values, one shorter than the input. Panics if a difference
+/// overflows `i64`, see [`nth_diffs_wide`] for inputs close to the limits.
+pub fn diffs(values: &[i64]) -> Vec<i64> {
+    values
+        .windows(2)
+        .map(|w| w[1].checked_sub(w[0]).expect("difference overflows i64"))
+        .collect()
+}
+
+/// Applies [`diffs`] `n` times.
+pub fn nth_diffs(values: &[i64], n: usize) -> Vec<i64> {
+    (0..n).fold(values.to_vec(), |values, _| diffs(&values))
+}
+
+/// Like [`nth_diffs`] but computed in `i128`, so high order differences of large values don't
+/// overflow.
+pub fn nth_diffs_wide(values: &[i64], n: usize) -> Vec<i128> {
+    (0..n).fold(values.iter().map(|&v| v as i128).collect(), |values, _| {
+        values.windows(2).map(|w| w[1] - w[0]).collect()
+    })
+}
+
+/// Whether all `d`-th differences are equal. Sequences with at most `d + 1` values trivially
+/// qualify.
+pub fn is_polynomial_of_degree(values: &[i64], d: usize) -> bool {
+    let last = nth_diffs_wide(values, d);
+    last.windows(2).all(|w| w[0] == w[1])
+}
+
+pub fn is_arithmetic(values: &[i64]) -> bool {
+    is_polynomial_of_degree(values, 1)
+}
+
+/// The value at index `n` of the quadratic sequence starting with `samples`, i.e. the one whose
+/// second differences all equal that of the samples. Negative `n` extrapolates backwards.
+pub fn quadratic_at(samples: [i64; 3], n: i64) -> i128 {
+    let [a, b, c] = samples.map(|v| v as i128);
+    let (d1, d2) = (b - a, c - 2 * b + a);
+    let n = n as i128;
+    a + n * d1 + n * (n - 1) / 2 * d2
+}
+
+/// Running sums, the inverse of [`diffs`] when the first value is kept:
+/// `cumsum(&[values[0], diffs(values)...]) == values`.
+pub fn cumsum(values: &[i64]) -> Vec<i64> {
+    values
+        .iter()
+        .scan(0i64, |sum, &v| {
+            *sum += v;
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// Adjacent pairs `(a, b), (b, c), ...` of any iterator.
+pub fn pairwise<T: Clone>(it: impl IntoIterator<Item = T>) -> impl Iterator<Item = (T, T)> {
+    let mut it = it.into_iter();
+    let mut prev = it.next();
+    std::iter::from_fn(move || {
+        let next = it.next()?;
+        Some((prev.replace(next.clone())?, next))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extrapolates by summing the last value of every difference level until all are zero.
+    fn next_value(values: &[i64]) -> i64 {
+        let mut level = values.to_vec();
+        let mut next = 0;
+        while level.iter().any(|&v| v != 0) {
+            next += level[level.len() - 1];
+            level = diffs(&level);
+        }
+        next
+    }
+
+    #[test]
+    fn oasis() {
+        let report = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+        let histories: Vec<Vec<i64>> = report.lines().map(crate::ints).collect();
+        let next: i64 = histories.iter().map(|h| next_value(h)).sum();
+        let prev: i64 = histories
+            .iter()
+            .map(|h| next_value(&h.iter().rev().copied().collect::<Vec<_>>()))
+            .sum();
+        assert_eq!((next, prev), (114, 2));
+        assert!(is_arithmetic(&histories[0]));
+        assert!(!is_arithmetic(&histories[1]));
+        assert!(is_polynomial_of_degree(&histories[1], 2));
+        assert!(is_polynomial_of_degree(&histories[2], 3));
+        assert!(!is_polynomial_of_degree(&histories[2], 2));
+    }
+
+    #[test]
+    fn polynomials() {
+        let squares: Vec<i64> = (0..10).map(|x| 3 * x * x - 5 * x + 7).collect();
+        assert!(is_polynomial_of_degree(&squares, 2));
+        assert_eq!(nth_diffs(&squares, 2), [6; 8]);
+        let cumulative = cumsum(&[&squares[..1], &diffs(&squares)].concat());
+        assert_eq!(cumulative, squares);
+        let large = [i64::MIN, i64::MAX, i64::MIN];
+        assert_eq!(nth_diffs_wide(&large, 2), [-4 * (i64::MAX as i128) - 2]);
+        let samples = [squares[0], squares[1], squares[2]];
+        assert_eq!(quadratic_at(samples, 9), squares[9] as i128);
+        assert_eq!(quadratic_at(samples, -1), 15);
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(
+            pairwise([1, 2, 3, 4]).collect::<Vec<_>>(),
+            [(1, 2), (2, 3), (3, 4)]
+        );
+        assert_eq!(pairwise([1]).count(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+struct Scheduled<E> {
+    time: u64,
+    seq: u64,
+    event: E,
+}
+impl<E> PartialEq for Scheduled<E> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.time, self.seq) == (other.time, other.seq)
+    }
+}
+impl<E> Eq for Scheduled<E> {}
+impl<E> PartialOrd for Scheduled<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<E> Ord for Scheduled<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+/// Events ordered by their timestamp for discrete event simulation. Events with equal
+/// timestamps come out in the order they were scheduled.
+pub struct EventQueue<E> {
+    heap: BinaryHeap<Reverse<Scheduled<E>>>,
+    next_seq: u64,
+    now: u64,
+}
+impl<E> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            next_seq: 0,
+            now: 0,
+        }
+    }
+}
+impl<E> EventQueue<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The time of the most recently taken event.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn schedule(&mut self, time: u64, event: E) {
+        assert!(
+            time >= self.now,
+            "can't schedule at {time}, already at {}",
+            self.now
+        );
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.heap.push(Reverse(Scheduled { time, seq, event }));
+    }
+
+    /// Schedules the event `dt` after the current time.
+    pub fn schedule_relative(&mut self, dt: u64, event: E) {
+        self.schedule(self.now + dt, event);
+    }
+
+    pub fn peek_time(&self) -> Option<u64> {
+        self.heap.peek().map(|Reverse(s)| s.time)
+    }
+
+    /// Takes the earliest event and advances the current time to it.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(u64, E)> {
+        let Reverse(Scheduled { time, event, .. }) = self.heap.pop()?;
+        self.now = time;
+        Some((time, event))
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Handles events in order until the queue is empty or the next event is later than
+    /// `until`. Handlers can schedule further events. Returns the number of handled events.
+    pub fn run(&mut self, until: Option<u64>, mut handle: impl FnMut(u64, E, &mut Self)) -> usize {
+        let mut handled = 0;
+        while self
+            .peek_time()
+            .is_some_and(|t| until.is_none_or(|until| t <= until))
+        {
+            let (time, event) = self.next().unwrap();
+            handle(time, event, self);
+            handled += 1;
+        }
+        handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tick {
+        Fast,
+        Slow,
+    }
+
+    #[test]
+    fn interleaved_processes() {
+        let mut queue = EventQueue::new();
+        queue.schedule(0, Tick::Slow);
+        queue.schedule(0, Tick::Fast);
+        let mut log = Vec::new();
+        let handled = queue.run(Some(6), |t, tick, queue| {
+            log.push((t, tick));
+            match tick {
+                Tick::Fast => queue.schedule_relative(2, Tick::Fast),
+                Tick::Slow => queue.schedule_relative(3, Tick::Slow),
+            }
+        });
+        // ties keep scheduling order, at 6 the slow tick was scheduled first (at 3, fast at 4)
+        assert_eq!(
+            log,
+            [
+                (0, Tick::Slow),
+                (0, Tick::Fast),
+                (2, Tick::Fast),
+                (3, Tick::Slow),
+                (4, Tick::Fast),
+                (6, Tick::Slow),
+                (6, Tick::Fast),
+            ]
+        );
+        assert_eq!(handled, 7);
+        assert_eq!(queue.now(), 6);
+        assert_eq!(queue.peek_time(), Some(8));
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn runs_until_empty() {
+        let mut queue = EventQueue::new();
+        for i in (0..5).rev() {
+            queue.schedule(i * 10, i);
+        }
+        let mut seen = Vec::new();
+        queue.run(None, |_, i, _| seen.push(i));
+        assert_eq!(seen, [0, 1, 2, 3, 4]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.next(), None);
+    }
+}
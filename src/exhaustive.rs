@@ -0,0 +1,289 @@
+use std::ops::Range;
+
+use crate::parallel_chunks;
+
+/// Tries every `(a, b)` in row-major order, `a` in the outer loop, and returns the first
+/// result of `f` that is `Some`.
+pub fn grid_search2<R>(
+    ra: Range<i64>,
+    rb: Range<i64>,
+    mut f: impl FnMut(i64, i64) -> Option<R>,
+) -> Option<R> {
+    ra.into_iter()
+        .find_map(|a| rb.clone().find_map(|b| f(a, b)))
+}
+
+/// Like [`grid_search2`] for three ranges, in lexicographic order of `(a, b, c)`.
+pub fn grid_search3<R>(
+    ra: Range<i64>,
+    rb: Range<i64>,
+    rc: Range<i64>,
+    mut f: impl FnMut(i64, i64, i64) -> Option<R>,
+) -> Option<R> {
+    grid_search2(ra, rb, |a, b| rc.clone().find_map(|c| f(a, b, c)))
+}
+
+/// The `(a, b)` with the smallest key among those where `key` isn't `None`, together with the
+/// key. Equal keys are won by the lexicographically smallest tuple.
+pub fn grid_search_min_by_key<K: Ord>(
+    ra: Range<i64>,
+    rb: Range<i64>,
+    mut key: impl FnMut(i64, i64) -> Option<K>,
+) -> Option<((i64, i64), K)> {
+    let mut best: Option<((i64, i64), K)> = None;
+    for a in ra {
+        for b in rb.clone() {
+            if let Some(k) = key(a, b) {
+                if best.as_ref().is_none_or(|(_, best)| k < *best) {
+                    best = Some(((a, b), k));
+                }
+            }
+        }
+    }
+    best
+}
+
+/// [`grid_search2`] with the rows of `a` spread over `threads` threads, see
+/// [`parallel_chunks`]. Returns the same result as the sequential search.
+pub fn par_grid_search2<R: Send>(
+    ra: Range<i64>,
+    rb: Range<i64>,
+    threads: usize,
+    f: impl Fn(i64, i64) -> Option<R> + Sync,
+) -> Option<R> {
+    let rows = ra.end.saturating_sub(ra.start).max(0) as u64;
+    parallel_chunks(0..rows, threads, |chunk| {
+        let (start, end) = (ra.start + chunk.start as i64, ra.start + chunk.end as i64);
+        grid_search2(start..end, rb.clone(), &f)
+    })
+}
+
+struct Backtrack<'a, T, F> {
+    choices: &'a [Vec<T>],
+    prune: F,
+    allowed: fn(&[T], &T) -> bool,
+    /// The index of the next choice to try at each depth up to the current one.
+    next: Vec<usize>,
+    prefix: Vec<T>,
+    done: bool,
+}
+
+impl<T: Clone, F: FnMut(&[T]) -> bool> Iterator for Backtrack<'_, T, F> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        while !self.done {
+            let depth = self.prefix.len();
+            if depth == self.choices.len() {
+                let assignment = self.prefix.clone();
+                self.prefix.pop();
+                self.next.pop();
+                self.done = depth == 0;
+                return Some(assignment);
+            }
+            let i = self.next[depth];
+            if i == self.choices[depth].len() {
+                self.done = depth == 0;
+                self.prefix.pop();
+                self.next.pop();
+                continue;
+            }
+            self.next[depth] += 1;
+            let choice = &self.choices[depth][i];
+            if !(self.allowed)(&self.prefix, choice) {
+                continue;
+            }
+            self.prefix.push(choice.clone());
+            if (self.prune)(&self.prefix) {
+                self.prefix.pop();
+            } else {
+                self.next.push(0);
+            }
+        }
+        None
+    }
+}
+
+/// Lazily yields every assignment taking one of `choices[i]` at position `i`, in lexicographic
+/// order of the choice indices. Every prefix, the complete assignments included, is passed to
+/// `prune` first and skipped with everything extending it if that returns true.
+pub fn backtrack<'a, T: Clone>(
+    choices: &'a [Vec<T>],
+    prune: impl FnMut(&[T]) -> bool + 'a,
+) -> impl Iterator<Item = Vec<T>> + 'a {
+    Backtrack {
+        choices,
+        prune,
+        allowed: |_, _| true,
+        next: vec![0],
+        prefix: Vec::new(),
+        done: false,
+    }
+}
+
+/// Like [`backtrack`], but a value can only be used once per assignment. With the same
+/// choices at every position, these are the permutations.
+pub fn backtrack_distinct<'a, T: Clone + PartialEq>(
+    choices: &'a [Vec<T>],
+    prune: impl FnMut(&[T]) -> bool + 'a,
+) -> impl Iterator<Item = Vec<T>> + 'a {
+    Backtrack {
+        choices,
+        prune,
+        allowed: |prefix, choice| !prefix.contains(choice),
+        next: vec![0],
+        prefix: Vec::new(),
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_circuits() {
+        let mut calls = 0;
+        let found = grid_search2(0..100, 0..100, |a, b| {
+            calls += 1;
+            (a * 3 + b * 7 == 23).then_some((a, b))
+        });
+        // rows 0 to 2 have no solution, (3, 2) is the first one
+        assert_eq!(found, Some((3, 2)));
+        assert_eq!(calls, 303);
+        let mut calls = 0;
+        let found = grid_search3(0..10, 0..10, 0..10, |a, b, c| {
+            calls += 1;
+            (a + b + c == 2).then_some((a, b, c))
+        });
+        assert_eq!(found, Some((0, 0, 2)));
+        assert_eq!(calls, 3);
+        assert_eq!(grid_search2(0..0, 0..5, |_, _| Some(())), None);
+    }
+
+    #[test]
+    fn min_ties() {
+        // the cheapest button presses reaching x = 8 with a = +2 and b = +1, cost 3a + b
+        let best =
+            grid_search_min_by_key(0..10, 0..10, |a, b| (2 * a + b == 8).then_some(3 * a + b));
+        assert_eq!(best, Some(((0, 8), 8)));
+        let tie = grid_search_min_by_key(-3..4, -3..4, |a, b| Some(a.abs() + b.abs() / 3));
+        assert_eq!(tie, Some(((0, -2), 0)));
+        assert_eq!(grid_search_min_by_key(0..3, 0..3, |_, _| None::<u8>), None);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let f = |a: i64, b: i64| ((a * 31 + b * 17) % 1009 == 5).then_some((a, b));
+        for threads in [1, 3, 8] {
+            assert_eq!(
+                par_grid_search2(-50..300, 0..200, threads, f),
+                grid_search2(-50..300, 0..200, f)
+            );
+        }
+        assert_eq!(par_grid_search2(5..5, 0..200, 4, f), None);
+    }
+
+    #[test]
+    fn backtrack_counts() {
+        let choices = [vec![1, 2], vec![3, 4, 5], vec![6, 7, 8, 9]];
+        let all: Vec<Vec<i32>> = backtrack(&choices, |_| false).collect();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all[0], [1, 3, 6]);
+        assert_eq!(all[23], [2, 5, 9]);
+        let digits = vec![(0..5).collect::<Vec<u8>>(); 5];
+        assert_eq!(backtrack_distinct(&digits, |_| false).count(), 120);
+        let overlapping = [vec![1, 2], vec![1, 2, 3], vec![2, 3]];
+        let distinct: Vec<Vec<i32>> = backtrack_distinct(&overlapping, |_| false).collect();
+        assert_eq!(distinct, [[1, 2, 3], [1, 3, 2], [2, 1, 3]]);
+        assert_eq!(backtrack::<u8>(&[], |_| false).count(), 1);
+        assert_eq!(backtrack(&[vec![1], vec![]], |_| false).count(), 0);
+    }
+
+    #[test]
+    fn backtrack_prunes_subtrees() {
+        let choices = vec![(0..5).collect::<Vec<u32>>(); 4];
+        let mut pruned: Vec<Vec<u32>> = Vec::new();
+        let mut calls = 0;
+        let found: Vec<Vec<u32>> = backtrack(&choices, |prefix| {
+            calls += 1;
+            assert!(!pruned.iter().any(|p| prefix.starts_with(p)));
+            let cut = prefix.iter().sum::<u32>() > 3;
+            if cut {
+                pruned.push(prefix.to_vec());
+            }
+            cut
+        })
+        .collect();
+        let expected: Vec<Vec<u32>> = backtrack(&choices, |_| false)
+            .filter(|a| a.iter().sum::<u32>() <= 3)
+            .collect();
+        assert_eq!(found, expected);
+        assert!(calls < 5 + 25 + 125 + 625);
+
+        // dropping the iterator stops the search
+        let mut calls = 0;
+        let first = backtrack(&choices, |_| {
+            calls += 1;
+            false
+        })
+        .next();
+        assert_eq!(first, Some(vec![0; 4]));
+        assert_eq!(calls, 4);
+    }
+
+    /// Runs an Intcode program that only adds, multiplies, reads and writes.
+    fn intcode(program: &[i64], mut input: impl Iterator<Item = i64>) -> Vec<i64> {
+        let mut mem = program.to_vec();
+        let mut output = Vec::new();
+        let mut ip = 0;
+        loop {
+            let op = mem[ip];
+            let arg = |mem: &[i64], n: u32| {
+                let raw = mem[ip + n as usize];
+                if op / 10i64.pow(n + 1) % 10 == 1 {
+                    raw
+                } else {
+                    mem[raw as usize]
+                }
+            };
+            match op % 100 {
+                1 | 2 => {
+                    let (a, b) = (arg(&mem, 1), arg(&mem, 2));
+                    let to = mem[ip + 3] as usize;
+                    mem[to] = if op % 100 == 1 { a + b } else { a * b };
+                    ip += 4;
+                }
+                3 => {
+                    let to = mem[ip + 1] as usize;
+                    mem[to] = input.next().unwrap();
+                    ip += 2;
+                }
+                4 => {
+                    output.push(arg(&mem, 1));
+                    ip += 2;
+                }
+                99 => return output,
+                _ => panic!("unknown opcode {op}"),
+            }
+        }
+    }
+
+    #[test]
+    fn amplifier_phases() {
+        let program = [
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+        let phases = vec![(0..5).collect::<Vec<i64>>(); 5];
+        let best = backtrack_distinct(&phases, |_| false)
+            .map(|settings| {
+                let thrust = settings.iter().fold(0, |signal, &phase| {
+                    intcode(&program, [phase, signal].into_iter())[0]
+                });
+                (thrust, settings)
+            })
+            .max()
+            .unwrap();
+        assert_eq!(best, (43210, vec![4, 3, 2, 1, 0]));
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Sent with every request so that the Advent of Code maintainers know where traffic comes from.
+pub const USER_AGENT: &str = concat!(
+    "aoch/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/LinusDikomey/aoch)"
+);
+
+/// Downloads a page with the session cookie. Implemented over HTTP by [`HttpSource`] with the
+/// `fetch` feature, tests can plug in their own.
+pub trait InputSource {
+    fn get(&self, url: &str, session: &str) -> io::Result<String>;
+}
+
+/// The real source using `ureq`.
+#[cfg(feature = "fetch")]
+pub struct HttpSource;
+#[cfg(feature = "fetch")]
+impl InputSource for HttpSource {
+    fn get(&self, url: &str, session: &str) -> io::Result<String> {
+        let request = ureq::get(url)
+            .set("User-Agent", USER_AGENT)
+            .set("Cookie", &format!("session={session}"));
+        match request.call() {
+            Ok(response) => response.into_string(),
+            Err(ureq::Error::Status(code, response)) => Err(status_error(
+                code,
+                &response.into_string().unwrap_or_default(),
+            )),
+            Err(err) => Err(io::Error::other(err)),
+        }
+    }
+}
+
+/// An error for a failed request that includes the response body, since that usually says
+/// what went wrong.
+pub fn status_error(code: u16, body: &str) -> io::Error {
+    let hint = if code == 400 {
+        " (the session cookie is probably stale)"
+    } else {
+        ""
+    };
+    io::Error::other(format!("HTTP {code}{hint}: {}", body.trim()))
+}
+
+/// The session cookie from `AOC_SESSION` or `~/.config/aoch/session`.
+pub fn session() -> io::Result<String> {
+    if let Ok(session) = std::env::var("AOC_SESSION") {
+        return Ok(session.trim().to_owned());
+    }
+    let path = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(".config/aoch/session"))
+        .filter(|path| path.exists())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no session cookie, set AOC_SESSION or write it to ~/.config/aoch/session",
+            )
+        })?;
+    Ok(fs::read_to_string(path)?.trim().to_owned())
+}
+
+/// The unix time at which the puzzle unlocks, midnight in UTC-5.
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    // days since 1970-01-01 of December `day`, from Howard Hinnant's days_from_civil
+    let (y, m, d) = (year as i64, 12, day as i64);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (m - 3) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    (days * 86400 + 5 * 3600) as u64
+}
+
+/// Returns `dir/dayNN.txt` if it exists, otherwise downloads the input from `source` and saves
+/// it there. Days that aren't unlocked at `now` are refused so that no error page gets cached.
+pub fn fetch_input_with(
+    source: &impl InputSource,
+    session: impl FnOnce() -> io::Result<String>,
+    dir: &Path,
+    year: u16,
+    day: u8,
+    now: SystemTime,
+) -> io::Result<String> {
+    if !(1..=25).contains(&day) || year < 2015 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("there is no puzzle for {year} day {day}"),
+        ));
+    }
+    let path = dir.join(format!("day{day:02}.txt"));
+    if let Ok(input) = fs::read_to_string(&path) {
+        return Ok(input);
+    }
+    let now = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let unlock = unlock_time(year, day);
+    if now < unlock {
+        return Err(io::Error::other(format!(
+            "{year} day {day} unlocks in {}s",
+            unlock - now
+        )));
+    }
+    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+    let input = source.get(&url, &session()?)?;
+    fs::create_dir_all(dir)?;
+    fs::write(&path, &input)?;
+    Ok(input)
+}
+
+/// Downloads the input of a day into `input/dayNN.txt` or reads it from there if it was
+/// downloaded before.
+#[cfg(feature = "fetch")]
+pub fn fetch_input(year: u16, day: u8) -> io::Result<String> {
+    fetch_input_with(
+        &HttpSource,
+        session,
+        Path::new("input"),
+        year,
+        day,
+        SystemTime::now(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, time::Duration};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Mock {
+        requests: RefCell<Vec<String>>,
+        status: Option<(u16, &'static str)>,
+    }
+    impl InputSource for Mock {
+        fn get(&self, url: &str, session: &str) -> io::Result<String> {
+            assert_eq!(session, "abc");
+            self.requests.borrow_mut().push(url.to_owned());
+            match self.status {
+                Some((code, body)) => Err(status_error(code, body)),
+                None => Ok("1\n2\n".to_owned()),
+            }
+        }
+    }
+
+    fn at(time: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(time)
+    }
+
+    #[test]
+    fn unlock_times() {
+        // 2023-12-01T05:00:00Z
+        assert_eq!(unlock_time(2023, 1), 1701406800);
+        assert_eq!(unlock_time(2024, 25), 1735102800);
+    }
+
+    #[test]
+    fn caches_downloads() {
+        let dir = std::env::temp_dir().join(format!("aoch-fetch-{}", std::process::id()));
+        let mock = Mock::default();
+        let session = || Ok("abc".to_owned());
+        let fetch = |day, now| fetch_input_with(&mock, session, &dir, 2023, day, now);
+        let err = fetch(2, at(unlock_time(2023, 2) - 1)).unwrap_err();
+        assert_eq!(err.to_string(), "2023 day 2 unlocks in 1s");
+        assert!(fetch(26, at(u32::MAX as u64)).is_err());
+        assert!(mock.requests.borrow().is_empty());
+
+        assert_eq!(fetch(2, at(unlock_time(2023, 2))).unwrap(), "1\n2\n");
+        assert_eq!(fetch(2, at(unlock_time(2023, 2))).unwrap(), "1\n2\n");
+        assert_eq!(
+            *mock.requests.borrow(),
+            ["https://adventofcode.com/2023/day/2/input"]
+        );
+        assert_eq!(fs::read_to_string(dir.join("day02.txt")).unwrap(), "1\n2\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let dir = std::env::temp_dir().join(format!("aoch-fetch-err-{}", std::process::id()));
+        let mock = Mock {
+            status: Some((
+                400,
+                "Puzzle inputs differ by user.  Please log in to get yours.\n",
+            )),
+            ..Mock::default()
+        };
+        let err = fetch_input_with(
+            &mock,
+            || Ok("abc".to_owned()),
+            &dir,
+            2022,
+            1,
+            at(u32::MAX as u64),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "HTTP 400 (the session cookie is probably stale): Puzzle inputs differ by user.  \
+             Please log in to get yours."
+        );
+        assert!(!dir.join("day01.txt").exists());
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::math::Field;
+
+/// An exact fraction, always reduced and with a positive denominator, so equal values have
+/// equal representations. Arithmetic panics with the operands when an `i128` overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frac {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Frac {
+    pub const ZERO: Frac = Frac { num: 0, den: 1 };
+    pub const ONE: Frac = Frac { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator in {num}/{den}");
+        let g = gcd(num, den);
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            let negated = num.checked_neg().zip(den.checked_neg());
+            let (num, den) = negated.unwrap_or_else(|| panic!("overflow negating {num}/{den}"));
+            Self { num, den }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn from_int(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+
+    pub fn num(self) -> i128 {
+        self.num
+    }
+
+    pub fn den(self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    /// The value as an `i64` if it is an integer in its range.
+    pub fn to_i64_exact(self) -> Option<i64> {
+        self.is_integer()
+            .then(|| i64::try_from(self.num).ok())
+            .flatten()
+    }
+
+    pub fn floor(self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn ceil(self) -> i128 {
+        self.floor() + (self.num.rem_euclid(self.den) != 0) as i128
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    #[must_use]
+    pub fn abs(self) -> Self {
+        if self.num < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl From<i64> for Frac {
+    fn from(n: i64) -> Self {
+        Self::from_int(n as i128)
+    }
+}
+
+fn checked(result: Option<i128>, a: Frac, op: &str, b: Frac) -> i128 {
+    result.unwrap_or_else(|| panic!("overflow in ({a}) {op} ({b})"))
+}
+
+impl Add for Frac {
+    type Output = Frac;
+
+    fn add(self, other: Frac) -> Frac {
+        let g = gcd(self.den, other.den);
+        let (a, b) = (other.den / g, self.den / g);
+        let left = checked(self.num.checked_mul(a), self, "+", other);
+        let right = checked(other.num.checked_mul(b), self, "+", other);
+        let num = checked(left.checked_add(right), self, "+", other);
+        Frac::new(num, checked(self.den.checked_mul(a), self, "+", other))
+    }
+}
+
+impl Neg for Frac {
+    type Output = Frac;
+
+    fn neg(self) -> Frac {
+        let num = self.num.checked_neg();
+        Frac {
+            num: num.unwrap_or_else(|| panic!("overflow in -({self})")),
+            den: self.den,
+        }
+    }
+}
+
+impl Sub for Frac {
+    type Output = Frac;
+
+    fn sub(self, other: Frac) -> Frac {
+        self + -other
+    }
+}
+
+impl Mul for Frac {
+    type Output = Frac;
+
+    fn mul(self, other: Frac) -> Frac {
+        // reducing crosswise first keeps the products as small as the result allows
+        let (g1, g2) = (
+            gcd(self.num, other.den).max(1),
+            gcd(other.num, self.den).max(1),
+        );
+        let num = (self.num / g1).checked_mul(other.num / g2);
+        let den = (self.den / g2).checked_mul(other.den / g1);
+        Frac::new(
+            checked(num, self, "*", other),
+            checked(den, self, "*", other),
+        )
+    }
+}
+
+impl Div for Frac {
+    type Output = Frac;
+
+    fn div(self, other: Frac) -> Frac {
+        assert!(other.num != 0, "division by zero in ({self}) / ({other})");
+        self * Frac::new(other.den, other.num)
+    }
+}
+
+impl Ord for Frac {
+    /// Compares by continued fraction expansion, which can't overflow.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut a, mut b, mut c, mut d) = (self.num, self.den, other.num, other.den);
+        let mut reversed = false;
+        loop {
+            let (qa, qc) = (a.div_euclid(b), c.div_euclid(d));
+            let (ra, rc) = (a.rem_euclid(b), c.rem_euclid(d));
+            let ord = match (qa.cmp(&qc), ra, rc) {
+                (Ordering::Equal, 0, 0) => Ordering::Equal,
+                (Ordering::Equal, 0, _) => Ordering::Less,
+                (Ordering::Equal, _, 0) => Ordering::Greater,
+                (Ordering::Equal, _, _) => {
+                    // ra/b < rc/d exactly when b/ra > d/rc
+                    (a, b, c, d) = (b, ra, d, rc);
+                    reversed = !reversed;
+                    continue;
+                }
+                (ord, _, _) => ord,
+            };
+            return if reversed { ord.reverse() } else { ord };
+        }
+    }
+}
+
+impl PartialOrd for Frac {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// `n` for integers and `n/d` otherwise.
+impl Display for Frac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Field for Frac {
+    fn zero() -> Self {
+        Frac::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.to_f64().abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::solve_nxn;
+
+    fn f(num: i128, den: i128) -> Frac {
+        Frac::new(num, den)
+    }
+
+    #[test]
+    fn reduced() {
+        assert_eq!(f(6, -8), f(-3, 4));
+        assert_eq!((f(6, -8).num(), f(6, -8).den()), (-3, 4));
+        assert_eq!(f(0, -5), Frac::ZERO);
+        assert_eq!(f(1, 6) + f(1, 3), f(1, 2));
+        assert_eq!(f(1, 6) - f(1, 2), f(-1, 3));
+        assert_eq!(f(2, 3) * f(9, 4), f(3, 2));
+        assert_eq!(f(2, 3) / f(-4, 9), f(-3, 2));
+        assert_eq!((f(7, 2).floor(), f(7, 2).ceil()), (3, 4));
+        assert_eq!((f(-7, 2).floor(), f(-7, 2).ceil()), (-4, -3));
+        assert_eq!((f(-4, 2).floor(), f(-4, 2).ceil()), (-2, -2));
+        assert_eq!(f(10, 5).to_i64_exact(), Some(2));
+        assert_eq!(f(1, 2).to_i64_exact(), None);
+        assert_eq!(Frac::from_int(1 << 70).to_i64_exact(), None);
+        assert_eq!(f(-3, 4).to_string(), "-3/4");
+        assert_eq!(Frac::from(5).to_string(), "5");
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values = vec![
+            f(1, 3),
+            f(-1, 2),
+            Frac::ZERO,
+            f(-1, 3),
+            f(7, 3),
+            f(2, 1),
+            f(-7, 3),
+            f(1, 2),
+            f(-2, 1),
+        ];
+        values.sort();
+        let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            ["-7/3", "-2", "-1/2", "-1/3", "0", "1/3", "1/2", "2", "7/3"]
+        );
+        // would overflow when cross multiplying
+        let big = i128::MAX;
+        assert!(f(big - 1, big) < f(big, big - 1));
+        assert!(f(-(big - 1), big) > f(-big, big - 1));
+        assert!(f(big - 2, big - 1) < f(big - 1, big));
+        assert_eq!(f(big, 3).cmp(&f(big, 3)), Ordering::Equal);
+    }
+
+    #[test]
+    fn exact_solve() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3, eliminating goes through halves
+        let a: Vec<Vec<Frac>> = [[2, 1, -1], [-3, -1, 2], [-2, 1, 2]]
+            .iter()
+            .map(|row| row.iter().map(|&n| Frac::from(n)).collect())
+            .collect();
+        let b = [8, -11, -3].map(Frac::from);
+        let x = solve_nxn(&a, &b).unwrap();
+        assert_eq!(x, [2, 3, -1].map(Frac::from));
+        let x = solve_nxn(
+            &[vec![f(1, 3), f(1, 2)], vec![f(1, 5), f(1, 7)]],
+            &[Frac::ONE; 2],
+        );
+        assert_eq!(x, Some(vec![f(75, 11), f(-28, 11)]));
+        let singular = [vec![Frac::ONE, f(2, 1)], vec![f(1, 2), Frac::ONE]];
+        assert_eq!(solve_nxn(&singular, &[Frac::ONE, Frac::ONE]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in (170141183460469231731687303715884105727) + (1)")]
+    fn overflow() {
+        let _ = Frac::from_int(i128::MAX) + Frac::ONE;
+    }
+}
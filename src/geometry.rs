@@ -0,0 +1,565 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use vecm::{PolyVec2, PolyVec3, Vec2i, Vec3i};
+
+use crate::{approx_eq, FloatVec2};
+
+/// The z component of the cross product of two 2D vectors, exact for all `i64` inputs.
+pub fn cross2(a: PolyVec2<i64>, b: PolyVec2<i64>) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
+
+/// The positions from `a` to `b`, both included, on a horizontal, vertical or 45° diagonal
+/// line. Panics for any other direction.
+pub fn line_between(a: Vec2i, b: Vec2i) -> impl Iterator<Item = Vec2i> {
+    let d = b - a;
+    assert!(
+        d.x == 0 || d.y == 0 || d.x.abs() == d.y.abs(),
+        "{a:?} to {b:?} isn't horizontal, vertical or diagonal"
+    );
+    (0..=d.x.abs().max(d.y.abs())).map(move |i| a + Vec2i::new(d.x.signum() * i, d.y.signum() * i))
+}
+
+/// How close to parallel, as the sine of the angle between them, two lines have to be for
+/// [`ray_intersection_2d`] to treat them as parallel.
+pub const PARALLEL_SIN: f64 = 1e-12;
+
+/// Intersects the lines `p1 + t * v1` and `p2 + s * v2`. Returns `t`, `s` and the intersection
+/// point, or `None` if the lines are parallel up to [`PARALLEL_SIN`]. The tolerance is relative
+/// to the lengths of the directions, so scaling a velocity doesn't change the outcome.
+pub fn ray_intersection_2d(
+    p1: [f64; 2],
+    v1: [f64; 2],
+    p2: [f64; 2],
+    v2: [f64; 2],
+) -> Option<(f64, f64, [f64; 2])> {
+    let denom = v1.cross_z(v2);
+    if approx_eq(denom, 0.0, 0.0, PARALLEL_SIN * v1.length() * v2.length()) {
+        return None;
+    }
+    let d = [p2[0] - p1[0], p2[1] - p1[1]];
+    let t = d.cross_z(v2) / denom;
+    let s = d.cross_z(v1) / denom;
+    Some((t, s, [p1[0] + t * v1[0], p1[1] + t * v1[1]]))
+}
+
+/// Counts the pairs of stones, given as position and velocity, whose paths in the xy plane
+/// cross at a point with both x and y in `region` and at non-negative times for both stones.
+/// Parallel paths never count. Times are checked exactly, only the region test uses floats.
+pub fn future_intersections_in_region(
+    stones: &[(PolyVec3<i64>, PolyVec3<i64>)],
+    region: [f64; 2],
+) -> usize {
+    let xy = |v: PolyVec3<i64>| PolyVec2::new(v.x, v.y);
+    let mut count = 0;
+    for (i, &(p1, v1)) in stones.iter().enumerate() {
+        for &(p2, v2) in &stones[i + 1..] {
+            let (v1, v2) = (xy(v1), xy(v2));
+            let denom = cross2(v1, v2);
+            if denom == 0 {
+                continue;
+            }
+            let d = PolyVec2::new(p2.x - p1.x, p2.y - p1.y);
+            let (t, s) = (cross2(d, v2), cross2(d, v1));
+            // t / denom and s / denom have to be non-negative
+            let future = |n: i128| n == 0 || (n < 0) == (denom < 0);
+            if !future(t) || !future(s) {
+                continue;
+            }
+            let at = |p: i64, v: i64| (p as i128 * denom + t * v as i128) as f64 / denom as f64;
+            let point = [at(p1.x, v1.x), at(p1.y, v1.y)];
+            if point.iter().all(|c| (region[0]..=region[1]).contains(c)) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn cross_at(o: Vec2i, a: Vec2i, b: Vec2i) -> i64 {
+    let (a, b) = (a - o, b - o);
+    a.x as i64 * b.y as i64 - a.y as i64 * b.x as i64
+}
+
+/// The corners of the convex hull with Andrew's monotone chain, counterclockwise with +y up
+/// (so clockwise on screen) starting at the smallest `(x, y)`. Points on an edge aren't
+/// corners and are left out. Collinear inputs give their two ends, a single distinct point
+/// gives just that point.
+pub fn convex_hull(points: &[Vec2i]) -> Vec<Vec2i> {
+    let mut points = points.to_vec();
+    points.sort_unstable_by_key(|p| (p.x, p.y));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut hull: Vec<Vec2i> = Vec::with_capacity(points.len() + 1);
+    let push = |hull: &mut Vec<Vec2i>, p: Vec2i, min_len: usize| {
+        while hull.len() >= min_len && cross_at(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    };
+    for &p in &points {
+        push(&mut hull, p, 2);
+    }
+    let lower = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        push(&mut hull, p, lower);
+    }
+    // the lower chain ended on the last point and the upper one on the first
+    hull.pop();
+    hull
+}
+
+/// How [`farthest_pair`] measures distances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// The squared euclidean distance.
+    EuclideanSquared,
+    Manhattan,
+}
+impl Metric {
+    pub fn distance(self, a: Vec2i, b: Vec2i) -> i64 {
+        let (dx, dy) = ((a.x - b.x) as i64, (a.y - b.y) as i64);
+        match self {
+            Self::EuclideanSquared => dx * dx + dy * dy,
+            Self::Manhattan => dx.abs() + dy.abs(),
+        }
+    }
+}
+
+/// Two of the points that are farthest apart and their distance. Euclidean uses rotating
+/// calipers on the convex hull, Manhattan the extremes of `x + y` and `x - y`. Panics for no
+/// points.
+pub fn farthest_pair(points: &[Vec2i], metric: Metric) -> (Vec2i, Vec2i, i64) {
+    assert!(!points.is_empty(), "no points to choose from");
+    let pair = |a: Vec2i, b: Vec2i| (a, b, metric.distance(a, b));
+    if metric == Metric::Manhattan {
+        let extremes = |key: fn(&Vec2i) -> i64| {
+            let min = *points.iter().min_by_key(|p| key(p)).unwrap();
+            let max = *points.iter().max_by_key(|p| key(p)).unwrap();
+            pair(min, max)
+        };
+        let sum = extremes(|p| p.x as i64 + p.y as i64);
+        let diff = extremes(|p| p.x as i64 - p.y as i64);
+        return if sum.2 >= diff.2 { sum } else { diff };
+    }
+    let hull = convex_hull(points);
+    let m = hull.len();
+    if m < 3 {
+        return pair(hull[0], hull[m - 1]);
+    }
+    let mut best = pair(hull[0], hull[1]);
+    let mut j = 1;
+    for i in 0..m {
+        let next = (i + 1) % m;
+        // advance to the corner farthest from the edge i -> next
+        while cross_at(hull[i], hull[next], hull[(j + 1) % m])
+            > cross_at(hull[i], hull[next], hull[j])
+        {
+            j = (j + 1) % m;
+        }
+        for candidate in [pair(hull[i], hull[j]), pair(hull[next], hull[j])] {
+            if candidate.2 > best.2 {
+                best = candidate;
+            }
+        }
+    }
+    best
+}
+
+/// Whether `p` is inside of or on the border of a hull from [`convex_hull`].
+pub fn point_in_convex_hull(hull: &[Vec2i], p: Vec2i) -> bool {
+    match hull {
+        [] => false,
+        [a] => *a == p,
+        [a, b] => {
+            cross_at(*a, *b, p) == 0
+                && (a.x.min(b.x)..=a.x.max(b.x)).contains(&p.x)
+                && (a.y.min(b.y)..=a.y.max(b.y)).contains(&p.y)
+        }
+        _ => (0..hull.len()).all(|i| cross_at(hull[i], hull[(i + 1) % hull.len()], p) >= 0),
+    }
+}
+
+/// Twice the area enclosed by the polygon through `points` in either winding order, with the
+/// shoelace formula. The closing edge back to the first point is implied.
+pub fn polygon_double_area(points: &[Vec2i]) -> i64 {
+    let n = points.len();
+    let sum: i128 = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128
+        })
+        .sum();
+    sum.unsigned_abs() as i64
+}
+
+pub fn polygon_area(points: &[Vec2i]) -> f64 {
+    polygon_double_area(points) as f64 / 2.0
+}
+
+/// The number of lattice points on the border of the polygon, which is its length for
+/// horizontal and vertical edges.
+pub fn polygon_perimeter(points: &[Vec2i]) -> i64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let d = points[(i + 1) % n] - points[i];
+            crate::gcd(d.x.unsigned_abs() as u64, d.y.unsigned_abs() as u64) as i64
+        })
+        .sum()
+}
+
+/// The number of lattice points strictly inside of the polygon by Pick's theorem,
+/// `A = i + b/2 - 1`. With the centers of a loop of grid cells as `points`, these are the
+/// cells enclosed by the loop.
+pub fn interior_points(points: &[Vec2i]) -> i64 {
+    (polygon_double_area(points) - polygon_perimeter(points) + 2) / 2
+}
+
+/// An inclusive box of integer points, as its lowest and highest corner.
+type Cuboid = ([i64; 3], [i64; 3]);
+
+/// The manhattan distance from `p` to the nearest point of the box.
+fn distance_to_box(p: [i64; 3], (lo, hi): Cuboid) -> i64 {
+    (0..3)
+        .map(|a| (lo[a] - p[a]).max(p[a] - hi[a]).max(0))
+        .sum()
+}
+
+/// The point covered by the most of the manhattan `spheres` given as center and radius. Of the
+/// points with the most spheres, the one closest to the origin wins, then the smallest by
+/// `(x, y, z)`. Returns the point together with the number of spheres covering it.
+///
+/// Boxes are searched best first by the number of spheres reaching into them, which can only
+/// shrink when a box is split, so the first single point taken from the queue has the most.
+pub fn best_covered_point(spheres: &[(Vec3i, i64)]) -> (Vec3i, usize) {
+    assert!(
+        spheres.iter().all(|&(_, r)| r >= 0),
+        "spheres can't have a negative radius"
+    );
+    let spheres: Vec<([i64; 3], i64)> = spheres
+        .iter()
+        .map(|&(c, r)| ([c.x as i64, c.y as i64, c.z as i64], r))
+        .collect();
+    let covering = |cuboid: Cuboid| {
+        spheres
+            .iter()
+            .filter(|&&(c, r)| distance_to_box(c, cuboid) <= r)
+            .count()
+    };
+    let (min, max) = (i32::MIN as i64, i32::MAX as i64);
+    let mut lo = [0; 3];
+    let mut hi = [0; 3];
+    for a in 0..3 {
+        lo[a] = spheres
+            .iter()
+            .map(|&(c, r)| c[a] - r)
+            .fold(0, i64::min)
+            .max(min);
+        hi[a] = spheres
+            .iter()
+            .map(|&(c, r)| c[a] + r)
+            .fold(0, i64::max)
+            .min(max);
+    }
+    let entry = |cuboid: Cuboid| {
+        let dist = distance_to_box([0; 3], cuboid);
+        (covering(cuboid), Reverse(dist), Reverse(cuboid))
+    };
+    let mut queue = BinaryHeap::from([entry((lo, hi))]);
+    let mut best: Option<(usize, i64, [i64; 3])> = None;
+    while let Some((count, Reverse(dist), Reverse((lo, hi)))) = queue.pop() {
+        if let Some((best_count, best_dist, _)) = best {
+            if (count, dist) != (best_count, best_dist) {
+                break;
+            }
+        }
+        if lo == hi {
+            // boxes left with the same count and distance can still hold a smaller point
+            if best.is_none_or(|(_, _, p)| lo < p) {
+                best = Some((count, dist, lo));
+            }
+            continue;
+        }
+        let halves = |a: usize| {
+            let mid = lo[a] + (hi[a] - lo[a]) / 2;
+            if lo[a] == hi[a] {
+                vec![(lo[a], hi[a])]
+            } else {
+                vec![(lo[a], mid), (mid + 1, hi[a])]
+            }
+        };
+        for &(x0, x1) in &halves(0) {
+            for &(y0, y1) in &halves(1) {
+                for &(z0, z1) in &halves(2) {
+                    queue.push(entry(([x0, y0, z0], [x1, y1, z1])));
+                }
+            }
+        }
+    }
+    let (count, _, [x, y, z]) = best.unwrap();
+    (Vec3i::new(x as i32, y as i32, z as i32), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::PolyVec3;
+
+    use super::*;
+
+    #[test]
+    fn hailstones() {
+        let stones: Vec<(PolyVec3<i64>, PolyVec3<i64>)> = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3"
+            .lines()
+            .map(|line| {
+                let n = crate::ints(&line.replace([',', '@'], " "));
+                (
+                    PolyVec3::new(n[0], n[1], n[2]),
+                    PolyVec3::new(n[3], n[4], n[5]),
+                )
+            })
+            .collect();
+        assert_eq!(future_intersections_in_region(&stones, [7.0, 27.0]), 2);
+    }
+
+    #[test]
+    fn rays() {
+        let (t, s, point) =
+            ray_intersection_2d([19.0, 13.0], [-2.0, 1.0], [18.0, 19.0], [-1.0, -1.0]).unwrap();
+        assert!((point[0] - 14.333).abs() < 1e-3 && (point[1] - 15.333).abs() < 1e-3);
+        assert!(t > 0.0 && s > 0.0);
+        // these two paths crossed in the past of the first stone
+        let (t, _, _) =
+            ray_intersection_2d([19.0, 13.0], [-2.0, 1.0], [20.0, 19.0], [1.0, -5.0]).unwrap();
+        assert!(t < 0.0);
+        assert_eq!(
+            ray_intersection_2d([18.0, 19.0], [-1.0, -1.0], [20.0, 25.0], [-2.0, -2.0]),
+            None
+        );
+        // nearly parallel paths are parallel however fast either stone moves
+        let (p1, p2) = ([0.0, 0.0], [5.0, -3.0]);
+        for scale in [1e-6, 1.0, 1e6, 1e12] {
+            let v2 = [scale, scale * (1.0 + 1e-13)];
+            assert_eq!(ray_intersection_2d(p1, [1.0, 1.0], p2, v2), None);
+            assert_eq!(ray_intersection_2d(p2, v2, p1, [-3.0, -3.0]), None);
+            let v2 = [scale, scale * (1.0 + 1e-6)];
+            let (t, _, _) = ray_intersection_2d(p1, [1.0, 1.0], p2, v2).unwrap();
+            crate::assert_approx_eq!(t, 8e6 + 5.0, 1e-6, 0.0);
+        }
+        assert_eq!(
+            cross2(PolyVec2::new(i64::MAX, 0), PolyVec2::new(0, i64::MAX)),
+            (i64::MAX as i128).pow(2)
+        );
+    }
+
+    #[test]
+    fn points_on_lines() {
+        let points = |a: (i32, i32), b: (i32, i32)| {
+            line_between(Vec2i::new(a.0, a.1), Vec2i::new(b.0, b.1))
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(points((1, 1), (1, 3)), [(1, 1), (1, 2), (1, 3)]);
+        assert_eq!(points((9, 7), (7, 9)), [(9, 7), (8, 8), (7, 9)]);
+        assert_eq!(points((2, 2), (2, 2)), [(2, 2)]);
+        assert_eq!(points((3, 0), (0, 0)), [(3, 0), (2, 0), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't horizontal, vertical or diagonal")]
+    fn skewed_line() {
+        line_between(Vec2i::new(0, 0), Vec2i::new(2, 1)).count();
+    }
+
+    fn v(points: &[(i32, i32)]) -> Vec<Vec2i> {
+        points.iter().map(|&(x, y)| Vec2i::new(x, y)).collect()
+    }
+
+    #[test]
+    fn hulls() {
+        // a square with points inside and on its edges
+        let points = v(&[
+            (0, 0),
+            (2, 0),
+            (4, 0),
+            (1, 1),
+            (4, 4),
+            (2, 2),
+            (0, 4),
+            (0, 2),
+            (3, 1),
+        ]);
+        let hull = convex_hull(&points);
+        assert_eq!(hull, v(&[(0, 0), (4, 0), (4, 4), (0, 4)]));
+        assert!(points.iter().all(|&p| point_in_convex_hull(&hull, p)));
+        assert!(!point_in_convex_hull(&hull, Vec2i::new(5, 2)));
+        assert!(!point_in_convex_hull(&hull, Vec2i::new(-1, -1)));
+        let triangle = convex_hull(&v(&[(3, 5), (0, 0), (6, 0), (3, 1)]));
+        assert_eq!(triangle, v(&[(0, 0), (6, 0), (3, 5)]));
+    }
+
+    #[test]
+    fn degenerate_hulls() {
+        let line = convex_hull(&v(&[(2, 2), (0, 0), (3, 3), (1, 1), (3, 3)]));
+        assert_eq!(line, v(&[(0, 0), (3, 3)]));
+        assert!(point_in_convex_hull(&line, Vec2i::new(2, 2)));
+        assert!(!point_in_convex_hull(&line, Vec2i::new(4, 4)));
+        assert!(!point_in_convex_hull(&line, Vec2i::new(2, 1)));
+        assert_eq!(convex_hull(&v(&[(1, 7), (1, 7)])), v(&[(1, 7)]));
+        assert_eq!(convex_hull(&[]), []);
+        assert!(!point_in_convex_hull(&[], Vec2i::new(0, 0)));
+        assert_eq!(
+            farthest_pair(&v(&[(1, 1), (0, 0), (5, 5)]), Metric::EuclideanSquared),
+            (Vec2i::new(0, 0), Vec2i::new(5, 5), 50)
+        );
+        assert_eq!(farthest_pair(&v(&[(4, 2)]), Metric::Manhattan).2, 0);
+    }
+
+    #[test]
+    fn farthest_pairs_match_brute_force() {
+        let mut rng = crate::Pcg32::new(3);
+        for round in 0..200 {
+            let n = rng.index(30) + 1;
+            let points: Vec<Vec2i> = (0..n)
+                .map(|_| Vec2i::new(rng.below(41) as i32 - 20, rng.below(41) as i32 - 20))
+                .collect();
+            for metric in [Metric::EuclideanSquared, Metric::Manhattan] {
+                let brute = points
+                    .iter()
+                    .flat_map(|&a| points.iter().map(move |&b| metric.distance(a, b)))
+                    .max()
+                    .unwrap();
+                let (a, b, d) = farthest_pair(&points, metric);
+                assert_eq!(d, brute, "round {round}, {metric:?}: {points:?}");
+                assert_eq!(metric.distance(a, b), d);
+            }
+            let hull = convex_hull(&points);
+            assert!(points.iter().all(|&p| point_in_convex_hull(&hull, p)));
+        }
+    }
+
+    #[test]
+    fn polygons() {
+        let rect = v(&[(0, 0), (4, 0), (4, 3), (0, 3)]);
+        assert_eq!(polygon_double_area(&rect), 24);
+        assert_eq!(polygon_perimeter(&rect), 14);
+        assert_eq!(interior_points(&rect), 6);
+        // the same rectangle clockwise and with extra points along its edges
+        let rect = v(&[(0, 0), (0, 3), (2, 3), (4, 3), (4, 0), (1, 0)]);
+        assert_eq!(polygon_area(&rect), 12.0);
+        assert_eq!(interior_points(&rect), 6);
+        let l = v(&[(0, 0), (2, 0), (2, 4), (6, 4), (6, 6), (0, 6)]);
+        assert_eq!(polygon_double_area(&l), 2 * 20);
+        assert_eq!(polygon_perimeter(&l), 24);
+        assert_eq!(interior_points(&l), 9);
+        let diamond = v(&[(0, 2), (2, 0), (4, 2), (2, 4)]);
+        assert_eq!(interior_points(&diamond), 5);
+    }
+
+    #[test]
+    fn lava_lagoon() {
+        let plan = "R 6 (#70c710)\nD 5 (#0dc571)\nL 2 (#5713f0)\nD 2 (#d2c081)\nR 2 (#59c680)\n\
+                    D 2 (#411b91)\nL 5 (#8ceee2)\nU 2 (#caa173)\nL 1 (#1b58a2)\nU 2 (#caa171)\n\
+                    R 2 (#7807d2)\nU 3 (#a77fa3)\nL 2 (#015232)\nU 2 (#7a21e3)";
+        let lagoon = |steps: &mut dyn Iterator<Item = (char, i32)>| {
+            let mut pos = Vec2i::new(0, 0);
+            let corners: Vec<Vec2i> = steps
+                .map(|(dir, n)| {
+                    pos += match dir {
+                        'R' => Vec2i::new(n, 0),
+                        'L' => Vec2i::new(-n, 0),
+                        'D' => Vec2i::new(0, n),
+                        _ => Vec2i::new(0, -n),
+                    };
+                    pos
+                })
+                .collect();
+            interior_points(&corners) + polygon_perimeter(&corners)
+        };
+        let small = lagoon(&mut plan.lines().map(|line| {
+            let mut parts = line.split(' ');
+            let dir = parts.next().unwrap().chars().next().unwrap();
+            (dir, parts.next().unwrap().parse().unwrap())
+        }));
+        assert_eq!(small, 62);
+        let big = lagoon(&mut plan.lines().map(|line| {
+            let hex = &line[line.len() - 7..line.len() - 1];
+            let dir = b"RDLU"[(hex.as_bytes()[5] - b'0') as usize] as char;
+            (dir, i32::from_str_radix(&hex[..5], 16).unwrap())
+        }));
+        assert_eq!(big, 952408144115);
+    }
+
+    #[test]
+    fn teleportation_spot() {
+        let bots: Vec<(Vec3i, i64)> = "pos=<10,12,12>, r=2
+pos=<12,14,12>, r=2
+pos=<16,12,12>, r=4
+pos=<14,14,14>, r=6
+pos=<50,50,50>, r=200
+pos=<10,10,10>, r=5"
+            .lines()
+            .map(|line| {
+                let [x, y, z, r] = crate::ints_n::<4>(line);
+                (Vec3i::new(x as i32, y as i32, z as i32), r)
+            })
+            .collect();
+        let (point, count) = best_covered_point(&bots);
+        assert_eq!((point, count), (Vec3i::new(12, 12, 12), 5));
+        assert_eq!(point.x + point.y + point.z, 36);
+    }
+
+    #[test]
+    fn covered_point_ties() {
+        // one point at distance 4 in each, the negative one is smaller
+        let apart = [(Vec3i::new(5, 0, 0), 1), (Vec3i::new(-5, 0, 0), 1)];
+        assert_eq!(best_covered_point(&apart), (Vec3i::new(-4, 0, 0), 1));
+        // (0, 1, 3), (0, 2, 2) and (0, 3, 1) are all at distance 4
+        assert_eq!(
+            best_covered_point(&[(Vec3i::new(0, 3, 3), 2)]),
+            (Vec3i::new(0, 1, 3), 1)
+        );
+        assert_eq!(
+            best_covered_point(&[(Vec3i::new(-7, 2, 1), 20)]),
+            (Vec3i::new(0, 0, 0), 1)
+        );
+        assert_eq!(best_covered_point(&[]), (Vec3i::new(0, 0, 0), 0));
+
+        let mut rng = crate::Pcg32::new(283);
+        let coord = |rng: &mut crate::Pcg32| rng.below(13) as i32 - 6;
+        for _ in 0..30 {
+            let spheres: Vec<(Vec3i, i64)> = (0..6)
+                .map(|_| {
+                    let c = Vec3i::new(coord(&mut rng), coord(&mut rng), coord(&mut rng));
+                    (c, rng.below(4) as i64)
+                })
+                .collect();
+            let mut expected = (0, Reverse(0), Reverse((0, 0, 0)));
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    for z in -10..=10 {
+                        let p = Vec3i::new(x, y, z);
+                        let count = spheres
+                            .iter()
+                            .filter(|&&(c, r)| crate::Vec3Ext::manhattan(c, p) <= r)
+                            .count();
+                        expected = expected.max((
+                            count,
+                            Reverse(x.abs() + y.abs() + z.abs()),
+                            Reverse((x, y, z)),
+                        ));
+                    }
+                }
+            }
+            let (count, _, Reverse((x, y, z))) = expected;
+            assert_eq!(best_covered_point(&spheres), (Vec3i::new(x, y, z), count));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::Hash,
+};
+
+/// The successors of every node. Nodes that only appear as targets get an empty list.
+pub fn adjacency<T: Eq + Hash + Clone>(
+    edges: impl IntoIterator<Item = (T, T)>,
+) -> HashMap<T, Vec<T>> {
+    let mut adjacency: HashMap<T, Vec<T>> = HashMap::new();
+    for (from, to) in edges {
+        adjacency.entry(to.clone()).or_default();
+        adjacency.entry(from).or_default().push(to);
+    }
+    adjacency
+}
+
+/// The same graph with every edge pointing the other way.
+pub fn reverse_edges<T: Eq + Hash + Clone>(adjacency: &HashMap<T, Vec<T>>) -> HashMap<T, Vec<T>> {
+    let mut reversed: HashMap<T, Vec<T>> = adjacency
+        .keys()
+        .map(|node| (node.clone(), Vec::new()))
+        .collect();
+    for (from, targets) in adjacency {
+        for to in targets {
+            reversed.entry(to.clone()).or_default().push(from.clone());
+        }
+    }
+    reversed
+}
+
+/// Every node reachable from `start`, including itself.
+pub fn reachable_from<T: Eq + Hash + Clone>(
+    start: T,
+    adjacency: &HashMap<T, Vec<T>>,
+) -> HashSet<T> {
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for next in adjacency.get(&node).into_iter().flatten() {
+            if seen.insert(next.clone()) {
+                stack.push(next.clone());
+            }
+        }
+    }
+    seen
+}
+
+/// Orders the nodes so that every edge points forward. Of the nodes that could come next, the
+/// smallest one is taken. If there is none because of a cycle, returns the nodes of one cycle
+/// in edge order, starting with its smallest node.
+pub fn topo_sort<T: Eq + Hash + Clone + Ord>(
+    edges: impl IntoIterator<Item = (T, T)>,
+) -> Result<Vec<T>, Vec<T>> {
+    let adjacency = adjacency(edges);
+    let mut before_count: HashMap<&T, usize> = adjacency.keys().map(|node| (node, 0)).collect();
+    for to in adjacency.values().flatten() {
+        *before_count.get_mut(to).unwrap() += 1;
+    }
+    let mut ready: BTreeSet<&T> = before_count
+        .iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&node, _)| node)
+        .collect();
+    let mut order = Vec::with_capacity(adjacency.len());
+    while let Some(node) = ready.pop_first() {
+        order.push(node.clone());
+        for next in &adjacency[node] {
+            let count = before_count.get_mut(next).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(next);
+            }
+        }
+    }
+    if order.len() == adjacency.len() {
+        return Ok(order);
+    }
+    // every node that is left has a predecessor that is left, so walking backwards from any
+    // of them has to run into a cycle
+    let reversed = reverse_edges(&adjacency);
+    let left = |node: &&T| before_count[*node] > 0;
+    let mut node = before_count.keys().copied().filter(left).min().unwrap();
+    let mut walked = vec![node];
+    let mut seen = HashMap::from([(node, 0)]);
+    let start = loop {
+        node = reversed[node].iter().filter(left).min().unwrap();
+        if let Some(&i) = seen.get(node) {
+            break i;
+        }
+        seen.insert(node, walked.len());
+        walked.push(node);
+    };
+    let mut cycle: Vec<T> = walked[start..]
+        .iter()
+        .rev()
+        .map(|&node| node.clone())
+        .collect();
+    let smallest = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap();
+    cycle.rotate_left(smallest);
+    Err(cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_order() {
+        let steps = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+        let edges = steps.lines().map(|line| {
+            let b = line.as_bytes();
+            (b[5] as char, b[36] as char)
+        });
+        let order: String = topo_sort(edges).unwrap().into_iter().collect();
+        assert_eq!(order, "CABDFE");
+        assert_eq!(topo_sort(Vec::<(u8, u8)>::new()), Ok(vec![]));
+        // separate components and a node that is only a target
+        assert_eq!(
+            topo_sort([(5, 1), (3, 4), (0, 9)]),
+            Ok(vec![0, 3, 4, 5, 1, 9])
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        // 1 -> 2 -> 3 -> 4 -> 2 with 0 before and 5 after the cycle
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 2), (4, 5)];
+        assert_eq!(topo_sort(edges), Err(vec![2, 3, 4]));
+        assert_eq!(topo_sort([(1, 2), (7, 7)]), Err(vec![7]));
+        let edges = [("b", "a"), ("a", "c"), ("c", "b"), ("d", "a")];
+        assert_eq!(topo_sort(edges), Err(vec!["a", "c", "b"]));
+    }
+
+    #[test]
+    fn reachability() {
+        let adjacency = adjacency([(1, 2), (2, 3), (4, 2), (3, 1), (5, 6)]);
+        assert_eq!(adjacency[&6], []);
+        assert_eq!(reachable_from(2, &adjacency), HashSet::from([1, 2, 3]));
+        assert_eq!(reachable_from(6, &adjacency), HashSet::from([6]));
+        let reversed = reverse_edges(&adjacency);
+        assert_eq!(reachable_from(2, &reversed), HashSet::from([1, 2, 3, 4]));
+        assert_eq!(reversed[&5], []);
+    }
+}
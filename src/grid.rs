@@ -1,11 +1,63 @@
 use std::{
     fmt::Display,
+    hash::{Hash, Hasher},
     ops::{Index, IndexMut},
 };
 
-use color_format::cwrite;
 use vecm::{PolyVec2, Vec2i};
 
+use crate::Color;
+
+mod annotations;
+mod cascade;
+mod edit;
+mod generate;
+mod histogram;
+mod incremental;
+mod iter;
+mod morphology;
+mod numbers;
+mod palette;
+mod paths;
+mod poi;
+mod portals;
+mod profile;
+mod reach;
+mod recorded;
+mod rle;
+mod rooms;
+mod sand;
+mod search;
+mod shape;
+mod shrink;
+mod squares;
+mod topology;
+mod transform;
+mod view;
+mod visibility;
+mod visited;
+mod walk;
+mod water;
+mod wrap;
+
+pub use annotations::{render_side_by_side, Annotations};
+pub use incremental::{first_disconnecting, IncrementalConnectivity};
+pub use iter::{IntoIter, Iter, IterMut};
+pub use numbers::GridNumber;
+pub use palette::Palette;
+pub use poi::PoiMatrix;
+pub use portals::MazePortals;
+pub use reach::{count_reachable_at_exact, nearest_in_reading_order, ExtrapolationError};
+pub use recorded::RecordedGrid;
+pub use rle::RleCell;
+pub use rooms::RoomGraph;
+pub use sand::DropResult;
+pub use shape::Shape;
+pub use shrink::{remap_chars, shrink_grid_fixture};
+pub use view::GridView;
+pub use visited::{DirVisited, StateVisited, VisitDir};
+pub use wrap::{FlatWrap, PathStep, Turn, WrapRule};
+
 pub const DIRS4: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
 pub const DIRS8: [(i32, i32); 8] = [
     (0, -1),
@@ -18,6 +70,21 @@ pub const DIRS8: [(i32, i32); 8] = [
     (1, 1),
 ];
 
+/// Which cells count as neighbors: the 4 orthogonal ones or all 8 including diagonals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+impl Connectivity {
+    pub fn dirs(self) -> &'static [(i32, i32)] {
+        match self {
+            Self::Four => &DIRS4,
+            Self::Eight => &DIRS8,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Side {
     L,
@@ -56,18 +123,130 @@ impl Side {
     }
 }
 
+/// A facing direction for moving agents, screen coordinates so `Up` is negative y.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+impl Dir {
+    /// All directions in [`DIRS4`] order.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Left, Dir::Right, Dir::Down];
+
+    pub fn as_vec(self) -> Vec2i {
+        Side::from(self).dir()
+    }
+
+    pub fn step(self, pos: Vec2i) -> Vec2i {
+        pos + self.as_vec()
+    }
+
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Side::from(self).rot_left().into()
+    }
+
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Side::from(self).rot_right().into()
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+impl From<Side> for Dir {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::L => Self::Left,
+            Side::R => Self::Right,
+            Side::T => Self::Up,
+            Side::B => Self::Down,
+        }
+    }
+}
+impl From<Dir> for Side {
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::Left => Self::L,
+            Dir::Right => Self::R,
+            Dir::Up => Self::T,
+            Dir::Down => Self::B,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidDir(pub char);
+impl Display for InvalidDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+impl std::error::Error for InvalidDir {}
+
+impl TryFrom<char> for Dir {
+    type Error = InvalidDir;
+
+    /// Accepts arrows (`^v<>`), `UDLR` and compass directions (`NSEW`).
+    fn try_from(c: char) -> Result<Self, InvalidDir> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Self::Up),
+            'v' | 'D' | 'S' => Ok(Self::Down),
+            '<' | 'L' | 'W' => Ok(Self::Left),
+            '>' | 'R' | 'E' => Ok(Self::Right),
+            c => Err(InvalidDir(c)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Grid<T> {
     buf: Box<[T]>,
     width: usize,
     height: usize,
+    /// The row lengths before padding, only present for grids built from ragged rows.
+    row_lens: Option<Box<[usize]>>,
+}
+/// Grids are compared by their cells only, so a padded grid equals the same grid built without
+/// padding.
+impl<T: PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.width, self.height) == (other.width, other.height) && self.buf == other.buf
+    }
+}
+impl<T: Eq> Eq for Grid<T> {}
+impl<T: Hash> Hash for Grid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.width, self.height).hash(state);
+        self.buf.hash(state);
+    }
 }
 impl<T> Grid<T> {
+    fn from_buf(buf: Box<[T]>, width: usize, height: usize) -> Self {
+        assert_eq!(buf.len(), width * height, "buffer doesn't match grid size");
+        Self {
+            buf,
+            width,
+            height,
+            row_lens: None,
+        }
+    }
+
     pub fn from_nested(v: Vec<Vec<T>>) -> Self {
         let height = v.len();
         let width = v[0].len();
         let buf: Box<[T]> = v.into_iter().flatten().collect();
         assert_eq!(buf.len(), width * height, "mismatched buffer row lengths");
-        Self { buf, width, height }
+        Self::from_buf(buf, width, height)
     }
 
     pub fn from_nested_slice(v: &[Vec<T>]) -> Self
@@ -78,7 +257,76 @@ impl<T> Grid<T> {
         let width = v[0].len();
         let buf: Box<[T]> = v.iter().flatten().cloned().collect();
         assert_eq!(buf.len(), width * height, "mismatched buffer row lengths");
-        Self { buf, width, height }
+        Self::from_buf(buf, width, height)
+    }
+
+    /// The cells as nested arrays of rows, `None` unless the grid is exactly `W` x `H`.
+    pub fn to_array<const W: usize, const H: usize>(&self) -> Option<[[T; W]; H]>
+    where
+        T: Clone,
+    {
+        (self.width == W && self.height == H)
+            .then(|| std::array::from_fn(|y| std::array::from_fn(|x| self[(x, y)].clone())))
+    }
+
+    /// A `width` x `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(width, height, |_| fill.clone())
+    }
+
+    /// Calls `f` once per cell in row-major order.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Vec2i) -> T) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "grid dimensions have to be non-zero, got {width}x{height}"
+        );
+        let buf = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Vec2i::new(x as i32, y as i32)))
+            .map(&mut f)
+            .collect();
+        Self::from_buf(buf, width, height)
+    }
+
+    /// A grid with the same dimensions as this one, filled with `fill`.
+    pub fn same_size_with<U: Clone>(&self, fill: U) -> Grid<U> {
+        Grid::new(self.width, self.height, fill)
+    }
+
+    /// Pads the grid on the right and bottom with `fill` to the new size, which can't be smaller
+    /// than the current one.
+    pub fn grow(&mut self, new_width: usize, new_height: usize, fill: T)
+    where
+        T: Clone,
+    {
+        assert!(
+            new_width >= self.width && new_height >= self.height,
+            "can't grow a {}x{} grid to {new_width}x{new_height}",
+            self.width,
+            self.height
+        );
+        let old = std::mem::replace(&mut self.buf, Box::new([]));
+        let mut old = old.into_vec().into_iter();
+        let mut buf = Vec::with_capacity(new_width * new_height);
+        for y in 0..new_height {
+            if y < self.height {
+                buf.extend(old.by_ref().take(self.width));
+                buf.extend(std::iter::repeat_n(fill.clone(), new_width - self.width));
+            } else {
+                buf.extend(std::iter::repeat_n(fill.clone(), new_width));
+            }
+        }
+        self.buf = buf.into_boxed_slice();
+        self.width = new_width;
+        self.height = new_height;
+        // recorded padding stays as is, the new rows are padding only
+        if let Some(lens) = &mut self.row_lens {
+            let mut lens = std::mem::take(lens).into_vec();
+            lens.resize(new_height, 0);
+            self.row_lens = Some(lens.into_boxed_slice());
+        }
     }
 
     pub fn width(&self) -> usize {
@@ -89,14 +337,39 @@ impl<T> Grid<T> {
         self.height
     }
 
+    /// The length of row `y` before it was padded, or the width for grids without padding.
+    pub fn unpadded_row_len(&self, y: usize) -> usize {
+        assert!(y < self.height, "row {y} is outside of the grid");
+        self.row_lens.as_ref().map_or(self.width, |lens| lens[y])
+    }
+
     pub fn map<F: FnMut(T) -> U, U>(self, f: F) -> Grid<U> {
         Grid {
             buf: IntoIterator::into_iter(self.buf).map(f).collect(),
             width: self.width,
             height: self.height,
+            row_lens: self.row_lens,
         }
     }
 
+    pub fn try_map<F, U, E>(self, mut f: F) -> Result<Grid<U>, GridMapError<E>>
+    where
+        F: FnMut(Vec2i, T) -> Result<U, E>,
+    {
+        let width = self.width;
+        let mut buf = Vec::with_capacity(self.buf.len());
+        for (i, item) in IntoIterator::into_iter(self.buf).enumerate() {
+            let pos = Vec2i::new((i % width) as i32, (i / width) as i32);
+            buf.push(f(pos, item).map_err(|error| GridMapError { pos, error })?);
+        }
+        Ok(Grid {
+            buf: buf.into_boxed_slice(),
+            width,
+            height: self.height,
+            row_lens: self.row_lens,
+        })
+    }
+
     pub fn rows(&self) -> impl Iterator<Item = &[T]> {
         (0..self.height).map(|i| &self.buf[i * self.width..(i + 1) * self.width])
     }
@@ -106,6 +379,50 @@ impl<T> Grid<T> {
         (0..self.height as i32).flat_map(move |y| (0..width as i32).map(move |x| Vec2i::new(x, y)))
     }
 
+    /// The neighbor of `pos` in direction `dir`, `None` if it lies outside of the grid.
+    pub fn neighbor_in_dir(&self, pos: Vec2i, dir: Dir) -> Option<Vec2i> {
+        let next = dir.step(pos);
+        self.in_bounds(next).then_some(next)
+    }
+
+    /// The first position holding `value` in row-major order.
+    pub fn find(&self, value: &T) -> Option<Vec2i>
+    where
+        T: PartialEq,
+    {
+        self.find_by(|_, v| v == value)
+    }
+
+    /// All positions holding `value` in row-major order.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Vec2i> + 'a
+    where
+        T: PartialEq,
+    {
+        self.find_all_by(move |_, v| v == value)
+    }
+
+    pub fn find_by(&self, pred: impl Fn(Vec2i, &T) -> bool) -> Option<Vec2i> {
+        self.find_all_by(pred).next()
+    }
+
+    pub fn find_all_by<'a>(
+        &'a self,
+        pred: impl Fn(Vec2i, &T) -> bool + 'a,
+    ) -> impl Iterator<Item = Vec2i> + 'a {
+        self.buf
+            .iter()
+            .enumerate()
+            .filter(move |&(i, v)| pred(self.pos_of(i), v))
+            .map(|(i, _)| self.pos_of(i))
+    }
+
+    pub fn count(&self, value: &T) -> usize
+    where
+        T: PartialEq,
+    {
+        self.buf.iter().filter(|v| *v == value).count()
+    }
+
     pub fn neighbor_positions4(&self, pos: Vec2i) -> impl Iterator<Item = Vec2i> {
         let width = self.width;
         let height = self.height;
@@ -126,9 +443,66 @@ impl<T> Grid<T> {
         })
     }
 
-    pub fn pretty(&self) -> PrettyGrid<T> {
+    pub fn neighbor_positions(
+        &self,
+        pos: Vec2i,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Vec2i> + '_ {
+        connectivity
+            .dirs()
+            .iter()
+            .map(move |&(dx, dy)| pos + Vec2i::new(dx, dy))
+            .filter(|&p| self.in_bounds(p))
+    }
+
+    pub fn pretty(&self) -> PrettyGrid<'_, T> {
         PrettyGrid::new(self)
     }
+
+    /// The index of `pos` in the row-major cell buffer, `y * width + x`. This layout is stable,
+    /// so indices can be used for dense side tables like visited arrays or union-find parents.
+    pub fn index_of(&self, pos: Vec2i) -> usize {
+        self.checked_index_of(pos)
+            .unwrap_or_else(|| panic!("position {pos:?} is outside of the grid"))
+    }
+
+    pub fn checked_index_of(&self, pos: Vec2i) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.y as usize * self.width + pos.x as usize)
+    }
+
+    /// The inverse of [`Grid::index_of`].
+    pub fn pos_of(&self, index: usize) -> Vec2i {
+        assert!(
+            index < self.buf.len(),
+            "index {index} is outside of the grid"
+        );
+        Vec2i::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    /// The cell at `pos`, or `None` for coordinates outside of the grid, including negative
+    /// ones.
+    pub fn get(&self, pos: impl GridIndex) -> Option<&T> {
+        self.buf.get(pos.cell_index(self.width, self.height)?)
+    }
+
+    pub fn get_mut(&mut self, pos: impl GridIndex) -> Option<&mut T> {
+        self.buf.get_mut(pos.cell_index(self.width, self.height)?)
+    }
+
+    fn in_bounds(&self, pos: Vec2i) -> bool {
+        (0..self.width as i32).contains(&pos.x) && (0..self.height as i32).contains(&pos.y)
+    }
+}
+/// Rows of a fixture written as nested arrays, like `Grid::from([[1, 2, 3], [4, 5, 6]])`.
+impl<T, const W: usize, const H: usize> From<[[T; W]; H]> for Grid<T> {
+    fn from(rows: [[T; W]; H]) -> Self {
+        assert!(
+            W > 0 && H > 0,
+            "grid dimensions have to be non-zero, got {W}x{H}"
+        );
+        Self::from_buf(rows.into_iter().flatten().collect(), W, H)
+    }
 }
 impl<T: Display> Display for Grid<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -163,13 +537,36 @@ impl Grid<char> {
             }
         }
 
-        Self {
-            buf: buf.into_boxed_slice(),
-            width: prev_width.expect("got empty grid"),
+        Self::from_buf(
+            buf.into_boxed_slice(),
+            prev_width.expect("got empty grid"),
             height,
-        }
+        )
+    }
+
+    /// Like [`Grid::from_str_chars`] but pads rows shorter than the longest one with `fill`.
+    pub fn from_str_chars_padded(s: &str, fill: char) -> Self {
+        Self::from_separated_padded(s.chars().filter(|&c| c != '\r'), '\n', fill)
+    }
+
+    pub fn parse_cells<U: TryFrom<char>>(self) -> Result<Grid<U>, GridMapError<U::Error>> {
+        self.try_map(|_, c| U::try_from(c))
+    }
+}
+
+/// Error returned by [`Grid::try_map`], carrying the position of the cell that failed to convert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridMapError<E> {
+    pub pos: Vec2i,
+    pub error: E,
+}
+impl<E: Display> Display for GridMapError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at ({}, {}): {}", self.pos.x, self.pos.y, self.error)
     }
 }
+impl<E: std::error::Error> std::error::Error for GridMapError<E> {}
+
 impl<T: PartialEq> Grid<T> {
     pub fn from_separated(s: impl IntoIterator<Item = T>, sep: T) -> Grid<T> {
         let s = s.into_iter();
@@ -196,16 +593,113 @@ impl<T: PartialEq> Grid<T> {
         assert_eq!(final_width, width, "differing width in line {height}");
         debug_assert_eq!(buf.len(), height * prev_width.unwrap());
 
-        Self {
-            buf: buf.into_boxed_slice(),
-            width: final_width,
-            height,
+        Self::from_buf(buf.into_boxed_slice(), final_width, height)
+    }
+}
+impl<T: PartialEq + Clone> Grid<T> {
+    /// Like [`Grid::from_separated`] but allows ragged rows, padding them to the longest row with
+    /// `fill`. The original lengths are available through [`Grid::unpadded_row_len`].
+    pub fn from_separated_padded(s: impl IntoIterator<Item = T>, sep: T, fill: T) -> Grid<T> {
+        let mut rows = vec![Vec::new()];
+        for item in s {
+            if item == sep {
+                rows.push(Vec::new());
+            } else {
+                rows.last_mut().unwrap().push(item);
+            }
+        }
+        if rows.len() > 1 && rows.last().is_some_and(Vec::is_empty) {
+            rows.pop();
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        assert!(width > 0, "got empty grid");
+        let row_lens: Box<[usize]> = rows.iter().map(Vec::len).collect();
+        let height = rows.len();
+        let mut buf = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            buf.append(&mut row);
+        }
+        let mut grid = Self::from_buf(buf.into_boxed_slice(), width, height);
+        grid.row_lens = Some(row_lens);
+        grid
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from the lines of `s`, `push_line(y, line, buf)` appends the cells of one
+    /// line. Lines can end in `\r\n` and a trailing newline is ignored.
+    fn from_lines(s: &str, mut push_line: impl FnMut(usize, &str, &mut Vec<T>)) -> Self {
+        let mut buf = Vec::with_capacity(s.len());
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in s.lines().enumerate() {
+            let before = buf.len();
+            push_line(y, line, &mut buf);
+            let w = buf.len() - before;
+            assert_eq!(
+                *width.get_or_insert(w),
+                w,
+                "differing width in line {}",
+                y + 1
+            );
+            height += 1;
         }
+        Self::from_buf(
+            buf.into_boxed_slice(),
+            width.expect("got empty grid"),
+            height,
+        )
+    }
+
+    /// Parses every character into a cell with `f`, like [`Grid::from_str_chars`].
+    pub fn from_str_map(s: &str, mut f: impl FnMut(char) -> T) -> Self {
+        Self::from_lines(s, |_, line, buf| buf.extend(line.chars().map(&mut f)))
     }
 }
+
 impl Grid<u8> {
     pub fn from_str_bytes(s: &str) -> Self {
-        Self::from_separated(s.as_bytes().iter().copied(), b'\n')
+        Self::from_lines(s, |_, line, buf| buf.extend_from_slice(line.as_bytes()))
+    }
+
+    /// A grid of single digits `0-9`.
+    pub fn from_str_digits(s: &str) -> Self {
+        Self::from_lines(s, |y, line, buf| {
+            for (x, c) in line.chars().enumerate() {
+                let digit = c.to_digit(10).unwrap_or_else(|| {
+                    panic!(
+                        "expected a digit at line {}, column {} but found {c:?}",
+                        y + 1,
+                        x + 1
+                    )
+                });
+                buf.push(digit as u8);
+            }
+        })
+    }
+}
+
+/// Coordinates that can address a cell, see [`Grid::get`].
+pub trait GridIndex {
+    /// The position in the row-major cell buffer of a `width` x `height` grid, `None` if the
+    /// coordinates are outside of it.
+    fn cell_index(self, width: usize, height: usize) -> Option<usize>;
+}
+impl<I> GridIndex for PolyVec2<I>
+where
+    usize: TryFrom<I>,
+{
+    fn cell_index(self, width: usize, height: usize) -> Option<usize> {
+        let x = usize::try_from(self.x).ok()?;
+        let y = usize::try_from(self.y).ok()?;
+        (x, y).cell_index(width, height)
+    }
+}
+impl GridIndex for (usize, usize) {
+    fn cell_index(self, width: usize, height: usize) -> Option<usize> {
+        let (x, y) = self;
+        (x < width && y < height).then(|| y * width + x)
     }
 }
 
@@ -250,52 +744,139 @@ impl<T> IndexMut<(usize, usize)> for Grid<T> {
     }
 }
 
+impl<T> Index<usize> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.buf[index]
+    }
+}
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.buf[index]
+    }
+}
+
+type CellColor<'a> = Box<dyn Fn((usize, usize)) -> Option<Color> + 'a>;
+type CellRule<'a, T, R> = Box<dyn Fn((usize, usize), &T) -> Option<R> + 'a>;
+
 pub struct PrettyGrid<'a, T> {
     grid: &'a Grid<T>,
-    with_red: Option<Box<dyn Fn((usize, usize)) -> bool + 'a>>,
-    with_green: Option<Box<dyn Fn((usize, usize)) -> bool + 'a>>,
+    /// Color rules with whether they render bold, later ones win.
+    color_rules: Vec<(CellRule<'a, T, Color>, bool)>,
+    overrides: Vec<CellRule<'a, T, String>>,
+    palette: Option<&'a Palette<'a, T>>,
+    annotation_color: Option<CellColor<'a>>,
+    colored: bool,
+    compact: bool,
 }
 
 impl<'a, T> PrettyGrid<'a, T> {
     pub fn new(grid: &'a Grid<T>) -> Self {
         Self {
             grid,
-            with_red: None,
-            with_green: None,
+            color_rules: Vec::new(),
+            overrides: Vec::new(),
+            palette: None,
+            annotation_color: None,
+            colored: crate::color::enabled(),
+            compact: false,
         }
     }
+    /// Colors the cells for which `f` returns a color. Rules are layered: a later rule wins
+    /// over earlier ones and all rules win over palette and annotation colors.
+    pub fn with_color(mut self, f: impl Fn((usize, usize), &T) -> Option<Color> + 'a) -> Self {
+        self.color_rules.push((Box::new(f), false));
+        self
+    }
+    /// Bold red highlight, layered like [`PrettyGrid::with_color`].
     pub fn with_red(mut self, f: impl Fn((usize, usize)) -> bool + 'a) -> Self {
-        self.with_red = Some(Box::new(f));
+        let rule = move |pos, _: &T| f(pos).then_some(Color::Red);
+        self.color_rules.push((Box::new(rule), true));
         self
     }
+    /// Bold green highlight, layered like [`PrettyGrid::with_color`].
     pub fn with_green(mut self, f: impl Fn((usize, usize)) -> bool + 'a) -> Self {
-        self.with_green = Some(Box::new(f));
+        let rule = move |pos, _: &T| f(pos).then_some(Color::Green);
+        self.color_rules.push((Box::new(rule), true));
+        self
+    }
+    /// Renders the returned text instead of the cell, e.g. to draw a path as `O`. Later
+    /// overrides win.
+    pub fn with_override(mut self, f: impl Fn((usize, usize), &T) -> Option<String> + 'a) -> Self {
+        self.overrides.push(Box::new(f));
+        self
+    }
+    /// Renders cells through the palette. Color rules still take precedence over the palette
+    /// colors.
+    pub fn with_palette(mut self, palette: &'a Palette<'a, T>) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+    /// Overrides whether escape codes are written, which defaults to [`crate::color::enabled`].
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+    pub fn plain(self) -> Self {
+        self.colored(false)
+    }
+    /// Drops the separating space between cells, which stay right-aligned to the widest one.
+    /// Single-character grids are unaffected since they are never padded.
+    pub fn compact(mut self) -> Self {
+        self.compact = true;
         self
     }
 }
 
 impl<T: Display> Display for PrettyGrid<'_, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let max_cell_len = self
+        let cells: Vec<(String, Color, bool)> = self
             .grid
-            .buf
             .iter()
-            .map(|i| i.to_string().len())
+            .map(|(pos, item)| {
+                let (x, y) = (pos.x as usize, pos.y as usize);
+                let (mut text, palette_color) =
+                    match self.palette.and_then(|palette| palette.lookup(item)) {
+                        Some((glyph, color)) => (glyph.to_string(), color),
+                        None => (item.to_string(), None),
+                    };
+                if let Some(replaced) = self.overrides.iter().rev().find_map(|o| o((x, y), item)) {
+                    text = replaced;
+                }
+                let base = self
+                    .annotation_color
+                    .as_ref()
+                    .and_then(|f| f((x, y)))
+                    .or(palette_color)
+                    .unwrap_or(Color::DEFAULT);
+                let (color, bold) = self
+                    .color_rules
+                    .iter()
+                    .rev()
+                    .find_map(|(rule, bold)| Some((rule((x, y), item)?, *bold)))
+                    .unwrap_or((base, false));
+                (text, color, bold)
+            })
+            .collect();
+        let max_cell_len = cells
+            .iter()
+            .map(|(text, _, _)| text.chars().count())
             .max()
             .unwrap_or(0);
-        for (y, row) in self.grid.rows().enumerate() {
-            for (x, item) in row.iter().enumerate() {
-                let len = item.to_string().len();
+        let gap = usize::from(!self.compact);
+        for row in cells.chunks(self.grid.width) {
+            for (text, color, bold) in row {
                 if max_cell_len > 1 {
-                    write!(f, "{:<width$}", "", width = max_cell_len - len + 1)?;
+                    let len = text.chars().count();
+                    write!(f, "{:<width$}", "", width = max_cell_len - len + gap)?;
                 }
-                if self.with_red.as_ref().map_or(false, |f| f((x, y))) {
-                    cwrite!(f, "#bold<#red<{item}>>")?;
-                } else if self.with_green.as_ref().map_or(false, |f| f((x, y))) {
-                    cwrite!(f, "#bold<#green<{item}>>")?;
+                let painted = if *bold {
+                    color.paint_bold(text)
                 } else {
-                    cwrite!(f, "#rgb(192,192,192)<{item}>")?;
-                }
+                    color.paint(text)
+                };
+                write!(f, "{}", painted.when(self.colored))?;
             }
             writeln!(f)?;
         }
@@ -328,4 +909,330 @@ mod tests {
         assert_eq!(g[(1, 2)], b'h');
         assert_eq!(g[(2, 3)], b'l');
     }
+
+    #[test]
+    fn crlf_and_trailing_newline() {
+        let g = Grid::from_str_bytes("ab\r\ncd\r\n");
+        assert_eq!((g.width(), g.height()), (2, 2));
+        assert_eq!(g[(1, 0)], b'b');
+        let heights = Grid::from_str_digits("2199943210\n3987894921\n9856789892\n");
+        assert_eq!((heights.width(), heights.height()), (10, 3));
+        assert_eq!(heights[(9, 1)], 1);
+        assert_eq!(heights.values().map(|&h| h as u32).sum::<u32>(), 171);
+        assert_eq!(
+            Grid::from_str_digits("12\r\n34"),
+            Grid::from_nested(vec![vec![1, 2], vec![3, 4]])
+        );
+    }
+
+    #[test]
+    fn map_while_parsing() {
+        let walls = Grid::from_str_map("#.#\n..#\n", |c| c == '#');
+        assert_eq!(
+            walls,
+            Grid::from([[true, false, true], [false, false, true]])
+        );
+    }
+
+    #[test]
+    fn array_fixtures() {
+        const FIXTURE: [[u8; 3]; 2] = [[1, 2, 3], [4, 5, 6]];
+        let grid = Grid::from(FIXTURE);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid, Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+        assert_eq!(grid.to_array(), Some(FIXTURE));
+        assert_eq!(grid.to_array::<2, 3>(), None);
+        assert_eq!(grid.to_array::<3, 3>(), None);
+        let column = Grid::from([['a'], ['b']]);
+        assert_eq!(column.transpose().to_array(), Some([['a', 'b']]));
+    }
+
+    #[test]
+    #[should_panic(expected = "grid dimensions have to be non-zero, got 0x2")]
+    fn empty_array_fixture() {
+        let _ = Grid::<u8>::from([[], []]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a digit at line 2, column 3 but found 'x'")]
+    fn non_digit() {
+        Grid::from_str_digits("123\n45x");
+    }
+
+    #[test]
+    #[should_panic(expected = "differing width in line 2")]
+    fn ragged_bytes() {
+        Grid::from_str_bytes("abc\nde\n");
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Tile {
+        Wall,
+        Floor,
+    }
+    impl TryFrom<char> for Tile {
+        type Error = char;
+
+        fn try_from(c: char) -> Result<Self, char> {
+            match c {
+                '#' => Ok(Self::Wall),
+                '.' => Ok(Self::Floor),
+                c => Err(c),
+            }
+        }
+    }
+
+    #[test]
+    fn grid_parse_cells() {
+        let g: Grid<Tile> = Grid::from_str_chars("#.#\n...").parse_cells().unwrap();
+        assert_eq!(g[(0, 0)], Tile::Wall);
+        assert_eq!(g[(1, 0)], Tile::Floor);
+        assert_eq!(g[(2, 1)], Tile::Floor);
+    }
+
+    #[test]
+    fn grid_try_map_error_position() {
+        let err = Grid::from_str_chars("#.#\n..x\n###")
+            .parse_cells::<Tile>()
+            .unwrap_err();
+        assert_eq!(err.pos, Vec2i::new(2, 1));
+        assert_eq!(err.error, 'x');
+    }
+
+    #[test]
+    fn padded_monkey_map() {
+        let board = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+";
+        let g = Grid::from_str_chars_padded(board, ' ');
+        assert_eq!((g.width(), g.height()), (16, 12));
+        let lens: Vec<usize> = (0..12).map(|y| g.unpadded_row_len(y)).collect();
+        assert_eq!(lens, [12, 12, 12, 12, 12, 12, 12, 12, 16, 16, 16, 16]);
+        assert_eq!(g[(11, 0)], '#');
+        assert_eq!(g[(12, 0)], ' ');
+        assert_eq!(g[(15, 11)], '.');
+        assert_eq!(Grid::from_str_chars("ab\ncd").unpadded_row_len(1), 2);
+        // the padding isn't part of the grid's identity
+        let padded = Grid::from_str_chars_padded("ab\nc", ' ');
+        let full = Grid::from_str_chars("ab\nc ");
+        assert_eq!(padded, full);
+        assert_eq!(std::collections::HashSet::from([padded, full]).len(), 1);
+    }
+
+    #[test]
+    fn linear_indices() {
+        let mut g = Grid::from_str_chars("abcd\nefgh\nijkl");
+        for (i, pos) in g.positions().enumerate() {
+            assert_eq!(g.index_of(pos), i);
+            assert_eq!(g.pos_of(i), pos);
+            assert_eq!(g[i], g[pos]);
+        }
+        g[6] = 'x';
+        assert_eq!(g[(2, 1)], 'x');
+        assert_eq!(g.checked_index_of(Vec2i::new(4, 0)), None);
+        assert_eq!(g.checked_index_of(Vec2i::new(0, -1)), None);
+        assert_eq!(g.checked_index_of(Vec2i::new(3, 2)), Some(11));
+    }
+
+    #[test]
+    fn directions() {
+        for dir in Dir::ALL {
+            let mut turned = dir;
+            for _ in 0..4 {
+                turned = turned.turn_right();
+            }
+            assert_eq!(turned, dir);
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_ne!(dir.opposite(), dir);
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.opposite());
+            assert_eq!(Dir::from(Side::from(dir)), dir);
+            assert_eq!(dir.as_vec(), Side::from(dir).dir());
+        }
+        let vecs: Vec<(i32, i32)> = Dir::ALL
+            .iter()
+            .map(|d| (d.as_vec().x, d.as_vec().y))
+            .collect();
+        assert_eq!(vecs, DIRS4);
+        assert_eq!(Dir::Up.turn_right(), Dir::Right);
+        let parsed: Result<Vec<Dir>, _> = "^v<>UDLRNSWE".chars().map(Dir::try_from).collect();
+        assert_eq!(
+            parsed.unwrap(),
+            [Dir::Up, Dir::Down, Dir::Left, Dir::Right].repeat(3)
+        );
+        assert_eq!(Dir::try_from('x'), Err(InvalidDir('x')));
+    }
+
+    #[test]
+    fn neighbor_in_dir_edges() {
+        let g = Grid::new(3, 2, 0);
+        let corner = Vec2i::new(2, 1);
+        assert_eq!(g.neighbor_in_dir(corner, Dir::Right), None);
+        assert_eq!(g.neighbor_in_dir(corner, Dir::Down), None);
+        assert_eq!(g.neighbor_in_dir(corner, Dir::Up), Some(Vec2i::new(2, 0)));
+        assert_eq!(g.neighbor_in_dir(Vec2i::new(0, 0), Dir::Left), None);
+        assert_eq!(g.neighbor_in_dir(Vec2i::new(0, 0), Dir::Up), None);
+    }
+
+    #[test]
+    fn constructors() {
+        let canvas = Grid::new(3, 2, '.');
+        assert_eq!(canvas, Grid::from_str_chars("...\n..."));
+        let mut calls = Vec::new();
+        let g = Grid::from_fn(3, 2, |p| {
+            calls.push(p);
+            p.x + 10 * p.y
+        });
+        assert_eq!(g, Grid::from_nested(vec![vec![0, 1, 2], vec![10, 11, 12]]));
+        assert_eq!(calls, g.positions().collect::<Vec<_>>());
+        let visited = g.same_size_with(false);
+        assert_eq!((visited.width(), visited.height()), (3, 2));
+        assert!(visited.rows().flatten().all(|&v| !v));
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero")]
+    fn zero_sized() {
+        Grid::new(0, 3, 0u8);
+    }
+
+    #[test]
+    fn grow_pads() {
+        let mut g = Grid::from_str_chars("ab\ncd");
+        g.grow(3, 3, '.');
+        assert_eq!(g, Grid::from_str_chars("ab.\ncd.\n..."));
+        g.grow(3, 3, '#');
+        assert_eq!(g.count(&'#'), 0);
+        let mut ragged = Grid::from_str_chars_padded("abc\nd", ' ');
+        ragged.grow(4, 3, ' ');
+        assert_eq!(
+            (0..3)
+                .map(|y| ragged.unpadded_row_len(y))
+                .collect::<Vec<_>>(),
+            [3, 1, 0]
+        );
+    }
+
+    #[test]
+    fn find_values() {
+        let g = Grid::from_str_chars("a#b\n##S\n.#.");
+        assert_eq!(g.find(&'S'), Some(Vec2i::new(2, 1)));
+        assert_eq!(g.find(&'x'), None);
+        let walls: Vec<Vec2i> = g.find_all(&'#').collect();
+        assert_eq!(
+            walls,
+            [
+                Vec2i::new(1, 0),
+                Vec2i::new(0, 1),
+                Vec2i::new(1, 1),
+                Vec2i::new(1, 2)
+            ]
+        );
+        assert_eq!(g.find_all(&'x').count(), 0);
+        assert_eq!((g.count(&'#'), g.count(&'.'), g.count(&'x')), (4, 2, 0));
+        assert_eq!(
+            g.find_by(|p, &c| c == '#' && p.y > 0),
+            Some(Vec2i::new(0, 1))
+        );
+        // the iterators only borrow the grid, so it can be indexed while iterating
+        let below_walls: Vec<char> = g
+            .find_all_by(|p, _| p.y < 2)
+            .filter(|&p| g[p] == '#')
+            .map(|p| g[p + Vec2i::new(0, 1)])
+            .collect();
+        assert_eq!(below_walls, ['#', '.', '#']);
+    }
+
+    #[test]
+    fn checked_get() {
+        let mut g = Grid::from_str_chars("ab\ncd\nef");
+        assert_eq!(g.get(Vec2i::new(1, 2)), Some(&'f'));
+        assert_eq!(g.get((1, 2)), Some(&g[(1, 2)]));
+        assert_eq!(g.get(Vec2i::new(-1, 0)), None);
+        assert_eq!(g.get(Vec2i::new(0, -1)), None);
+        assert_eq!(g.get(Vec2i::new(2, 0)), None);
+        assert_eq!(g.get(Vec2i::new(0, 3)), None);
+        assert_eq!(g.get((2, 0)), None);
+        assert_eq!(g.get((0, 3)), None);
+        *g.get_mut((0, 1)).unwrap() = 'x';
+        assert_eq!(g[Vec2i::new(0, 1)], 'x');
+        assert!(g.get_mut(Vec2i::new(-1, -1)).is_none());
+
+        let mut pos = Vec2i::new(0, 0);
+        let mut diagonal = String::new();
+        while let Some(&c) = g.get(pos) {
+            diagonal.push(c);
+            pos += Vec2i::new(1, 1);
+        }
+        assert_eq!(diagonal, "ad");
+    }
+
+    #[test]
+    #[should_panic(expected = "position")]
+    fn index_of_out_of_bounds() {
+        Grid::from_str_chars("ab\ncd").index_of(Vec2i::new(2, 0));
+    }
+
+    fn strip_escapes(s: &str) -> String {
+        let mut stripped = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                stripped.push(c);
+            }
+        }
+        stripped
+    }
+
+    #[test]
+    fn pretty_layers_and_overrides() {
+        let g = Grid::from_str_chars("S..\n.#E");
+        let path = [(0, 0), (1, 0), (2, 0), (2, 1)];
+        let rendered = g
+            .pretty()
+            .with_red(|p| p == (2, 1))
+            .with_color(|_, &c| (c == '#').then_some(Color::Blue))
+            .with_color(|p, _| (p.0 == 2).then_some(Color::Rgb(1, 2, 3)))
+            .with_override(move |p, _| path.contains(&p).then(|| "O".to_owned()))
+            .colored(true)
+            .to_string();
+        assert_eq!(strip_escapes(&rendered), "OOO\n.#O\n");
+        let rgb = Color::Rgb(1, 2, 3).paint("O").to_string();
+        assert!(rendered.ends_with(&format!("{}{rgb}\n", Color::Blue.paint('#'))));
+
+        let wide = Grid::from_nested(vec![vec![1, 22], vec![333, 4]]);
+        assert_eq!(
+            wide.pretty().compact().plain().to_string(),
+            "  1 22\n333  4\n"
+        );
+        assert_eq!(g.pretty().compact().plain().to_string(), "S..\n.#E\n");
+    }
+
+    #[test]
+    fn pretty_colored_override() {
+        let g = Grid::from_nested(vec![vec![1, 22], vec![333, 4]]);
+        let colored = g
+            .pretty()
+            .with_red(|p| p == (0, 0))
+            .colored(true)
+            .to_string();
+        let plain = g.pretty().with_red(|p| p == (0, 0)).plain().to_string();
+        assert!(colored.contains('\x1b'));
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain, "   1  22\n 333   4\n");
+        assert_eq!(strip_escapes(&colored), plain);
+    }
 }
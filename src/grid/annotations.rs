@@ -0,0 +1,148 @@
+use std::{collections::HashMap, fmt::Display};
+
+use vecm::Vec2i;
+
+use crate::Color;
+
+use super::{Grid, PrettyGrid};
+
+/// Sparse per-cell values stored next to a grid, for example distances or visit order while
+/// debugging, without changing the grid's cell type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotations<T> {
+    values: HashMap<Vec2i, T>,
+}
+impl<T> Annotations<T> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn set(&mut self, pos: Vec2i, value: T) -> Option<T> {
+        self.values.insert(pos, value)
+    }
+
+    pub fn get(&self, pos: Vec2i) -> Option<&T> {
+        self.values.get(&pos)
+    }
+
+    pub fn remove(&mut self, pos: Vec2i) -> Option<T> {
+        self.values.remove(&pos)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2i, &T)> {
+        self.values.iter().map(|(&pos, value)| (pos, value))
+    }
+}
+impl<T> Default for Annotations<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> PrettyGrid<'a, T> {
+    /// Colors annotated cells by the style of their annotation.
+    pub fn with_annotations<A>(
+        mut self,
+        annotations: &'a Annotations<A>,
+        style: impl Fn(&A) -> Color + 'a,
+    ) -> Self {
+        self.annotation_color = Some(Box::new(move |(x, y)| {
+            annotations.get(Vec2i::new(x as i32, y as i32)).map(&style)
+        }));
+        self
+    }
+}
+
+/// Renders the grid next to a second panel showing the annotation value of each cell (or `.`)
+/// with all columns right-aligned to the widest annotation.
+pub fn render_side_by_side<T: Display, A: Display>(
+    grid: &Grid<T>,
+    annotations: &Annotations<A>,
+) -> String {
+    let left = grid.pretty().colored(false).to_string();
+    let width = annotations
+        .values
+        .values()
+        .map(|value| value.to_string().chars().count())
+        .max()
+        .unwrap_or(1);
+    let mut out = String::new();
+    for (y, line) in left.lines().enumerate() {
+        out.push_str(line);
+        out.push_str(" |");
+        for x in 0..grid.width() {
+            let value = annotations
+                .get(Vec2i::new(x as i32, y as i32))
+                .map_or_else(|| ".".to_owned(), ToString::to_string);
+            out.push_str(&format!(" {value:>width$}"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::{Color, Grid};
+
+    use super::{render_side_by_side, Annotations};
+
+    fn annotated() -> (Grid<char>, Annotations<u32>) {
+        let grid = Grid::from_str_chars("S.#\n..E");
+        let mut annotations = Annotations::new();
+        annotations.set(Vec2i::new(0, 0), 0);
+        annotations.set(Vec2i::new(1, 0), 1);
+        annotations.set(Vec2i::new(2, 1), 12);
+        (grid, annotations)
+    }
+
+    #[test]
+    fn side_by_side() {
+        let (grid, annotations) = annotated();
+        assert_eq!(
+            render_side_by_side(&grid, &annotations),
+            "S.# |  0  1  .\n..E |  .  . 12\n"
+        );
+    }
+
+    #[test]
+    fn annotation_colors() {
+        let (grid, annotations) = annotated();
+        let rendered = grid
+            .pretty()
+            .with_annotations(
+                &annotations,
+                |&d| if d > 5 { Color::Red } else { Color::Cyan },
+            )
+            .colored(true)
+            .to_string();
+        let default = |c| Color::DEFAULT.paint(c).to_string();
+        let cyan = |c| Color::Cyan.paint(c).to_string();
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n",
+            cyan('S'),
+            cyan('.'),
+            default('#'),
+            default('.'),
+            default('.'),
+            Color::Red.paint('E'),
+        );
+        assert_eq!(rendered, expected);
+    }
+}
@@ -0,0 +1,112 @@
+use super::{Connectivity, Grid};
+
+impl<T> Grid<T> {
+    /// One round of a chain reaction: `increment` every cell, then every cell for which
+    /// `triggers` holds fires exactly once, applying `on_trigger` to itself and
+    /// `affect_neighbors` to all neighbors that haven't fired yet this round, which can make
+    /// them fire in turn. Returns the number of cells that fired.
+    pub fn cascade_step(
+        &mut self,
+        increment: impl Fn(&mut T),
+        triggers: impl Fn(&T) -> bool,
+        on_trigger: impl Fn(&mut T),
+        affect_neighbors: impl Fn(&mut T),
+        connectivity: Connectivity,
+    ) -> usize {
+        self.buf.iter_mut().for_each(&increment);
+        let mut fired = vec![false; self.buf.len()];
+        let mut worklist: Vec<usize> = (0..self.buf.len())
+            .filter(|&i| triggers(&self.buf[i]))
+            .collect();
+        let mut count = 0;
+        while let Some(i) = worklist.pop() {
+            // a cell can be queued by several neighbors before it's processed
+            if fired[i] {
+                continue;
+            }
+            fired[i] = true;
+            count += 1;
+            on_trigger(&mut self.buf[i]);
+            let neighbors: Vec<usize> = self
+                .neighbor_positions(self.pos_of(i), connectivity)
+                .map(|pos| self.index_of(pos))
+                .collect();
+            for j in neighbors {
+                if !fired[j] {
+                    affect_neighbors(&mut self.buf[j]);
+                    if triggers(&self.buf[j]) {
+                        worklist.push(j);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Runs `round` (usually a [`Grid::cascade_step`]) until `stop` returns true for the grid,
+    /// the number of rounds so far and the result of the last round. Returns the number of
+    /// rounds and the sum of all round results.
+    pub fn run_until(
+        &mut self,
+        mut round: impl FnMut(&mut Self) -> usize,
+        mut stop: impl FnMut(&Self, usize, usize) -> bool,
+    ) -> (usize, usize) {
+        let mut rounds = 0;
+        let mut total = 0;
+        loop {
+            let result = round(self);
+            rounds += 1;
+            total += result;
+            if stop(self, rounds, result) {
+                return (rounds, total);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OCTOPUSES: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
+    fn flash_step(grid: &mut Grid<u8>) -> usize {
+        grid.cascade_step(
+            |e| *e += 1,
+            |&e| e > 9,
+            |e| *e = 0,
+            |e| *e += 1,
+            Connectivity::Eight,
+        )
+    }
+
+    #[test]
+    fn dumbo_octopuses() {
+        let octopuses: Grid<u8> = Grid::from_nested(
+            OCTOPUSES
+                .lines()
+                .map(|l| l.bytes().map(|b| b - b'0').collect())
+                .collect(),
+        );
+        let mut grid = octopuses.clone();
+        let (_, flashes) = grid.run_until(flash_step, |_, rounds, _| rounds == 10);
+        assert_eq!(flashes, 204);
+        let (_, flashes) = grid.run_until(flash_step, |_, rounds, _| rounds == 90);
+        assert_eq!(flashes, 1656 - 204);
+
+        let mut grid = octopuses;
+        let cells = grid.width() * grid.height();
+        let (rounds, _) = grid.run_until(flash_step, |_, _, flashed| flashed == cells);
+        assert_eq!(rounds, 195);
+        assert!(grid.rows().flatten().all(|&e| e == 0));
+    }
+}
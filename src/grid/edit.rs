@@ -0,0 +1,169 @@
+use super::Grid;
+
+/// Inserting and removing rows and columns. Every edit moves the cells after it (rows) or
+/// rebuilds the whole buffer (columns), so each call takes time linear in the grid size. Row
+/// edits keep the unpadded row lengths of ragged grids, column edits discard them.
+impl<T> Grid<T> {
+    /// Inserts `row` before row `y`, `y == height` appends it.
+    pub fn insert_row(&mut self, y: usize, row: Vec<T>) {
+        assert!(
+            y <= self.height,
+            "can't insert row {y} into a grid with {} rows",
+            self.height
+        );
+        assert_eq!(
+            row.len(),
+            self.width,
+            "inserted row has length {} but the grid is {} wide",
+            row.len(),
+            self.width
+        );
+        let mut buf = std::mem::take(&mut self.buf).into_vec();
+        buf.splice(y * self.width..y * self.width, row);
+        self.buf = buf.into_boxed_slice();
+        self.height += 1;
+        if let Some(lens) = &mut self.row_lens {
+            let mut v = std::mem::take(lens).into_vec();
+            v.insert(y, self.width);
+            *lens = v.into_boxed_slice();
+        }
+    }
+
+    /// Inserts `col` before column `x`, `x == width` appends it.
+    pub fn insert_column(&mut self, x: usize, col: Vec<T>) {
+        assert!(
+            x <= self.width,
+            "can't insert column {x} into a grid with {} columns",
+            self.width
+        );
+        assert_eq!(
+            col.len(),
+            self.height,
+            "inserted column has length {} but the grid is {} high",
+            col.len(),
+            self.height
+        );
+        let width = self.width;
+        let mut old = std::mem::take(&mut self.buf).into_vec().into_iter();
+        let mut buf = Vec::with_capacity((width + 1) * self.height);
+        for cell in col {
+            buf.extend(old.by_ref().take(x));
+            buf.push(cell);
+            buf.extend(old.by_ref().take(width - x));
+        }
+        *self = Grid::from_buf(buf.into_boxed_slice(), width + 1, self.height);
+    }
+
+    pub fn remove_row(&mut self, y: usize) -> Vec<T> {
+        assert!(
+            y < self.height,
+            "can't remove row {y} from a grid with {} rows",
+            self.height
+        );
+        assert!(self.height > 1, "can't remove the only row of a grid");
+        let mut buf = std::mem::take(&mut self.buf).into_vec();
+        let row = buf.drain(y * self.width..(y + 1) * self.width).collect();
+        self.buf = buf.into_boxed_slice();
+        self.height -= 1;
+        if let Some(lens) = &mut self.row_lens {
+            let mut v = std::mem::take(lens).into_vec();
+            v.remove(y);
+            *lens = v.into_boxed_slice();
+        }
+        row
+    }
+
+    pub fn remove_column(&mut self, x: usize) -> Vec<T> {
+        assert!(
+            x < self.width,
+            "can't remove column {x} from a grid with {} columns",
+            self.width
+        );
+        assert!(self.width > 1, "can't remove the only column of a grid");
+        let width = self.width;
+        let mut buf = Vec::with_capacity((width - 1) * self.height);
+        let mut col = Vec::with_capacity(self.height);
+        for (i, cell) in std::mem::take(&mut self.buf)
+            .into_vec()
+            .into_iter()
+            .enumerate()
+        {
+            if i % width == x {
+                col.push(cell);
+            } else {
+                buf.push(cell);
+            }
+        }
+        *self = Grid::from_buf(buf.into_boxed_slice(), width - 1, self.height);
+        col
+    }
+
+    pub fn push_row(&mut self, row: Vec<T>) {
+        self.insert_row(self.height, row);
+    }
+
+    pub fn push_column(&mut self, col: Vec<T>) {
+        self.insert_column(self.width, col);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::Grid;
+
+    #[test]
+    fn interleaved_edits() {
+        let mut grid = Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        grid.insert_row(1, vec![7, 8, 9]);
+        grid.insert_column(0, vec![10, 11, 12]);
+        assert_eq!(grid.remove_column(2), [2, 8, 5]);
+        grid.push_column(vec![13, 14, 15]);
+        grid.push_row(vec![16, 17, 18, 19]);
+        assert_eq!(grid.remove_row(0), [10, 1, 3, 13]);
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(
+            grid,
+            Grid::from_nested(vec![
+                vec![11, 7, 9, 14],
+                vec![12, 4, 6, 15],
+                vec![16, 17, 18, 19]
+            ])
+        );
+        assert_eq!(grid[Vec2i::new(3, 1)], 15);
+        assert_eq!(grid[(1, 2)], 17);
+        assert_eq!(grid.pos_of(7), Vec2i::new(3, 1));
+    }
+
+    #[test]
+    fn cosmic_expansion() {
+        let mut image = Grid::from_str_chars("#..\n...\n..#");
+        for y in (0..image.height()).rev() {
+            if image.rows().nth(y).unwrap().iter().all(|&c| c == '.') {
+                image.insert_row(y, vec!['.'; image.width()]);
+            }
+        }
+        for x in (0..image.width()).rev() {
+            if (0..image.height()).all(|y| image[(x, y)] == '.') {
+                image.insert_column(x, vec!['.'; image.height()]);
+            }
+        }
+        assert_eq!(
+            image.find_all(&'#').collect::<Vec<_>>(),
+            [Vec2i::new(0, 0), Vec2i::new(3, 3)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "inserted row has length 2 but the grid is 3 wide")]
+    fn row_length_mismatch() {
+        Grid::new(3, 2, 0).insert_row(0, vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't remove column 3 from a grid with 3 columns")]
+    fn column_out_of_range() {
+        Grid::new(3, 2, 0).remove_column(3);
+    }
+}
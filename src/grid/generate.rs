@@ -0,0 +1,122 @@
+use vecm::Vec2i;
+
+use crate::Pcg32;
+
+use super::{Grid, DIRS4};
+
+impl Grid<bool> {
+    /// A perfect maze generated with a randomized depth-first backtracker, where `true` is
+    /// open. Rooms are the cells with even coordinates and every room is reachable from every
+    /// other one through exactly one path. With an even width or height the last column or row
+    /// has no rooms and stays closed.
+    pub fn generate_maze(width: usize, height: usize, rng: &mut Pcg32) -> Self {
+        assert!(width > 0 && height > 0, "maze can't be empty");
+        let mut grid = Self::from_buf(
+            vec![false; width * height].into_boxed_slice(),
+            width,
+            height,
+        );
+        let start = Vec2i::new(0, 0);
+        grid[start] = true;
+        let mut stack = vec![start];
+        let mut options = Vec::with_capacity(4);
+        while let Some(&pos) = stack.last() {
+            options.clear();
+            options.extend(
+                DIRS4
+                    .into_iter()
+                    .map(|(dx, dy)| Vec2i::new(dx, dy))
+                    .filter(|&d| {
+                        let next = pos + d + d;
+                        grid.in_bounds(next) && !grid[next]
+                    }),
+            );
+            if options.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let d = options[rng.index(options.len())];
+            grid[pos + d] = true;
+            grid[pos + d + d] = true;
+            stack.push(pos + d + d);
+        }
+        grid
+    }
+
+    /// A grid where each cell is closed with probability `density`, `true` meaning open.
+    pub fn random_obstacles(width: usize, height: usize, density: f64, rng: &mut Pcg32) -> Self {
+        Self::from_buf(
+            (0..width * height).map(|_| !rng.chance(density)).collect(),
+            width,
+            height,
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Surrounds the grid with a one cell wide border of `wall_value`, shifting all positions by
+    /// `(1, 1)`.
+    pub fn with_border_walls(self, wall_value: T) -> Self {
+        let (width, height) = (self.width + 2, self.height + 2);
+        let mut buf = Vec::with_capacity(width * height);
+        buf.extend(std::iter::repeat_n(wall_value.clone(), width));
+        for row in self.rows() {
+            buf.push(wall_value.clone());
+            buf.extend_from_slice(row);
+            buf.push(wall_value.clone());
+        }
+        buf.extend(std::iter::repeat_n(wall_value, width));
+        Self::from_buf(buf.into_boxed_slice(), width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::{Grid, Pcg32};
+
+    fn open_cells(grid: &Grid<bool>) -> usize {
+        grid.rows().flatten().filter(|&&open| open).count()
+    }
+
+    #[test]
+    fn maze_is_connected_tree() {
+        let mut rng = Pcg32::new(1);
+        for (w, h) in [(1, 1), (9, 7), (10, 8), (31, 15)] {
+            let maze = Grid::generate_maze(w, h, &mut rng);
+            let reached = maze.positions_within_steps(Vec2i::new(0, 0), usize::MAX, |_, &o| o);
+            assert_eq!(
+                reached.len(),
+                open_cells(&maze),
+                "{w}x{h} maze isn't connected"
+            );
+            let rooms = w.div_ceil(2) * h.div_ceil(2);
+            assert_eq!(open_cells(&maze), 2 * rooms - 1);
+            if w % 2 == 0 {
+                assert!((0..h).all(|y| !maze[(w - 1, y)]));
+            }
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let a = Grid::generate_maze(21, 21, &mut Pcg32::new(5));
+        let b = Grid::generate_maze(21, 21, &mut Pcg32::new(5));
+        let c = Grid::generate_maze(21, 21, &mut Pcg32::new(6));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        let obstacles = Grid::random_obstacles(50, 50, 0.3, &mut Pcg32::new(5));
+        let closed = 2500 - open_cells(&obstacles);
+        assert!((600..900).contains(&closed));
+    }
+
+    #[test]
+    fn border_walls() {
+        let grid = Grid::from_str_chars("ab\ncd").with_border_walls('#');
+        assert_eq!(
+            grid.pretty().plain().to_string(),
+            "####\n#ab#\n#cd#\n####\n"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::{collections::HashMap, hash::Hash};
+
+use vecm::Vec2i;
+
+use super::Grid;
+
+impl<T: Clone + Eq + Hash> Grid<T> {
+    /// How often every `w` x `h` neighborhood occurs among the [`Grid::windows`], with the
+    /// cells of a neighborhood in reading order.
+    pub fn neighborhood_histogram(&self, w: usize, h: usize) -> HashMap<Vec<T>, usize> {
+        let mut histogram = HashMap::new();
+        for (_, window) in self.windows(w, h) {
+            *histogram
+                .entry(window.iter().cloned().collect())
+                .or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// The windows whose neighborhood occurs at most `max_count` times, with their origins in
+    /// reading order. Markers hidden in an otherwise regular input show up here.
+    pub fn rare_neighborhoods(&self, w: usize, h: usize, max_count: usize) -> Vec<(Vec2i, Vec<T>)> {
+        let histogram = self.neighborhood_histogram(w, h);
+        self.windows(w, h)
+            .map(|(origin, window)| (origin, window.iter().cloned().collect::<Vec<T>>()))
+            .filter(|(_, cells)| histogram[cells] <= max_count)
+            .collect()
+    }
+
+    /// The Shannon entropy of the cell values in bits, 0 for a grid of a single value.
+    pub fn value_entropy(&self) -> f64 {
+        let mut counts: HashMap<&T, usize> = HashMap::new();
+        for cell in self.buf.iter() {
+            *counts.entry(cell).or_insert(0) += 1;
+        }
+        let total = self.buf.len() as f64;
+        counts
+            .values()
+            .map(|&n| {
+                let p = n as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_counts() {
+        let grid = Grid::from_str_bytes("#.#.#\n.#.#.\n#.#.#\n.#.#.");
+        let histogram = grid.neighborhood_histogram(3, 3);
+        assert_eq!(histogram.values().sum::<usize>(), 3 * 2);
+        assert_eq!(histogram[&b"#.#.#.#.#".to_vec()], 3);
+        assert_eq!(histogram[&b".#.#.#.#.".to_vec()], 3);
+        let cells = grid.neighborhood_histogram(1, 1);
+        assert_eq!(cells[&vec![b'#']], 10);
+        assert_eq!(cells.values().sum::<usize>(), 20);
+        assert!(grid.neighborhood_histogram(6, 1).is_empty());
+    }
+
+    #[test]
+    fn planted_anomaly() {
+        let mut grid = Grid::from_fn(12, 10, |pos| (pos.x + pos.y) % 3);
+        let planted = Vec2i::new(5, 4);
+        grid[planted] = 7;
+        let rare = grid.rare_neighborhoods(3, 3, 1);
+        assert_eq!(rare.len(), 9);
+        assert_eq!(rare[0].0, Vec2i::new(3, 2));
+        for (origin, cells) in &rare {
+            let offset = planted - *origin;
+            assert!((0..3).contains(&offset.x) && (0..3).contains(&offset.y));
+            assert_eq!(cells[(offset.y * 3 + offset.x) as usize], 7);
+        }
+        // without the anomaly there are only the three diagonal phases
+        grid[planted] = 0;
+        assert_eq!(grid.neighborhood_histogram(3, 3).len(), 3);
+        assert!(grid.rare_neighborhoods(3, 3, 5).is_empty());
+    }
+
+    #[test]
+    fn entropy() {
+        assert_eq!(Grid::new(4, 3, 'x').value_entropy(), 0.0);
+        let uniform = Grid::from_fn(4, 4, |pos| pos.x);
+        assert!((uniform.value_entropy() - 2.0).abs() < 1e-12);
+        let halves = Grid::from_fn(3, 2, |pos| pos.y);
+        assert!((halves.value_entropy() - 1.0).abs() < 1e-12);
+        let skewed = Grid::from_str_bytes("aaab");
+        assert!(skewed.value_entropy() > 0.0 && skewed.value_entropy() < 1.0);
+    }
+}
@@ -0,0 +1,181 @@
+use vecm::Vec2i;
+
+use super::{Grid, DIRS4};
+use crate::UnionFind;
+
+/// Tracks whether `goal` can still be reached from `start` while cells get blocked one by one.
+/// Keeps the last shortest path and only searches again when a cell on it gets blocked, as
+/// blocking any other cell can't make that path longer.
+#[derive(Debug, Clone)]
+pub struct IncrementalConnectivity {
+    blocked: Grid<bool>,
+    start: Vec2i,
+    goal: Vec2i,
+    path: Option<Vec<Vec2i>>,
+    on_path: Grid<bool>,
+    /// The number of searches so far, including the initial one.
+    pub searches: usize,
+}
+impl IncrementalConnectivity {
+    pub fn new(width: usize, height: usize, start: Vec2i, goal: Vec2i) -> Self {
+        let blocked = Grid::new(width, height, false);
+        assert!(
+            blocked.in_bounds(start) && blocked.in_bounds(goal),
+            "start {start:?} and goal {goal:?} have to be inside the {width}x{height} grid"
+        );
+        let mut this = Self {
+            on_path: blocked.clone(),
+            blocked,
+            start,
+            goal,
+            path: None,
+            searches: 0,
+        };
+        this.search();
+        this
+    }
+
+    fn search(&mut self) {
+        self.searches += 1;
+        // shortest_path treats the start as reached even if it isn't passable
+        self.path = if self.blocked[self.start] {
+            None
+        } else {
+            self.blocked
+                .shortest_path(self.start, self.goal, |_, &blocked| !blocked)
+        };
+        self.on_path = self.blocked.same_size_with(false);
+        for &pos in self.path.iter().flatten() {
+            self.on_path[pos] = true;
+        }
+    }
+
+    pub fn block(&mut self, pos: Vec2i) {
+        if std::mem::replace(&mut self.blocked[pos], true) || !self.on_path[pos] {
+            return;
+        }
+        self.search();
+    }
+
+    pub fn connected(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// The current shortest path including both endpoints.
+    pub fn path(&self) -> Option<&[Vec2i]> {
+        self.path.as_deref()
+    }
+
+    /// The number of steps on the current shortest path.
+    pub fn distance(&self) -> Option<usize> {
+        Some(self.path.as_ref()?.len() - 1)
+    }
+}
+
+/// The index of the first obstacle after which `goal` can't be reached from `start` anymore,
+/// orthogonal moves only. Blocks all obstacles and then removes them in reverse with a
+/// union-find, so it takes a single pass instead of a search per obstacle.
+pub fn first_disconnecting(
+    width: usize,
+    height: usize,
+    start: Vec2i,
+    goal: Vec2i,
+    obstacles: &[Vec2i],
+) -> Option<usize> {
+    let mut first_blocked: Grid<Option<usize>> = Grid::new(width, height, None);
+    for (i, &pos) in obstacles.iter().enumerate() {
+        first_blocked[pos].get_or_insert(i);
+    }
+    let mut sets = UnionFind::new(width * height);
+    let open_cell = |sets: &mut UnionFind, blocked: &Grid<Option<usize>>, pos: Vec2i| {
+        let i = blocked.index_of(pos);
+        for (dx, dy) in DIRS4 {
+            let next = pos + Vec2i::new(dx, dy);
+            if blocked.in_bounds(next) && blocked[next].is_none() {
+                sets.union(i, blocked.index_of(next));
+            }
+        }
+    };
+    for pos in first_blocked.positions() {
+        if first_blocked[pos].is_none() {
+            open_cell(&mut sets, &first_blocked, pos);
+        }
+    }
+    let (s, g) = (first_blocked.index_of(start), first_blocked.index_of(goal));
+    let connected = |sets: &mut UnionFind, blocked: &Grid<Option<usize>>| {
+        blocked[start].is_none() && blocked[goal].is_none() && sets.same(s, g)
+    };
+    if connected(&mut sets, &first_blocked) {
+        return None;
+    }
+    for (i, &pos) in obstacles.iter().enumerate().rev() {
+        if first_blocked[pos] != Some(i) {
+            continue;
+        }
+        first_blocked[pos] = None;
+        open_cell(&mut sets, &first_blocked, pos);
+        if connected(&mut sets, &first_blocked) {
+            return Some(i);
+        }
+    }
+    unreachable!("the grid without obstacles is connected")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    const BYTES: &str = "5,4 4,2 4,5 3,0 2,1 6,3 2,4 1,5 0,6 3,3 2,6 5,1 1,2 \
+                         5,5 2,5 6,5 1,4 0,4 6,4 1,1 6,1 1,0 0,5 1,6 2,0";
+
+    fn bytes() -> Vec<Vec2i> {
+        BYTES
+            .split_whitespace()
+            .map(|b| {
+                let (x, y) = b.split_once(',').unwrap();
+                Vec2i::new(crate::int(x) as i32, crate::int(y) as i32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn falling_bytes() {
+        let bytes = bytes();
+        let (start, goal) = (Vec2i::new(0, 0), Vec2i::new(6, 6));
+        let mut memory = IncrementalConnectivity::new(7, 7, start, goal);
+        for &b in &bytes[..12] {
+            memory.block(b);
+        }
+        assert_eq!(memory.distance(), Some(22));
+        let first = bytes[12..]
+            .iter()
+            .position(|&b| {
+                memory.block(b);
+                !memory.connected()
+            })
+            .map(|i| i + 12);
+        assert_eq!(first, Some(20));
+        assert_eq!(bytes[20], Vec2i::new(6, 1));
+        assert!(memory.searches < 20);
+        assert_eq!(first_disconnecting(7, 7, start, goal, &bytes), Some(20));
+        assert_eq!(first_disconnecting(7, 7, start, goal, &bytes[..20]), None);
+    }
+
+    #[test]
+    fn reverse_matches_incremental() {
+        let mut rng = Pcg32::new(21);
+        for _ in 0..50 {
+            let (w, h) = (rng.index(6) + 1, rng.index(6) + 1);
+            let pos = |rng: &mut Pcg32| Vec2i::new(rng.index(w) as i32, rng.index(h) as i32);
+            let (start, goal) = (pos(&mut rng), pos(&mut rng));
+            let obstacles: Vec<Vec2i> = (0..rng.index(w * h + 3)).map(|_| pos(&mut rng)).collect();
+            let mut inc = IncrementalConnectivity::new(w, h, start, goal);
+            let expected = obstacles.iter().position(|&b| {
+                inc.block(b);
+                !inc.connected()
+            });
+            assert_eq!(first_disconnecting(w, h, start, goal, &obstacles), expected);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::iter::Enumerate;
+
+use vecm::Vec2i;
+
+use super::Grid;
+
+fn pos_at(i: usize, width: usize) -> Vec2i {
+    Vec2i::new((i % width) as i32, (i / width) as i32)
+}
+
+/// Cells with their positions in row-major order, see [`Grid::iter`].
+pub struct Iter<'a, T> {
+    cells: Enumerate<std::slice::Iter<'a, T>>,
+    width: usize,
+}
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Vec2i, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.cells.next()?;
+        Some((pos_at(i, self.width), cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+/// Mutable cells with their positions in row-major order, see [`Grid::iter_mut`].
+pub struct IterMut<'a, T> {
+    cells: Enumerate<std::slice::IterMut<'a, T>>,
+    width: usize,
+}
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Vec2i, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.cells.next()?;
+        Some((pos_at(i, self.width), cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+/// Owned cells with their positions in row-major order.
+pub struct IntoIter<T> {
+    cells: Enumerate<std::vec::IntoIter<T>>,
+    width: usize,
+}
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Vec2i, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.cells.next()?;
+        Some((pos_at(i, self.width), cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<T> Grid<T> {
+    /// All cells with their positions. The order is always row-major: left to right within a
+    /// row, rows from top to bottom.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            cells: self.buf.iter().enumerate(),
+            width: self.width,
+        }
+    }
+
+    /// Like [`Grid::iter`], but the cells can be modified.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            cells: self.buf.iter_mut().enumerate(),
+            width: self.width,
+        }
+    }
+
+    /// All cells in row-major order.
+    pub fn values(&self) -> std::slice::Iter<'_, T> {
+        self.buf.iter()
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.buf.chunks_mut(self.width)
+    }
+}
+
+/// Iterating a grid yields `(position, cell)` pairs in row-major order, like [`Grid::iter`].
+impl<T> IntoIterator for Grid<T> {
+    type Item = (Vec2i, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            cells: self.buf.into_vec().into_iter().enumerate(),
+            width: self.width,
+        }
+    }
+}
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = (Vec2i, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
+    type Item = (Vec2i, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutate_through_iter_mut() {
+        let mut grid = Grid::from_nested(vec![vec!['.', 'O', '#'], vec!['O', 'O', '.']]);
+        grid.iter_mut()
+            .filter(|(_, c)| **c == 'O')
+            .for_each(|(pos, c)| *c = if pos.y == 0 { 'x' } else { 'y' });
+        assert_eq!(
+            grid,
+            Grid::from_nested(vec![vec!['.', 'x', '#'], vec!['y', 'y', '.']])
+        );
+        for (pos, c) in &mut grid {
+            if pos.x == 2 {
+                *c = '|';
+            }
+        }
+        for row in grid.rows_mut() {
+            row[0] = '>';
+        }
+        assert_eq!(
+            grid,
+            Grid::from_nested(vec![vec!['>', 'x', '|'], vec!['>', 'y', '|']])
+        );
+    }
+
+    #[test]
+    fn row_major_sum() {
+        let grid = Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let mut sum = 0;
+        let mut order = Vec::new();
+        for (pos, value) in &grid {
+            sum += value;
+            order.push(pos);
+        }
+        assert_eq!(sum, 21);
+        assert_eq!(order, grid.positions().collect::<Vec<_>>());
+        assert_eq!(grid.values().sum::<i32>(), 21);
+        let owned: Vec<(Vec2i, i32)> = grid.into_iter().collect();
+        assert_eq!(owned[4], (Vec2i::new(1, 1), 5));
+    }
+}
@@ -0,0 +1,99 @@
+use vecm::Vec2i;
+
+use super::{Connectivity, Grid};
+
+impl Grid<bool> {
+    fn neighborhood(
+        &self,
+        pos: Vec2i,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = bool> + '_ {
+        connectivity.dirs().iter().map(move |&(dx, dy)| {
+            let p = pos + Vec2i::new(dx, dy);
+            self.in_bounds(p) && self[p]
+        })
+    }
+
+    fn map_cells(&self, mut f: impl FnMut(Vec2i, bool) -> bool) -> Self {
+        Self::from_buf(
+            self.positions().map(|pos| f(pos, self[pos])).collect(),
+            self.width,
+            self.height,
+        )
+    }
+
+    /// Sets every cell with a set neighbor. Cells outside the grid count as unset.
+    pub fn dilate(&self, connectivity: Connectivity) -> Self {
+        self.map_cells(|pos, set| set || self.neighborhood(pos, connectivity).any(|n| n))
+    }
+
+    /// Keeps only the cells whose neighbors are all set. Cells outside the grid count as unset,
+    /// so set cells on the edge are always removed.
+    pub fn erode(&self, connectivity: Connectivity) -> Self {
+        self.map_cells(|pos, set| set && self.neighborhood(pos, connectivity).all(|n| n))
+    }
+
+    /// The set cells with an unset orthogonal neighbor or on the edge of the grid.
+    pub fn boundary(&self) -> Self {
+        self.map_cells(|pos, set| set && !self.neighborhood(pos, Connectivity::Four).all(|n| n))
+    }
+
+    /// Applies an operation like [`Grid::dilate`] `n` times.
+    pub fn n_iterations(&self, op: impl Fn(&Self) -> Self, n: usize) -> Self {
+        crate::nth_iterate(self.clone(), op, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::{grid::Connectivity, Grid};
+
+    fn parse(s: &str) -> Grid<bool> {
+        Grid::from_str_chars(s).map(|c| c == '#')
+    }
+
+    #[test]
+    fn dilate_point() {
+        let mut point = Grid::from_nested(vec![vec![false; 5]; 5]);
+        point[Vec2i::new(2, 2)] = true;
+        assert_eq!(
+            point.dilate(Connectivity::Four),
+            parse(".....\n..#..\n.###.\n..#..\n.....")
+        );
+        assert_eq!(
+            point.dilate(Connectivity::Eight),
+            parse(".....\n.###.\n.###.\n.###.\n.....")
+        );
+        let diamond = point.n_iterations(|g| g.dilate(Connectivity::Four), 2);
+        assert_eq!(diamond, parse("..#..\n.###.\n#####\n.###.\n..#.."));
+    }
+
+    #[test]
+    fn closing_keeps_convex_shapes() {
+        let square = parse(".......\n.......\n..###..\n..###..\n..###..\n.......\n.......");
+        let closed = square
+            .dilate(Connectivity::Eight)
+            .erode(Connectivity::Eight);
+        assert_eq!(closed, square);
+        let diamond = parse(".......\n...#...\n..###..\n.#####.\n..###..\n...#...\n.......");
+        let closed = diamond.dilate(Connectivity::Four).erode(Connectivity::Four);
+        assert_eq!(closed, diamond);
+        assert_eq!(
+            diamond.erode(Connectivity::Four),
+            parse(".......\n.......\n...#...\n..###..\n...#...\n.......\n.......")
+        );
+    }
+
+    #[test]
+    fn boundary_of_rectangle() {
+        let filled = Grid::from_nested(vec![vec![true; 5]; 4]);
+        assert_eq!(filled.boundary(), parse("#####\n#...#\n#...#\n#####"));
+        let inner = parse("......\n.####.\n.####.\n.####.\n......");
+        assert_eq!(
+            inner.boundary(),
+            parse("......\n.####.\n.#..#.\n.####.\n......")
+        );
+    }
+}
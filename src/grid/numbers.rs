@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use vecm::Vec2i;
+
+use super::Grid;
+
+/// A run of decimal digits within a row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridNumber {
+    pub value: u64,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+impl GridNumber {
+    /// The ring of cells around the number, clipped to the grid.
+    pub fn adjacent_positions<'a, T>(&self, grid: &'a Grid<T>) -> impl Iterator<Item = Vec2i> + 'a {
+        let y = self.row as i32;
+        let (x0, x1) = (self.cols.start as i32 - 1, self.cols.end as i32);
+        let above_below = (x0..=x1).flat_map(move |x| [Vec2i::new(x, y - 1), Vec2i::new(x, y + 1)]);
+        [Vec2i::new(x0, y), Vec2i::new(x1, y)]
+            .into_iter()
+            .chain(above_below)
+            .filter(|&p| grid.in_bounds(p))
+    }
+}
+
+impl Grid<char> {
+    /// All numbers in row-major order. Numbers don't continue across rows.
+    pub fn numbers(&self) -> Vec<GridNumber> {
+        let mut numbers = Vec::new();
+        for (y, row) in self.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                let mut value: u64 = 0;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    value = value * 10 + row[x].to_digit(10).unwrap() as u64;
+                    x += 1;
+                }
+                numbers.push(GridNumber {
+                    value,
+                    row: y,
+                    cols: start..x,
+                });
+            }
+        }
+        numbers
+    }
+
+    /// The cells whose character satisfies `pred`, in row-major order.
+    pub fn symbols(&self, pred: impl Fn(char) -> bool) -> Vec<(Vec2i, char)> {
+        self.find_all_by(|_, &c| pred(c))
+            .map(|p| (p, self[p]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use vecm::Vec2i;
+
+    use super::GridNumber;
+    use crate::Grid;
+
+    const SCHEMATIC: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn is_symbol(c: char) -> bool {
+        c != '.' && !c.is_ascii_digit()
+    }
+
+    #[test]
+    fn engine_schematic() {
+        let grid = Grid::from_str_chars(SCHEMATIC);
+        let numbers = grid.numbers();
+        assert_eq!(numbers.len(), 10);
+        let part_sum: u64 = numbers
+            .iter()
+            .filter(|n| n.adjacent_positions(&grid).any(|p| is_symbol(grid[p])))
+            .map(|n| n.value)
+            .sum();
+        assert_eq!(part_sum, 4361);
+
+        let mut gears: HashMap<Vec2i, Vec<u64>> = grid
+            .symbols(|c| c == '*')
+            .into_iter()
+            .map(|(p, _)| (p, Vec::new()))
+            .collect();
+        for n in &numbers {
+            for p in n.adjacent_positions(&grid) {
+                if let Some(adjacent) = gears.get_mut(&p) {
+                    adjacent.push(n.value);
+                }
+            }
+        }
+        let ratios: u64 = gears
+            .values()
+            .filter(|v| v.len() == 2)
+            .map(|v| v[0] * v[1])
+            .sum();
+        assert_eq!(ratios, 467835);
+    }
+
+    #[test]
+    fn numbers_at_edges() {
+        let grid = Grid::from_str_chars("12.\n..#\n.45");
+        assert_eq!(
+            grid.numbers(),
+            [
+                GridNumber {
+                    value: 12,
+                    row: 0,
+                    cols: 0..2
+                },
+                GridNumber {
+                    value: 45,
+                    row: 2,
+                    cols: 1..3
+                }
+            ]
+        );
+        let corner: Vec<Vec2i> = grid.numbers()[0].adjacent_positions(&grid).collect();
+        assert_eq!(
+            corner,
+            [
+                Vec2i::new(2, 0),
+                Vec2i::new(0, 1),
+                Vec2i::new(1, 1),
+                Vec2i::new(2, 1)
+            ]
+        );
+        let last: Vec<Vec2i> = grid.numbers()[1].adjacent_positions(&grid).collect();
+        assert_eq!(
+            last,
+            [
+                Vec2i::new(0, 2),
+                Vec2i::new(0, 1),
+                Vec2i::new(1, 1),
+                Vec2i::new(2, 1)
+            ]
+        );
+        assert_eq!(grid.symbols(|c| c == '#'), [(Vec2i::new(2, 1), '#')]);
+    }
+}
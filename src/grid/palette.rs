@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use crate::Color;
+
+use super::{Grid, PrettyGrid};
+
+type Matcher<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+/// Maps cell values to display glyphs and optional colors. Entries are checked in the order
+/// they were added and the first match wins.
+pub struct Palette<'a, T> {
+    entries: Vec<(Matcher<'a, T>, char, Option<Color>)>,
+}
+impl<'a, T> Palette<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn from_values(entries: impl IntoIterator<Item = (T, char, Option<Color>)>) -> Self
+    where
+        T: PartialEq + 'a,
+    {
+        entries
+            .into_iter()
+            .fold(Self::new(), |palette, (value, glyph, color)| {
+                palette.value(value, glyph, color)
+            })
+    }
+
+    pub fn value(self, value: T, glyph: char, color: Option<Color>) -> Self
+    where
+        T: PartialEq + 'a,
+    {
+        self.matching(move |v| *v == value, glyph, color)
+    }
+
+    pub fn matching(
+        mut self,
+        matcher: impl Fn(&T) -> bool + 'a,
+        glyph: char,
+        color: Option<Color>,
+    ) -> Self {
+        self.entries.push((Box::new(matcher), glyph, color));
+        self
+    }
+
+    pub fn lookup(&self, value: &T) -> Option<(char, Option<Color>)> {
+        self.entries
+            .iter()
+            .find(|(matcher, _, _)| matcher(value))
+            .map(|&(_, glyph, color)| (glyph, color))
+    }
+}
+impl<T> Default for Palette<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Display> Grid<T> {
+    /// Renders the grid like [`PrettyGrid`] with the given palette but without any colors.
+    pub fn render_with(&self, palette: &Palette<T>) -> String {
+        PrettyGrid::new(self)
+            .with_palette(palette)
+            .colored(false)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Grid};
+
+    use super::Palette;
+
+    fn palette() -> Palette<'static, char> {
+        Palette::from_values([('#', '█', Some(Color::Blue)), ('.', ' ', None)]).matching(
+            |c| c.is_ascii_uppercase(),
+            '@',
+            Some(Color::Red),
+        )
+    }
+
+    #[test]
+    fn render_palette_colored() {
+        let grid = Grid::from_str_chars("#.E\n+#.");
+        let palette = palette();
+        let rendered = grid
+            .pretty()
+            .with_palette(&palette)
+            .colored(true)
+            .to_string();
+        let default = "\x1b[38;2;192;192;192m";
+        assert_eq!(
+            rendered,
+            format!(
+                "\x1b[34m█\x1b[0m{default} \x1b[0m\x1b[31m@\x1b[0m\n\
+                 {default}+\x1b[0m\x1b[34m█\x1b[0m{default} \x1b[0m\n"
+            )
+        );
+    }
+
+    #[test]
+    fn render_palette_plain() {
+        let grid = Grid::from_str_chars("#.E\n+#.");
+        assert_eq!(grid.render_with(&palette()), "█ @\n+█ \n");
+    }
+}
@@ -0,0 +1,148 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+const UNVISITED: u8 = 0;
+const ON_STACK: u8 = 1;
+const DONE: u8 = 2;
+
+impl<T> Grid<T> {
+    /// Counts all distinct paths that start on a cell matching `starts`, only take 4-neighbor
+    /// steps from `a` to `b` where `step_ok(a, b)` holds and stop at the first cell matching
+    /// `ends`. The step relation has to form a DAG, a cycle causes a panic naming one of its
+    /// cells.
+    pub fn count_paths_increasing(
+        &self,
+        starts: impl Fn(&T) -> bool,
+        step_ok: impl Fn(&T, &T) -> bool,
+        ends: impl Fn(&T) -> bool,
+    ) -> u64 {
+        let mut memo = vec![0u64; self.buf.len()];
+        let mut state = vec![UNVISITED; self.buf.len()];
+        let mut total = 0;
+        for start in self.positions() {
+            if !starts(&self[start]) {
+                continue;
+            }
+            self.paths_dfs(start, &step_ok, &ends, &mut memo, &mut state);
+            total += memo[self.index_of(start)];
+        }
+        total
+    }
+
+    fn paths_dfs(
+        &self,
+        start: Vec2i,
+        step_ok: &impl Fn(&T, &T) -> bool,
+        ends: &impl Fn(&T) -> bool,
+        memo: &mut [u64],
+        state: &mut [u8],
+    ) {
+        let mut stack = vec![(start, false)];
+        while let Some((pos, expanded)) = stack.pop() {
+            let i = self.index_of(pos);
+            if expanded {
+                memo[i] = self
+                    .neighbor_positions4(pos)
+                    .filter(|&n| step_ok(&self[pos], &self[n]))
+                    .map(|n| memo[self.index_of(n)])
+                    .sum();
+                state[i] = DONE;
+                continue;
+            }
+            match state[i] {
+                DONE => continue,
+                ON_STACK => panic!(
+                    "cycle detected through ({}, {}), step_ok is not a strict ordering",
+                    pos.x, pos.y
+                ),
+                _ => {}
+            }
+            if ends(&self[pos]) {
+                memo[i] = 1;
+                state[i] = DONE;
+                continue;
+            }
+            state[i] = ON_STACK;
+            stack.push((pos, true));
+            for n in self.neighbor_positions4(pos) {
+                if step_ok(&self[pos], &self[n]) {
+                    let j = self.index_of(n);
+                    if state[j] == ON_STACK {
+                        panic!(
+                            "cycle detected through ({}, {}), step_ok is not a strict ordering",
+                            n.x, n.y
+                        );
+                    }
+                    if state[j] == UNVISITED {
+                        stack.push((n, false));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts the distinct cells matching `ends` that can be reached from `start` by steps
+    /// where `step_ok` holds. In contrast to [`Grid::count_paths_increasing`] each end cell
+    /// only counts once no matter how many paths lead to it.
+    pub fn reachable_ends(
+        &self,
+        start: Vec2i,
+        step_ok: impl Fn(&T, &T) -> bool,
+        ends: impl Fn(&T) -> bool,
+    ) -> usize {
+        let mut visited = vec![false; self.buf.len()];
+        visited[self.index_of(start)] = true;
+        let mut stack = vec![start];
+        let mut count = 0;
+        while let Some(pos) = stack.pop() {
+            if ends(&self[pos]) {
+                count += 1;
+                continue;
+            }
+            for n in self.neighbor_positions4(pos) {
+                let j = self.index_of(n);
+                if !visited[j] && step_ok(&self[pos], &self[n]) {
+                    visited[j] = true;
+                    stack.push(n);
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    const TRAILS: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    #[test]
+    fn trailhead_score_and_rating() {
+        let grid = Grid::from_str_bytes(TRAILS);
+        let step = |a: &u8, b: &u8| *b == a + 1;
+        let score: usize = grid
+            .positions()
+            .filter(|&p| grid[p] == b'0')
+            .map(|p| grid.reachable_ends(p, step, |&h| h == b'9'))
+            .sum();
+        assert_eq!(score, 36);
+        let rating = grid.count_paths_increasing(|&h| h == b'0', step, |&h| h == b'9');
+        assert_eq!(rating, 81);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle detected")]
+    fn cycle_is_reported() {
+        let grid = Grid::from_str_bytes("ab\ncd");
+        grid.count_paths_increasing(|&c| c == b'a', |_, _| true, |&c| c == b'z');
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use vecm::Vec2i;
+
+use super::Grid;
+use crate::BitSet64;
+
+/// Shortest distances between every pair of labeled points of interest, each with the blocking
+/// labels on the route. Bit `i` of a required set stands for the `i`-th point, so a route
+/// through door `A` can require key `a` and be checked against the set of collected keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoiMatrix {
+    pois: Vec<(char, Vec2i)>,
+    /// `(distance, required)` from point `i` to point `j` at `i * n + j`.
+    entries: Vec<Option<(u32, BitSet64)>>,
+}
+impl PoiMatrix {
+    pub fn pois(&self) -> &[(char, Vec2i)] {
+        &self.pois
+    }
+
+    /// The index of the point with the label, which is also its bit in required sets.
+    pub fn index(&self, label: char) -> Option<usize> {
+        self.pois.iter().position(|&(l, _)| l == label)
+    }
+
+    fn entry(&self, a: char, b: char) -> Option<(u32, BitSet64)> {
+        let index = |label| {
+            self.index(label)
+                .unwrap_or_else(|| panic!("no point of interest {label:?}"))
+        };
+        self.entries[index(a) * self.pois.len() + index(b)]
+    }
+
+    /// The length of a shortest route between the points, `None` if they aren't connected.
+    /// Panics for unknown labels.
+    pub fn dist(&self, a: char, b: char) -> Option<u32> {
+        self.entry(a, b).map(|(dist, _)| dist)
+    }
+
+    /// The points whose labels block the shortest route between `a` and `b`.
+    pub fn required(&self, a: char, b: char) -> Option<BitSet64> {
+        self.entry(a, b).map(|(_, required)| required)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Runs a BFS from every point of interest through `passable` cells. Every cell that
+    /// `blockers` gives a label adds the point with that label to the required set of the
+    /// routes through it, labels that aren't points are ignored. Of several shortest routes
+    /// the one found first is kept. Panics for more than 64 points or duplicate labels.
+    pub fn poi_distance_matrix(
+        &self,
+        pois: &[(char, Vec2i)],
+        passable: impl Fn(Vec2i, &T) -> bool,
+        blockers: impl Fn(&T) -> Option<char>,
+    ) -> PoiMatrix {
+        assert!(
+            pois.len() <= 64,
+            "{} points don't fit into a BitSet64",
+            pois.len()
+        );
+        for (i, &(label, _)) in pois.iter().enumerate() {
+            assert!(
+                pois[..i].iter().all(|&(l, _)| l != label),
+                "duplicate point of interest {label:?}"
+            );
+        }
+        let blocker_bit = |pos: Vec2i| {
+            let label = blockers(&self[pos])?;
+            pois.iter().position(|&(l, _)| l == label)
+        };
+        let mut entries = Vec::with_capacity(pois.len() * pois.len());
+        for &(_, start) in pois {
+            let mut reached: Grid<Option<(u32, BitSet64)>> = self.same_size_with(None);
+            reached[start] = Some((0, BitSet64::EMPTY));
+            let mut queue = VecDeque::from([start]);
+            while let Some(pos) = queue.pop_front() {
+                let (d, required) = reached[pos].unwrap();
+                for next in self.neighbor_positions4(pos) {
+                    if reached[next].is_none() && passable(next, &self[next]) {
+                        let required = match blocker_bit(next) {
+                            Some(bit) => required.with(bit as u32),
+                            None => required,
+                        };
+                        reached[next] = Some((d + 1, required));
+                        queue.push_back(next);
+                    }
+                }
+            }
+            entries.extend(pois.iter().map(|&(_, end)| reached[end]));
+        }
+        PoiMatrix {
+            pois: pois.to_vec(),
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra;
+
+    /// Collects every key of a vault with a Dijkstra over `(position, collected keys)`.
+    fn collect_all_keys(vault: &str) -> u64 {
+        let grid = Grid::from_str_chars(vault);
+        let pois: Vec<(char, Vec2i)> = grid
+            .iter()
+            .filter(|(_, c)| c.is_ascii_lowercase() || **c == '@')
+            .map(|(pos, &c)| (c, pos))
+            .collect();
+        let matrix = grid.poi_distance_matrix(
+            &pois,
+            |_, &c| c != '#',
+            |&c| c.is_ascii_uppercase().then(|| c.to_ascii_lowercase()),
+        );
+        let keys: Vec<char> = pois.iter().map(|&(c, _)| c).filter(|&c| c != '@').collect();
+        let all = keys.iter().fold(BitSet64::EMPTY, |set, &k| {
+            set.with(matrix.index(k).unwrap() as u32)
+        });
+        let (cost, _) = dijkstra(
+            ('@', BitSet64::EMPTY),
+            |&(at, collected)| {
+                keys.iter()
+                    .filter(|&&k| !collected.contains(matrix.index(k).unwrap() as u32))
+                    .filter_map(|&k| {
+                        let required = matrix.required(at, k)?;
+                        let bit = matrix.index(k).unwrap() as u32;
+                        required
+                            .is_subset(collected)
+                            .then(|| ((k, collected.with(bit)), matrix.dist(at, k).unwrap() as u64))
+                    })
+                    .collect()
+            },
+            |&(_, collected)| collected == all,
+        )
+        .unwrap();
+        cost
+    }
+
+    #[test]
+    fn vault_keys() {
+        assert_eq!(collect_all_keys("#########\n#b.A.@.a#\n#########"), 8);
+        assert_eq!(
+            collect_all_keys(
+                "########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################"
+            ),
+            86
+        );
+        assert_eq!(
+            collect_all_keys(
+                "########################
+#...............b.C.D.f#
+#.######################
+#.....@.a.B.c.d.A.e.F.g#
+########################"
+            ),
+            132
+        );
+    }
+
+    #[test]
+    fn matrix_entries() {
+        let grid = Grid::from_str_chars("#########\n#b.A.@.a#\n####.####\n#c...#..#\n#########");
+        let pois: Vec<(char, Vec2i)> = "@abc"
+            .chars()
+            .map(|c| (c, grid.find(&c).unwrap()))
+            .collect();
+        let matrix = grid.poi_distance_matrix(
+            &pois,
+            |_, &c| c != '#',
+            |&c| c.is_ascii_uppercase().then(|| c.to_ascii_lowercase()),
+        );
+        assert_eq!(matrix.pois(), &pois[..]);
+        assert_eq!(matrix.dist('@', 'a'), Some(2));
+        assert_eq!(matrix.dist('a', 'b'), Some(6));
+        assert_eq!(matrix.dist('b', 'b'), Some(0));
+        assert_eq!(matrix.required('b', 'a'), Some(BitSet64::EMPTY.with(1)));
+        assert_eq!(matrix.required('@', 'a'), Some(BitSet64::EMPTY));
+        assert_eq!(matrix.dist('c', 'b'), Some(8));
+        assert_eq!(matrix.required('c', 'b'), Some(BitSet64::EMPTY.with(1)));
+        // points in separate rooms aren't connected
+        let walled = Grid::from_str_chars("#####\n#a#b#\n#####");
+        let pois = [('a', Vec2i::new(1, 1)), ('b', Vec2i::new(3, 1))];
+        let matrix = walled.poi_distance_matrix(&pois, |_, &c| c != '#', |_| None);
+        assert_eq!(matrix.dist('a', 'b'), None);
+        assert_eq!(matrix.required('b', 'a'), None);
+    }
+}
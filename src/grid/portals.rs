@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use vecm::Vec2i;
+
+use super::{Grid, DIRS4};
+
+impl<T> Grid<T> {
+    /// BFS from `start` to `goal` where stepping onto a cell in `portals` also allows a single
+    /// step to the paired cell, changing the level by the portal's delta. The level never
+    /// becomes negative and the goal only counts at level 0. Levels are capped at the number of
+    /// portal cells to keep unsolvable recursive mazes finite, which is a heuristic: use
+    /// [`Grid::bfs_with_portals_at_level`] for a different cap.
+    pub fn bfs_with_portals(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+        portals: &HashMap<Vec2i, (Vec2i, i32)>,
+    ) -> Option<usize> {
+        let max_level = portals.len() as i32;
+        self.bfs_with_portals_at_level(start, goal, passable, portals, Some(0), max_level)
+    }
+
+    /// Like [`Grid::bfs_with_portals`], but the goal is accepted at `goal_level` or at any level
+    /// when it is `None`, and levels above `max_level` are never entered.
+    pub fn bfs_with_portals_at_level(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+        portals: &HashMap<Vec2i, (Vec2i, i32)>,
+        goal_level: Option<i32>,
+        max_level: i32,
+    ) -> Option<usize> {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert((start, 0));
+        queue.push_back((start, 0, 0));
+        while let Some((pos, level, dist)) = queue.pop_front() {
+            if pos == goal && goal_level.is_none_or(|l| l == level) {
+                return Some(dist);
+            }
+            let portal = portals
+                .get(&pos)
+                .map(|&(target, delta)| (target, level + delta));
+            let steps = self
+                .neighbor_positions4(pos)
+                .filter(|&n| passable(n, &self[n]))
+                .map(|n| (n, level))
+                .chain(portal);
+            for (next, next_level) in steps {
+                if (0..=max_level).contains(&next_level) && visited.insert((next, next_level)) {
+                    queue.push_back((next, next_level, dist + 1));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The portals of a labeled maze, see [`Grid::maze_portals`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MazePortals {
+    /// Maps every portal cell to its paired cell and level delta.
+    pub portals: HashMap<Vec2i, (Vec2i, i32)>,
+    /// Labels that only occur once, like the `AA` entrance and `ZZ` exit, with their cell.
+    pub unpaired: HashMap<String, Vec2i>,
+}
+
+impl Grid<char> {
+    /// Finds all two-letter labels next to open `.` cells. Returns the label, the open cell and
+    /// whether the label is on the outer edge of the grid.
+    pub fn portal_labels(&self) -> Vec<(String, Vec2i, bool)> {
+        let letter = |p: Vec2i| {
+            self.in_bounds(p)
+                .then(|| self[p])
+                .filter(char::is_ascii_uppercase)
+        };
+        let mut labels = Vec::new();
+        for pos in self.positions().filter(|&p| self[p] == '.') {
+            for (dx, dy) in DIRS4 {
+                let d = Vec2i::new(dx, dy);
+                let (Some(near), Some(far)) = (letter(pos + d), letter(pos + d + d)) else {
+                    continue;
+                };
+                let label: String = if dx < 0 || dy < 0 {
+                    [far, near].into_iter().collect()
+                } else {
+                    [near, far].into_iter().collect()
+                };
+                let outer = pos + d + d;
+                let outer = outer.x == 0
+                    || outer.y == 0
+                    || outer.x == self.width as i32 - 1
+                    || outer.y == self.height as i32 - 1;
+                labels.push((label, pos, outer));
+            }
+        }
+        labels
+    }
+
+    /// Pairs up equally labeled portals. In a recursive maze inner portals go one level
+    /// deeper (+1) and outer portals one level up (-1), otherwise all deltas are 0.
+    pub fn maze_portals(&self, recursive: bool) -> MazePortals {
+        let mut by_label: HashMap<String, Vec<(Vec2i, bool)>> = HashMap::new();
+        for (label, pos, outer) in self.portal_labels() {
+            by_label.entry(label).or_default().push((pos, outer));
+        }
+        let mut portals = HashMap::new();
+        let mut unpaired = HashMap::new();
+        for (label, cells) in by_label {
+            match cells[..] {
+                [(pos, _)] => {
+                    unpaired.insert(label, pos);
+                }
+                [(a, a_outer), (b, _)] => {
+                    let delta = if !recursive {
+                        0
+                    } else if a_outer {
+                        -1
+                    } else {
+                        1
+                    };
+                    portals.insert(a, (b, delta));
+                    portals.insert(b, (a, -delta));
+                }
+                _ => panic!("label {label} occurs {} times", cells.len()),
+            }
+        }
+        MazePortals { portals, unpaired }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    fn parse_maze(s: &str) -> Grid<char> {
+        let width = s.lines().map(|line| line.len()).max().unwrap();
+        let padded: Vec<Vec<char>> = s
+            .lines()
+            .map(|line| format!("{line:width$}").chars().collect())
+            .collect();
+        Grid::from_nested(padded)
+    }
+
+    fn solve(s: &str, recursive: bool) -> Option<usize> {
+        let maze = parse_maze(s);
+        let portals = maze.maze_portals(recursive);
+        maze.bfs_with_portals(
+            portals.unpaired["AA"],
+            portals.unpaired["ZZ"],
+            |_, &c| c == '.',
+            &portals.portals,
+        )
+    }
+
+    const SMALL: &str = "         A
+         A
+  #######.#########
+  #######.........#
+  #######.#######.#
+  #######.#######.#
+  #######.#######.#
+  #####  B    ###.#
+BC...##  C    ###.#
+  ##.##       ###.#
+  ##...DE  F  ###.#
+  #####    G  ###.#
+  #########.#####.#
+DE..#######...###.#
+  #.#########.###.#
+FG..#########.....#
+  ###########.#####
+             Z
+             Z       ";
+
+    const LARGER: &str = "                   A
+                   A
+  #################.#############
+  #.#...#...................#.#.#
+  #.#.#.###.###.###.#########.#.#
+  #.#.#.......#...#.....#.#.#...#
+  #.#########.###.#####.#.#.###.#
+  #.............#.#.....#.......#
+  ###.###########.###.#.#.#.#.###
+  #.....#        A   C    #.#.#.#
+  #######        S   P    #####.#
+  #.#...#                 #......VT
+  #.#.#.#                 #.#####
+  #...#.#               YN....#.#
+  #.###.#                 #####.#
+DI....#.#                 #.....#
+  #####.#                 #.###.#
+ZZ......#               QG....#..AS
+  ###.###                 #######
+JO..#.#.#                 #.....#
+  #.#.#.#                 ###.#.#
+  #...#..DI             BU....#..LF
+  #####.#                 #.#####
+YN......#               VT..#....QG
+  #.###.#                 #.###.#
+  #.#...#                 #.....#
+  ###.###    J L     J    #.#.###
+  #.....#    O F     P    #.#...#
+  #.###.#####.#.#####.#####.###.#
+  #...#.#.#...#.....#.....#.#...#
+  #.#####.###.###.#.#.#########.#
+  #...#.#.....#...#.#.#.#.....#.#
+  #.###.#####.###.###.#.#.#######
+  #.#.........#...#.............#
+  #########.###.###.#############
+           B   J   C
+           U   P   P";
+
+    const RECURSIVE: &str = "             Z L X W       C
+             Z P Q B       K
+  ###########.#.#.#.#######.###############
+  #...#.......#.#.......#.#.......#.#.#...#
+  ###.#.#.#.#.#.#.#.###.#.#.#######.#.#.###
+  #.#...#.#.#...#.#.#...#...#...#.#.......#
+  #.###.#######.###.###.#.###.###.#.#######
+  #...#.......#.#...#...#.............#...#
+  #.#########.#######.#.#######.#######.###
+  #...#.#    F       R I       Z    #.#.#.#
+  #.###.#    D       E C       H    #.#.#.#
+  #.#...#                           #...#.#
+  #.###.#                           #.###.#
+  #.#....OA                       WB..#.#..ZH
+  #.###.#                           #.#.#.#
+CJ......#                           #.....#
+  #######                           #######
+  #.#....CK                         #......IC
+  #.###.#                           #.###.#
+  #.....#                           #...#.#
+  ###.###                           #.#.#.#
+XF....#.#                         RF..#.#.#
+  #####.#                           #######
+  #......CJ                       NM..#...#
+  ###.#.#                           #.###.#
+RE....#.#                           #......RF
+  ###.###        X   X       L      #.#.#.#
+  #.....#        F   Q       P      #.#.#.#
+  ###.###########.###.#######.#########.###
+  #.....#...#.....#.......#...#.....#.#...#
+  #####.#.###.#######.#######.###.###.#.#.#
+  #.......#.......#.#.#.#.#...#...#...#.#.#
+  #####.###.#####.#.#.#.#.###.###.#.###.###
+  #.......#.....#.#...#...............#...#
+  #############.#.#.###.###################
+               A O F   N
+               A A D   M";
+
+    #[test]
+    fn flat_mazes() {
+        assert_eq!(solve(SMALL, false), Some(23));
+        assert_eq!(solve(LARGER, false), Some(58));
+    }
+
+    #[test]
+    fn recursive_mazes() {
+        assert_eq!(solve(SMALL, true), Some(26));
+        assert_eq!(solve(RECURSIVE, true), Some(396));
+        assert_eq!(solve(LARGER, true), None);
+    }
+
+    #[test]
+    fn level_cap() {
+        let maze = parse_maze(RECURSIVE);
+        let portals = maze.maze_portals(true);
+        let solve_capped = |max_level| {
+            maze.bfs_with_portals_at_level(
+                portals.unpaired["AA"],
+                portals.unpaired["ZZ"],
+                |_, &c| c == '.',
+                &portals.portals,
+                Some(0),
+                max_level,
+            )
+        };
+        assert_eq!(solve_capped(0), None);
+        assert_eq!(solve_capped(9), None);
+        assert_eq!(solve_capped(10), Some(396));
+    }
+}
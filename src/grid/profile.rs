@@ -0,0 +1,55 @@
+use vecm::Vec2i;
+
+use super::{Grid, Side};
+
+impl Grid<bool> {
+    /// The number of consecutive true cells counted inwards from `side`. Profiles from the top
+    /// or bottom have one entry per column, from the left or right one per row.
+    pub fn column_heights_from(&self, side: Side) -> Vec<usize> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let lines = if matches!(side, Side::T | Side::B) {
+            w
+        } else {
+            h
+        };
+        let start = |line| match side {
+            Side::T => Vec2i::new(line, 0),
+            Side::B => Vec2i::new(line, h - 1),
+            Side::L => Vec2i::new(0, line),
+            Side::R => Vec2i::new(w - 1, line),
+        };
+        // walking inwards is the opposite of the direction pointing towards the side
+        let inwards = Vec2i::new(0, 0) - side.dir();
+        (0..lines)
+            .map(|line| {
+                let mut pos = start(line);
+                let mut height = 0;
+                while self.get(pos) == Some(&true) {
+                    height += 1;
+                    pos += inwards;
+                }
+                height
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Grid, Side};
+
+    #[test]
+    fn profiles() {
+        let block = Grid::from_str_chars(
+            "##..
+#.#.
+###.
+.###",
+        )
+        .map(|c| c == '#');
+        assert_eq!(block.column_heights_from(Side::T), [3, 1, 0, 0]);
+        assert_eq!(block.column_heights_from(Side::B), [0, 2, 3, 1]);
+        assert_eq!(block.column_heights_from(Side::L), [2, 1, 3, 0]);
+        assert_eq!(block.column_heights_from(Side::R), [0, 0, 0, 3]);
+    }
+}
@@ -0,0 +1,640 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
+
+use vecm::Vec2i;
+
+use crate::{
+    diffs::{is_polynomial_of_degree, quadratic_at},
+    turns::{min_reading_order, reading_order_key},
+};
+
+use super::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrapolationError {
+    /// The grid has to be square so the reachable area grows by the same period in every
+    /// direction.
+    NotSquare { width: usize, height: usize },
+    /// The counts at `n, n + w, n + 2w, n + 3w` don't have a constant second difference.
+    NotQuadratic { steps: [u64; 4], counts: [u64; 4] },
+}
+impl Display for ExtrapolationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotSquare { width, height } => {
+                write!(f, "can't extrapolate on a non-square {width}x{height} grid")
+            }
+            Self::NotQuadratic { steps, counts } => write!(
+                f,
+                "reachable counts {counts:?} at steps {steps:?} don't grow quadratically"
+            ),
+        }
+    }
+}
+impl std::error::Error for ExtrapolationError {}
+
+/// The reachable target with the smallest distance and that distance, ties broken by taking the
+/// target first in reading order. Targets outside of `distances` count as unreachable.
+pub fn nearest_in_reading_order(
+    distances: &Grid<Option<u32>>,
+    targets: &HashSet<Vec2i>,
+) -> Option<(Vec2i, u32)> {
+    targets
+        .iter()
+        .filter_map(|&t| Some((t, (*distances.get(t)?)?)))
+        .min_by_key(|&(t, d)| (d, reading_order_key(t)))
+}
+
+/// The cells of a [`Grid::bfs_tiled`] result that can be reached in exactly `steps` steps, those
+/// at most `steps` away with the same parity since walking back and forth is allowed.
+pub fn count_reachable_at_exact(distances: &HashMap<(Vec2i, Vec2i), usize>, steps: usize) -> usize {
+    distances
+        .values()
+        .filter(|&&d| d <= steps && d % 2 == steps % 2)
+        .count()
+}
+
+impl<T> Grid<T> {
+    /// All cells reachable from `start` in at most `k` steps through `passable` cells, with their
+    /// BFS distance, in BFS order. The start itself is always included with distance 0.
+    pub fn positions_within_steps(
+        &self,
+        start: Vec2i,
+        k: usize,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Vec<(Vec2i, usize)> {
+        let mut dist = vec![usize::MAX; self.buf.len()];
+        let mut reached = Vec::new();
+        let mut queue = VecDeque::new();
+        dist[self.index_of(start)] = 0;
+        queue.push_back(start);
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[self.index_of(pos)];
+            reached.push((pos, d));
+            if d == k {
+                continue;
+            }
+            for next in self.neighbor_positions4(pos) {
+                let i = self.index_of(next);
+                if dist[i] == usize::MAX && passable(next, &self[next]) {
+                    dist[i] = d + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Shortest 4-neighbor distances from `start` through `passable` cells, `None` for
+    /// unreachable cells.
+    pub fn bfs_distances(
+        &self,
+        start: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        self.bfs_distances_multi([start], passable)
+    }
+
+    /// A shortest path from `start` to `goal` including both ends. Walks back from the goal
+    /// along decreasing distances, which is valid because passability only depends on the
+    /// entered cell.
+    pub fn shortest_path(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Option<Vec<Vec2i>> {
+        let dist = self.bfs_distances(start, passable);
+        let mut d = (*dist.get(goal)?)?;
+        let mut path = vec![goal];
+        let mut pos = goal;
+        while d > 0 {
+            d -= 1;
+            pos = self
+                .neighbor_positions4(pos)
+                .find(|&n| dist[n] == Some(d))
+                .unwrap();
+            path.push(pos);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// BFS distances from the nearest of `sources` through `passable` cells, `None` for
+    /// unreachable cells. Sources count as reached even if they aren't passable themselves.
+    pub fn bfs_distances_multi(
+        &self,
+        sources: impl IntoIterator<Item = Vec2i>,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        let mut dist = self.same_size_with(None);
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if dist[source].is_none() {
+                dist[source] = Some(0);
+                queue.push_back(source);
+            }
+        }
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[pos].unwrap();
+            for next in self.neighbor_positions4(pos) {
+                if dist[next].is_none() && passable(next, &self[next]) {
+                    dist[next] = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        dist
+    }
+
+    /// The smallest BFS distance from `sources` to a cell satisfying `is_target`, together with
+    /// all targets at that distance in reading order.
+    pub fn nearest_targets_by_bfs(
+        &self,
+        sources: impl IntoIterator<Item = Vec2i>,
+        is_target: impl Fn(Vec2i) -> bool,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Option<(u32, Vec<Vec2i>)> {
+        let dist = self.bfs_distances_multi(sources, passable);
+        let nearest = self
+            .positions()
+            .filter_map(|p| dist[p].filter(|_| is_target(p)))
+            .min()?;
+        // positions are already in reading order
+        let targets = self
+            .positions()
+            .filter(|&p| dist[p] == Some(nearest) && is_target(p))
+            .collect();
+        Some((nearest, targets))
+    }
+
+    /// The neighbor of `from` to step on to approach the nearest target, ties broken by reading
+    /// order first for the target and then for the step. `None` if no target is reachable or
+    /// `from` is a target already.
+    pub fn first_step_towards(
+        &self,
+        from: Vec2i,
+        is_target: impl Fn(Vec2i) -> bool,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Option<Vec2i> {
+        let (d, targets) = self.nearest_targets_by_bfs([from], is_target, &passable)?;
+        if d == 0 {
+            return None;
+        }
+        let back = self.bfs_distances(targets[0], &passable);
+        min_reading_order(
+            self.neighbor_positions4(from)
+                .filter(|&n| back[n] == Some(d - 1) && passable(n, &self[n])),
+        )
+    }
+
+    /// Like [`Grid::first_step_towards`] for a set of targets: a first BFS from `from` picks the
+    /// nearest target, first in reading order among equally near ones, and a second BFS from
+    /// that target picks the step onto a neighbor one closer to it, again first in reading order
+    /// among several. Only the chosen target matters for the step, other targets at the same
+    /// distance don't. `None` if no target is reachable or `from` is one already.
+    pub fn choose_step_toward(
+        &self,
+        from: Vec2i,
+        targets: &HashSet<Vec2i>,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Option<Vec2i> {
+        let (target, d) = nearest_in_reading_order(&self.bfs_distances(from, &passable), targets)?;
+        if d == 0 {
+            return None;
+        }
+        let back = self.bfs_distances(target, &passable);
+        min_reading_order(
+            self.neighbor_positions4(from)
+                .filter(|&n| back[n] == Some(d - 1) && passable(n, &self[n])),
+        )
+    }
+
+    /// For a course where `path` is the only route, counts the cheats by the time they save. A
+    /// cheat jumps from one path cell to a later one at most `max_cheat` manhattan steps away,
+    /// ignoring walls. Only the diamond around each cell is visited, so this is linear in the
+    /// path length for small `max_cheat`.
+    pub fn shortcut_savings(&self, path: &[Vec2i], max_cheat: usize) -> HashMap<usize, usize> {
+        let mut index = vec![usize::MAX; self.buf.len()];
+        for (i, &pos) in path.iter().enumerate() {
+            index[self.index_of(pos)] = i;
+        }
+        let r = max_cheat as i32;
+        let mut savings = HashMap::new();
+        for (i, &pos) in path.iter().enumerate() {
+            for dy in -r..=r {
+                let rest = r - dy.abs();
+                for dx in -rest..=rest {
+                    let Some(end) = self.checked_index_of(pos + Vec2i::new(dx, dy)) else {
+                        continue;
+                    };
+                    let j = index[end];
+                    let cheat = (dx.abs() + dy.abs()) as usize;
+                    if j != usize::MAX && j > i + cheat {
+                        *savings.entry(j - i - cheat).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        savings
+    }
+
+    /// For the infinitely tiled grid, counts the cells that can be reached in exactly `s` steps
+    /// for each `s` in `steps`. Walking back and forth is allowed, so these are the cells with a
+    /// BFS distance of at most `s` and the same parity. Runs a single BFS on a tiled copy that is
+    /// large enough for the biggest step count.
+    pub fn reachable_counts_tiled(
+        &self,
+        start: Vec2i,
+        steps: &[u64],
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Vec<u64> {
+        let Some(&max) = steps.iter().max() else {
+            return Vec::new();
+        };
+        let (w, h) = (self.width as u64, self.height as u64);
+        // tiles needed on each side of the center tile
+        let radius = max.div_ceil(w.min(h)) as usize;
+        let open = Grid::from_buf(
+            self.positions().map(|p| passable(p, &self[p])).collect(),
+            self.width,
+            self.height,
+        );
+        let tiled = open.repeat(2 * radius + 1, 2 * radius + 1);
+        let center =
+            start + Vec2i::new((radius * self.width) as i32, (radius * self.height) as i32);
+        let mut at_dist = vec![0u64; max as usize + 1];
+        for (_, d) in tiled.positions_within_steps(center, max as usize, |_, &open| open) {
+            at_dist[d] += 1;
+        }
+        steps
+            .iter()
+            .map(|&s| (0..=s as usize).rev().step_by(2).map(|d| at_dist[d]).sum())
+            .collect()
+    }
+
+    /// BFS over the infinite tiling of the grid up to `max_steps` steps from `start`. Positions
+    /// are split into the tile, which has negative coordinates left of or above the original,
+    /// and the cell in that tile, the keys of the returned distances.
+    pub fn bfs_tiled(
+        &self,
+        start: Vec2i,
+        max_steps: usize,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> HashMap<(Vec2i, Vec2i), usize> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let split = |pos: Vec2i| {
+            let tile = Vec2i::new(pos.x.div_euclid(w), pos.y.div_euclid(h));
+            (tile, Vec2i::new(pos.x.rem_euclid(w), pos.y.rem_euclid(h)))
+        };
+        let mut dist = HashMap::from([(split(start), 0)]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((pos, d)) = queue.pop_front() {
+            if d == max_steps {
+                continue;
+            }
+            for (dx, dy) in super::DIRS4 {
+                let next = pos + Vec2i::new(dx, dy);
+                let key = split(next);
+                if !dist.contains_key(&key) && passable(key.1, &self[key.1]) {
+                    dist.insert(key, d + 1);
+                    queue.push_back((next, d + 1));
+                }
+            }
+        }
+        dist
+    }
+
+    /// [`Grid::reachable_counts_tiled`] for step counts too large to simulate. Samples the
+    /// counts at `n, n + w, n + 2w` with `n = steps % w` and extrapolates quadratically, which
+    /// holds for inputs with clear lanes through the start. A fourth sample at `n + 3w`
+    /// validates that assumption.
+    pub fn reachable_extrapolate(
+        &self,
+        start: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+        steps: u64,
+    ) -> Result<u64, ExtrapolationError> {
+        if self.width != self.height {
+            return Err(ExtrapolationError::NotSquare {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let w = self.width as u64;
+        let n = steps % w;
+        let sample_steps = [0, 1, 2, 3].map(|i| n + i * w);
+        if steps <= sample_steps[3] {
+            return Ok(self.reachable_counts_tiled(start, &[steps], passable)[0]);
+        }
+        let counts = self.reachable_counts_tiled(start, &sample_steps, passable);
+        let counts: [u64; 4] = counts.try_into().unwrap();
+        if !is_polynomial_of_degree(&counts.map(|c| c as i64), 2) {
+            return Err(ExtrapolationError::NotQuadratic {
+                steps: sample_steps,
+                counts,
+            });
+        }
+        let samples = [counts[0], counts[1], counts[2]].map(|c| c as i64);
+        let count = quadratic_at(samples, (steps / w) as i64);
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use vecm::Vec2i;
+
+    use super::{count_reachable_at_exact, nearest_in_reading_order, ExtrapolationError};
+    use crate::{sort_reading_order, Grid};
+
+    const TRACK: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+    fn track_savings(max_cheat: usize) -> HashMap<usize, usize> {
+        let grid = Grid::from_str_bytes(TRACK);
+        let start = grid.find(&b'S').unwrap();
+        let path: Vec<Vec2i> = grid
+            .positions_within_steps(start, usize::MAX, |_, &c| c != b'#')
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(path.len(), 85);
+        grid.shortcut_savings(&path, max_cheat)
+    }
+
+    const GARDEN: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn tiled_garden() {
+        let grid = Grid::from_str_bytes(GARDEN);
+        let start = grid.find(&b'S').unwrap();
+        let counts = grid.reachable_counts_tiled(start, &[6, 10, 50, 100], |_, &c| c != b'#');
+        assert_eq!(counts, [16, 50, 1594, 6536]);
+        let dist = grid.bfs_tiled(start, 100, |_, &c| c != b'#');
+        let exact = [6, 10, 50, 100].map(|s| count_reachable_at_exact(&dist, s));
+        assert_eq!(exact, [16, 50, 1594, 6536]);
+        assert_eq!(dist[&(Vec2i::new(0, 0), start)], 0);
+        // 100 steps reach several copies in every direction
+        assert!(dist.contains_key(&(Vec2i::new(-5, 0), start)));
+        assert!(dist.contains_key(&(Vec2i::new(3, -4), Vec2i::new(0, 0))));
+        assert!(dist
+            .keys()
+            .all(|(tile, _)| tile.x.abs() <= 10 && tile.y.abs() <= 10));
+    }
+
+    #[test]
+    fn tiled_parity() {
+        let open = Grid::new(2, 3, true);
+        let dist = open.bfs_tiled(Vec2i::new(1, 1), 8, |_, &open| open);
+        for steps in 0..=8 {
+            // the cells of the same parity within a diamond of radius `steps`
+            assert_eq!(
+                count_reachable_at_exact(&dist, steps),
+                (steps + 1) * (steps + 1)
+            );
+        }
+        assert_eq!(dist[&(Vec2i::new(-1, 0), Vec2i::new(1, 1))], 2);
+        assert_eq!(dist[&(Vec2i::new(-1, -1), Vec2i::new(0, 0))], 7);
+        // walls in every copy stay walls
+        let walled = Grid::from_str_bytes(".#\n..");
+        let dist = walled.bfs_tiled(Vec2i::new(0, 0), 6, |_, &c| c == b'.');
+        assert!(dist.keys().all(|&(_, cell)| cell != Vec2i::new(1, 0)));
+        assert_eq!(dist[&(Vec2i::new(0, -1), Vec2i::new(0, 1))], 1);
+    }
+
+    #[test]
+    fn extrapolation() {
+        let open = Grid::from_nested(vec![vec![true; 5]; 5]);
+        let start = Vec2i::new(2, 2);
+        let passable = |_: Vec2i, &open: &bool| open;
+        assert_eq!(open.reachable_extrapolate(start, passable, 12), Ok(169));
+        assert_eq!(
+            open.reachable_extrapolate(start, passable, 1000),
+            Ok(1001 * 1001)
+        );
+        let garden = Grid::from_str_bytes(GARDEN);
+        let start = garden.find(&b'S').unwrap();
+        let err = garden
+            .reachable_extrapolate(start, |_, &c| c != b'#', 5000)
+            .unwrap_err();
+        assert!(
+            matches!(err, ExtrapolationError::NotQuadratic { .. }),
+            "{err}"
+        );
+        let wide = Grid::from_nested(vec![vec![true; 3]; 2]);
+        assert_eq!(
+            wide.reachable_extrapolate(Vec2i::new(0, 0), passable, 100),
+            Err(ExtrapolationError::NotSquare {
+                width: 3,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn bfs_paths() {
+        let grid = Grid::from_str_bytes(
+            "S.#...
+#.#.#.
+#...#E
+######",
+        );
+        let open = |_: Vec2i, &c: &u8| c != b'#';
+        let start = grid.find(&b'S').unwrap();
+        let goal = grid.find(&b'E').unwrap();
+        let dist = grid.bfs_distances(start, open);
+        assert_eq!(dist[goal], Some(11));
+        assert_eq!(dist[Vec2i::new(0, 1)], None);
+        assert_eq!(dist[Vec2i::new(0, 3)], None);
+        let path = grid.shortest_path(start, goal, open).unwrap();
+        assert_eq!(path.len(), 12);
+        assert_eq!((path[0], path[11]), (start, goal));
+        assert!(path.windows(2).all(|w| {
+            (w[0].x - w[1].x).abs() + (w[0].y - w[1].y).abs() == 1 && open(w[1], &grid[w[1]])
+        }));
+        assert_eq!(grid.shortest_path(start, start, open), Some(vec![start]));
+        assert_eq!(grid.shortest_path(start, Vec2i::new(0, 3), open), None);
+        assert_eq!(grid.shortest_path(start, Vec2i::new(9, 9), open), None);
+    }
+
+    #[test]
+    fn multi_source() {
+        let grid = Grid::from_str_bytes("a..#\n.#..\n...a\n#a..");
+        let open = |_: Vec2i, &c: &u8| c != b'#';
+        let starts: Vec<Vec2i> = grid.find_all(&b'a').collect();
+        let multi = grid.bfs_distances_multi(starts.iter().copied(), open);
+        let singles: Vec<_> = starts
+            .iter()
+            .map(|&s| grid.bfs_distances(s, open))
+            .collect();
+        for pos in grid.positions() {
+            let nearest = singles.iter().filter_map(|d| d[pos]).min();
+            assert_eq!(multi[pos], nearest, "{pos:?}");
+        }
+        assert_eq!(multi[Vec2i::new(2, 0)], Some(2));
+        assert_eq!(multi[Vec2i::new(3, 0)], None);
+    }
+
+    /// The open cells next to units of the other kind than the one at `unit`.
+    fn in_range(grid: &Grid<u8>, unit: Vec2i) -> HashSet<Vec2i> {
+        let enemy = if grid[unit] == b'E' { b'G' } else { b'E' };
+        grid.find_all(&enemy)
+            .flat_map(|e| grid.neighbor_positions4(e))
+            .filter(|&p| grid[p] == b'.' || p == unit)
+            .collect()
+    }
+
+    #[test]
+    fn combat_steps() {
+        let grid = Grid::from_str_bytes("#######\n#E..G.#\n#...#.#\n#.G.#G#\n#######");
+        let open = |_: Vec2i, &c: &u8| c == b'.';
+        let elf = Vec2i::new(1, 1);
+        let targets = in_range(&grid, elf);
+        assert_eq!(targets.len(), 6);
+        let dist = grid.bfs_distances(elf, open);
+        // (3, 1), (2, 2) and (1, 3) are all two steps away
+        assert_eq!(
+            nearest_in_reading_order(&dist, &targets),
+            Some((Vec2i::new(3, 1), 2))
+        );
+        assert_eq!(
+            grid.choose_step_toward(elf, &targets, open),
+            Some(Vec2i::new(2, 1))
+        );
+
+        // both steps are as close to the chosen target, the one first in reading order wins
+        let grid = Grid::from_str_bytes("#######\n#.E...#\n#.....#\n#...G.#\n#######");
+        let elf = Vec2i::new(2, 1);
+        let targets = in_range(&grid, elf);
+        let dist = grid.bfs_distances(elf, open);
+        assert_eq!(
+            nearest_in_reading_order(&dist, &targets),
+            Some((Vec2i::new(4, 2), 3))
+        );
+        assert_eq!(
+            grid.choose_step_toward(elf, &targets, open),
+            Some(Vec2i::new(3, 1))
+        );
+        // standing in range already or with no way to any target, there is no step
+        let goblin = Vec2i::new(4, 3);
+        assert_eq!(
+            grid.choose_step_toward(goblin, &[goblin].into(), open),
+            None
+        );
+        let walled = [Vec2i::new(0, 0), Vec2i::new(20, 20)].into();
+        assert_eq!(grid.choose_step_toward(elf, &walled, open), None);
+    }
+
+    #[test]
+    fn combat_movement_rounds() {
+        let rounds = [
+            "#########\n#G..G..G#\n#.......#\n#.......#\n#G..E..G#\n#.......#\n#.......#\n#G..G..G#\n#########",
+            "#########\n#.G...G.#\n#...G...#\n#...E..G#\n#.G.....#\n#.......#\n#G..G..G#\n#.......#\n#########",
+            "#########\n#..G.G..#\n#...G...#\n#.G.E.G.#\n#.......#\n#G..G..G#\n#.......#\n#.......#\n#########",
+            "#########\n#.......#\n#..GGG..#\n#..GEG..#\n#G..G...#\n#......G#\n#.......#\n#.......#\n#########",
+        ];
+        let mut grid = Grid::from_str_bytes(rounds[0]);
+        for expected in &rounds[1..] {
+            let mut units: Vec<Vec2i> = grid
+                .positions()
+                .filter(|&p| grid[p] != b'#' && grid[p] != b'.')
+                .collect();
+            sort_reading_order(&mut units);
+            for unit in units {
+                let targets = in_range(&grid, unit);
+                if let Some(step) = grid.choose_step_toward(unit, &targets, |_, &c| c == b'.') {
+                    grid[step] = grid[unit];
+                    grid[unit] = b'.';
+                }
+            }
+            assert_eq!(grid, Grid::from_str_bytes(expected));
+        }
+    }
+
+    #[test]
+    fn step_limit() {
+        let grid = Grid::from_str_bytes(TRACK);
+        let reached = grid.positions_within_steps(Vec2i::new(1, 3), 3, |_, &c| c != b'#');
+        assert_eq!(
+            reached,
+            [
+                (Vec2i::new(1, 3), 0),
+                (Vec2i::new(1, 2), 1),
+                (Vec2i::new(1, 1), 2),
+                (Vec2i::new(2, 1), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn racetrack_cheats() {
+        let short: HashMap<usize, usize> = [
+            (2, 14),
+            (4, 14),
+            (6, 2),
+            (8, 4),
+            (10, 2),
+            (12, 3),
+            (20, 1),
+            (36, 1),
+            (38, 1),
+            (40, 1),
+            (64, 1),
+        ]
+        .into();
+        assert_eq!(track_savings(2), short);
+
+        let mut long: Vec<(usize, usize)> = track_savings(20)
+            .into_iter()
+            .filter(|&(saved, _)| saved >= 50)
+            .collect();
+        long.sort_unstable();
+        assert_eq!(
+            long,
+            [
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3),
+            ]
+        );
+    }
+}
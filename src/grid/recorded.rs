@@ -0,0 +1,236 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+/// A grid that records every change, so earlier states can be rebuilt to find where a long
+/// simulation went wrong. Changes are grouped into steps with [`RecordedGrid::end_step`].
+/// Every `keyframe_interval` steps the whole grid is kept as a snapshot, so rebuilding a state
+/// replays at most that many steps.
+#[derive(Debug, Clone)]
+pub struct RecordedGrid<T> {
+    current: Grid<T>,
+    /// The new values of all changes in order.
+    changes: Vec<(Vec2i, T)>,
+    /// The number of changes after each completed step, starting with 0 for the initial state.
+    step_ends: Vec<usize>,
+    /// Snapshots by step, always starting with the initial state.
+    keyframes: Vec<(usize, Grid<T>)>,
+    keyframe_interval: usize,
+}
+impl<T: Clone + PartialEq> RecordedGrid<T> {
+    pub fn new(initial: Grid<T>) -> Self {
+        Self {
+            keyframes: vec![(0, initial.clone())],
+            current: initial,
+            changes: Vec::new(),
+            step_ends: vec![0],
+            keyframe_interval: 1000,
+        }
+    }
+
+    /// Snapshots the grid every `interval` steps from now on, `0` never does. Defaults to 1000.
+    pub fn keyframe_interval(mut self, interval: usize) -> Self {
+        self.keyframe_interval = interval;
+        self
+    }
+
+    /// The current state, including changes of the step that hasn't ended yet.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    /// The number of completed steps.
+    pub fn steps(&self) -> usize {
+        self.step_ends.len() - 1
+    }
+
+    pub fn set(&mut self, pos: Vec2i, value: T) {
+        self.current[pos] = value.clone();
+        self.changes.push((pos, value));
+    }
+
+    pub fn set_all(&mut self, changes: impl IntoIterator<Item = (Vec2i, T)>) {
+        for (pos, value) in changes {
+            self.set(pos, value);
+        }
+    }
+
+    /// Replaces the whole grid, recording only the cells that differ. Panics if the size
+    /// changes.
+    pub fn replace(&mut self, next: Grid<T>) {
+        assert!(
+            (next.width, next.height) == (self.current.width, self.current.height),
+            "can't replace a {}x{} grid with a {}x{} one",
+            self.current.width,
+            self.current.height,
+            next.width,
+            next.height
+        );
+        for (pos, value) in next {
+            if self.current[pos] != value {
+                self.set(pos, value);
+            }
+        }
+    }
+
+    /// Completes the current step, everything set since the last call belongs to it.
+    pub fn end_step(&mut self) {
+        self.step_ends.push(self.changes.len());
+        let step = self.steps();
+        if self.keyframe_interval != 0 && step.is_multiple_of(self.keyframe_interval) {
+            self.keyframes.push((step, self.current.clone()));
+        }
+    }
+
+    fn check_step(&self, step: usize) {
+        assert!(
+            step <= self.steps(),
+            "step {step} out of the {} recorded",
+            self.steps()
+        );
+    }
+
+    /// The grid after the first `step` steps, replayed from the closest earlier keyframe.
+    pub fn state_at(&self, step: usize) -> Grid<T> {
+        self.check_step(step);
+        let keyframe = self.keyframes.partition_point(|&(s, _)| s <= step) - 1;
+        let (from, snapshot) = &self.keyframes[keyframe];
+        let mut grid = snapshot.clone();
+        for (pos, value) in &self.changes[self.step_ends[*from]..self.step_ends[step]] {
+            grid[*pos] = value.clone();
+        }
+        grid
+    }
+
+    /// Goes back to the state after `step` steps, forgetting everything recorded after it.
+    pub fn rewind_to(&mut self, step: usize) {
+        self.current = self.state_at(step);
+        self.changes.truncate(self.step_ends[step]);
+        self.step_ends.truncate(step + 1);
+        self.keyframes.retain(|&(s, _)| s <= step);
+    }
+
+    /// The cells that differ between the states after `a` and after `b` steps in reading
+    /// order, with their values at `a` and at `b`.
+    pub fn diff_between(&self, a: usize, b: usize) -> Vec<(Vec2i, T, T)> {
+        let after = self.state_at(b);
+        self.state_at(a)
+            .into_iter()
+            .filter(|(pos, value)| after[*pos] != *value)
+            .map(|(pos, value)| (pos, value, after[pos].clone()))
+            .collect()
+    }
+
+    /// The first step whose state is `bad`, by binary search over the completed steps, so
+    /// `bad` has to stay true once it holds. `None` if even the last state isn't bad.
+    pub fn bisect(&self, bad: impl Fn(&Grid<T>) -> bool) -> Option<usize> {
+        if !bad(&self.state_at(self.steps())) {
+            return None;
+        }
+        if bad(&self.state_at(0)) {
+            return Some(0);
+        }
+        let (mut good, mut first_bad) = (0, self.steps());
+        while first_bad - good > 1 {
+            let mid = good + (first_bad - good) / 2;
+            if bad(&self.state_at(mid)) {
+                first_bad = mid;
+            } else {
+                good = mid;
+            }
+        }
+        Some(first_bad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    /// Records random changes into grids with the keyframe interval, next to a copy of every
+    /// state.
+    fn record(interval: usize, steps: usize) -> (RecordedGrid<u32>, Vec<Grid<u32>>) {
+        let mut rng = Pcg32::new(310);
+        let initial = Grid::from_fn(6, 4, |pos| (pos.x + pos.y) as u32);
+        let mut recorded = RecordedGrid::new(initial.clone()).keyframe_interval(interval);
+        let mut states = vec![initial];
+        for step in 0..steps {
+            let mut expected = states.last().unwrap().clone();
+            for _ in 0..rng.index(4) {
+                let pos = Vec2i::new(rng.below(6) as i32, rng.below(4) as i32);
+                let value = rng.below(10);
+                recorded.set(pos, value);
+                expected[pos] = value;
+            }
+            if step % 7 == 0 {
+                let next = expected.clone().map(|v| v / 2);
+                recorded.replace(next.clone());
+                expected = next;
+            }
+            assert_eq!(recorded.grid(), &expected);
+            recorded.end_step();
+            states.push(expected);
+        }
+        (recorded, states)
+    }
+
+    #[test]
+    fn rebuilds_every_state() {
+        for interval in [0, 1, 3, 16, 1000] {
+            let (mut recorded, states) = record(interval, 60);
+            assert_eq!(recorded.steps(), 60);
+            for (step, state) in states.iter().enumerate() {
+                assert_eq!(&recorded.state_at(step), state, "interval {interval}");
+            }
+            let diff = recorded.diff_between(10, 20);
+            let expected: Vec<(Vec2i, u32, u32)> = states[10]
+                .iter()
+                .filter(|&(pos, v)| states[20][pos] != *v)
+                .map(|(pos, &v)| (pos, v, states[20][pos]))
+                .collect();
+            assert_eq!(diff, expected);
+            assert!(recorded.diff_between(5, 5).is_empty());
+
+            // rewinding drops the later history and recording goes on from there
+            recorded.set(Vec2i::new(0, 0), 77);
+            recorded.rewind_to(25);
+            assert_eq!(recorded.steps(), 25);
+            assert_eq!(recorded.grid(), &states[25]);
+            recorded.set(Vec2i::new(5, 3), 42);
+            recorded.end_step();
+            let mut changed = states[25].clone();
+            changed[Vec2i::new(5, 3)] = 42;
+            assert_eq!(recorded.state_at(26), changed);
+            assert_eq!(recorded.state_at(24), states[24]);
+        }
+    }
+
+    #[test]
+    fn bisects_to_defect() {
+        let mut recorded = RecordedGrid::new(Grid::new(8, 8, 0u32)).keyframe_interval(50);
+        for step in 1..=10_000 {
+            let pos = Vec2i::new(step % 8, step / 8 % 8);
+            // the defect lands in step 6_789 and is never overwritten
+            let value = if step == 6_789 { 99 } else { step as u32 % 10 };
+            if recorded.grid()[pos] != 99 {
+                recorded.set(pos, value);
+            }
+            recorded.end_step();
+        }
+        let has_defect = |grid: &Grid<u32>| grid.count(&99) > 0;
+        assert_eq!(recorded.bisect(has_defect), Some(6_789));
+        assert!(!has_defect(&recorded.state_at(6_788)));
+        assert_eq!(recorded.bisect(|_| true), Some(0));
+        assert_eq!(recorded.bisect(|grid| grid.count(&1234) > 0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "step 3 out of the 2 recorded")]
+    fn future_step() {
+        let mut recorded = RecordedGrid::new(Grid::new(2, 2, false));
+        recorded.end_step();
+        recorded.end_step();
+        recorded.state_at(3);
+    }
+}
@@ -0,0 +1,198 @@
+use std::io::{self, Read, Write};
+
+use super::Grid;
+
+const MAGIC: &[u8; 7] = b"AOCHRLE";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Cell types that [`Grid::write_rle`] can store. Sealed, since the format has a tag for each.
+pub trait RleCell: Copy + Eq + sealed::Sealed {
+    #[doc(hidden)]
+    const TAG: u8;
+    #[doc(hidden)]
+    const SIZE: usize;
+    #[doc(hidden)]
+    fn write_to(self, out: &mut Vec<u8>);
+    /// `None` for bytes that aren't a valid value.
+    #[doc(hidden)]
+    fn read_from(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! rle_cell {
+    ($t:ty, $tag:literal, $size:literal, |$v:ident, $out:ident| $write:expr, |$b:ident| $read:expr) => {
+        impl sealed::Sealed for $t {}
+        impl RleCell for $t {
+            const TAG: u8 = $tag;
+            const SIZE: usize = $size;
+            fn write_to(self, $out: &mut Vec<u8>) {
+                let $v = self;
+                $write
+            }
+            fn read_from($b: &[u8]) -> Option<Self> {
+                $read
+            }
+        }
+    };
+}
+rle_cell!(u8, 0, 1, |v, out| out.push(v), |b| Some(b[0]));
+rle_cell!(bool, 1, 1, |v, out| out.push(v as u8), |b| match b[0] {
+    0 => Some(false),
+    1 => Some(true),
+    _ => None,
+});
+rle_cell!(
+    char,
+    2,
+    4,
+    |v, out| out.extend((v as u32).to_le_bytes()),
+    |b| char::from_u32(u32::from_le_bytes(b.try_into().unwrap()))
+);
+rle_cell!(i64, 3, 8, |v, out| out.extend(v.to_le_bytes()), |b| Some(
+    i64::from_le_bytes(b.try_into().unwrap())
+));
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Checkpoints in a run-length encoded format: a magic header with the cell type, width and
+/// height, then `(count, value)` runs in reading order. Unpadded row lengths aren't stored.
+impl<T: RleCell> Grid<T> {
+    pub fn write_rle<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let mut out = Vec::with_capacity(24);
+        out.extend(MAGIC);
+        out.push(T::TAG);
+        out.extend((self.width as u64).to_le_bytes());
+        out.extend((self.height as u64).to_le_bytes());
+        let mut cells = self.buf.iter().peekable();
+        while let Some(&value) = cells.next() {
+            let mut count = 1u64;
+            while cells.next_if_eq(&&value).is_some() {
+                count += 1;
+            }
+            out.extend(count.to_le_bytes());
+            value.write_to(&mut out);
+        }
+        w.write_all(&out)
+    }
+
+    /// Reads a grid written by [`Grid::write_rle`]. Truncated or otherwise invalid data and
+    /// grids of a different cell type are `InvalidData` or `UnexpectedEof` errors.
+    pub fn read_rle<R: Read>(mut r: R) -> io::Result<Grid<T>> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if magic[..7] != MAGIC[..] {
+            return Err(invalid("not a run-length encoded grid"));
+        }
+        if magic[7] != T::TAG {
+            return Err(invalid(format!(
+                "the grid has cell type {} but {} was expected",
+                magic[7],
+                T::TAG
+            )));
+        }
+        let width = usize::try_from(read_u64(&mut r)?).map_err(|_| invalid("width too large"))?;
+        let height = usize::try_from(read_u64(&mut r)?).map_err(|_| invalid("height too large"))?;
+        let cells = width
+            .checked_mul(height)
+            .filter(|&cells| cells > 0)
+            .filter(|cells| {
+                cells
+                    .checked_mul(size_of::<T>())
+                    .is_some_and(|b| b <= isize::MAX as usize)
+            })
+            .ok_or_else(|| invalid(format!("a {width}x{height} grid is empty or too large")))?;
+        let mut buf = Vec::new();
+        let mut value = [0; 8];
+        while buf.len() < cells {
+            let count = read_u64(&mut r)?;
+            if count == 0 || count > (cells - buf.len()) as u64 {
+                return Err(invalid(format!("invalid run of {count} cells")));
+            }
+            r.read_exact(&mut value[..T::SIZE])?;
+            let value =
+                T::read_from(&value[..T::SIZE]).ok_or_else(|| invalid("invalid cell value"))?;
+            // the header alone can claim more cells than fit into memory
+            buf.try_reserve(count as usize)
+                .map_err(|_| invalid(format!("no memory for a {width}x{height} grid")))?;
+            buf.resize(buf.len() + count as usize, value);
+        }
+        Ok(Grid::from_buf(buf.into_boxed_slice(), width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::*;
+
+    fn round_trip<T: RleCell + std::fmt::Debug>(grid: &Grid<T>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        grid.write_rle(&mut bytes).unwrap();
+        assert_eq!(Grid::<T>::read_rle(bytes.as_slice()).unwrap(), *grid);
+        bytes
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(&Grid::from_str_bytes("..##\n#..."));
+        round_trip(&Grid::from_str_chars("aé\n🦀🦀"));
+        round_trip(&Grid::from_fn(5, 3, |p| p.x > p.y));
+        round_trip(&Grid::from_fn(4, 4, |p| i64::MIN + (p.x / 2) as i64));
+    }
+
+    #[test]
+    fn sparse_grids_shrink() {
+        let mut grid = Grid::new(1000, 1000, false);
+        for i in 0..50 {
+            grid[Vec2i::new(i * 13, i * 17)] = true;
+        }
+        let bytes = round_trip(&grid);
+        assert!(bytes.len() < 2000, "{} bytes", bytes.len());
+        assert!(bytes.len() * 100 < grid.width() * grid.height());
+    }
+
+    #[test]
+    fn corrupt_input() {
+        let mut bytes = Vec::new();
+        Grid::from_str_bytes("aaab\nbbbb")
+            .write_rle(&mut bytes)
+            .unwrap();
+        let err = Grid::<u8>::read_rle(&bytes[..bytes.len() - 3]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let err = Grid::<char>::read_rle(bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // the first run claims more cells than the grid has
+        let mut long_run = bytes.clone();
+        long_run[24] = 9;
+        assert!(Grid::<u8>::read_rle(long_run.as_slice()).is_err());
+        let mut huge = bytes.clone();
+        huge[8..24].fill(0xff);
+        assert!(Grid::<u8>::read_rle(huge.as_slice()).is_err());
+        huge[8..24].fill(0);
+        assert!(Grid::<u8>::read_rle(huge.as_slice()).is_err());
+        // a header that fits into the address space but not into memory
+        let mut too_large = Vec::from(&bytes[..8]);
+        for n in [1u64 << 31, 1 << 31, 1 << 62] {
+            too_large.extend(n.to_le_bytes());
+        }
+        too_large.push(b'a');
+        let err = Grid::<u8>::read_rle(too_large.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let mut not_bool = Vec::new();
+        Grid::new(2, 1, true).write_rle(&mut not_bool).unwrap();
+        *not_bool.last_mut().unwrap() = 7;
+        assert!(Grid::<bool>::read_rle(not_bool.as_slice()).is_err());
+        assert!(Grid::<u8>::read_rle(&b"garbage!"[..]).is_err());
+    }
+}
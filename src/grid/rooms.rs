@@ -0,0 +1,291 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use vecm::Vec2i;
+
+use super::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Wall,
+    Room(usize),
+    Door(usize),
+}
+
+/// A maze split into rooms, the connected areas of passable cells that aren't doors, and the
+/// doors between them. Distances between the doors are computed once, so a query only has to
+/// search the rooms of its two ends and then the much smaller door graph.
+#[derive(Debug, Clone)]
+pub struct RoomGraph {
+    cells: Grid<Cell>,
+    doors: Vec<Vec2i>,
+    /// `(door, distance)` for every door reachable from a door without passing another one.
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl<T> Grid<T> {
+    /// Splits the `passable` cells into rooms and doors, 4-connected. Doors have to be passable.
+    pub fn room_graph(
+        &self,
+        passable: impl Fn(Vec2i, &T) -> bool,
+        is_door: impl Fn(Vec2i, &T) -> bool,
+    ) -> RoomGraph {
+        let mut doors = Vec::new();
+        let mut cells = Grid::from_fn(self.width, self.height, |pos| {
+            if !passable(pos, &self[pos]) {
+                Cell::Wall
+            } else if is_door(pos, &self[pos]) {
+                doors.push(pos);
+                Cell::Door(doors.len() - 1)
+            } else {
+                Cell::Room(usize::MAX)
+            }
+        });
+        let mut rooms = 0;
+        for pos in cells.positions() {
+            if cells[pos] != Cell::Room(usize::MAX) {
+                continue;
+            }
+            cells[pos] = Cell::Room(rooms);
+            let mut stack = vec![pos];
+            while let Some(p) = stack.pop() {
+                for next in self.neighbor_positions4(p) {
+                    if cells[next] == Cell::Room(usize::MAX) {
+                        cells[next] = Cell::Room(rooms);
+                        stack.push(next);
+                    }
+                }
+            }
+            rooms += 1;
+        }
+        let mut graph = RoomGraph {
+            cells,
+            edges: vec![Vec::new(); doors.len()],
+            doors,
+        };
+        for door in 0..graph.doors.len() {
+            let pos = graph.doors[door];
+            let mut edges: HashMap<usize, usize> = HashMap::new();
+            for next in graph.cells.neighbor_positions4(pos) {
+                match graph.cells[next] {
+                    Cell::Door(other) => {
+                        edges.insert(other, 1);
+                    }
+                    Cell::Room(room) => {
+                        for (other, d) in graph.explore(pos, room, None).0 {
+                            let e = edges.entry(other).or_insert(d);
+                            *e = (*e).min(d);
+                        }
+                    }
+                    Cell::Wall => {}
+                }
+            }
+            edges.remove(&door);
+            graph.edges[door] = edges.into_iter().collect();
+        }
+        graph
+    }
+}
+
+impl RoomGraph {
+    pub fn door_count(&self) -> usize {
+        self.doors.len()
+    }
+
+    /// BFS from `start` through the cells of `room`. Returns the distances to the doors next to
+    /// the room and to `target` if it's in the room.
+    fn explore(
+        &self,
+        start: Vec2i,
+        room: usize,
+        target: Option<Vec2i>,
+    ) -> (Vec<(usize, usize)>, Option<usize>) {
+        let mut dist = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        let mut doors = Vec::new();
+        let mut found = (target == Some(start)).then_some(0);
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[&pos];
+            if pos != start && Some(pos) != target && !matches!(self.cells[pos], Cell::Room(_)) {
+                continue;
+            }
+            for next in self.cells.neighbor_positions4(pos) {
+                if dist.contains_key(&next) {
+                    continue;
+                }
+                match self.cells[next] {
+                    Cell::Room(r) if r == room => {}
+                    Cell::Door(door) => doors.push((door, d + 1)),
+                    _ => continue,
+                }
+                if Some(next) == target {
+                    found.get_or_insert(d + 1);
+                }
+                dist.insert(next, d + 1);
+                if matches!(self.cells[next], Cell::Room(_)) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        (doors, found)
+    }
+
+    /// The doors an end of a query leads to with their distances, and the distance to `other`
+    /// without passing a door.
+    fn ends(&self, pos: Vec2i, other: Vec2i) -> (Vec<(usize, usize)>, Option<usize>) {
+        match self.cells[pos] {
+            Cell::Wall => (Vec::new(), None),
+            Cell::Door(door) => {
+                let direct = self
+                    .cells
+                    .neighbor_positions4(pos)
+                    .filter_map(|next| match self.cells[next] {
+                        Cell::Room(room) => self.explore(pos, room, Some(other)).1,
+                        _ => None,
+                    })
+                    .min();
+                (vec![(door, 0)], direct.or((pos == other).then_some(0)))
+            }
+            Cell::Room(room) => self.explore(pos, room, Some(other)),
+        }
+    }
+
+    /// The length of a shortest 4-neighbor path through passable cells, `None` if there is none
+    /// or an end isn't passable.
+    pub fn shortest(&self, from: Vec2i, to: Vec2i) -> Option<usize> {
+        if !self.cells.in_bounds(from) || !self.cells.in_bounds(to) {
+            return None;
+        }
+        if self.cells[from] == Cell::Wall || self.cells[to] == Cell::Wall {
+            return None;
+        }
+        let (sources, direct) = self.ends(from, to);
+        let (targets, _) = self.ends(to, from);
+        let mut dist = vec![usize::MAX; self.doors.len()];
+        let mut heap = BinaryHeap::new();
+        for (door, d) in sources {
+            if d < dist[door] {
+                dist[door] = d;
+                heap.push(Reverse((d, door)));
+            }
+        }
+        while let Some(Reverse((d, door))) = heap.pop() {
+            if d > dist[door] {
+                continue;
+            }
+            for &(next, w) in &self.edges[door] {
+                if d + w < dist[next] {
+                    dist[next] = d + w;
+                    heap.push(Reverse((d + w, next)));
+                }
+            }
+        }
+        targets
+            .into_iter()
+            .filter(|&(door, _)| dist[door] != usize::MAX)
+            .map(|(door, d)| dist[door] + d)
+            .chain(direct)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    fn bfs(grid: &Grid<char>, from: Vec2i, to: Vec2i) -> Option<usize> {
+        if grid[from] == '#' {
+            return None;
+        }
+        grid.bfs_distances(from, |_, &c| c != '#')[to].map(|d| d as usize)
+    }
+
+    #[test]
+    fn matches_bfs() {
+        let mut rng = Pcg32::new(17);
+        for round in 0..40 {
+            let (w, h) = (rng.index(12) + 2, rng.index(12) + 2);
+            let grid = Grid::from_fn(w, h, |_| match rng.below(10) {
+                0..=2 => '#',
+                3 => 'D',
+                _ => '.',
+            });
+            let rooms = grid.room_graph(|_, &c| c != '#', |_, &c| c == 'D');
+            for _ in 0..30 {
+                let from = Vec2i::new(rng.index(w) as i32, rng.index(h) as i32);
+                let to = Vec2i::new(rng.index(w) as i32, rng.index(h) as i32);
+                assert_eq!(
+                    rooms.shortest(from, to),
+                    bfs(&grid, from, to),
+                    "round {round}, {from:?} to {to:?}\n{grid}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn corridors_and_doors() {
+        let grid = Grid::from_str_chars(
+            "#########\n\
+             #...D...#\n\
+             #.#####D#\n\
+             #.#...#.#\n\
+             #...#...#\n\
+             #########",
+        );
+        let rooms = grid.room_graph(|_, &c| c != '#', |_, &c| c == 'D');
+        assert_eq!(rooms.door_count(), 2);
+        assert_eq!(rooms.shortest(Vec2i::new(1, 1), Vec2i::new(7, 1)), Some(6));
+        assert_eq!(rooms.shortest(Vec2i::new(3, 1), Vec2i::new(7, 4)), Some(7));
+        assert_eq!(rooms.shortest(Vec2i::new(4, 1), Vec2i::new(4, 1)), Some(0));
+        assert_eq!(rooms.shortest(Vec2i::new(0, 0), Vec2i::new(1, 1)), None);
+    }
+
+    /// Rooms of 9x9 cells with a door in each wall between neighbors.
+    fn mansion(rooms: usize) -> Grid<char> {
+        let size = rooms * 10 + 1;
+        Grid::from_fn(size, size, |pos| {
+            let (x, y) = (pos.x as usize % 10, pos.y as usize % 10);
+            if x == 0 && y == 0 || pos.x as usize == size - 1 || pos.y as usize == size - 1 {
+                '#'
+            } else if (x == 0 && y == 5 && pos.x > 0) || (y == 0 && x == 5 && pos.y > 0) {
+                'D'
+            } else if x == 0 || y == 0 {
+                '#'
+            } else {
+                '.'
+            }
+        })
+    }
+
+    #[test]
+    #[ignore = "timing comparison on a large maze"]
+    fn faster_than_bfs() {
+        let grid = mansion(30);
+        let rooms = grid.room_graph(|_, &c| c != '#', |_, &c| c == 'D');
+        let mut rng = Pcg32::new(5);
+        let size = grid.width();
+        let queries: Vec<(Vec2i, Vec2i)> = (0..200)
+            .map(|_| {
+                let mut pos = || Vec2i::new(rng.index(size) as i32, rng.index(size) as i32);
+                (pos(), pos())
+            })
+            .collect();
+        let time = std::time::Instant::now();
+        let fast: Vec<_> = queries.iter().map(|&(a, b)| rooms.shortest(a, b)).collect();
+        let fast_time = time.elapsed();
+        let time = std::time::Instant::now();
+        let slow: Vec<_> = queries.iter().map(|&(a, b)| bfs(&grid, a, b)).collect();
+        let slow_time = time.elapsed();
+        assert_eq!(fast, slow);
+        println!(
+            "room graph: {}, BFS: {}",
+            crate::fmt_duration(fast_time),
+            crate::fmt_duration(slow_time)
+        );
+        assert!(fast_time < slow_time);
+    }
+}
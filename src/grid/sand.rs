@@ -0,0 +1,150 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+/// Where a unit dropped by [`Grid::drop_from`] ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropResult {
+    Settled(Vec2i),
+    /// Fell out of the grid at the bottom or the sides.
+    FellOff,
+    /// The source cell itself is blocked, nothing was dropped.
+    SourceBlocked,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Drops one unit from `source`: it moves down if that cell isn't blocked, otherwise down-left
+    /// and then down-right, and settles as `settle_value` once all three are blocked.
+    ///
+    /// With a floor, row `floor` counts as blocked everywhere and the grid grows down to it and
+    /// to the right as far as the unit gets, filled with copies of the open source cell. The
+    /// grid can't grow to the left without moving all positions, so it has to leave at least
+    /// `floor - source.y` columns on the left of the source, reaching x < 0 panics.
+    pub fn drop_from(
+        &mut self,
+        source: Vec2i,
+        blocked: impl Fn(&T) -> bool,
+        settle_value: T,
+        floor: Option<usize>,
+    ) -> DropResult {
+        if blocked(&self[source]) {
+            return DropResult::SourceBlocked;
+        }
+        if let Some(floor) = floor {
+            if self.height < floor {
+                self.grow(self.width, floor, self[source].clone());
+            }
+        }
+        let mut pos = source;
+        'falling: loop {
+            if floor.is_some_and(|floor| pos.y as usize + 1 == floor) {
+                break;
+            }
+            for dx in [0, -1, 1] {
+                let next = pos + Vec2i::new(dx, 1);
+                if floor.is_some() {
+                    assert!(
+                        next.x >= 0,
+                        "the grid needs more room left of the source {source:?} for the floor"
+                    );
+                    if next.x as usize >= self.width {
+                        self.grow(next.x as usize + 1, self.height, self[source].clone());
+                    }
+                } else if !self.in_bounds(next) {
+                    return DropResult::FellOff;
+                }
+                if !blocked(&self[next]) {
+                    pos = next;
+                    continue 'falling;
+                }
+            }
+            break;
+        }
+        self[pos] = settle_value;
+        DropResult::Settled(pos)
+    }
+
+    /// Drops units from `source` until one falls off or the source is blocked, see
+    /// [`Grid::drop_from`]. Returns the number of settled units.
+    pub fn fill_from(
+        &mut self,
+        source: Vec2i,
+        blocked: impl Fn(&T) -> bool,
+        settle_value: T,
+        floor: Option<usize>,
+    ) -> usize {
+        let mut settled = 0;
+        while let DropResult::Settled(_) =
+            self.drop_from(source, &blocked, settle_value.clone(), floor)
+        {
+            settled += 1;
+        }
+        settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::line_between;
+
+    const ROCKS: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    fn cave() -> (Grid<char>, usize) {
+        let paths: Vec<Vec<Vec2i>> = ROCKS
+            .lines()
+            .map(|l| {
+                l.split(" -> ")
+                    .map(|p| {
+                        let (x, y) = p.split_once(',').unwrap();
+                        Vec2i::new(x.parse().unwrap(), y.parse().unwrap())
+                    })
+                    .collect()
+            })
+            .collect();
+        let max = paths.iter().flatten().fold(Vec2i::new(0, 0), |m, p| {
+            Vec2i::new(m.x.max(p.x), m.y.max(p.y))
+        });
+        let mut grid = Grid::new(max.x as usize + 1, max.y as usize + 1, '.');
+        for path in &paths {
+            for pair in path.windows(2) {
+                for p in line_between(pair[0], pair[1]) {
+                    grid[p] = '#';
+                }
+            }
+        }
+        (grid, max.y as usize + 2)
+    }
+
+    #[test]
+    fn sand_without_floor() {
+        let (mut grid, _) = cave();
+        let source = Vec2i::new(500, 0);
+        let blocked = |&c: &char| c != '.';
+        assert_eq!(
+            grid.drop_from(source, blocked, 'o', None),
+            DropResult::Settled(Vec2i::new(500, 8))
+        );
+        assert_eq!(grid.fill_from(source, blocked, 'o', None), 23);
+        assert_eq!(
+            grid.drop_from(source, blocked, 'o', None),
+            DropResult::FellOff
+        );
+        assert_eq!(grid.count(&'o'), 24);
+    }
+
+    #[test]
+    fn sand_with_floor() {
+        let (mut grid, floor) = cave();
+        let source = Vec2i::new(500, 0);
+        let blocked = |&c: &char| c != '.';
+        assert_eq!(grid.fill_from(source, blocked, 'o', Some(floor)), 93);
+        assert_eq!(grid.height(), floor);
+        assert_eq!(grid.width(), 500 + floor);
+        assert_eq!(
+            grid.drop_from(source, blocked, 'o', Some(floor)),
+            DropResult::SourceBlocked
+        );
+    }
+}
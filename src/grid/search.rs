@@ -0,0 +1,473 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use vecm::Vec2i;
+
+use super::{Grid, Side, StateVisited, DIRS4, DIRS8};
+
+const SIDES: [Side; 4] = [Side::L, Side::R, Side::T, Side::B];
+
+fn side_index(side: Side) -> usize {
+    match side {
+        Side::L => 0,
+        Side::R => 1,
+        Side::T => 2,
+        Side::B => 3,
+    }
+}
+
+/// Dijkstra over states identified by indices in `0..n`. Stale heap entries are skipped by
+/// comparing against the best known cost. Returns the cost of the first goal state popped.
+pub(crate) fn dijkstra_dense(
+    n: usize,
+    starts: impl IntoIterator<Item = (usize, u64)>,
+    mut successors: impl FnMut(usize, &mut Vec<(usize, u64)>),
+    mut is_goal: impl FnMut(usize) -> bool,
+) -> Option<u64> {
+    let mut best = vec![u64::MAX; n];
+    let mut heap = BinaryHeap::new();
+    for (state, cost) in starts {
+        if cost < best[state] {
+            best[state] = cost;
+            heap.push(Reverse((cost, state)));
+        }
+    }
+    let mut next = Vec::new();
+    while let Some(Reverse((cost, state))) = heap.pop() {
+        if cost > best[state] {
+            continue;
+        }
+        if is_goal(state) {
+            return Some(cost);
+        }
+        next.clear();
+        successors(state, &mut next);
+        for &(succ, step) in &next {
+            let new_cost = cost + step;
+            if new_cost < best[succ] {
+                best[succ] = new_cost;
+                heap.push(Reverse((new_cost, succ)));
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra that settles states as they are popped. `visit` marks a state and returns whether
+/// it was new, so the visited set can be more compact than a cost per state.
+pub(crate) fn dijkstra_visited(
+    starts: impl IntoIterator<Item = (usize, u64)>,
+    mut successors: impl FnMut(usize, &mut Vec<(usize, u64)>),
+    mut is_goal: impl FnMut(usize) -> bool,
+    mut visit: impl FnMut(usize) -> bool,
+) -> Option<u64> {
+    let mut heap: BinaryHeap<_> = starts
+        .into_iter()
+        .map(|(state, cost)| Reverse((cost, state)))
+        .collect();
+    let mut next = Vec::new();
+    while let Some(Reverse((cost, state))) = heap.pop() {
+        if !visit(state) {
+            continue;
+        }
+        if is_goal(state) {
+            return Some(cost);
+        }
+        next.clear();
+        successors(state, &mut next);
+        heap.extend(
+            next.drain(..)
+                .map(|(succ, step)| Reverse((cost + step, succ))),
+        );
+    }
+    None
+}
+
+impl<T> Grid<T> {
+    /// Cheapest path cost from `start` to `goal` where each entered cell costs `cost(pos, cell)`
+    /// and the path has to move in straight runs of `min_run..=max_run` cells before turning.
+    /// The final run into the goal has to be at least `min_run` long as well. Reversing is not
+    /// allowed.
+    pub fn dijkstra_run_constrained(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        cost: impl Fn(Vec2i, &T) -> u64,
+        min_run: u8,
+        max_run: u8,
+    ) -> Option<u64> {
+        assert!(max_run > 0, "max_run has to be at least 1");
+        assert!(min_run <= max_run, "min_run can't exceed max_run");
+        let runs = max_run as usize + 1;
+        let encode = |pos: Vec2i, side: Side, run: u8| {
+            (self.index_of(pos) * 4 + side_index(side)) * runs + run as usize
+        };
+        let decode = |state: usize| {
+            let run = (state % runs) as u8;
+            let side = SIDES[state / runs % 4];
+            let i = state / runs / 4;
+            let pos = self.pos_of(i);
+            (pos, side, run)
+        };
+        if start == goal {
+            return Some(0);
+        }
+        // the start has no incoming direction, so every direction starts a fresh run
+        let starts = SIDES.into_iter().filter_map(|side| {
+            let next = start + side.dir();
+            self.in_bounds(next)
+                .then(|| (encode(next, side, 1), cost(next, &self[next])))
+        });
+        let successors = |state, out: &mut Vec<(usize, u64)>| {
+            let (pos, side, run) = decode(state);
+            let mut push = |side: Side, run: u8| {
+                let next = pos + side.dir();
+                if self.in_bounds(next) {
+                    out.push((encode(next, side, run), cost(next, &self[next])));
+                }
+            };
+            if run < max_run {
+                push(side, run + 1);
+            }
+            if run >= min_run {
+                push(side.rot_left(), 1);
+                push(side.rot_right(), 1);
+            }
+        };
+        let is_goal = |state| {
+            let (pos, _, run) = decode(state);
+            pos == goal && run >= min_run
+        };
+        // short runs fit every (side, run) of a cell into the bits of a StateVisited, longer
+        // ones need the dense cost array over all states
+        if 4 * runs <= 16 {
+            let mut visited = StateVisited::new(self.width, self.height, 4, runs as u32);
+            dijkstra_visited(starts, successors, is_goal, |state| {
+                let (pos, side, run) = decode(state);
+                visited.insert(pos, side, run as u32)
+            })
+        } else {
+            dijkstra_dense(self.buf.len() * 4 * runs, starts, successors, is_goal)
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Orthogonal shortest path where entering a cell costs 0 or 1: `cost(pos, cell)` is
+    /// `Some(true)` for cells costing 1, `Some(false)` for free ones and `None` for walls.
+    /// See [`crate::search::bfs01`].
+    pub fn bfs01(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        cost: impl Fn(Vec2i, &T) -> Option<bool>,
+    ) -> Option<u64> {
+        if !self.in_bounds(start) || !self.in_bounds(goal) {
+            return None;
+        }
+        let mut best = vec![u64::MAX; self.buf.len()];
+        let mut deque = VecDeque::new();
+        best[self.index_of(start)] = 0;
+        deque.push_back((self.index_of(start), 0));
+        while let Some((i, dist)) = deque.pop_front() {
+            if dist > best[i] {
+                continue;
+            }
+            let pos = self.pos_of(i);
+            if pos == goal {
+                return Some(dist);
+            }
+            for &(dx, dy) in &DIRS4 {
+                let next = pos + Vec2i::new(dx, dy);
+                if !self.in_bounds(next) {
+                    continue;
+                }
+                let Some(costs_one) = cost(next, &self[next]) else {
+                    continue;
+                };
+                let j = self.index_of(next);
+                let new_dist = dist + costs_one as u64;
+                if new_dist < best[j] {
+                    best[j] = new_dist;
+                    if costs_one {
+                        deque.push_back((j, new_dist));
+                    } else {
+                        deque.push_front((j, new_dist));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T> Grid<T> {
+    /// A* from `start` to `goal` with unit step costs, moving orthogonally or also diagonally.
+    /// Returns the number of steps and the path including both endpoints.
+    pub fn astar(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        passable: impl Fn(Vec2i, &T) -> bool,
+        diagonal: bool,
+    ) -> Option<(u64, Vec<Vec2i>)> {
+        self.astar_cells(start, goal, diagonal, 1, |pos| {
+            passable(pos, &self[pos]).then_some(1)
+        })
+    }
+
+    /// A* where entering a cell costs `cost(pos, cell)`, `None` meaning the cell is impassable.
+    /// The heuristic is the distance to the goal times the cheapest cell cost in the grid.
+    pub fn astar_weighted(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        cost: impl Fn(Vec2i, &T) -> Option<u64>,
+        diagonal: bool,
+    ) -> Option<(u64, Vec<Vec2i>)> {
+        let min_cost = self
+            .positions()
+            .filter_map(|pos| cost(pos, &self[pos]))
+            .min()?;
+        self.astar_cells(start, goal, diagonal, min_cost, |pos| cost(pos, &self[pos]))
+    }
+
+    fn astar_cells(
+        &self,
+        start: Vec2i,
+        goal: Vec2i,
+        diagonal: bool,
+        min_cost: u64,
+        cost: impl Fn(Vec2i) -> Option<u64>,
+    ) -> Option<(u64, Vec<Vec2i>)> {
+        if !self.in_bounds(start) || !self.in_bounds(goal) {
+            return None;
+        }
+        if start == goal {
+            return Some((0, vec![start]));
+        }
+        if cost(start).is_none() || cost(goal).is_none() {
+            return None;
+        }
+        let heuristic = |pos: Vec2i| {
+            let (dx, dy) = (pos.x.abs_diff(goal.x), pos.y.abs_diff(goal.y));
+            let steps = if diagonal { dx.max(dy) } else { dx + dy };
+            steps as u64 * min_cost
+        };
+        let dirs: &[(i32, i32)] = if diagonal { &DIRS8 } else { &DIRS4 };
+        let mut best = vec![u64::MAX; self.buf.len()];
+        let mut prev = vec![usize::MAX; self.buf.len()];
+        let mut heap = BinaryHeap::new();
+        best[self.index_of(start)] = 0;
+        heap.push(Reverse((heuristic(start), 0, self.index_of(start))));
+        while let Some(Reverse((_, cost_so_far, i))) = heap.pop() {
+            if cost_so_far > best[i] {
+                continue;
+            }
+            let pos = self.pos_of(i);
+            if pos == goal {
+                let mut path = vec![pos];
+                let mut i = i;
+                while prev[i] != usize::MAX {
+                    i = prev[i];
+                    path.push(self.pos_of(i));
+                }
+                path.reverse();
+                return Some((cost_so_far, path));
+            }
+            for &(dx, dy) in dirs {
+                let next = pos + Vec2i::new(dx, dy);
+                if !self.in_bounds(next) {
+                    continue;
+                }
+                let Some(step) = cost(next) else { continue };
+                let j = self.index_of(next);
+                let new_cost = cost_so_far + step;
+                if new_cost < best[j] {
+                    best[j] = new_cost;
+                    prev[j] = i;
+                    heap.push(Reverse((new_cost + heuristic(next), new_cost, j)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::{Grid, Pcg32};
+
+    const CITY: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    fn heat_loss(s: &str, min_run: u8, max_run: u8) -> Option<u64> {
+        let grid = Grid::from_str_bytes(s);
+        let goal = Vec2i::new(grid.width() as i32 - 1, grid.height() as i32 - 1);
+        grid.dijkstra_run_constrained(
+            Vec2i::new(0, 0),
+            goal,
+            |_, &c| (c - b'0') as u64,
+            min_run,
+            max_run,
+        )
+    }
+
+    #[test]
+    fn bfs01_matches_weighted_astar() {
+        let mut rng = Pcg32::new(8);
+        for _ in 0..50 {
+            let (w, h) = (rng.index(8) + 1, rng.index(8) + 1);
+            let grid = Grid::from_fn(w, h, |pos| {
+                if pos == Vec2i::new(0, 0) {
+                    b'.'
+                } else {
+                    b"#..~~"[rng.index(5)]
+                }
+            });
+            let cost = |_, &c: &u8| match c {
+                b'#' => None,
+                b'~' => Some(true),
+                _ => Some(false),
+            };
+            let goal = Vec2i::new(w as i32 - 1, h as i32 - 1);
+            let start = Vec2i::new(0, 0);
+            let expected = grid
+                .astar_weighted(start, goal, |pos, c| cost(pos, c).map(u64::from), false)
+                .map(|(dist, _)| dist);
+            assert_eq!(grid.bfs01(start, goal, cost), expected, "{grid:?}");
+        }
+    }
+
+    #[test]
+    fn crucible() {
+        assert_eq!(heat_loss(CITY, 0, 3), Some(102));
+    }
+
+    #[test]
+    fn ultra_crucible() {
+        assert_eq!(heat_loss(CITY, 4, 10), Some(94));
+        let second = "111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+        assert_eq!(heat_loss(second, 4, 10), Some(71));
+    }
+
+    fn assert_valid_path(path: &[Vec2i], start: Vec2i, goal: Vec2i, diagonal: bool) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for w in path.windows(2) {
+            let (dx, dy) = ((w[1].x - w[0].x).abs(), (w[1].y - w[0].y).abs());
+            let ok = if diagonal {
+                dx.max(dy) == 1
+            } else {
+                dx + dy == 1
+            };
+            assert!(ok, "invalid step from {:?} to {:?}", w[0], w[1]);
+        }
+    }
+
+    fn bfs_distance(grid: &Grid<bool>, start: Vec2i, goal: Vec2i) -> Option<u64> {
+        if !grid[start] {
+            return None;
+        }
+        grid.positions_within_steps(start, usize::MAX, |_, &open| open)
+            .into_iter()
+            .find(|&(p, _)| p == goal)
+            .map(|(_, d)| d as u64)
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let mut rng = Pcg32::new(3);
+        for i in 0..200 {
+            let grid = if i % 2 == 0 {
+                Grid::random_obstacles(15, 15, 0.3, &mut rng)
+            } else {
+                Grid::generate_maze(15, 15, &mut rng)
+            };
+            let (start, goal) = (Vec2i::new(0, 0), Vec2i::new(14, 14));
+            let open = |_: Vec2i, &open: &bool| open;
+            let found = grid.astar(start, goal, open, false);
+            assert_eq!(
+                found.as_ref().map(|(d, _)| *d),
+                bfs_distance(&grid, start, goal)
+            );
+            let weighted = grid.astar_weighted(start, goal, |_, &open| open.then_some(1), false);
+            assert_eq!(weighted.map(|(d, _)| d), found.as_ref().map(|(d, _)| *d));
+            if let Some((steps, path)) = found {
+                assert_eq!(path.len() as u64, steps + 1);
+                assert!(path.iter().all(|&p| grid[p]));
+                assert_valid_path(&path, start, goal, false);
+            }
+        }
+    }
+
+    #[test]
+    fn astar_diagonal() {
+        let grid = Grid::from_nested(vec![vec![true; 10]; 10]);
+        let open = |_: Vec2i, &open: &bool| open;
+        let (start, goal) = (Vec2i::new(0, 0), Vec2i::new(9, 9));
+        let (orthogonal, _) = grid.astar(start, goal, open, false).unwrap();
+        let (diagonal, path) = grid.astar(start, goal, open, true).unwrap();
+        assert_eq!((orthogonal, diagonal), (18, 9));
+        assert_valid_path(&path, start, goal, true);
+        assert_eq!(
+            grid.astar(start, start, open, false),
+            Some((0, vec![start]))
+        );
+    }
+
+    #[test]
+    fn astar_unreachable() {
+        let grid = Grid::from_str_bytes("..#..\n..#..\n..#..");
+        let open = |_: Vec2i, &c: &u8| c == b'.';
+        assert_eq!(
+            grid.astar(Vec2i::new(0, 0), Vec2i::new(4, 2), open, false),
+            None
+        );
+        assert!(grid
+            .astar(Vec2i::new(0, 0), Vec2i::new(4, 2), open, true)
+            .is_none());
+        assert_eq!(
+            grid.astar(Vec2i::new(0, 0), Vec2i::new(2, 1), open, true),
+            None
+        );
+    }
+
+    #[test]
+    fn astar_weighted_matches_dijkstra() {
+        let grid = Grid::from_str_bytes(CITY);
+        let goal = Vec2i::new(12, 12);
+        let (cost, path) = grid
+            .astar_weighted(
+                Vec2i::new(0, 0),
+                goal,
+                |_, &c| Some((c - b'0') as u64),
+                false,
+            )
+            .unwrap();
+        let reference =
+            grid.dijkstra_run_constrained(Vec2i::new(0, 0), goal, |_, &c| (c - b'0') as u64, 0, 24);
+        assert_eq!(Some(cost), reference);
+        let path_cost: u64 = path[1..].iter().map(|&p| (grid[p] - b'0') as u64).sum();
+        assert_eq!(path_cost, cost);
+    }
+}
@@ -0,0 +1,179 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+/// A pattern of expected cell values at offsets from an origin. Wildcard cells aren't stored,
+/// they only take up space in the bounding box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape<T> {
+    cells: Vec<(Vec2i, T)>,
+    width: usize,
+    height: usize,
+}
+impl<T> Shape<T> {
+    /// A shape from offsets inside of a `width` x `height` box.
+    pub fn from_cells(cells: Vec<(Vec2i, T)>, width: usize, height: usize) -> Self {
+        assert!(
+            cells.iter().all(|(p, _)| (0..width as i32).contains(&p.x)
+                && (0..height as i32).contains(&p.y)),
+            "shape cells have to be inside of its bounds"
+        );
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn cells(&self) -> &[(Vec2i, T)] {
+        &self.cells
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn transformed(&self, width: usize, height: usize, f: impl Fn(Vec2i) -> Vec2i) -> Self
+    where
+        T: Clone,
+    {
+        let mut cells: Vec<(Vec2i, T)> =
+            self.cells.iter().map(|(p, v)| (f(*p), v.clone())).collect();
+        cells.sort_by_key(|(p, _)| (p.y, p.x));
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Rotated by 90 degrees clockwise.
+    pub fn rotated(&self) -> Self
+    where
+        T: Clone,
+    {
+        let h = self.height as i32;
+        self.transformed(self.height, self.width, |p| Vec2i::new(h - 1 - p.y, p.x))
+    }
+
+    /// Mirrored horizontally.
+    pub fn flipped(&self) -> Self
+    where
+        T: Clone,
+    {
+        let w = self.width as i32;
+        self.transformed(self.width, self.height, |p| Vec2i::new(w - 1 - p.x, p.y))
+    }
+
+    /// All distinct rotations and reflections, at most 8.
+    pub fn orientations(&self) -> Vec<Self>
+    where
+        T: Clone + PartialEq,
+    {
+        let mut out: Vec<Self> = Vec::new();
+        let mut shape = self.transformed(self.width, self.height, |p| p);
+        for _ in 0..4 {
+            for candidate in [shape.flipped(), shape.clone()] {
+                if !out.contains(&candidate) {
+                    out.push(candidate);
+                }
+            }
+            shape = shape.rotated();
+        }
+        out
+    }
+}
+impl Shape<char> {
+    /// A shape from lines of characters where `wildcard` matches anything.
+    pub fn from_pattern(s: &str, wildcard: char) -> Self {
+        let lines: Vec<&str> = s.lines().collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let cells = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c != wildcard)
+                    .map(move |(x, c)| (Vec2i::new(x as i32, y as i32), c))
+            })
+            .collect();
+        Self::from_cells(cells, width, lines.len())
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Whether all non-wildcard cells of `shape` placed at `origin` match. Wildcards may lie
+    /// outside of the grid.
+    pub fn match_shape_at(&self, origin: Vec2i, shape: &Shape<T>) -> bool {
+        shape
+            .cells
+            .iter()
+            .all(|(offset, value)| self.get(origin + *offset) == Some(value))
+    }
+
+    /// All origins where `shape` matches, in reading order.
+    pub fn find_shape(&self, shape: &Shape<T>) -> Vec<Vec2i> {
+        let Some(min_x) = shape.cells.iter().map(|(p, _)| p.x).min() else {
+            return self.positions().collect();
+        };
+        let min_y = shape.cells.iter().map(|(p, _)| p.y).min().unwrap();
+        let max_x = shape.cells.iter().map(|(p, _)| p.x).max().unwrap();
+        let max_y = shape.cells.iter().map(|(p, _)| p.y).max().unwrap();
+        let (w, h) = (self.width as i32, self.height as i32);
+        (-min_y..h - max_y)
+            .flat_map(|y| (-min_x..w - max_x).map(move |x| Vec2i::new(x, y)))
+            .filter(|&origin| self.match_shape_at(origin, shape))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::Shape;
+    use crate::Grid;
+
+    const WORDS: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn x_mas() {
+        let grid = Grid::from_str_chars(WORDS);
+        let x = Shape::from_pattern("M.S\n.A.\nM.S", '.');
+        let orientations = x.orientations();
+        assert_eq!(orientations.len(), 4);
+        let count: usize = orientations.iter().map(|s| grid.find_shape(s).len()).sum();
+        assert_eq!(count, 9);
+        assert!(grid.match_shape_at(Vec2i::new(1, 0), &x));
+    }
+
+    #[test]
+    fn wildcards_at_edges() {
+        let grid = Grid::from_str_chars("#..\n...\n..#");
+        let corner = Shape::from_pattern("??\n?#", '?');
+        assert_eq!(
+            grid.find_shape(&corner),
+            [Vec2i::new(-1, -1), Vec2i::new(1, 1)]
+        );
+        assert!(!grid.match_shape_at(Vec2i::new(2, 2), &corner));
+        let l = Shape::from_pattern("#.\n##", '?');
+        assert_eq!(l.orientations().len(), 4);
+        let f = Shape::from_pattern("##\n#?\n##\n#?", '?');
+        assert_eq!(f.orientations().len(), 8);
+        assert!(f.orientations().iter().all(|s| s.cells().len() == 6));
+    }
+}
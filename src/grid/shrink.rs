@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use super::Grid;
+
+/// Removes the rows (or with `cols` set the columns) `start..start + len`.
+fn without_lines(grid: &Grid<char>, start: usize, len: usize, cols: bool) -> Grid<char> {
+    let removed = |i: usize| (start..start + len).contains(&i);
+    let buf = grid
+        .positions()
+        .filter(|p| !removed(if cols { p.x } else { p.y } as usize))
+        .map(|p| grid[p])
+        .collect();
+    let (width, height) = if cols {
+        (grid.width - len, grid.height)
+    } else {
+        (grid.width, grid.height - len)
+    };
+    Grid::from_buf(buf, width, height)
+}
+
+/// Tries removing blocks of `len` rows or columns, keeping every removal `keep` accepts.
+fn shrink_lines(grid: &mut Grid<char>, cols: bool, keep: &impl Fn(&Grid<char>) -> bool) -> bool {
+    let mut shrunk = false;
+    let lines = |grid: &Grid<char>| if cols { grid.width } else { grid.height };
+    let mut len = lines(grid) / 2;
+    while len > 0 {
+        let mut start = 0;
+        while start + len <= lines(grid) && len < lines(grid) {
+            let candidate = without_lines(grid, start, len, cols);
+            if keep(&candidate) {
+                *grid = candidate;
+                shrunk = true;
+            } else {
+                start += len;
+            }
+        }
+        len = len.min(lines(grid)) / 2;
+    }
+    shrunk
+}
+
+/// Delta-debugging style reduction of a fixture: removes blocks of rows and columns, halving the
+/// block size whenever no block can be removed, for as long as `keep` still holds. The result is
+/// minimal in that removing any single row or column makes `keep` fail. `keep` has to hold for
+/// the input grid.
+pub fn shrink_grid_fixture(grid: &Grid<char>, keep: impl Fn(&Grid<char>) -> bool) -> Grid<char> {
+    assert!(
+        keep(grid),
+        "the predicate doesn't hold for the original grid"
+    );
+    let mut grid = Grid::from_buf(grid.buf.clone(), grid.width, grid.height);
+    while shrink_lines(&mut grid, false, &keep) | shrink_lines(&mut grid, true, &keep) {}
+    grid
+}
+
+/// Replaces characters according to `mapping`, keeping unmapped ones.
+pub fn remap_chars(grid: &Grid<char>, mapping: &HashMap<char, char>) -> Grid<char> {
+    let buf = grid
+        .buf
+        .iter()
+        .map(|c| mapping.get(c).copied().unwrap_or(*c))
+        .collect();
+    Grid {
+        buf,
+        width: grid.width,
+        height: grid.height,
+        row_lens: grid.row_lens.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use super::{remap_chars, shrink_grid_fixture, without_lines};
+    use crate::{Grid, Pcg32};
+
+    fn wall_in_each_quadrant(grid: &Grid<char>) -> bool {
+        let (hw, hh) = (grid.width() as i32 / 2, grid.height() as i32 / 2);
+        let mut quadrants = [false; 4];
+        for pos in grid.positions() {
+            if grid[pos] == '#' {
+                quadrants[(pos.x >= hw) as usize + 2 * (pos.y >= hh) as usize] = true;
+            }
+        }
+        quadrants == [true; 4]
+    }
+
+    #[test]
+    fn shrink_quadrants() {
+        let mut rng = Pcg32::new(47);
+        let grid = Grid::from_nested(
+            (0..50)
+                .map(|_| {
+                    (0..50)
+                        .map(|_| if rng.chance(0.05) { '#' } else { '.' })
+                        .collect()
+                })
+                .collect(),
+        );
+        let accepted = RefCell::new(Vec::new());
+        let shrunk = shrink_grid_fixture(&grid, |g| {
+            let keep = wall_in_each_quadrant(g);
+            if keep {
+                accepted.borrow_mut().push((g.width(), g.height()));
+            }
+            keep
+        });
+        assert!(wall_in_each_quadrant(&shrunk));
+        assert!(shrunk.width() * shrunk.height() <= 16, "{shrunk}");
+        for (x, y) in [(shrunk.width(), 0), (0, shrunk.height())] {
+            for i in 0..x.max(y) {
+                let smaller = without_lines(&shrunk, i, 1, x > 0);
+                assert!(!wall_in_each_quadrant(&smaller));
+            }
+        }
+        let accepted = accepted.into_inner();
+        assert!(accepted
+            .windows(2)
+            .skip(1)
+            .all(|w| w[1].0 * w[1].1 < w[0].0 * w[0].1));
+    }
+
+    #[test]
+    fn remap() {
+        let grid = Grid::from_str_chars("AB.\n.BA");
+        let mapping = HashMap::from([('A', 'x'), ('B', 'y')]);
+        assert_eq!(
+            remap_chars(&grid, &mapping),
+            Grid::from_str_chars("xy.\n.yx")
+        );
+    }
+}
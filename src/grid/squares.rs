@@ -0,0 +1,167 @@
+use vecm::Vec2i;
+
+use super::Grid;
+use crate::monotonic::{next_smaller_indices, prev_smaller_indices};
+
+impl<T: Copy + Into<i64>> Grid<T> {
+    /// The square of any size with the largest sum as its top left corner, size and sum. Ties go
+    /// to the smaller square, then to the first corner in reading order. Every square is summed
+    /// in constant time from a summed-area table, and the search stops early once a square
+    /// holds all of the positive values.
+    pub fn max_square_sum(&self) -> (Vec2i, usize, i64) {
+        let (w, h) = (self.width, self.height);
+        // table[y][x] is the sum of the cells above and left of (x, y)
+        let mut table = vec![0i64; (w + 1) * (h + 1)];
+        for y in 0..h {
+            for x in 0..w {
+                table[(y + 1) * (w + 1) + x + 1] = self.buf[y * w + x].into()
+                    + table[y * (w + 1) + x + 1]
+                    + table[(y + 1) * (w + 1) + x]
+                    - table[y * (w + 1) + x];
+            }
+        }
+        let at = |x: usize, y: usize| table[y * (w + 1) + x];
+        let positive: i64 = self.buf.iter().map(|&v| v.into().max(0)).sum();
+        let mut best = (Vec2i::new(0, 0), 1, i64::MIN);
+        for size in 1..=w.min(h) {
+            for y in 0..=h - size {
+                for x in 0..=w - size {
+                    let sum = at(x + size, y + size) - at(x, y + size) - at(x + size, y) + at(x, y);
+                    if sum > best.2 {
+                        best = (Vec2i::new(x as i32, y as i32), size, sum);
+                    }
+                }
+            }
+            if best.2 >= positive {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Grid<bool> {
+    /// The top left corner and size of the largest square of true cells, the first one in
+    /// reading order among equally large ones. Size 0 if there are no true cells.
+    pub fn largest_true_square(&self) -> (Vec2i, usize) {
+        // the size of the largest square ending at each cell of the previous and current row
+        let mut above = vec![0; self.width];
+        let mut best = (Vec2i::new(0, 0), 0);
+        for (y, row) in self.rows().enumerate() {
+            let mut current = vec![0; self.width];
+            for (x, &cell) in row.iter().enumerate() {
+                if !cell {
+                    continue;
+                }
+                current[x] = if x == 0 {
+                    1
+                } else {
+                    above[x].min(current[x - 1]).min(above[x - 1]) + 1
+                };
+                let size = current[x];
+                let corner = Vec2i::new((x + 1 - size) as i32, (y + 1 - size) as i32);
+                if size > best.1 || size == best.1 && (corner.y, corner.x) < (best.0.y, best.0.x) {
+                    best = (corner, size);
+                }
+            }
+            above = current;
+        }
+        best
+    }
+
+    /// The top left corner, width and height of the largest rectangle of true cells by area.
+    /// Every row is the base of a histogram of the true cells above it, where the largest
+    /// rectangle spans between the nearest lower bars on both sides.
+    pub fn largest_true_rectangle(&self) -> (Vec2i, usize, usize) {
+        let mut heights = vec![0usize; self.width];
+        let mut best = (Vec2i::new(0, 0), 0, 0);
+        for (y, row) in self.rows().enumerate() {
+            for (height, &cell) in heights.iter_mut().zip(row) {
+                *height = if cell { *height + 1 } else { 0 };
+            }
+            let prev = prev_smaller_indices(&heights);
+            let next = next_smaller_indices(&heights);
+            for (x, &height) in heights.iter().enumerate() {
+                let left = prev[x].map_or(0, |i| i + 1);
+                let width = next[x].unwrap_or(self.width) - left;
+                if width * height > best.1 * best.2 {
+                    let corner = Vec2i::new(left as i32, (y + 1 - height) as i32);
+                    best = (corner, width, height);
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    fn power_grid(serial: i64) -> Grid<i64> {
+        Grid::from_fn(300, 300, |pos| {
+            let (x, y) = (pos.x as i64 + 1, pos.y as i64 + 1);
+            let rack = x + 10;
+            ((rack * y + serial) * rack / 100 % 10) - 5
+        })
+    }
+
+    #[test]
+    fn power_squares() {
+        let grid = power_grid(18);
+        assert_eq!(grid[Vec2i::new(89, 268)], 3);
+        assert_eq!(grid.max_square_sum(), (Vec2i::new(89, 268), 16, 113));
+        assert_eq!(
+            power_grid(42).max_square_sum(),
+            (Vec2i::new(231, 250), 12, 119)
+        );
+    }
+
+    #[test]
+    fn square_sums() {
+        let grid = Grid::from_nested(vec![vec![1, -9, 1], vec![-9, 5, 1], vec![1, 1, 1]]);
+        assert_eq!(grid.max_square_sum(), (Vec2i::new(1, 1), 2, 8));
+        // with only positive cells the largest square that fits wins
+        let positive = Grid::new(3, 2, 1u8);
+        assert_eq!(positive.max_square_sum(), (Vec2i::new(0, 0), 2, 4));
+        let negative = Grid::from_nested(vec![vec![-3i32, -1], vec![-2, -1]]);
+        assert_eq!(negative.max_square_sum(), (Vec2i::new(1, 0), 1, -1));
+    }
+
+    fn all_true(grid: &Grid<bool>, corner: Vec2i, w: usize, h: usize) -> bool {
+        grid.view(corner, w, h).iter().all(|&c| c)
+    }
+
+    #[test]
+    fn true_regions_match_brute_force() {
+        let mut rng = Pcg32::new(11);
+        for _ in 0..200 {
+            let (w, h) = (1 + rng.index(7), 1 + rng.index(7));
+            let density = [0.5, 0.8, 0.95][rng.index(3)];
+            let grid = Grid::from_fn(w, h, |_| rng.chance(density));
+            let fits = |cw: usize, ch: usize| {
+                grid.positions().any(|p| {
+                    p.x as usize + cw <= w && p.y as usize + ch <= h && all_true(&grid, p, cw, ch)
+                })
+            };
+            let (corner, size) = grid.largest_true_square();
+            let brute = (1..=w.min(h)).filter(|&s| fits(s, s)).max().unwrap_or(0);
+            assert_eq!(size, brute, "{grid}");
+            assert!(size == 0 || all_true(&grid, corner, size, size));
+
+            let (corner, rw, rh) = grid.largest_true_rectangle();
+            let brute = (1..=w)
+                .flat_map(|cw| (1..=h).map(move |ch| (cw, ch)))
+                .filter(|&(cw, ch)| fits(cw, ch))
+                .map(|(cw, ch)| cw * ch)
+                .max()
+                .unwrap_or(0);
+            assert_eq!(rw * rh, brute, "{grid}");
+            assert!(brute == 0 || all_true(&grid, corner, rw, rh));
+        }
+        let empty = Grid::new(3, 3, false);
+        assert_eq!(empty.largest_true_square(), (Vec2i::new(0, 0), 0));
+        assert_eq!(empty.largest_true_rectangle(), (Vec2i::new(0, 0), 0, 0));
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use vecm::Vec2i;
+
+use super::{Connectivity, Grid};
+
+impl<T> Grid<T> {
+    /// Labels the connected components of `include`d cells, where neighbors belong to the same
+    /// component if `same` holds for them. Returns the component of every cell (`usize::MAX`
+    /// for excluded cells) and, per component, whether it touches the border.
+    fn label_by(
+        &self,
+        include: impl Fn(&T) -> bool,
+        same: impl Fn(&T, &T) -> bool,
+        connectivity: Connectivity,
+    ) -> (Vec<usize>, Vec<bool>) {
+        let mut labels = vec![usize::MAX; self.buf.len()];
+        let mut touches_border = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.buf.len() {
+            if !include(&self.buf[start]) || labels[start] != usize::MAX {
+                continue;
+            }
+            let label = touches_border.len();
+            let mut border = false;
+            labels[start] = label;
+            stack.push(start);
+            while let Some(i) = stack.pop() {
+                let pos = self.pos_of(i);
+                border |= pos.x == 0
+                    || pos.y == 0
+                    || pos.x as usize == self.width - 1
+                    || pos.y as usize == self.height - 1;
+                for next in self.neighbor_positions(pos, connectivity) {
+                    let j = self.index_of(next);
+                    if labels[j] == usize::MAX
+                        && include(&self.buf[j])
+                        && same(&self.buf[i], &self.buf[j])
+                    {
+                        labels[j] = label;
+                        stack.push(j);
+                    }
+                }
+            }
+            touches_border.push(border);
+        }
+        (labels, touches_border)
+    }
+
+    /// The connected components of cells where neighbors with `same` values are joined, each
+    /// as its positions in row-major order. Every cell is in exactly one region.
+    pub fn regions_by(
+        &self,
+        same: impl Fn(&T, &T) -> bool,
+        connectivity: Connectivity,
+    ) -> Vec<Vec<Vec2i>> {
+        let (labels, touches_border) = self.label_by(|_| true, same, connectivity);
+        let mut regions = vec![Vec::new(); touches_border.len()];
+        for (i, label) in labels.into_iter().enumerate() {
+            regions[label].push(self.pos_of(i));
+        }
+        regions
+    }
+
+    /// 4-connected regions of equal cells.
+    pub fn regions4(&self) -> Vec<Vec<Vec2i>>
+    where
+        T: PartialEq,
+    {
+        self.regions_by(T::eq, Connectivity::Four)
+    }
+
+    /// The cells 4-connected to `start` through cells satisfying `predicate`, empty if the start
+    /// doesn't satisfy it.
+    pub fn flood_fill4(
+        &self,
+        start: Vec2i,
+        predicate: impl Fn(Vec2i, &T) -> bool,
+    ) -> HashSet<Vec2i> {
+        let mut filled = HashSet::new();
+        if !predicate(start, &self[start]) {
+            return filled;
+        }
+        filled.insert(start);
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for next in self.neighbor_positions4(pos) {
+                if !filled.contains(&next) && predicate(next, &self[next]) {
+                    filled.insert(next);
+                    stack.push(next);
+                }
+            }
+        }
+        filled
+    }
+}
+
+impl Grid<char> {
+    /// The number of cells strictly inside of a loop of cells, by casting a ray along each
+    /// row that toggles at every loop cell connecting upwards. Pipes (`|-LJ7F`) connect as
+    /// drawn, so the ray can squeeze between parallel pipes. Any other loop cell, like the
+    /// start or a plain `#` loop, connects upwards if the cell above is in the loop and isn't
+    /// a pipe without a downward end.
+    pub fn enclosed_by(&self, loop_cells: &HashSet<Vec2i>) -> usize {
+        let connects_up = |pos: Vec2i| match self[pos] {
+            '|' | 'L' | 'J' => true,
+            '-' | '7' | 'F' => false,
+            _ => {
+                let above = Vec2i::new(pos.x, pos.y - 1);
+                loop_cells.contains(&above) && !matches!(self[above], '-' | 'L' | 'J')
+            }
+        };
+        let mut count = 0;
+        for y in 0..self.height as i32 {
+            let mut inside = false;
+            for x in 0..self.width as i32 {
+                let pos = Vec2i::new(x, y);
+                if loop_cells.contains(&pos) {
+                    inside ^= connects_up(pos);
+                } else if inside {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+impl Grid<bool> {
+    fn label_components(&self, value: bool, connectivity: Connectivity) -> (Vec<usize>, Vec<bool>) {
+        self.label_by(|&v| v == value, |_, _| true, connectivity)
+    }
+
+    /// The number of connected components of set cells.
+    pub fn component_count(&self, connectivity: Connectivity) -> usize {
+        self.label_components(true, connectivity).1.len()
+    }
+
+    /// The number of regions of unset cells that are enclosed by set cells, i.e. don't touch
+    /// the border.
+    ///
+    /// Foreground and background need complementary connectivity, otherwise a diagonal gap
+    /// either counts as both closed and open or as neither. Here the set cells are treated as
+    /// 4-connected, so the background is 8-connected and leaks through diagonal gaps.
+    pub fn hole_count(&self) -> usize {
+        let (_, touches_border) = self.label_components(false, Connectivity::Eight);
+        touches_border.iter().filter(|&&border| !border).count()
+    }
+
+    /// Whether the set cells form a single 4-connected component without holes.
+    pub fn is_simply_connected(&self) -> bool {
+        self.component_count(Connectivity::Four) == 1 && self.hole_count() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use vecm::Vec2i;
+
+    use crate::{grid::Connectivity, Grid};
+
+    fn parse(s: &str) -> Grid<bool> {
+        Grid::from_str_chars(s).map(|c| c == '#')
+    }
+
+    #[test]
+    fn ring_and_figure_eight() {
+        let ring = parse(".....\n.###.\n.#.#.\n.###.\n.....");
+        assert_eq!(ring.component_count(Connectivity::Four), 1);
+        assert_eq!(ring.hole_count(), 1);
+        assert!(!ring.is_simply_connected());
+
+        let eight = parse(".......\n.#####.\n.#.#.#.\n.#####.\n.......");
+        assert_eq!(eight.component_count(Connectivity::Four), 1);
+        assert_eq!(eight.hole_count(), 2);
+
+        let blob = parse("....\n.##.\n.##.\n....");
+        assert!(blob.is_simply_connected());
+    }
+
+    #[test]
+    fn border_and_diagonals() {
+        assert_eq!(parse("###\n#.#\n###").hole_count(), 1);
+        assert_eq!(parse("#.#\n#.#\n###").hole_count(), 0);
+        let diamond = parse(".#.\n#.#\n.#.");
+        assert_eq!(diamond.component_count(Connectivity::Four), 4);
+        assert_eq!(diamond.component_count(Connectivity::Eight), 1);
+        assert_eq!(diamond.hole_count(), 0);
+    }
+
+    fn assert_partition<T>(grid: &Grid<T>, regions: &[Vec<Vec2i>]) {
+        let mut seen = grid.same_size_with(false);
+        for pos in regions.iter().flatten() {
+            assert!(!seen[*pos], "{pos:?} is in multiple regions");
+            seen[*pos] = true;
+        }
+        assert!(seen.rows().flatten().all(|&s| s));
+    }
+
+    #[test]
+    fn regions() {
+        let single = Grid::new(141, 141, 'A');
+        let regions = single.regions4();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].len(), 141 * 141);
+        assert_eq!(
+            single.flood_fill4(Vec2i::new(70, 70), |_, _| true).len(),
+            141 * 141
+        );
+
+        let checkerboard = Grid::from_fn(6, 5, |p| (p.x + p.y) % 2 == 0);
+        let regions = checkerboard.regions4();
+        assert_eq!(regions.len(), 30);
+        assert_partition(&checkerboard, &regions);
+        assert_eq!(
+            checkerboard.regions_by(bool::eq, Connectivity::Eight).len(),
+            2
+        );
+
+        let garden = Grid::from_str_chars("AAAA\nBBCD\nBBCC\nEEEC");
+        let regions = garden.regions4();
+        assert_partition(&garden, &regions);
+        let mut shapes: Vec<(char, usize)> =
+            regions.iter().map(|r| (garden[r[0]], r.len())).collect();
+        shapes.sort_unstable();
+        assert_eq!(shapes, [('A', 4), ('B', 4), ('C', 4), ('D', 1), ('E', 3)]);
+    }
+
+    #[test]
+    fn diagonal_regions() {
+        let grid = Grid::from_str_chars("X..\n.X.\n..X");
+        let xs = |conn| {
+            grid.regions_by(char::eq, conn)
+                .into_iter()
+                .filter(|r| grid[r[0]] == 'X')
+                .count()
+        };
+        assert_eq!(xs(Connectivity::Four), 3);
+        assert_eq!(xs(Connectivity::Eight), 1);
+        let filled = grid.flood_fill4(Vec2i::new(1, 0), |_, &c| c == '.');
+        assert_eq!(
+            filled,
+            [Vec2i::new(1, 0), Vec2i::new(2, 0), Vec2i::new(2, 1)].into()
+        );
+        assert!(grid
+            .flood_fill4(Vec2i::new(0, 0), |_, &c| c == '.')
+            .is_empty());
+    }
+
+    #[test]
+    fn loop_interior() {
+        let grid = Grid::from_str_chars(
+            "..........\n\
+             .S------7.\n\
+             .|F----7|.\n\
+             .||....||.\n\
+             .||....||.\n\
+             .|L-7F-J|.\n\
+             .|..||..|.\n\
+             .L--JL--J.\n\
+             ..........",
+        );
+        // the ray squeezes between the parallel pipes, so the middle is outside
+        let loop_cells: HashSet<Vec2i> = grid.find_all_by(|_, &c| c != '.').collect();
+        assert_eq!(grid.enclosed_by(&loop_cells), 4);
+        let ring = Grid::from_str_chars(
+            "#####.\n\
+             #...#.\n\
+             #...##\n\
+             ##...#\n\
+             .#####",
+        );
+        assert_eq!(ring.enclosed_by(&ring.find_all(&'#').collect()), 9);
+    }
+}
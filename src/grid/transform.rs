@@ -0,0 +1,390 @@
+use vecm::Vec2i;
+
+use crate::Rect;
+
+use super::Grid;
+
+impl<T> Grid<T> {
+    /// Returns a grid where each cell takes the value from `pos - offset` in this grid, cells
+    /// whose source lies outside of the grid are set to `fill`.
+    pub fn shifted(&self, offset: Vec2i, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let buf = self
+            .positions()
+            .map(|pos| {
+                let src = pos - offset;
+                if self.in_bounds(src) {
+                    self[src].clone()
+                } else {
+                    fill.clone()
+                }
+            })
+            .collect();
+        Grid::from_buf(buf, self.width, self.height)
+    }
+
+    /// In-place version of [`Grid::shifted`].
+    pub fn shift(&mut self, offset: Vec2i, fill: T)
+    where
+        T: Clone,
+    {
+        // visit destinations in an order where every source is visited after its destination,
+        // so swapping moves each value exactly once
+        let xs: Vec<i32> = if offset.x > 0 {
+            (0..self.width as i32).rev().collect()
+        } else {
+            (0..self.width as i32).collect()
+        };
+        let ys: Vec<i32> = if offset.y > 0 {
+            (0..self.height as i32).rev().collect()
+        } else {
+            (0..self.height as i32).collect()
+        };
+        for &y in &ys {
+            for &x in &xs {
+                let dst = Vec2i::new(x, y);
+                let src = dst - offset;
+                let dst = self.index_of(dst);
+                if self.in_bounds(src) {
+                    let src = self.index_of(src);
+                    self.buf.swap(dst, src);
+                } else {
+                    self.buf[dst] = fill.clone();
+                }
+            }
+        }
+    }
+
+    /// Cyclically rotates row `y` to the right by `by` cells (to the left for negative values).
+    pub fn rotated_rows(&mut self, y: usize, by: i32) {
+        assert!(y < self.height, "row {y} out of range");
+        let by = by.rem_euclid(self.width as i32) as usize;
+        self.buf[y * self.width..(y + 1) * self.width].rotate_right(by);
+    }
+
+    /// Cyclically rotates column `x` down by `by` cells (up for negative values).
+    pub fn rotated_cols(&mut self, x: usize, by: i32) {
+        assert!(x < self.width, "column {x} out of range");
+        let by = by.rem_euclid(self.height as i32) as usize;
+        let reverse = |grid: &mut Self, mut a: usize, mut b: usize| {
+            while a < b {
+                grid.buf.swap(a * grid.width + x, b * grid.width + x);
+                a += 1;
+                b -= 1;
+            }
+        };
+        if by == 0 {
+            return;
+        }
+        let h = self.height;
+        reverse(self, 0, h - 1);
+        reverse(self, 0, by - 1);
+        reverse(self, by, h - 1);
+    }
+
+    /// Sets all cells in the rectangle at `origin` with the given size to `value`. The rectangle
+    /// is clipped to the grid.
+    pub fn fill_rect(&mut self, origin: Vec2i, width: usize, height: usize, value: T)
+    where
+        T: Clone,
+    {
+        let x0 = (origin.x.max(0) as usize).min(self.width);
+        let y0 = (origin.y.max(0) as usize).min(self.height);
+        let x1 = (origin.x + width as i32).clamp(0, self.width as i32) as usize;
+        let y1 = (origin.y + height as i32).clamp(0, self.height as i32) as usize;
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        for y in y0..y1 {
+            self.buf[y * self.width + x0..y * self.width + x1].fill(value.clone());
+        }
+    }
+
+    /// Applies `f` to every cell in the rectangle spanned by the inclusive corners `a` and `b`,
+    /// given in any order. The rectangle is clipped to the grid.
+    pub fn apply_rect(&mut self, a: Vec2i, b: Vec2i, mut f: impl FnMut(&mut T)) {
+        let bounds = Rect::from_size(Vec2i::new(0, 0), self.width, self.height);
+        let Some(rect) = Rect::new(a, b).intersect(&bounds) else {
+            return;
+        };
+        for pos in rect.positions() {
+            f(&mut self[pos]);
+        }
+    }
+
+    /// Tiles the grid `nx` times horizontally and `ny` times vertically, passing the tile
+    /// coordinates to `transform` for every copied cell.
+    pub fn tile(&self, nx: usize, ny: usize, transform: impl Fn(Vec2i, &T) -> T) -> Grid<T> {
+        let (width, height) = (self.width * nx, self.height * ny);
+        let buf = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let tile = Vec2i::new((x / self.width) as i32, (y / self.height) as i32);
+                transform(tile, &self[(x % self.width, y % self.height)])
+            })
+            .collect();
+        Grid::from_buf(buf, width, height)
+    }
+
+    pub fn repeat(&self, nx: usize, ny: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.tile(nx, ny, |_, value| value.clone())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Rearranges the cells into a `width` x `height` grid where index `i` takes the cell at
+    /// `src(i)`. Cycles of the permutation are followed in place by swapping, so no cell buffer
+    /// is allocated.
+    fn permuted(self, width: usize, height: usize, src: impl Fn(usize, usize) -> usize) -> Self {
+        let mut buf = self.buf;
+        let mut visited = vec![false; buf.len()];
+        for start in 0..buf.len() {
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                let j = src(i % width, i / width);
+                if j == start {
+                    break;
+                }
+                buf.swap(i, j);
+                i = j;
+            }
+        }
+        Grid::from_buf(buf, width, height)
+    }
+
+    pub fn transpose(self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.permuted(h, w, |x, y| x * w + y)
+    }
+
+    /// Rotates by 90 degrees clockwise, the left column becomes the top row.
+    pub fn rotate_cw(self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.permuted(h, w, |x, y| (h - 1 - x) * w + y)
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.permuted(h, w, |x, y| x * w + (w - 1 - y))
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.permuted(w, h, |x, y| y * w + (w - 1 - x))
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.permuted(w, h, |x, y| (h - 1 - y) * w + x)
+    }
+}
+
+impl Grid<u8> {
+    /// Adds `add` to every value, wrapping around within `min..=max`. Values outside the range
+    /// are wrapped into it too, so `0` in `1..=9` acts like `9`.
+    pub fn map_values_wrapping_add(&self, add: u8, min: u8, max: u8) -> Grid<u8> {
+        assert!(min <= max, "empty value range");
+        let span = (max - min) as i32 + 1;
+        let buf = self
+            .buf
+            .iter()
+            .map(|&v| (v as i32 - min as i32 + add as i32).rem_euclid(span) + min as i32)
+            .map(|v| v as u8)
+            .collect();
+        Grid::from_buf(buf, self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::Grid;
+
+    fn digits() -> Grid<u8> {
+        Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    }
+
+    #[test]
+    fn shifted_offsets() {
+        let g = digits();
+        assert_eq!(
+            g.shifted(Vec2i::new(1, 0), 0),
+            Grid::from_nested(vec![vec![0, 1, 2], vec![0, 4, 5]])
+        );
+        assert_eq!(
+            g.shifted(Vec2i::new(-1, 1), 0),
+            Grid::from_nested(vec![vec![0, 0, 0], vec![2, 3, 0]])
+        );
+        assert_eq!(
+            g.shifted(Vec2i::new(0, -1), 9),
+            Grid::from_nested(vec![vec![4, 5, 6], vec![9, 9, 9]])
+        );
+        assert_eq!(
+            g.shifted(Vec2i::new(5, 0), 0),
+            Grid::from_nested(vec![vec![0; 3]; 2])
+        );
+    }
+
+    #[test]
+    fn shift_in_place_matches() {
+        for offset in [(1, 0), (-1, 1), (2, -1), (-2, -1), (0, 0)] {
+            let offset = Vec2i::new(offset.0, offset.1);
+            let mut g = digits();
+            g.shift(offset, 0);
+            assert_eq!(g, digits().shifted(offset, 0), "offset {offset:?}");
+        }
+    }
+
+    #[test]
+    fn screen_operations() {
+        let mut screen = Grid::from_nested(vec![vec![false; 7]; 3]);
+        screen.fill_rect(Vec2i::new(0, 0), 3, 2, true);
+        screen.rotated_cols(1, 1);
+        screen.rotated_rows(0, 4);
+        screen.rotated_cols(1, 1);
+        let expected = Grid::from_str_chars(".#..#.#\n#.#....\n.#.....").map(|c| c == '#');
+        assert_eq!(screen, expected);
+        assert_eq!(screen.rows().flatten().filter(|&&lit| lit).count(), 6);
+    }
+
+    #[test]
+    fn fill_rect_outside() {
+        let mut g = Grid::from_nested(vec![vec![0u8; 3]; 2]);
+        g.fill_rect(Vec2i::new(5, 0), 3, 1, 7);
+        g.fill_rect(Vec2i::new(0, 4), 3, 1, 7);
+        g.fill_rect(Vec2i::new(-4, 0), 2, 2, 7);
+        assert_eq!(g, Grid::from_nested(vec![vec![0u8; 3]; 2]));
+        g.fill_rect(Vec2i::new(2, -1), 5, 2, 7);
+        assert_eq!(g, Grid::from_nested(vec![vec![0, 0, 7], vec![0, 0, 0]]));
+    }
+
+    #[test]
+    fn rotate_negative() {
+        let mut g = digits();
+        g.rotated_rows(1, -1);
+        g.rotated_cols(0, -3);
+        assert_eq!(g, Grid::from_nested(vec![vec![5, 2, 3], vec![1, 6, 4]]));
+    }
+
+    /// Not `Clone`, so the transforms have to move cells.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Cell(u8);
+
+    fn cells(rows: &[&[u8]]) -> Grid<Cell> {
+        Grid::from_nested(
+            rows.iter()
+                .map(|row| row.iter().map(|&v| Cell(v)).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let g = || cells(&[&[1, 2], &[3, 4], &[5, 6]]);
+        assert_eq!((g().width(), g().height()), (2, 3));
+        let cw = g().rotate_cw();
+        assert_eq!((cw.width(), cw.height()), (3, 2));
+        assert_eq!(cw, cells(&[&[5, 3, 1], &[6, 4, 2]]));
+        assert_eq!(g().rotate_ccw(), cells(&[&[2, 4, 6], &[1, 3, 5]]));
+        assert_eq!(g().transpose(), cells(&[&[1, 3, 5], &[2, 4, 6]]));
+        assert_eq!(g().flip_horizontal(), cells(&[&[2, 1], &[4, 3], &[6, 5]]));
+        assert_eq!(g().flip_vertical(), cells(&[&[5, 6], &[3, 4], &[1, 2]]));
+        assert_eq!(g().rotate_cw().rotate_ccw(), g());
+        assert_eq!(
+            g().rotate_cw().rotate_cw(),
+            g().flip_vertical().flip_horizontal()
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = crate::Pcg32::new(52);
+        for _ in 0..20 {
+            let (w, h) = (rng.index(7) + 1, rng.index(7) + 1);
+            let g = Grid::from_nested(
+                (0..h)
+                    .map(|_| (0..w).map(|_| rng.below(100)).collect())
+                    .collect(),
+            );
+            let cw4 = g.clone().rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+            assert_eq!(cw4, g);
+            assert_eq!(g.clone().transpose().transpose(), g);
+            assert_eq!(
+                g.clone().rotate_cw(),
+                g.clone().transpose().flip_horizontal()
+            );
+            let t = g.clone().transpose();
+            for pos in g.positions() {
+                assert_eq!(t[(pos.y as usize, pos.x as usize)], g[pos]);
+            }
+        }
+    }
+
+    #[test]
+    fn repeat_plain() {
+        let g = digits().repeat(2, 1);
+        assert_eq!(
+            g,
+            Grid::from_nested(vec![vec![1, 2, 3, 1, 2, 3], vec![4, 5, 6, 4, 5, 6]])
+        );
+    }
+
+    #[test]
+    fn chiton_expansion() {
+        let cave = Grid::from_str_bytes(
+            "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581",
+        )
+        .map(|b| b - b'0');
+        let expanded = cave.tile(5, 5, |tile, &risk| {
+            (risk - 1 + (tile.x + tile.y) as u8) % 9 + 1
+        });
+        assert_eq!((expanded.width(), expanded.height()), (50, 50));
+        let shifted = cave.map_values_wrapping_add(3, 1, 9);
+        for pos in cave.positions() {
+            assert_eq!(expanded[pos + Vec2i::new(20, 10)], shifted[pos]);
+        }
+        let row =
+            |y: usize| -> String { (0..50).map(|x| (b'0' + expanded[(x, y)]) as char).collect() };
+        assert_eq!(row(0), "11637517422274862853338597396444961841755517295286");
+        assert_eq!(
+            row(49),
+            "67554889357866599146897761125791887223681299833479"
+        );
+        let goal = Vec2i::new(49, 49);
+        let (risk, _) = expanded
+            .astar_weighted(Vec2i::new(0, 0), goal, |_, &r| Some(r as u64), false)
+            .unwrap();
+        assert_eq!(risk, 315);
+    }
+
+    #[test]
+    fn wrapping_add_out_of_range() {
+        let g = Grid::from_nested(vec![vec![0u8, 1, 9, 12]]);
+        assert_eq!(
+            g.map_values_wrapping_add(1, 1, 9),
+            Grid::from_nested(vec![vec![1u8, 2, 1, 4]])
+        );
+        assert_eq!(
+            g.map_values_wrapping_add(0, 5, 5),
+            Grid::from_nested(vec![vec![5u8; 4]])
+        );
+    }
+}
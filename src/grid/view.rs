@@ -0,0 +1,232 @@
+use std::ops::Index;
+
+use vecm::Vec2i;
+
+use super::{Grid, GridIndex};
+
+/// A borrowed rectangle of a grid, indexed by coordinates relative to its top left corner.
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for GridView<'_, T> {}
+
+impl<T> Grid<T> {
+    /// The `width` x `height` rectangle with its top left corner at `origin`. Panics if it
+    /// doesn't fit into the grid.
+    pub fn view(&self, origin: Vec2i, width: usize, height: usize) -> GridView<'_, T> {
+        let fits = |start: i32, len: usize, max: usize| start >= 0 && start as usize + len <= max;
+        assert!(
+            fits(origin.x, width, self.width) && fits(origin.y, height, self.height),
+            "a {width}x{height} view at {origin:?} doesn't fit into a {}x{} grid",
+            self.width,
+            self.height
+        );
+        GridView {
+            grid: self,
+            x: origin.x as usize,
+            y: origin.y as usize,
+            width,
+            height,
+        }
+    }
+
+    /// Every `w` x `h` view that fits into the grid with its origin, in reading order.
+    /// Consecutive windows overlap.
+    pub fn windows(&self, w: usize, h: usize) -> impl Iterator<Item = (Vec2i, GridView<'_, T>)> {
+        let xs = (self.width + 1).saturating_sub(w);
+        let ys = (self.height + 1).saturating_sub(h);
+        (0..ys).flat_map(move |y| {
+            (0..xs).map(move |x| {
+                let origin = Vec2i::new(x as i32, y as i32);
+                (origin, self.view(origin, w, h))
+            })
+        })
+    }
+
+    /// Copies `other` into this grid with its top left corner at `origin`. Panics if it doesn't
+    /// fit.
+    pub fn paste(&mut self, origin: Vec2i, other: &Grid<T>)
+    where
+        T: Clone,
+    {
+        // checks the bounds
+        self.view(origin, other.width, other.height);
+        let (x, y) = (origin.x as usize, origin.y as usize);
+        for (dy, row) in other.rows().enumerate() {
+            let start = (y + dy) * self.width + x;
+            self.buf[start..start + other.width].clone_from_slice(row);
+        }
+    }
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The position of the view's top left corner in the grid.
+    pub fn origin(&self) -> Vec2i {
+        Vec2i::new(self.x as i32, self.y as i32)
+    }
+
+    pub fn get(&self, pos: impl GridIndex) -> Option<&'a T> {
+        let i = pos.cell_index(self.width, self.height)?;
+        let (x, y) = (i % self.width, i / self.width);
+        Some(&self.grid[(self.x + x, self.y + y)])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + 'a {
+        let (x, width, grid) = (self.x, self.width, self.grid);
+        (self.y..self.y + self.height).map(move |y| {
+            let start = y * grid.width + x;
+            &grid.buf[start..start + width]
+        })
+    }
+
+    /// The cells in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.rows().flatten()
+    }
+
+    /// Whether every `Some` cell of `pattern` equals the cell below it, `None` cells match
+    /// anything. Patterns of a different size never match.
+    pub fn matches(&self, pattern: &Grid<Option<T>>) -> bool
+    where
+        T: PartialEq,
+    {
+        pattern.width == self.width
+            && pattern.height == self.height
+            && self
+                .iter()
+                .zip(pattern.buf.iter())
+                .all(|(cell, expected)| expected.as_ref().is_none_or(|e| e == cell))
+    }
+
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_buf(self.iter().cloned().collect(), self.width, self.height)
+    }
+}
+
+impl<T, P: GridIndex> Index<P> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: P) -> &T {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "position outside of the {}x{} view",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T: PartialEq> PartialEq<GridView<'_, T>> for GridView<'_, T> {
+    fn eq(&self, other: &GridView<'_, T>) -> bool {
+        self.width == other.width && self.height == other.height && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for GridView<'_, T> {}
+
+impl<T: PartialEq> PartialEq<Grid<T>> for GridView<'_, T> {
+    fn eq(&self, other: &Grid<T>) -> bool {
+        self.width == other.width && self.height == other.height && self.iter().eq(other.buf.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_windows() {
+        let grid = Grid::from_str_chars(
+            "aaa.\n\
+             aaab\n\
+             .aa.",
+        );
+        let pattern = Grid::from_str_chars("aa\naa");
+        let found: Vec<Vec2i> = grid
+            .windows(2, 2)
+            .filter(|(_, view)| *view == pattern)
+            .map(|(pos, _)| pos)
+            .collect();
+        // all three matches overlap at (1, 1)
+        assert_eq!(
+            found,
+            [Vec2i::new(0, 0), Vec2i::new(1, 0), Vec2i::new(1, 1)]
+        );
+        assert_eq!(grid.windows(3, 2).count(), 4);
+        assert_eq!(grid.windows(5, 1).count(), 0);
+        let view = grid.view(Vec2i::new(1, 1), 3, 2);
+        assert_eq!(view[Vec2i::new(2, 0)], 'b');
+        assert_eq!(view[(0, 1)], 'a');
+        assert_eq!(view.get(Vec2i::new(3, 0)), None);
+        assert_eq!(
+            view.rows().collect::<Vec<_>>(),
+            [&['a', 'a', 'b'], &['a', 'a', '.']]
+        );
+        assert_eq!(view, grid.view(Vec2i::new(1, 1), 3, 2));
+        assert_ne!(view, grid.view(Vec2i::new(0, 0), 3, 2));
+        assert_eq!(view, Grid::from_str_chars("aab\naa."));
+        assert_eq!(view.to_grid(), Grid::from_str_chars("aab\naa."));
+    }
+
+    #[test]
+    fn wildcard_monster() {
+        let grid = Grid::from_str_chars(
+            ".#.#..\n\
+             #.#.#.\n\
+             ...#.#\n\
+             ..#.#.",
+        );
+        // a tiny sea monster, only its '#' cells count. The first two share a cell.
+        let monster = Grid::from_str_chars(".#.\n#.#\n").map(|c| (c == '#').then_some('#'));
+        let found: Vec<Vec2i> = grid
+            .windows(3, 2)
+            .filter(|(_, view)| view.matches(&monster))
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(
+            found,
+            [
+                Vec2i::new(0, 0),
+                Vec2i::new(2, 0),
+                Vec2i::new(3, 1),
+                Vec2i::new(2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn stitching() {
+        let mut image = Grid::new(4, 2, '.');
+        image.paste(Vec2i::new(0, 0), &Grid::from_str_chars("ab\ncd"));
+        image.paste(Vec2i::new(2, 0), &Grid::from_str_chars("ef\ngh"));
+        assert_eq!(image, Grid::from_str_chars("abef\ncdgh"));
+        image.paste(Vec2i::new(1, 1), &Grid::from_str_chars("xyz"));
+        assert_eq!(image, Grid::from_str_chars("abef\ncxyz"));
+    }
+
+    #[test]
+    #[should_panic(expected = "a 2x2 view at")]
+    fn view_outside() {
+        Grid::new(3, 3, 0).view(Vec2i::new(2, 0), 2, 2);
+    }
+}
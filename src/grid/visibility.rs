@@ -0,0 +1,135 @@
+use crate::monotonic::prev_indices_by;
+
+use super::Grid;
+
+impl<T> Grid<T> {
+    /// The cell indices of every line, ordered from the edge a cell looks towards, for the
+    /// directions in [`super::DIRS4`] order (up, left, right, down).
+    fn lines_towards(&self) -> [Vec<Vec<usize>>; 4] {
+        let (w, h) = (self.width, self.height);
+        let cols = |rev: bool| -> Vec<Vec<usize>> {
+            (0..w)
+                .map(|x| {
+                    let ys: Box<dyn Iterator<Item = usize>> = if rev {
+                        Box::new((0..h).rev())
+                    } else {
+                        Box::new(0..h)
+                    };
+                    ys.map(|y| y * w + x).collect()
+                })
+                .collect()
+        };
+        let rows = |rev: bool| -> Vec<Vec<usize>> {
+            (0..h)
+                .map(|y| {
+                    let xs: Box<dyn Iterator<Item = usize>> = if rev {
+                        Box::new((0..w).rev())
+                    } else {
+                        Box::new(0..w)
+                    };
+                    xs.map(|x| y * w + x).collect()
+                })
+                .collect()
+        };
+        [cols(false), rows(false), rows(true), cols(true)]
+    }
+
+    /// For each direction (up, left, right, down as in [`super::DIRS4`]) computes how many
+    /// cells each cell can see: the distance to the first cell `b` with `ge(b, cell)`,
+    /// inclusive, or to the edge of the grid. Uses a monotonic stack per line, so this is
+    /// linear in the grid size as long as `ge` is transitive.
+    pub fn directional_view_distances(&self, ge: impl Fn(&T, &T) -> bool) -> [Grid<u32>; 4] {
+        self.lines_towards().map(|lines| {
+            let mut out = self.same_size_with(0u32);
+            for line in lines {
+                let values: Vec<&T> = line.iter().map(|&cell| &self.buf[cell]).collect();
+                let blockers = prev_indices_by(&values, |other, value| ge(other, value));
+                for (i, (&cell, blocker)) in line.iter().zip(blockers).enumerate() {
+                    out.buf[cell] = (i - blocker.unwrap_or(0)) as u32;
+                }
+            }
+            out
+        })
+    }
+
+    /// Cells that are strictly greater than all cells between them and at least one edge.
+    pub fn visible_from_outside(&self) -> Grid<bool>
+    where
+        T: Ord,
+    {
+        let mut visible = self.same_size_with(false);
+        for lines in self.lines_towards() {
+            for line in lines {
+                let mut max: Option<&T> = None;
+                for cell in line {
+                    let value = &self.buf[cell];
+                    if max.is_none_or(|max| value > max) {
+                        visible.buf[cell] = true;
+                        max = Some(value);
+                    }
+                }
+            }
+        }
+        visible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use crate::{grid::DIRS4, Grid, Pcg32};
+
+    const FOREST: &str = "30373
+25512
+65332
+33549
+35390";
+
+    #[test]
+    fn forest_sample() {
+        let grid = Grid::from_str_bytes(FOREST);
+        let visible = grid.visible_from_outside();
+        assert_eq!(visible.rows().flatten().filter(|&&v| v).count(), 21);
+        let [up, left, right, down] = grid.directional_view_distances(|a, b| a >= b);
+        let best = grid
+            .positions()
+            .map(|p| up[p] * left[p] * right[p] * down[p])
+            .max();
+        assert_eq!(best, Some(8));
+        assert_eq!([up, left, right, down].map(|g| g[(2, 3)]), [2, 2, 2, 1]);
+    }
+
+    fn naive_view_distance(grid: &Grid<u8>, pos: Vec2i, dir: (i32, i32)) -> u32 {
+        let d = Vec2i::new(dir.0, dir.1);
+        let mut p = pos + d;
+        let mut dist = 0;
+        while grid.in_bounds(p) {
+            dist += 1;
+            if grid[p] >= grid[pos] {
+                break;
+            }
+            p += d;
+        }
+        dist
+    }
+
+    #[test]
+    fn matches_naive() {
+        let mut rng = Pcg32::new(16);
+        for _ in 0..20 {
+            let (w, h) = (rng.index(9) + 1, rng.index(9) + 1);
+            let grid = Grid::from_nested(
+                (0..h)
+                    .map(|_| (0..w).map(|_| rng.below(5) as u8).collect())
+                    .collect(),
+            );
+            let distances = grid.directional_view_distances(|a, b| a >= b);
+            for (i, dir) in DIRS4.into_iter().enumerate() {
+                for pos in grid.positions() {
+                    assert_eq!(distances[i][pos], naive_view_distance(&grid, pos, dir));
+                }
+            }
+        }
+    }
+}
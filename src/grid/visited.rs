@@ -0,0 +1,282 @@
+use vecm::Vec2i;
+
+use super::{Dir, Grid, Side, DIRS8};
+
+/// A direction that [`DirVisited`] tracks as one bit. [`Dir`] and [`Side`] take the bits
+/// of the first four [`DIRS8`] steps, so they share them with `(i32, i32)` steps, which can be
+/// any of the eight.
+pub trait VisitDir {
+    fn bit(self) -> u32;
+}
+impl VisitDir for Dir {
+    fn bit(self) -> u32 {
+        Dir::ALL.iter().position(|&d| d == self).unwrap() as u32
+    }
+}
+impl VisitDir for Side {
+    fn bit(self) -> u32 {
+        Dir::from(self).bit()
+    }
+}
+impl VisitDir for (i32, i32) {
+    fn bit(self) -> u32 {
+        DIRS8
+            .iter()
+            .position(|&d| d == self)
+            .unwrap_or_else(|| panic!("{self:?} isn't a step to a neighbor")) as u32
+    }
+}
+
+/// A set of `(position, direction)` states over a grid, one bit per direction in each cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirVisited {
+    bits: Grid<u8>,
+}
+impl DirVisited {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: Grid::new(width, height, 0),
+        }
+    }
+
+    /// Adds the state and returns whether it is new. Panics outside of the grid.
+    pub fn insert(&mut self, pos: Vec2i, dir: impl VisitDir) -> bool {
+        let bit = 1 << dir.bit();
+        let cell = &mut self.bits[pos];
+        let new = *cell & bit == 0;
+        *cell |= bit;
+        new
+    }
+
+    /// Whether the state was inserted, false outside of the grid.
+    pub fn contains(&self, pos: Vec2i, dir: impl VisitDir) -> bool {
+        let bit = 1 << dir.bit();
+        self.bits.get(pos).is_some_and(|cell| cell & bit != 0)
+    }
+
+    /// Whether `pos` was visited in any direction.
+    pub fn any(&self, pos: Vec2i) -> bool {
+        self.bits.get(pos).is_some_and(|&cell| cell != 0)
+    }
+
+    /// The number of cells visited in at least one direction.
+    pub fn count_cells(&self) -> usize {
+        self.bits.iter().filter(|(_, &cell)| cell != 0).count()
+    }
+
+    /// Removes every state, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.bits.buf.fill(0);
+    }
+}
+
+/// Like [`DirVisited`] with an extra state next to the direction, `dirs * states` bits per cell
+/// in a `Grid<u16>`. The state `s` in direction `d` is bit `s * dirs + d`.
+/// [`Grid::dijkstra_run_constrained`] uses it for runs of up to 3 cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateVisited {
+    bits: Grid<u16>,
+    dirs: u32,
+    states: u32,
+}
+impl StateVisited {
+    /// Tracks `dirs` directions, 4 or 8, with `states` extra states each.
+    pub fn new(width: usize, height: usize, dirs: u32, states: u32) -> Self {
+        assert!(
+            dirs == 4 || dirs == 8,
+            "expected 4 or 8 directions, got {dirs}"
+        );
+        assert!(
+            states > 0 && dirs * states <= 16,
+            "{dirs} directions with {states} states don't fit into 16 bits"
+        );
+        Self {
+            bits: Grid::new(width, height, 0),
+            dirs,
+            states,
+        }
+    }
+
+    fn bit(&self, dir: impl VisitDir, state: u32) -> u16 {
+        let dir = dir.bit();
+        assert!(
+            dir < self.dirs,
+            "direction {dir} out of {} tracked",
+            self.dirs
+        );
+        assert!(
+            state < self.states,
+            "state {state} out of 0..{}",
+            self.states
+        );
+        1 << (state * self.dirs + dir)
+    }
+
+    /// Adds the state and returns whether it is new. Panics outside of the grid.
+    pub fn insert(&mut self, pos: Vec2i, dir: impl VisitDir, state: u32) -> bool {
+        let bit = self.bit(dir, state);
+        let cell = &mut self.bits[pos];
+        let new = *cell & bit == 0;
+        *cell |= bit;
+        new
+    }
+
+    pub fn contains(&self, pos: Vec2i, dir: impl VisitDir, state: u32) -> bool {
+        let bit = self.bit(dir, state);
+        self.bits.get(pos).is_some_and(|cell| cell & bit != 0)
+    }
+
+    pub fn any(&self, pos: Vec2i) -> bool {
+        self.bits.get(pos).is_some_and(|&cell| cell != 0)
+    }
+
+    pub fn count_cells(&self) -> usize {
+        self.bits.iter().filter(|(_, &cell)| cell != 0).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.buf.fill(0);
+    }
+}
+
+impl<T> Grid<T> {
+    /// Follows a beam entering `start` moving in `dir`. In every cell, `deflect` gives the
+    /// direction it leaves in and optionally a second one it splits into. Returns the cells
+    /// passed with the directions they were entered in, each state is followed only once so
+    /// loops end.
+    pub fn trace_beam(
+        &self,
+        start: Vec2i,
+        dir: Dir,
+        deflect: impl Fn(&T, Dir) -> (Dir, Option<Dir>),
+    ) -> DirVisited {
+        let mut visited = DirVisited::new(self.width, self.height);
+        let mut beams = vec![(start, dir)];
+        while let Some((pos, dir)) = beams.pop() {
+            if !self.in_bounds(pos) || !visited.insert(pos, dir) {
+                continue;
+            }
+            let (a, b) = deflect(&self[pos], dir);
+            beams.extend(std::iter::once(a).chain(b).map(|d| (d.step(pos), d)));
+        }
+        visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::Pcg32;
+
+    const CONTRAPTION: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    fn deflect(&c: &u8, dir: Dir) -> (Dir, Option<Dir>) {
+        use Dir::*;
+        match (c, dir) {
+            (b'/', Right) | (b'\\', Left) => (Up, None),
+            (b'/', Left) | (b'\\', Right) => (Down, None),
+            (b'/', Up) | (b'\\', Down) => (Right, None),
+            (b'/', Down) | (b'\\', Up) => (Left, None),
+            (b'|', Left | Right) => (Up, Some(Down)),
+            (b'-', Up | Down) => (Left, Some(Right)),
+            _ => (dir, None),
+        }
+    }
+
+    #[test]
+    fn beam_contraption() {
+        let grid = Grid::from_str_bytes(CONTRAPTION);
+        let energized = grid.trace_beam(Vec2i::new(0, 0), Dir::Right, deflect);
+        assert_eq!(energized.count_cells(), 46);
+        assert!(energized.contains(Vec2i::new(1, 0), Dir::Right));
+        assert!(energized.contains(Vec2i::new(1, 1), Dir::Down));
+        assert!(!energized.any(Vec2i::new(9, 0)));
+        let (w, h) = (grid.width() as i32, grid.height() as i32);
+        let edges = (0..w)
+            .flat_map(|x| {
+                [
+                    (Vec2i::new(x, 0), Dir::Down),
+                    (Vec2i::new(x, h - 1), Dir::Up),
+                ]
+            })
+            .chain((0..h).flat_map(|y| {
+                [
+                    (Vec2i::new(0, y), Dir::Right),
+                    (Vec2i::new(w - 1, y), Dir::Left),
+                ]
+            }));
+        let best = edges
+            .map(|(start, dir)| grid.trace_beam(start, dir, deflect).count_cells())
+            .max();
+        assert_eq!(best, Some(51));
+    }
+
+    #[test]
+    fn matches_hash_set() {
+        let mut rng = Pcg32::new(16);
+        let (mut visited, mut states) = (DirVisited::new(7, 5), StateVisited::new(7, 5, 4, 3));
+        let (mut set, mut state_set) = (HashSet::new(), HashSet::new());
+        for round in 0..3 {
+            for _ in 0..200 {
+                let pos = Vec2i::new(rng.below(7) as i32, rng.below(5) as i32);
+                let step = DIRS8[rng.index(8)];
+                assert_eq!(visited.insert(pos, step), set.insert((pos, step)));
+                let (dir, state) = (Dir::ALL[rng.index(4)], rng.below(3));
+                assert_eq!(
+                    states.insert(pos, dir, state),
+                    state_set.insert((pos, dir, state))
+                );
+            }
+            for pos in Grid::new(7, 5, ()).positions() {
+                for step in DIRS8 {
+                    assert_eq!(visited.contains(pos, step), set.contains(&(pos, step)));
+                }
+                for (dir, state) in Dir::ALL
+                    .into_iter()
+                    .flat_map(|d| (0..3).map(move |s| (d, s)))
+                {
+                    assert_eq!(
+                        states.contains(pos, dir, state),
+                        state_set.contains(&(pos, dir, state))
+                    );
+                }
+                assert_eq!(visited.any(pos), set.iter().any(|&(p, _)| p == pos));
+            }
+            let cells: HashSet<Vec2i> = set.iter().map(|&(p, _)| p).collect();
+            assert_eq!(visited.count_cells(), cells.len());
+            let cells: HashSet<Vec2i> = state_set.iter().map(|&(p, _, _)| p).collect();
+            assert_eq!(states.count_cells(), cells.len());
+            if round == 1 {
+                visited.clear();
+                states.clear();
+                set.clear();
+                state_set.clear();
+                assert_eq!(visited.count_cells(), 0);
+            }
+        }
+        // the first four steps are the same bits as the directions
+        let mut fresh = DirVisited::new(2, 2);
+        assert!(fresh.insert(Vec2i::new(1, 0), Dir::Left));
+        assert!(fresh.contains(Vec2i::new(1, 0), (-1, 0)));
+        assert!(!fresh.insert(Vec2i::new(1, 0), Side::L));
+        assert!(!fresh.contains(Vec2i::new(1, 0), (-1, -1)));
+        assert!(!fresh.contains(Vec2i::new(-1, 0), Side::L));
+    }
+
+    #[test]
+    #[should_panic(expected = "4 directions with 5 states don't fit into 16 bits")]
+    fn too_many_states() {
+        StateVisited::new(2, 2, 4, 5);
+    }
+}
@@ -0,0 +1,149 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+impl<T> Grid<T> {
+    /// The positions from `start` on, stepping by `dir` until the edge of the grid. Empty if
+    /// `start` is outside, see [`line_between`](crate::geometry::line_between) for a fixed end.
+    pub fn walk(&self, start: Vec2i, dir: (i32, i32)) -> impl Iterator<Item = Vec2i> + '_ {
+        assert!(dir != (0, 0), "walking with a zero step never ends");
+        let step = Vec2i::new(dir.0, dir.1);
+        std::iter::successors(Some(start).filter(|&p| self.in_bounds(p)), move |&p| {
+            Some(p + step).filter(|&next| self.in_bounds(next))
+        })
+    }
+
+    /// Like [`Grid::walk`] with the cells.
+    pub fn walk_values(
+        &self,
+        start: Vec2i,
+        dir: (i32, i32),
+    ) -> impl Iterator<Item = (Vec2i, &T)> + '_ {
+        self.walk(start, dir).map(|pos| (pos, &self[pos]))
+    }
+
+    /// Every row left to right, then every column top to bottom. With `include_diagonals`, the
+    /// lines going down to the right follow, starting from the bottom of the left column and
+    /// then along the top row, and after those the lines going down to the left, starting
+    /// along the top row and then down the right column.
+    pub fn all_lines(
+        &self,
+        include_diagonals: bool,
+    ) -> impl Iterator<Item = Vec<(Vec2i, &T)>> + '_ {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let top = (0..w).map(|x| Vec2i::new(x, 0));
+        let mut starts: Vec<(Vec2i, (i32, i32))> = (0..h)
+            .map(|y| (Vec2i::new(0, y), (1, 0)))
+            .chain(top.clone().map(|p| (p, (0, 1))))
+            .collect();
+        if include_diagonals {
+            let left = (1..h).rev().map(|y| Vec2i::new(0, y));
+            starts.extend(left.chain(top.clone()).map(|p| (p, (1, 1))));
+            let right = (1..h).map(|y| Vec2i::new(w - 1, y));
+            starts.extend(top.chain(right).map(|p| (p, (-1, 1))));
+        }
+        starts
+            .into_iter()
+            .map(|(start, dir)| self.walk_values(start, dir).collect())
+    }
+
+    /// Like [`Grid::all_lines`] without the positions.
+    pub fn all_lines_values(&self, include_diagonals: bool) -> impl Iterator<Item = Vec<&T>> + '_ {
+        self.all_lines(include_diagonals)
+            .map(|line| line.into_iter().map(|(_, value)| value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::DIRS8;
+
+    const WORDS: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn word_search() {
+        let grid = Grid::from_str_chars(WORDS);
+        let count: usize = grid
+            .positions()
+            .map(|p| {
+                DIRS8
+                    .iter()
+                    .filter(|&&d| {
+                        grid.walk_values(p, d)
+                            .take(4)
+                            .map(|(_, &c)| c)
+                            .eq("XMAS".chars())
+                    })
+                    .count()
+            })
+            .sum();
+        assert_eq!(count, 18);
+    }
+
+    #[test]
+    fn edges() {
+        let grid = Grid::new(4, 3, 0);
+        assert_eq!(grid.walk(Vec2i::new(4, 0), (-1, 0)).count(), 0);
+        assert_eq!(grid.walk(Vec2i::new(-1, -1), (1, 1)).count(), 0);
+        assert_eq!(
+            grid.walk(Vec2i::new(0, 2), (1, 0)).collect::<Vec<_>>(),
+            (0..4).map(|x| Vec2i::new(x, 2)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            grid.walk(Vec2i::new(3, 0), (-1, 1)).collect::<Vec<_>>(),
+            [Vec2i::new(3, 0), Vec2i::new(2, 1), Vec2i::new(1, 2)]
+        );
+        assert_eq!(grid.walk(Vec2i::new(1, 1), (0, 5)).count(), 1);
+    }
+
+    #[test]
+    fn lines() {
+        let grid = Grid::from_fn(5, 3, |p| p.y * 5 + p.x);
+        let (w, h) = (grid.width(), grid.height());
+        assert_eq!(grid.all_lines(false).count(), h + w);
+        assert_eq!(grid.all_lines(true).count(), h + w + 2 * (h + w - 1));
+        for (diagonals, per_cell) in [(false, 2), (true, 4)] {
+            let mut seen = Grid::new(w, h, 0);
+            for line in grid.all_lines(diagonals) {
+                for (pos, _) in line {
+                    seen[pos] += 1;
+                }
+            }
+            assert!(seen.iter().all(|(_, &n)| n == per_cell));
+        }
+        let lines: Vec<Vec<i32>> = grid
+            .all_lines_values(true)
+            .map(|line| line.into_iter().copied().collect())
+            .collect();
+        assert_eq!(lines[0], [0, 1, 2, 3, 4]);
+        assert_eq!(lines[3], [0, 5, 10]);
+        assert_eq!(lines[8], [10]);
+        assert_eq!(lines[10], [0, 6, 12]);
+        assert_eq!(lines[15], [0]);
+        assert_eq!(lines[21], [14]);
+    }
+
+    #[test]
+    fn word_search_by_lines() {
+        let grid = Grid::from_str_chars(WORDS);
+        let count: usize = grid
+            .all_lines_values(true)
+            .map(|line| {
+                let line: String = line.into_iter().collect();
+                let reversed: String = line.chars().rev().collect();
+                line.matches("XMAS").count() + reversed.matches("XMAS").count()
+            })
+            .sum();
+        assert_eq!(count, 18);
+    }
+}
@@ -0,0 +1,193 @@
+use vecm::Vec2i;
+
+use super::Grid;
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// Lets water run from `source` through a vertical cross-section: it falls until it lands on
+    /// clay or settled water, then spreads both ways. A layer walled in on both sides settles,
+    /// and the water above it spreads again, otherwise it flows over the ends where it can fall
+    /// further. Water leaving the grid at the bottom or the sides is gone.
+    ///
+    /// Returns the number of settled and flowing cells between the highest and the lowest clay
+    /// row, the rows above the clay and below it only hold the falling streams.
+    pub fn flow_water(
+        &mut self,
+        source: Vec2i,
+        is_clay: impl Fn(&T) -> bool,
+        settled: T,
+        flowing: T,
+    ) -> (usize, usize) {
+        let clay_rows: Vec<i32> = self
+            .iter()
+            .filter(|(_, cell)| is_clay(cell))
+            .map(|(pos, _)| pos.y)
+            .collect();
+        let (Some(&top), Some(&bottom)) = (clay_rows.iter().min(), clay_rows.iter().max()) else {
+            return (0, 0);
+        };
+        let supports = |grid: &Self, pos: Vec2i| {
+            grid.get(pos)
+                .is_some_and(|cell| is_clay(cell) || *cell == settled)
+        };
+        let mut sources = vec![source];
+        while let Some(mut pos) = sources.pop() {
+            if supports(self, pos) {
+                continue;
+            }
+            self[pos] = flowing.clone();
+            // fall until something holds the water up, an existing stream already took care of
+            // everything below it
+            loop {
+                let below = pos + Vec2i::new(0, 1);
+                match self.get(below) {
+                    None => break,
+                    Some(cell) if *cell == flowing => break,
+                    Some(_) if supports(self, below) => break,
+                    Some(_) => {
+                        pos = below;
+                        self[pos] = flowing.clone();
+                    }
+                }
+            }
+            if !supports(self, pos + Vec2i::new(0, 1)) {
+                continue;
+            }
+            let mut ends = [pos.x; 2];
+            let mut walled = [false; 2];
+            for (side, dx) in [-1, 1].into_iter().enumerate() {
+                loop {
+                    let x = ends[side];
+                    let next = Vec2i::new(x + dx, pos.y);
+                    if !supports(self, Vec2i::new(x, pos.y + 1)) {
+                        sources.push(Vec2i::new(x, pos.y));
+                        break;
+                    }
+                    match self.get(next) {
+                        None => break,
+                        Some(cell) if is_clay(cell) => {
+                            walled[side] = true;
+                            break;
+                        }
+                        Some(_) => ends[side] = next.x,
+                    }
+                }
+            }
+            let fill = if walled == [true; 2] {
+                &settled
+            } else {
+                &flowing
+            };
+            for x in ends[0]..=ends[1] {
+                self[Vec2i::new(x, pos.y)] = fill.clone();
+            }
+            if walled == [true; 2] {
+                sources.extend(
+                    (ends[0]..=ends[1])
+                        .map(|x| Vec2i::new(x, pos.y - 1))
+                        .filter(|&above| self.get(above) == Some(&flowing)),
+                );
+            }
+        }
+        let counted = self
+            .iter()
+            .filter(|(pos, _)| (top..=bottom).contains(&pos.y));
+        counted.fold((0, 0), |(s, f), (_, cell)| {
+            (
+                s + (*cell == settled) as usize,
+                f + (*cell == flowing) as usize,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The grid for clay veins like `x=495, y=2..7`, one column of room on both sides.
+    fn scan(veins: &str) -> (Grid<char>, i32) {
+        let veins: Vec<(char, [i64; 3])> = veins
+            .lines()
+            .map(|line| (line.as_bytes()[0] as char, crate::ints_n::<3>(line)))
+            .collect();
+        let xs = veins.iter().flat_map(|&(axis, [a, b, c])| match axis {
+            'x' => vec![a],
+            _ => vec![b, c],
+        });
+        let (min_x, max_x) = (xs.clone().min().unwrap() - 1, xs.max().unwrap() + 1);
+        let max_y = veins
+            .iter()
+            .map(|&(axis, [a, _, c])| if axis == 'x' { c } else { a })
+            .max()
+            .unwrap();
+        let mut grid = Grid::new((max_x - min_x + 1) as usize, max_y as usize + 1, '.');
+        for (axis, [a, b, c]) in veins {
+            for i in b..=c {
+                let (x, y) = if axis == 'x' { (a, i) } else { (i, a) };
+                grid[Vec2i::new((x - min_x) as i32, y as i32)] = '#';
+            }
+        }
+        (grid, min_x as i32)
+    }
+
+    #[test]
+    fn reservoir() {
+        let (mut grid, min_x) = scan(
+            "x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504",
+        );
+        let (settled, flowing) =
+            grid.flow_water(Vec2i::new(500 - min_x, 0), |&c| c == '#', '~', '|');
+        assert_eq!(settled + flowing, 57);
+        assert_eq!(settled, 29);
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        assert_eq!(
+            rows.join("\n"),
+            "......|.......
+......|.....#.
+.#..#||||...#.
+.#..#~~#|.....
+.#..#~~#|.....
+.#~~~~~#|.....
+.#~~~~~#|.....
+.#######|.....
+........|.....
+...|||||||||..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#######|.."
+        );
+    }
+
+    #[test]
+    fn nested_buckets() {
+        // a bucket inside of a bucket that overflows into two more below it
+        let (mut grid, min_x) = scan(
+            "x=495, y=2..10
+x=505, y=2..10
+y=10, x=495..505
+x=499, y=5..7
+x=501, y=5..7
+y=7, x=499..501
+x=504, y=12..16
+x=509, y=13..16
+y=16, x=504..509
+x=490, y=14..18
+x=499, y=14..18
+y=18, x=490..499",
+        );
+        let counts = grid.flow_water(Vec2i::new(500 - min_x, 0), |&c| c == '#', '~', '|');
+        assert_eq!(counts, (109, 55));
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows[7], ".....|#~~~###~~~#|....");
+        assert_eq!(rows[12], ".....|.........#||||||");
+        assert_eq!(rows[13], "||||||||||||...#~~~~#|");
+    }
+}
@@ -0,0 +1,189 @@
+use vecm::Vec2i;
+
+use super::{Grid, Side, DIRS4};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    L,
+    R,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathStep {
+    Move(usize),
+    Turn(Turn),
+}
+impl PathStep {
+    /// Parses paths like `10R5L5`.
+    pub fn parse_all(s: &str) -> Vec<Self> {
+        let mut steps = Vec::new();
+        let mut number: Option<usize> = None;
+        for c in s.trim().chars() {
+            if let Some(d) = c.to_digit(10) {
+                number = Some(number.unwrap_or(0) * 10 + d as usize);
+                continue;
+            }
+            steps.extend(number.take().map(Self::Move));
+            steps.push(Self::Turn(match c {
+                'L' => Turn::L,
+                'R' => Turn::R,
+                _ => panic!("invalid path step {c:?} in {s:?}"),
+            }));
+        }
+        steps.extend(number.map(Self::Move));
+        steps
+    }
+}
+
+/// Decides where a walker ends up when stepping from `pos` towards `facing` would leave the
+/// board, either off the grid or onto a void cell.
+pub trait WrapRule<T> {
+    fn wrap(&self, grid: &Grid<T>, pos: Vec2i, facing: Side) -> (Vec2i, Side);
+}
+
+/// Wraps to the opposite end of the current row or column, keeping the facing.
+pub struct FlatWrap<F>(pub F);
+impl<T, F: Fn(&T) -> bool> WrapRule<T> for FlatWrap<F> {
+    fn wrap(&self, grid: &Grid<T>, pos: Vec2i, facing: Side) -> (Vec2i, Side) {
+        let back = Vec2i::new(0, 0) - facing.dir();
+        let mut pos = pos;
+        while grid.in_bounds(pos + back) && !(self.0)(&grid[pos + back]) {
+            pos += back;
+        }
+        (pos, facing)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Follows the path on a board with wrap-around at its edges, skipping `void` cells and
+    /// stopping in front of `wall` cells. Returns the final position and facing.
+    pub fn wrap_walk(
+        &self,
+        start: Vec2i,
+        facing: Side,
+        steps: &[PathStep],
+        void: impl Fn(&T) -> bool,
+        wall: impl Fn(&T) -> bool,
+    ) -> (Vec2i, Side) {
+        self.wrap_walk_with(start, facing, steps, &void, wall, &FlatWrap(&void))
+    }
+
+    /// Like [`Grid::wrap_walk`] with a custom rule for leaving the board, e.g. cube folding.
+    pub fn wrap_walk_with(
+        &self,
+        start: Vec2i,
+        facing: Side,
+        steps: &[PathStep],
+        void: impl Fn(&T) -> bool,
+        wall: impl Fn(&T) -> bool,
+        rule: &impl WrapRule<T>,
+    ) -> (Vec2i, Side) {
+        let (mut pos, mut facing) = (start, facing);
+        for step in steps {
+            match *step {
+                PathStep::Turn(Turn::L) => facing = facing.rot_left(),
+                PathStep::Turn(Turn::R) => facing = facing.rot_right(),
+                PathStep::Move(n) => {
+                    for _ in 0..n {
+                        let next = pos + facing.dir();
+                        let (next, next_facing) = if self.in_bounds(next) && !void(&self[next]) {
+                            (next, facing)
+                        } else {
+                            rule.wrap(self, pos, facing)
+                        };
+                        if wall(&self[next]) {
+                            break;
+                        }
+                        (pos, facing) = (next, next_facing);
+                    }
+                }
+            }
+        }
+        (pos, facing)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Maps any position onto the grid as if it was repeated infinitely in all directions, so
+    /// `-1` becomes `width - 1` and `width` becomes `0`.
+    pub fn wrap(&self, pos: Vec2i) -> Vec2i {
+        Vec2i::new(
+            pos.x.rem_euclid(self.width as i32),
+            pos.y.rem_euclid(self.height as i32),
+        )
+    }
+
+    pub fn get_wrapped(&self, pos: Vec2i) -> &T {
+        &self[self.wrap(pos)]
+    }
+
+    pub fn get_wrapped_mut(&mut self, pos: Vec2i) -> &mut T {
+        let pos = self.wrap(pos);
+        &mut self[pos]
+    }
+
+    /// The 4 orthogonal neighbors on the torus, wrapped onto the grid.
+    pub fn neighbor_positions4_wrapped(&self, pos: Vec2i) -> [Vec2i; 4] {
+        DIRS4.map(|(dx, dy)| self.wrap(pos + Vec2i::new(dx, dy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathStep;
+    use crate::{Grid, Side};
+    use vecm::Vec2i;
+
+    #[test]
+    fn monkey_map() {
+        let board = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.";
+        let grid = Grid::from_str_chars_padded(board, ' ');
+        let steps = PathStep::parse_all("10R5L5R10L4R5L5");
+        assert_eq!(steps.len(), 13);
+        let start = grid.find(&'.').unwrap();
+        let (end, facing) = grid.wrap_walk(start, Side::R, &steps, |&c| c == ' ', |&c| c == '#');
+        let facing_score = match facing {
+            Side::R => 0,
+            Side::B => 1,
+            Side::L => 2,
+            Side::T => 3,
+        };
+        let password = 1000 * (end.y + 1) + 4 * (end.x + 1) + facing_score;
+        assert_eq!(password, 6032);
+    }
+
+    #[test]
+    fn torus_coordinates() {
+        let mut grid = Grid::from_nested(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(grid.wrap(Vec2i::new(-1, -1)), Vec2i::new(2, 1));
+        assert_eq!(grid.wrap(Vec2i::new(3, 2)), Vec2i::new(0, 0));
+        assert_eq!(grid.wrap(Vec2i::new(-3_000_000, -4)), Vec2i::new(0, 0));
+        assert_eq!(
+            grid.wrap(Vec2i::new(-3_000_001, 1_000_001)),
+            Vec2i::new(2, 1)
+        );
+        assert_eq!(*grid.get_wrapped(Vec2i::new(1 + 1_000_000 * 3, -7)), 5);
+        *grid.get_wrapped_mut(Vec2i::new(-6, 2)) += 10;
+        assert_eq!(grid[(0, 0)], 11);
+        assert_eq!(
+            grid.neighbor_positions4_wrapped(Vec2i::new(0, 0)),
+            [
+                Vec2i::new(0, 1),
+                Vec2i::new(2, 0),
+                Vec2i::new(1, 0),
+                Vec2i::new(0, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+/// Three significant digits, for a value below 999.5.
+fn sig3(value: f64) -> String {
+    if value < 9.995 {
+        format!("{value:.2}")
+    } else if value < 99.95 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.0}")
+    }
+}
+
+/// `value` in the first unit that keeps it below 1000 after rounding, each unit `base` times
+/// the previous one. The last unit takes whatever is left.
+fn scaled(mut value: f64, base: f64, units: &[&str]) -> String {
+    for (i, unit) in units.iter().enumerate() {
+        if value < 999.5 || i == units.len() - 1 {
+            return format!("{}{unit}", sig3(value));
+        }
+        value /= base;
+    }
+    unreachable!("there has to be a unit")
+}
+
+/// A duration with three significant digits in ns, µs, ms or s, like `12.3ms`. Anything above
+/// 1000 seconds is shown in whole seconds.
+pub fn fmt_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1000 {
+        return format!("{nanos}ns");
+    }
+    scaled(nanos as f64 / 1000.0, 1000.0, &["µs", "ms", "s"])
+}
+
+/// Items per second with three significant digits and a metric suffix, like `1.20M items/s`.
+pub fn fmt_rate(items: u64, d: Duration) -> String {
+    if d.is_zero() {
+        return "∞ items/s".to_owned();
+    }
+    let rate = items as f64 / d.as_secs_f64();
+    scaled(rate, 1000.0, &["", "k", "M", "G", "T", "P", "E", "Z"]) + " items/s"
+}
+
+/// A byte count with three significant digits in binary units, like `1.50KiB`.
+pub fn fmt_bytes(n: u64) -> String {
+    if n < 1000 {
+        return format!("{n}B");
+    }
+    scaled(
+        n as f64 / 1024.0,
+        1024.0,
+        &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        let ns = Duration::from_nanos;
+        assert_eq!(fmt_duration(Duration::ZERO), "0ns");
+        assert_eq!(fmt_duration(ns(999)), "999ns");
+        assert_eq!(fmt_duration(ns(1000)), "1.00µs");
+        assert_eq!(fmt_duration(ns(1_234)), "1.23µs");
+        assert_eq!(fmt_duration(ns(12_345)), "12.3µs");
+        assert_eq!(fmt_duration(ns(999_499)), "999µs");
+        // would round to 1000µs
+        assert_eq!(fmt_duration(ns(999_500)), "1.00ms");
+        assert_eq!(fmt_duration(ns(123_456_789)), "123ms");
+        assert_eq!(fmt_duration(ns(999_999_999)), "1.00s");
+        assert_eq!(fmt_duration(Duration::from_secs(59)), "59.0s");
+        assert_eq!(fmt_duration(Duration::from_secs(7200)), "7200s");
+        assert_eq!(fmt_duration(Duration::MAX), "18446744073709551616s");
+    }
+
+    #[test]
+    fn rates_and_bytes() {
+        let second = Duration::from_secs(1);
+        assert_eq!(fmt_rate(1_200_000, second), "1.20M items/s");
+        assert_eq!(fmt_rate(999, second), "999 items/s");
+        assert_eq!(fmt_rate(999_600, second), "1.00M items/s");
+        assert_eq!(fmt_rate(5, Duration::from_millis(10)), "500 items/s");
+        assert_eq!(fmt_rate(0, second), "0.00 items/s");
+        assert_eq!(fmt_rate(3, Duration::ZERO), "∞ items/s");
+        assert_eq!(fmt_bytes(999), "999B");
+        assert_eq!(fmt_bytes(1000), "0.98KiB");
+        assert_eq!(fmt_bytes(1536), "1.50KiB");
+        assert_eq!(fmt_bytes(1023 * 1024), "1.00MiB");
+        assert_eq!(fmt_bytes(u64::MAX), "16.0EiB");
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::{color, ByteMap, Color};
+
+/// Quick statistics about a puzzle input, useful before writing a parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputReport {
+    pub line_count: usize,
+    pub min_line_len: usize,
+    pub max_line_len: usize,
+    /// The most common line length, preferring the shorter one on ties.
+    pub modal_line_len: usize,
+    /// All characters except line breaks with their number of occurrences.
+    pub chars: BTreeMap<char, usize>,
+    /// The number of non-empty blocks separated by blank lines.
+    pub blocks: usize,
+    /// Whether all lines have the same length and use at most 16 distinct characters.
+    pub looks_like_grid: bool,
+}
+
+const GRID_MAX_CHARS: usize = 16;
+
+pub fn input_report(s: &str) -> InputReport {
+    let lines: Vec<&str> = s.lines().collect();
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    // ASCII is counted in a flat table, only other chars go through the map
+    let mut ascii: ByteMap<usize> = ByteMap::new();
+    let mut chars = BTreeMap::new();
+    for line in &lines {
+        *lengths.entry(line.chars().count()).or_default() += 1;
+        for c in line.chars() {
+            if c.is_ascii() {
+                *ascii.get_or_insert_with(c as u8, || 0) += 1;
+            } else {
+                *chars.entry(c).or_default() += 1;
+            }
+        }
+    }
+    chars.extend(ascii.iter().map(|(b, &count)| (b as char, count)));
+    let mut blocks = 0;
+    let mut in_block = false;
+    for line in &lines {
+        let blank = line.trim().is_empty();
+        if !blank && !in_block {
+            blocks += 1;
+        }
+        in_block = !blank;
+    }
+    let modal_line_len = lengths
+        .iter()
+        .max_by_key(|&(&len, &count)| (count, std::cmp::Reverse(len)))
+        .map_or(0, |(&len, _)| len);
+    let looks_like_grid = lines.len() > 1
+        && lengths.len() == 1
+        && modal_line_len > 1
+        && chars.len() <= GRID_MAX_CHARS;
+    InputReport {
+        line_count: lines.len(),
+        min_line_len: lengths.keys().next().copied().unwrap_or(0),
+        max_line_len: lengths.keys().next_back().copied().unwrap_or(0),
+        modal_line_len,
+        chars,
+        blocks,
+        looks_like_grid,
+    }
+}
+
+impl Display for InputReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let colored = color::enabled();
+        let bold = |n: usize| color::bold(n).when(colored);
+        write!(
+            f,
+            "{} lines, length {}..={} (mode {}), {} block(s)",
+            bold(self.line_count),
+            bold(self.min_line_len),
+            bold(self.max_line_len),
+            bold(self.modal_line_len),
+            bold(self.blocks),
+        )?;
+        if self.looks_like_grid {
+            write!(
+                f,
+                ", {}",
+                Color::Green.paint("looks like a grid").when(colored)
+            )?;
+        }
+        writeln!(f)?;
+        let mut first = true;
+        for (c, count) in &self.chars {
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+            let c = format!("{c:?}");
+            write!(f, "{}:{count}", Color::Yellow.paint(c).when(colored))?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Checks that all lines have the same length and returns the index of the first line that
+/// differs from the first line's length otherwise.
+pub fn assert_rectangular(s: &str) -> Result<(), usize> {
+    let mut lines = s.lines().map(|line| line.chars().count());
+    let Some(width) = lines.next() else {
+        return Ok(());
+    };
+    match lines.position(|len| len != width) {
+        Some(i) => Err(i + 1),
+        None => Ok(()),
+    }
+}
+
+/// Splits the input at runs of blank lines, works with both `\n` and `\r\n` line endings.
+/// Blocks keep their internal lines as they are but lose their trailing line break.
+pub fn blocks(s: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    // byte range of the current block
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    for line in s.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            blocks.extend(current.take().map(|(start, end)| &s[start..end]));
+        } else {
+            let start = current.map_or(offset, |(start, _)| start);
+            current = Some((start, offset + content.len()));
+        }
+        offset += line.len();
+    }
+    blocks.extend(current.map(|(start, end)| &s[start..end]));
+    blocks
+}
+
+/// Parses every non-blank line, panicking with the line number and content on failure.
+pub fn parse_lines<T: FromStr>(s: &str) -> Vec<T>
+where
+    T::Err: Debug,
+{
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .unwrap_or_else(|err| panic!("failed to parse line {} {line:?}: {err:?}", i + 1))
+        })
+        .collect()
+}
+
+/// Parses every block from [`blocks`], panicking with the block index and content on failure.
+pub fn parse_blocks<T: FromStr>(s: &str) -> Vec<T>
+where
+    T::Err: Debug,
+{
+    blocks(s)
+        .into_iter()
+        .enumerate()
+        .map(|(i, block)| {
+            block
+                .parse()
+                .unwrap_or_else(|err| panic!("failed to parse block {i}: {err:?}\n{block}"))
+        })
+        .collect()
+}
+
+/// Like [`str::split_once`] with both halves trimmed, panics if `sep` is missing.
+pub fn split_once_trim<'a>(s: &'a str, sep: &str) -> (&'a str, &'a str) {
+    let (a, b) = s
+        .split_once(sep)
+        .unwrap_or_else(|| panic!("missing {sep:?} in {s:?}"));
+    (a.trim(), b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_report() {
+        let report = input_report("#.#\n.S.\n#.#\n");
+        assert_eq!(report.line_count, 3);
+        assert_eq!(
+            (
+                report.min_line_len,
+                report.max_line_len,
+                report.modal_line_len
+            ),
+            (3, 3, 3)
+        );
+        assert_eq!(report.chars[&'#'], 4);
+        assert_eq!(report.chars[&'.'], 4);
+        assert_eq!(report.chars[&'S'], 1);
+        assert_eq!(report.blocks, 1);
+        assert!(report.looks_like_grid);
+        assert_eq!(assert_rectangular("#.#\n.S.\n#.#\n"), Ok(()));
+    }
+
+    #[test]
+    fn numeric_list_report() {
+        let report = input_report("199\n200\n208\n210\n2000\n7\n");
+        assert_eq!(report.line_count, 6);
+        assert_eq!(
+            (
+                report.min_line_len,
+                report.max_line_len,
+                report.modal_line_len
+            ),
+            (1, 4, 3)
+        );
+        assert_eq!(report.chars.len(), 6);
+        assert_eq!(report.chars[&'0'], 7);
+        assert!(!report.looks_like_grid);
+        assert_eq!(assert_rectangular("199\n200\n2000\n7"), Err(2));
+    }
+
+    #[test]
+    fn multi_block_report() {
+        let report = input_report("1000\n2000\n\n4000\n\n\n5000\n6000\n");
+        assert_eq!(report.line_count, 8);
+        assert_eq!(report.blocks, 3);
+        assert_eq!(
+            (
+                report.min_line_len,
+                report.max_line_len,
+                report.modal_line_len
+            ),
+            (0, 4, 4)
+        );
+        assert!(!report.looks_like_grid);
+    }
+
+    #[test]
+    fn split_blocks() {
+        let input = "    [D]\n[N] [C]\n 1   2 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\n\n\n";
+        assert_eq!(
+            blocks(input),
+            [
+                "    [D]\n[N] [C]\n 1   2 ",
+                "move 1 from 2 to 1\nmove 3 from 1 to 3"
+            ]
+        );
+        assert_eq!(blocks("\r\n1\r\n2\r\n\r\n3\r\n"), ["1\r\n2", "3"]);
+        assert_eq!(blocks("\n \n"), Vec::<&str>::new());
+
+        let elves: Vec<u32> = parse_blocks::<String>("1000\n2000\n\n4000\n\n")
+            .iter()
+            .map(|block| parse_lines::<u32>(block).iter().sum())
+            .collect();
+        assert_eq!(elves, [3000, 4000]);
+        assert_eq!(split_once_trim("Card 1: 41 48 ", ":"), ("Card 1", "41 48"));
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse line 3 \"x\"")]
+    fn parse_error_names_line() {
+        parse_lines::<u32>("1\n\nx\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "missing \" | \" in \"1 2\"")]
+    fn missing_separator() {
+        split_once_trim("1 2", " | ");
+    }
+}
@@ -0,0 +1,146 @@
+use std::{collections::HashMap, sync::Arc};
+
+/// A string interned by an [`Interner`]. Symbols are numbered from 0 in the order their strings
+/// were first interned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sym(u32);
+impl Sym {
+    pub(crate) fn from_index(i: usize) -> Self {
+        Self(i as u32)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps strings to small [`Sym`] keys and back. Each distinct string is allocated once and
+/// shared between both directions.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, Sym>,
+}
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Sym {
+        if let Some(&sym) = self.ids.get(s) {
+            return sym;
+        }
+        let sym = Sym(u32::try_from(self.names.len()).expect("too many interned strings"));
+        let name: Arc<str> = s.into();
+        self.names.push(Arc::clone(&name));
+        self.ids.insert(name, sym);
+        sym
+    }
+
+    /// The symbol of `s` if it was interned before.
+    pub fn get(&self, s: &str) -> Option<Sym> {
+        self.ids.get(s).copied()
+    }
+
+    /// Panics for symbols that weren't created by this interner. A foreign symbol with an index
+    /// that happens to exist here can't be detected and resolves to an unrelated string.
+    pub fn resolve(&self, sym: Sym) -> &str {
+        self.names.get(sym.index()).unwrap_or_else(|| {
+            panic!(
+                "symbol {} doesn't belong to this interner, it only has {} strings",
+                sym.0,
+                self.names.len()
+            )
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// All symbols in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Sym, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (Sym(i as u32), &**name))
+    }
+}
+impl<'a> Extend<&'a str> for Interner {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for s in iter {
+            self.intern(s);
+        }
+    }
+}
+impl<'a> FromIterator<&'a str> for Interner {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        interner.extend(iter);
+        interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut interner: Interner = ["AA", "BB"].into_iter().collect();
+        let cc = interner.intern("CC");
+        assert_eq!(interner.intern("AA"), Sym(0));
+        assert_eq!(cc.index(), 2);
+        assert_eq!(interner.resolve(cc), "CC");
+        assert_eq!(interner.get("BB"), Some(Sym(1)));
+        assert_eq!(interner.get("DD"), None);
+        assert_eq!(
+            interner.iter().map(|(_, s)| s).collect::<Vec<_>>(),
+            ["AA", "BB", "CC"]
+        );
+
+        let mut flows: HashMap<Sym, u32> = HashMap::new();
+        for (valve, flow) in [("AA", 0), ("CC", 2), ("AA", 3)] {
+            *flows.entry(interner.intern(valve)).or_default() += flow;
+        }
+        assert_eq!(flows[&Sym(0)], 3);
+        assert_eq!(flows[&cc], 2);
+    }
+
+    #[test]
+    fn no_duplicates() {
+        let mut interner = Interner::new();
+        let first: Vec<Sym> = (0..1000)
+            .map(|i| interner.intern(&format!("n{i}")))
+            .collect();
+        let names_capacity = interner.names.capacity();
+        for round in 0..20 {
+            for i in (0..1000).rev() {
+                let sym = interner.intern(&format!("n{i}"));
+                assert_eq!(sym, first[i], "round {round}");
+            }
+        }
+        assert_eq!(interner.len(), 1000);
+        assert_eq!(interner.names.capacity(), names_capacity);
+        // the map and the list share one allocation per string
+        assert!(interner
+            .names
+            .iter()
+            .all(|name| Arc::strong_count(name) == 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "symbol 3 doesn't belong to this interner")]
+    fn foreign_symbol() {
+        let mut other = Interner::new();
+        let sym = ["a", "b", "c", "d"].map(|s| other.intern(s))[3];
+        let interner: Interner = ["a"].into_iter().collect();
+        interner.resolve(sym);
+    }
+}
@@ -0,0 +1,440 @@
+use std::{fmt::Display, ops::Range};
+
+/// How [`IntervalIndex::build`] treats overlapping ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overlaps {
+    /// Overlapping ranges are an error.
+    Reject,
+    /// Overlaps are allowed, [`IntervalIndex::query`] returns the range given first.
+    FirstMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlapError {
+    pub first: Range<i64>,
+    pub second: Range<i64>,
+}
+impl Display for OverlapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ranges {:?} and {:?} overlap", self.first, self.second)
+    }
+}
+impl std::error::Error for OverlapError {}
+
+/// Values attached to half-open ranges, answering which ranges contain a point by binary search.
+#[derive(Debug, Clone)]
+pub struct IntervalIndex<V> {
+    /// Sorted by start, with the position in the input.
+    entries: Vec<(Range<i64>, usize, V)>,
+    /// The largest end among `entries[..=i]`, to stop scanning early when overlaps are allowed.
+    max_end: Vec<i64>,
+    overlaps: Overlaps,
+}
+impl<V> IntervalIndex<V> {
+    /// Empty ranges are dropped since they can't contain anything.
+    pub fn build(
+        ranges: impl IntoIterator<Item = (Range<i64>, V)>,
+        overlaps: Overlaps,
+    ) -> Result<Self, OverlapError> {
+        let mut entries: Vec<(Range<i64>, usize, V)> = ranges
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (range, _))| !range.is_empty())
+            .map(|(i, (range, value))| (range, i, value))
+            .collect();
+        entries.sort_by_key(|(range, i, _)| (range.start, *i));
+        let mut max_end = Vec::with_capacity(entries.len());
+        for (i, (range, _, _)) in entries.iter().enumerate() {
+            let prev = max_end.last().copied().unwrap_or(i64::MIN);
+            if overlaps == Overlaps::Reject && prev > range.start {
+                return Err(OverlapError {
+                    first: entries[i - 1].0.clone(),
+                    second: range.clone(),
+                });
+            }
+            max_end.push(prev.max(range.end));
+        }
+        Ok(Self {
+            entries,
+            max_end,
+            overlaps,
+        })
+    }
+
+    /// Entries that start at or before `x` and might contain it, starting with the last one.
+    fn candidates(&self, x: i64) -> impl Iterator<Item = &(Range<i64>, usize, V)> {
+        let end = self
+            .entries
+            .partition_point(|(range, _, _)| range.start <= x);
+        let max_end = &self.max_end;
+        self.entries[..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(move |&(i, _)| max_end[i] > x)
+            .map(|(_, entry)| entry)
+            .filter(move |(range, _, _)| range.contains(&x))
+    }
+
+    /// The value of the range containing `x`, the first given one if several do.
+    pub fn query(&self, x: i64) -> Option<&V> {
+        match self.overlaps {
+            // at most one range can contain x and it's the last one starting before it
+            Overlaps::Reject => self.candidates(x).next(),
+            Overlaps::FirstMatch => self.candidates(x).min_by_key(|(_, i, _)| *i),
+        }
+        .map(|(_, _, value)| value)
+    }
+
+    /// The values of all ranges containing `x` in the order they were given.
+    pub fn query_all(&self, x: i64) -> Vec<&V> {
+        let mut found: Vec<_> = self.candidates(x).collect();
+        found.sort_unstable_by_key(|(_, i, _)| *i);
+        found.into_iter().map(|(_, _, value)| value).collect()
+    }
+
+    /// All ranges sorted by their start.
+    pub fn iter(&self) -> impl Iterator<Item = (&Range<i64>, &V)> {
+        self.entries.iter().map(|(range, _, value)| (range, value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The half-open range `start..end` of integers. Intervals with `end <= start` are empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    /// The interval containing just `x`.
+    pub fn point(x: i64) -> Self {
+        Self::new(x, x + 1)
+    }
+
+    pub fn len(&self) -> u64 {
+        (self.end - self.start).max(0) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    /// The common part, `None` if it's empty.
+    pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let common = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!common.is_empty()).then_some(common)
+    }
+
+    /// The union if it's a single interval, so if the two overlap or touch like `0..5` and
+    /// `5..8`. Empty intervals don't add anything to the union.
+    pub fn union_if_overlapping(&self, other: &Interval) -> Option<Interval> {
+        if self.is_empty() || other.is_empty() {
+            return Some(if self.is_empty() { *other } else { *self });
+        }
+        (self.start <= other.end && other.start <= self.end)
+            .then(|| Interval::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// The parts of `self` that aren't in `other`, no empty ones and at most two.
+    pub fn subtract(&self, other: &Interval) -> Vec<Interval> {
+        if self.intersect(other).is_none() {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+        [
+            Interval::new(self.start, other.start),
+            Interval::new(other.end, self.end),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+    }
+
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+impl From<Range<i64>> for Interval {
+    fn from(range: Range<i64>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+/// A set of integers stored as sorted disjoint intervals. Intervals that touch are merged, so
+/// no two stored intervals are adjacent either.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        // the stored intervals that overlap or touch `interval`
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |a, b| a.union_if_overlapping(b).unwrap());
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|i| i.end <= interval.start);
+        let last = self.intervals.partition_point(|i| i.start < interval.end);
+        let rest: Vec<Interval> = self.intervals[first..last]
+            .iter()
+            .flat_map(|i| i.subtract(&interval))
+            .collect();
+        self.intervals.splice(first..last, rest);
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= x);
+        self.intervals.get(i).is_some_and(|i| i.contains(x))
+    }
+
+    /// The number of integers in the set.
+    pub fn total_len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// The stored intervals in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    /// The number of disjoint intervals.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The parts of `bounds` that aren't in the set.
+    pub fn complement_within(&self, bounds: Interval) -> RangeSet {
+        let mut rest = RangeSet::new();
+        rest.insert(bounds);
+        for &i in &self.intervals {
+            rest.remove(i);
+        }
+        rest
+    }
+}
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    #[test]
+    fn boundaries() {
+        // seed-to-soil map ranges as source ranges with offsets
+        let index =
+            IntervalIndex::build([(98..100, -48), (50..98, 2), (7..7, 0)], Overlaps::Reject)
+                .unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.query(49), None);
+        assert_eq!(index.query(50), Some(&2));
+        assert_eq!(index.query(97), Some(&2));
+        assert_eq!(index.query(98), Some(&-48));
+        assert_eq!(index.query(99), Some(&-48));
+        assert_eq!(index.query(100), None);
+        assert_eq!(
+            index.iter().map(|(r, _)| r.clone()).collect::<Vec<_>>(),
+            [50..98, 98..100]
+        );
+    }
+
+    #[test]
+    fn overlap_error() {
+        let err = IntervalIndex::build(
+            [(0..10, 'a'), (20..30, 'b'), (5..12, 'c')],
+            Overlaps::Reject,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            OverlapError {
+                first: 0..10,
+                second: 5..12
+            }
+        );
+        assert_eq!(err.to_string(), "ranges 0..10 and 5..12 overlap");
+    }
+
+    #[test]
+    fn random_queries() {
+        let mut rng = Pcg32::new(3);
+        for round in 0..100 {
+            let ranges: Vec<(Range<i64>, usize)> = (0..rng.index(12))
+                .map(|i| {
+                    let start = rng.below(50) as i64 - 25;
+                    (start..start + rng.below(15) as i64, i)
+                })
+                .collect();
+            let index = IntervalIndex::build(ranges.clone(), Overlaps::FirstMatch).unwrap();
+            for x in -30..45 {
+                let linear: Vec<&usize> = ranges
+                    .iter()
+                    .filter(|(r, _)| r.contains(&x))
+                    .map(|(_, v)| v)
+                    .collect();
+                assert_eq!(index.query_all(x), linear, "round {round}, x = {x}");
+                assert_eq!(index.query(x), linear.first().copied());
+            }
+            if let Ok(strict) = IntervalIndex::build(ranges.clone(), Overlaps::Reject) {
+                for x in -30..45 {
+                    assert_eq!(strict.query(x), index.query(x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interval_edges() {
+        let a = Interval::new(0, 5);
+        assert_eq!(a.len(), 5);
+        assert_eq!(a.intersect(&Interval::new(5, 8)), None);
+        assert_eq!(
+            a.union_if_overlapping(&Interval::new(5, 8)),
+            Some(Interval::new(0, 8))
+        );
+        assert_eq!(a.union_if_overlapping(&Interval::new(6, 8)), None);
+        assert_eq!(a.subtract(&Interval::new(-3, 10)), []);
+        assert_eq!(
+            a.subtract(&Interval::point(2)),
+            [Interval::new(0, 2), Interval::new(3, 5)]
+        );
+        assert_eq!(a.subtract(&Interval::new(5, 6)), [a]);
+        assert_eq!(Interval::point(4).shift(-4), Interval::new(0, 1));
+        assert!(Interval::new(3, 3).is_empty());
+
+        let mut set: RangeSet = [Interval::new(0, 2), Interval::new(4, 6)]
+            .into_iter()
+            .collect();
+        set.insert(Interval::new(2, 4));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [Interval::new(0, 6)]);
+        set.remove(Interval::point(3));
+        assert_eq!(set.len(), 2);
+        assert!(!set.contains(3) && set.contains(2) && set.contains(4));
+        assert_eq!(set.total_len(), 5);
+    }
+
+    const SENSORS: [(i64, i64, i64, i64); 14] = [
+        (2, 18, -2, 15),
+        (9, 16, 10, 16),
+        (13, 2, 15, 3),
+        (12, 14, 10, 16),
+        (10, 20, 10, 16),
+        (14, 17, 10, 16),
+        (8, 7, 2, 10),
+        (2, 0, 2, 10),
+        (0, 11, 2, 10),
+        (20, 14, 25, 17),
+        (17, 20, 21, 22),
+        (16, 7, 15, 3),
+        (14, 3, 15, 3),
+        (20, 1, 15, 3),
+    ];
+
+    fn covered(y: i64) -> RangeSet {
+        SENSORS
+            .iter()
+            .map(|&(sx, sy, bx, by)| {
+                let reach = (sx - bx).abs() + (sy - by).abs() - (sy - y).abs();
+                Interval::new(sx - reach, sx + reach + 1)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sensor_rows() {
+        let mut row = covered(10);
+        for &(_, _, bx, by) in &SENSORS {
+            if by == 10 {
+                row.remove(Interval::point(bx));
+            }
+        }
+        assert_eq!(row.total_len(), 26);
+        let (x, y) = (0..=20)
+            .find_map(|y| {
+                let gaps = covered(y).complement_within(Interval::new(0, 21));
+                let first = gaps.iter().next()?;
+                Some((first.start, y))
+            })
+            .unwrap();
+        assert_eq!(x * 4000000 + y, 56000011);
+    }
+
+    #[test]
+    fn seed_ranges() {
+        // (destination, source, length) rules for seed-to-soil and soil-to-fertilizer
+        let maps = [
+            vec![(50, 98, 2), (52, 50, 48)],
+            vec![(0, 15, 37), (37, 52, 2), (39, 0, 15)],
+        ];
+        let map_point = |x: i64| {
+            maps.iter().fold(x, |x, rules| {
+                rules
+                    .iter()
+                    .find(|&&(_, src, len)| (src..src + len).contains(&x))
+                    .map_or(x, |&(dst, src, _)| x - src + dst)
+            })
+        };
+        let mut ranges: RangeSet = [Interval::new(79, 93), Interval::new(55, 68)]
+            .into_iter()
+            .collect();
+        for rules in &maps {
+            let mut unmapped = ranges.clone();
+            let mut mapped = RangeSet::new();
+            for &(dst, src, len) in rules {
+                let source = Interval::new(src, src + len);
+                for part in ranges.iter().filter_map(|r| r.intersect(&source)) {
+                    mapped.insert(part.shift(dst - src));
+                }
+                unmapped.remove(source);
+            }
+            ranges = unmapped.iter().chain(mapped.iter()).collect();
+        }
+        let expected: RangeSet = (79..93)
+            .chain(55..68)
+            .map(|x| Interval::point(map_point(x)))
+            .collect();
+        assert_eq!(ranges, expected);
+    }
+}
@@ -0,0 +1,167 @@
+/// A binary indexed tree over `n` values starting at zero, with logarithmic point updates and
+/// prefix sums.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fenwick {
+    tree: Vec<i64>,
+}
+impl Fenwick {
+    pub fn new(n: usize) -> Self {
+        Self {
+            tree: vec![0; n + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `v` to the value at `i`. Panics if `i` is out of range.
+    pub fn add(&mut self, i: usize, v: i64) {
+        assert!(i < self.len(), "index {i} out of 0..{}", self.len());
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            self.tree[i] += v;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The sum of the values at `0..i`, the total for any `i` from `len` on.
+    pub fn prefix_sum(&self, i: usize) -> i64 {
+        let mut i = i.min(self.len());
+        let mut sum = 0;
+        while i > 0 {
+            sum += self.tree[i];
+            i &= i - 1;
+        }
+        sum
+    }
+}
+
+/// The number of pairs `i < j` with `values[i] > values[j]`, in `O(n log n)` by counting the
+/// larger values seen so far in a [`Fenwick`] over the value ranks. Equal values don't count.
+pub fn count_inversions<T: Ord>(values: &[T]) -> u64 {
+    let mut sorted: Vec<&T> = values.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    let mut seen = Fenwick::new(sorted.len());
+    let mut inversions = 0;
+    for (count, value) in values.iter().enumerate() {
+        let rank = sorted.binary_search(&value).unwrap();
+        inversions += (count as i64 - seen.prefix_sum(rank + 1)) as u64;
+        seen.add(rank, 1);
+    }
+    inversions
+}
+
+/// The minimum number of swaps of adjacent elements turning `from` into `to`, `None` if they
+/// aren't permutations of each other. Equal values keep their relative order, the `k`-th
+/// occurrence of a value in `from` becomes its `k`-th occurrence in `to`, which never needs
+/// more swaps than any other matching.
+pub fn min_adjacent_swaps_to<T: Ord>(from: &[T], to: &[T]) -> Option<u64> {
+    if from.len() != to.len() {
+        return None;
+    }
+    // stable sorts line up the occurrences of each value in order
+    let mut from_order: Vec<usize> = (0..from.len()).collect();
+    from_order.sort_by_key(|&i| &from[i]);
+    let mut to_order: Vec<usize> = (0..to.len()).collect();
+    to_order.sort_by_key(|&i| &to[i]);
+    let mut target = vec![0; from.len()];
+    for (&i, &j) in from_order.iter().zip(&to_order) {
+        if from[i] != to[j] {
+            return None;
+        }
+        target[i] = j;
+    }
+    Some(count_inversions(&target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    fn brute_inversions(values: &[u32]) -> u64 {
+        let mut count = 0;
+        for (i, a) in values.iter().enumerate() {
+            count += values[i + 1..].iter().filter(|&b| a > b).count() as u64;
+        }
+        count
+    }
+
+    #[test]
+    fn inversions_match_brute_force() {
+        let mut rng = Pcg32::new(303);
+        for _ in 0..300 {
+            let len = rng.index(40);
+            let range = 1 + rng.below(20);
+            let values: Vec<u32> = (0..len).map(|_| rng.below(range)).collect();
+            assert_eq!(
+                count_inversions(&values),
+                brute_inversions(&values),
+                "{values:?}"
+            );
+        }
+        assert_eq!(count_inversions::<u8>(&[]), 0);
+        assert_eq!(count_inversions(&[5, 4, 3, 2, 1]), 10);
+        assert_eq!(count_inversions(&["b", "a", "a"]), 2);
+    }
+
+    /// Bubble sorts `from` into the positions given by `to`, swapping one neighbor at a time.
+    fn bubble_swaps(from: &[u32], to: &[u32]) -> u64 {
+        let mut current = from.to_vec();
+        let mut swaps = 0;
+        for (i, want) in to.iter().enumerate() {
+            let mut j = i + current[i..].iter().position(|v| v == want).unwrap();
+            while j > i {
+                current.swap(j - 1, j);
+                swaps += 1;
+                j -= 1;
+            }
+        }
+        swaps
+    }
+
+    #[test]
+    fn adjacent_swaps_with_duplicates() {
+        assert_eq!(min_adjacent_swaps_to(&[1, 2, 3], &[3, 2, 1]), Some(3));
+        // matching the first 1 to the first 1 needs only one swap
+        assert_eq!(min_adjacent_swaps_to(&[1, 1, 2], &[1, 2, 1]), Some(1));
+        assert_eq!(min_adjacent_swaps_to(&[2, 1, 1], &[1, 1, 2]), Some(2));
+        assert_eq!(min_adjacent_swaps_to(&[1, 1, 2], &[1, 2, 2]), None);
+        assert_eq!(min_adjacent_swaps_to(&[1, 2], &[1, 2, 3]), None);
+        let mut rng = Pcg32::new(2);
+        for _ in 0..100 {
+            let from: Vec<u32> = (0..rng.index(12)).map(|_| rng.below(4)).collect();
+            let mut to = from.clone();
+            rng.shuffle(&mut to);
+            assert_eq!(
+                min_adjacent_swaps_to(&from, &to),
+                Some(bubble_swaps(&from, &to)),
+                "{from:?} to {to:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fenwick_prefix_sums() {
+        let mut rng = Pcg32::new(7);
+        let mut fenwick = Fenwick::new(25);
+        let mut values = [0i64; 25];
+        for _ in 0..500 {
+            let (i, v) = (rng.index(25), rng.below(21) as i64 - 10);
+            fenwick.add(i, v);
+            values[i] += v;
+            let end = rng.index(27);
+            let expected: i64 = values[..end.min(25)].iter().sum();
+            assert_eq!(fenwick.prefix_sum(end), expected);
+        }
+        assert_eq!(fenwick.prefix_sum(0), 0);
+        assert_eq!(fenwick.len(), 25);
+        assert!(Fenwick::new(0).is_empty());
+    }
+}
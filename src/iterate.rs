@@ -0,0 +1,186 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Applies `f` until the state stops changing and returns the fixed point together with the
+/// number of applications that changed the state. Panics if no fixed point is reached within
+/// `max_iterations` applications.
+pub fn fixed_point<T: PartialEq>(
+    init: T,
+    max_iterations: usize,
+    mut f: impl FnMut(&T) -> T,
+) -> (T, usize) {
+    let mut state = init;
+    for i in 0..max_iterations {
+        let next = f(&state);
+        if next == state {
+            return (state, i);
+        }
+        state = next;
+    }
+    panic!("no fixed point reached after {max_iterations} iterations");
+}
+
+/// Lazily yields `init`, `f(init)`, `f(f(init))`, ...
+pub fn iterate<T>(init: T, mut f: impl FnMut(&T) -> T) -> impl Iterator<Item = T> {
+    std::iter::successors(Some(init), move |state| Some(f(state)))
+}
+
+/// Applies `f` to `init` `n` times.
+pub fn nth_iterate<T>(init: T, mut f: impl FnMut(&T) -> T, n: usize) -> T {
+    (0..n).fold(init, |state, _| f(&state))
+}
+
+/// The states of a sequence that eventually repeats: `states[start..]` is the loop, which has
+/// `length` states, and everything before it is the tail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub length: usize,
+    pub states: Vec<S>,
+}
+impl<S> Cycle<S> {
+    /// The state after `n` steps from the initial state.
+    pub fn state_at(&self, n: usize) -> &S {
+        if n < self.start {
+            &self.states[n]
+        } else {
+            &self.states[self.start + (n - self.start) % self.length]
+        }
+    }
+}
+
+/// Steps from `initial` until a state repeats. Loops forever if no state ever repeats.
+pub fn find_cycle<S: Eq + Hash + Clone>(initial: S, step: impl FnMut(&S) -> S) -> Cycle<S> {
+    find_cycle_by_key(initial, step, S::clone)
+}
+
+/// Like [`find_cycle`], but states are compared by `key`, for example to avoid hashing large
+/// grids. Keys have to be equal exactly when the states are.
+pub fn find_cycle_by_key<S, K: Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> Cycle<S> {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut states = vec![initial];
+    loop {
+        let current = states.last().unwrap();
+        match seen.entry(key(current)) {
+            Entry::Occupied(entry) => {
+                let start = *entry.get();
+                states.pop();
+                return Cycle {
+                    start,
+                    length: states.len() - start,
+                    states,
+                };
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(states.len() - 1);
+            }
+        }
+        let next = step(current);
+        states.push(next);
+    }
+}
+
+/// Repeatedly removes adjacent pairs of characters that react with each other until no such
+/// pair remains, in a single pass using a stack.
+pub fn reduce_adjacent(s: &str, reacts: impl Fn(char, char) -> bool) -> String {
+    let mut stack: Vec<char> = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        match stack.last() {
+            Some(&top) if reacts(top, c) => {
+                stack.pop();
+            }
+            _ => stack.push(c),
+        }
+    }
+    stack.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reacts(a: char, b: char) -> bool {
+        a != b && a.eq_ignore_ascii_case(&b)
+    }
+
+    #[test]
+    fn polymer() {
+        let polymer = "dabAcCaCBAcCcaDA";
+        assert_eq!(reduce_adjacent(polymer, reacts), "dabCBAcaDA");
+        let best = ('a'..='z')
+            .map(|unit| {
+                let removed: String = polymer
+                    .chars()
+                    .filter(|c| !c.eq_ignore_ascii_case(&unit))
+                    .collect();
+                reduce_adjacent(&removed, reacts).len()
+            })
+            .min();
+        assert_eq!(best, Some(4));
+    }
+
+    #[test]
+    fn fixed_point_iterations() {
+        assert_eq!(fixed_point(100u32, 100, |x| x / 2), (0, 7));
+        assert_eq!(fixed_point(5, 10, |&x| x), (5, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "no fixed point")]
+    fn fixed_point_limit() {
+        fixed_point(0u64, 10, |x| x + 1);
+    }
+
+    #[test]
+    fn iterate_lazily() {
+        let powers: Vec<u32> = iterate(1, |x| x * 3).take(5).collect();
+        assert_eq!(powers, [1, 3, 9, 27, 81]);
+        assert_eq!(nth_iterate(1, |x| x * 3, 4), 81);
+    }
+
+    #[test]
+    fn cycle_with_tail() {
+        let cycle = find_cycle(2u32, |&x| x * 3 % 11);
+        assert_eq!((cycle.start, cycle.length), (0, 5));
+        let step = |&x: &u32| if x < 4 { x + 1 } else { x * 3 % 7 + 3 };
+        let cycle = find_cycle(1, step);
+        assert_eq!(cycle.states, [1, 2, 3, 4, 8, 6, 7]);
+        assert_eq!((cycle.start, cycle.length), (2, 5));
+        assert_eq!(*cycle.state_at(1), 2);
+        assert_eq!(*cycle.state_at(7), 3);
+        assert_eq!(*cycle.state_at(1_000_000_000), 6);
+        assert_eq!(*cycle.state_at(1003), nth_iterate(1, step, 1003));
+    }
+
+    #[test]
+    fn immediate_cycle() {
+        let cycle = find_cycle(7, |&x| x);
+        assert_eq!((cycle.start, cycle.length), (0, 1));
+        assert_eq!(*cycle.state_at(12345), 7);
+
+        let cycle = find_cycle_by_key(vec![0u8; 3], |v| vec![v[1], v[2], v[0] ^ 1], |v| v.clone());
+        assert_eq!((cycle.start, cycle.length), (0, 6));
+        assert_eq!(cycle.state_at(7), &[0, 0, 1]);
+    }
+
+    #[test]
+    fn key_once_per_state() {
+        let calls = std::cell::Cell::new(0);
+        let cycle = find_cycle_by_key(
+            2u32,
+            |&x| x * 3 % 11,
+            |&x| {
+                calls.set(calls.get() + 1);
+                x
+            },
+        );
+        assert_eq!((cycle.start, cycle.length), (0, 5));
+        assert_eq!(calls.get(), 6);
+    }
+}
@@ -0,0 +1,295 @@
+use std::fmt::Display;
+
+/// A minimal JSON value for puzzles that ship JSON input. Numbers have to be integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonLite {
+    Null,
+    Bool(bool),
+    Num(i64),
+    Str(String),
+    Array(Vec<JsonLite>),
+    /// Fields in document order.
+    Object(Vec<(String, JsonLite)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// Byte offset into the input.
+    pub pos: usize,
+    pub message: String,
+}
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {}: {}", self.pos, self.message)
+    }
+}
+impl std::error::Error for JsonError {}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+impl Parser<'_> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, JsonError> {
+        Err(JsonError {
+            pos: self.pos,
+            message: message.into(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.s.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.s.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), JsonError> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected {:?}", c as char))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: JsonLite) -> Result<JsonLite, JsonError> {
+        if self.s[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            self.error("invalid literal")
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonLite, JsonError> {
+        match self.peek() {
+            None => self.error("unexpected end of input"),
+            Some(b'n') => self.keyword("null", JsonLite::Null),
+            Some(b't') => self.keyword("true", JsonLite::Bool(true)),
+            Some(b'f') => self.keyword("false", JsonLite::Bool(false)),
+            Some(b'"') => Ok(JsonLite::Str(self.string()?)),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(JsonLite::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(JsonLite::Array(items));
+                        }
+                        _ => return self.error("expected ',' or ']'"),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(JsonLite::Object(fields));
+                }
+                loop {
+                    if self.peek() != Some(b'"') {
+                        return self.error("expected a field name");
+                    }
+                    let name = self.string()?;
+                    self.expect(b':')?;
+                    fields.push((name, self.value()?));
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(JsonLite::Object(fields));
+                        }
+                        _ => return self.error("expected ',' or '}'"),
+                    }
+                }
+            }
+            Some(c) if c == b'-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.s.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    self.pos += 1;
+                }
+                if matches!(self.s.get(self.pos), Some(b'.' | b'e' | b'E')) {
+                    return self.error("only integer numbers are supported");
+                }
+                let text = std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+                match text.parse() {
+                    Ok(n) => Ok(JsonLite::Num(n)),
+                    Err(_) => self.error(format!("invalid number {text:?}")),
+                }
+            }
+            Some(c) => self.error(format!("unexpected {:?}", c as char)),
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let digits = self.s.get(self.pos..self.pos + 4);
+        let Some(n) = digits
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+        else {
+            return self.error("invalid unicode escape");
+        };
+        self.pos += 4;
+        Ok(n)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"')?;
+        let mut out = Vec::new();
+        loop {
+            let Some(&c) = self.s.get(self.pos) else {
+                return self.error("unterminated string");
+            };
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escape) = self.s.get(self.pos) else {
+                        return self.error("unterminated string");
+                    };
+                    self.pos += 1;
+                    let decoded = match escape {
+                        b'"' | b'\\' | b'/' => escape as char,
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.s[self.pos..].starts_with(b"\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                code = 0x10000
+                                    + ((code - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            match char::from_u32(code) {
+                                Some(c) => c,
+                                None => return self.error("invalid unicode escape"),
+                            }
+                        }
+                        _ => return self.error("invalid escape"),
+                    };
+                    let mut buf = [0; 4];
+                    out.extend_from_slice(decoded.encode_utf8(&mut buf).as_bytes());
+                }
+                c => out.push(c),
+            }
+        }
+        String::from_utf8(out).or_else(|_| self.error("invalid utf-8 in string"))
+    }
+}
+
+impl JsonLite {
+    pub fn parse(s: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser {
+            s: s.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value()?;
+        if parser.peek().is_some() {
+            return parser.error("trailing characters");
+        }
+        Ok(value)
+    }
+
+    pub fn sum_numbers(&self) -> i64 {
+        self.sum_numbers_excluding(|_| false)
+    }
+
+    /// Sums all numbers, skipping objects (and everything inside them) for which `exclude`
+    /// holds.
+    pub fn sum_numbers_excluding(&self, exclude: impl Fn(&JsonLite) -> bool) -> i64 {
+        fn sum(value: &JsonLite, exclude: &dyn Fn(&JsonLite) -> bool) -> i64 {
+            match value {
+                JsonLite::Num(n) => *n,
+                JsonLite::Array(items) => items.iter().map(|v| sum(v, exclude)).sum(),
+                JsonLite::Object(_) if exclude(value) => 0,
+                JsonLite::Object(fields) => fields.iter().map(|(_, v)| sum(v, exclude)).sum(),
+                _ => 0,
+            }
+        }
+        sum(self, &exclude)
+    }
+
+    /// Whether this is an object with a field whose value is the string `s`.
+    pub fn has_str_value(&self, s: &str) -> bool {
+        matches!(self, Self::Object(fields) if fields.iter().any(|(_, v)| matches!(v, Self::Str(v) if v == s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        let examples = [
+            ("[1,2,3]", 6),
+            (r#"{"a":2,"b":4}"#, 6),
+            ("[[[3]]]", 3),
+            (r#"{"a":{"b":4},"c":-1}"#, 3),
+            (r#"{"a":[-1,1]}"#, 0),
+            (r#"[-1,{"a":1}]"#, 0),
+            ("[]", 0),
+            ("{}", 0),
+        ];
+        for (doc, sum) in examples {
+            assert_eq!(JsonLite::parse(doc).unwrap().sum_numbers(), sum, "{doc}");
+        }
+    }
+
+    #[test]
+    fn red_exclusion() {
+        let red = |doc: &str| {
+            JsonLite::parse(doc)
+                .unwrap()
+                .sum_numbers_excluding(|v| v.has_str_value("red"))
+        };
+        assert_eq!(red("[1,2,3]"), 6);
+        assert_eq!(red(r#"[1,{"c":"red","b":2},3]"#), 4);
+        assert_eq!(red(r#"{"d":"red","e":[1,2,3,4],"f":5}"#), 0);
+        assert_eq!(red(r#"[1,"red",5]"#), 6);
+    }
+
+    #[test]
+    fn strings_and_errors() {
+        let value =
+            JsonLite::parse(r#" {"k\"ey": "a\\b\né😀", "n": [null, true, false]} "#).unwrap();
+        assert_eq!(
+            value,
+            JsonLite::Object(vec![
+                ("k\"ey".into(), JsonLite::Str("a\\b\né😀".into())),
+                (
+                    "n".into(),
+                    JsonLite::Array(vec![
+                        JsonLite::Null,
+                        JsonLite::Bool(true),
+                        JsonLite::Bool(false)
+                    ])
+                ),
+            ])
+        );
+        assert_eq!(JsonLite::parse("[1,]").unwrap_err().pos, 3);
+        assert!(JsonLite::parse(r#"{"a" 1}"#).is_err());
+        assert!(JsonLite::parse("[1] 2").is_err());
+        assert!(JsonLite::parse("1.5").is_err());
+    }
+}
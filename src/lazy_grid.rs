@@ -0,0 +1,131 @@
+use std::{collections::HashMap, rc::Rc};
+
+use vecm::Vec2i;
+
+use crate::{Grid, Rect};
+
+const TILE: i32 = 64;
+
+type Generator<'a, T> = Rc<dyn Fn(Vec2i, &mut LazyGrid<'a, T>) -> T + 'a>;
+
+/// An unbounded grid whose cells are computed by a rule on first access and kept in tiles of
+/// 64x64 cells. The rule gets the grid back, so a cell can be defined by other cells.
+pub struct LazyGrid<'a, T> {
+    tiles: HashMap<(i32, i32), Box<[Option<T>]>>,
+    generator: Generator<'a, T>,
+    computed: usize,
+}
+
+fn tile_of(pos: Vec2i) -> ((i32, i32), usize) {
+    let tile = (pos.x.div_euclid(TILE), pos.y.div_euclid(TILE));
+    let i = pos.y.rem_euclid(TILE) * TILE + pos.x.rem_euclid(TILE);
+    (tile, i as usize)
+}
+
+impl<'a, T> LazyGrid<'a, T> {
+    pub fn new(generator: impl Fn(Vec2i, &mut LazyGrid<'a, T>) -> T + 'a) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            generator: Rc::new(generator),
+            computed: 0,
+        }
+    }
+
+    /// The cell at `pos`, computed first if it wasn't requested before. Cells needed by the
+    /// rule are computed recursively, see [`LazyGrid::prefetch_rect`] to avoid deep recursion.
+    pub fn get(&mut self, pos: Vec2i) -> &T {
+        let (tile, i) = tile_of(pos);
+        if self.tiles.get(&tile).is_none_or(|cells| cells[i].is_none()) {
+            let generator = Rc::clone(&self.generator);
+            let value = generator(pos, self);
+            let cells = self
+                .tiles
+                .entry(tile)
+                .or_insert_with(|| (0..TILE * TILE).map(|_| None).collect());
+            cells[i] = Some(value);
+            self.computed += 1;
+        }
+        self.tiles[&tile][i].as_ref().unwrap()
+    }
+
+    /// Computes the cells of `rect` in row-major order, so that a rule depending on cells above
+    /// or to the left finds them computed already and the recursion stays shallow, no matter
+    /// how long the chains of dependencies are.
+    pub fn prefetch_rect(&mut self, rect: Rect) {
+        for pos in rect.positions() {
+            self.get(pos);
+        }
+    }
+
+    /// The cells of `rect` as a dense grid, its `(0, 0)` being `rect.min`.
+    pub fn to_grid(&mut self, rect: Rect) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.prefetch_rect(rect);
+        Grid::from_fn(rect.width(), rect.height(), |pos| {
+            self.get(pos + rect.min).clone()
+        })
+    }
+
+    /// The number of cells computed so far.
+    pub fn computed(&self) -> usize {
+        self.computed
+    }
+}
+
+/// The erosion levels of a cave with the depth and target, shared with the search tests.
+#[cfg(test)]
+pub(crate) fn erosion_levels<'a>(depth: u64, target: Vec2i) -> LazyGrid<'a, u64> {
+    LazyGrid::new(move |pos, grid| {
+        let index = if pos == Vec2i::new(0, 0) || pos == target {
+            0
+        } else if pos.y == 0 {
+            pos.x as u64 * 16807
+        } else if pos.x == 0 {
+            pos.y as u64 * 48271
+        } else {
+            let left = *grid.get(pos - Vec2i::new(1, 0));
+            left * grid.get(pos - Vec2i::new(0, 1))
+        };
+        (index + depth) % 20183
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cave_risk() {
+        let target = Vec2i::new(10, 10);
+        let mut levels = erosion_levels(510, target);
+        assert_eq!(*levels.get(Vec2i::new(1, 1)), 1805);
+        assert_eq!(levels.computed(), 3);
+        let cave = levels.to_grid(Rect::new(Vec2i::new(0, 0), target));
+        let risk: u64 = cave.iter().map(|(_, level)| level % 3).sum();
+        assert_eq!(risk, 114);
+        assert_eq!(levels.computed(), 121);
+        // memoized, the rule isn't run again
+        levels.prefetch_rect(Rect::new(Vec2i::new(0, 0), Vec2i::new(5, 5)));
+        assert_eq!(levels.computed(), 121);
+    }
+
+    #[test]
+    fn prefetch_bounds_recursion() {
+        // every cell depends on the one diagonally left of it in the other row, so a cold
+        // access to the far end would recurse through the whole strip
+        let mut zigzag = LazyGrid::new(|pos, grid| {
+            if pos.x == 0 {
+                pos.y as u64
+            } else {
+                grid.get(Vec2i::new(pos.x - 1, 1 - pos.y)) + 1
+            }
+        });
+        let end = 300_000;
+        zigzag.prefetch_rect(Rect::new(Vec2i::new(0, 0), Vec2i::new(end, 1)));
+        assert_eq!(*zigzag.get(Vec2i::new(end, 0)), end as u64);
+        assert_eq!(*zigzag.get(Vec2i::new(end - 1, 0)), end as u64);
+        assert_eq!(zigzag.computed(), 2 * (end as usize + 1));
+    }
+}
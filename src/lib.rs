@@ -1,22 +1,232 @@
+// lets the rendered scaffold template refer to the crate by name in tests
+#[cfg(test)]
+extern crate self as aoch;
+
+pub mod answers;
+pub mod approx;
+pub mod automaton;
+pub mod bitset;
+pub mod brackets;
+pub mod branch_bound;
+pub mod bytemap;
+pub mod canonical;
+pub mod chunks;
+pub mod circuit;
+pub mod claims;
+pub mod color;
+pub mod columns;
+pub mod counter;
+pub mod crt;
+pub mod cups;
+pub mod diffs;
+pub mod events;
+pub mod exhaustive;
+pub mod fetch;
+pub mod frac;
+pub mod geometry;
+pub mod graph;
 pub mod grid;
+pub mod human;
+pub mod input;
+pub mod interner;
+pub mod intervals;
+pub mod inversions;
+pub mod iterate;
+pub mod json;
+pub mod lazy_grid;
+pub mod life;
+pub mod lights;
+pub mod math;
+pub mod memo;
+pub mod monotonic;
+pub mod multipeek;
+pub mod nested;
+pub mod ocr;
+pub mod ordering;
+pub mod parallel;
+pub mod path_tree;
+pub mod permutation;
+pub mod probe;
+pub mod ranking;
+pub mod recipes;
+pub mod records;
+pub mod rect;
+pub mod rng;
+pub mod rockfall;
+pub mod rotation;
+pub mod router;
+pub mod rpg;
+pub mod runner;
+pub mod scaffold;
+pub mod schedule;
+pub mod schematics;
+pub mod search;
+pub mod segments;
+pub mod sensitivity;
+pub mod sparse;
+pub mod spiral;
+pub mod stats;
+pub mod string_ops;
+pub mod tree;
+pub mod trie;
+pub mod turns;
+pub mod union_find;
+pub mod vec2;
+pub mod vec3;
+pub mod voxels;
+pub mod workflow;
 
-pub use color_format::*;
 pub use itertools::Itertools;
 pub use std::collections::{BTreeSet, HashMap, HashSet};
 pub use vecm::*;
 
-pub use grid::{Grid, Side};
+pub use approx::{approx_cmp, approx_eq, round_to, FloatVec2};
+pub use automaton::{parse_rules, Line1D};
+pub use bitset::{best_partition_scores, disjoint_pairs_max, BitLabels, BitSet64};
+pub use brackets::{
+    check_brackets, completion_scores, corruption_score, middle_completion_score, BracketResult,
+};
+pub use branch_bound::{
+    branch_and_bound, BranchAndBound, CheckpointConfig, CheckpointState, SearchStats,
+};
+pub use bytemap::{ByteMap, ByteSet};
+pub use canonical::{canonical_under_grid_symmetries, canonical_under_permutation, Canonicalize};
+pub use chunks::{chunks_exact_array, columns_to_rows, ArrayChunks, Remainder};
+pub use circuit::WireCircuit;
+pub use claims::{claim_overlap_cells, non_overlapping_claims, parse_claim};
+pub use color::Color;
+pub use columns::{pair_columns, similarity_score, total_sorted_distance};
+pub use counter::Counter;
+pub use crt::ClockedVm;
+pub use cups::SuccessorRing;
+pub use diffs::{
+    cumsum, diffs, is_arithmetic, is_polynomial_of_degree, nth_diffs, nth_diffs_wide, pairwise,
+    quadratic_at,
+};
+pub use events::EventQueue;
+pub use exhaustive::{
+    backtrack, backtrack_distinct, grid_search2, grid_search3, grid_search_min_by_key,
+    par_grid_search2,
+};
+pub use frac::Frac;
+pub use graph::{adjacency, reachable_from, reverse_edges, topo_sort};
+pub use grid::{
+    count_reachable_at_exact, first_disconnecting, nearest_in_reading_order, Annotations,
+    Connectivity, Dir, DirVisited, DropResult, ExtrapolationError, Grid, GridIndex, GridMapError,
+    GridNumber, GridView, IncrementalConnectivity, Palette, PoiMatrix, RleCell, RoomGraph, Shape,
+    Side, StateVisited, VisitDir,
+};
+pub use human::{fmt_bytes, fmt_duration, fmt_rate};
+pub use input::{
+    assert_rectangular, blocks, input_report, parse_blocks, parse_lines, split_once_trim,
+    InputReport,
+};
+pub use interner::{Interner, Sym};
+pub use intervals::{Interval, IntervalIndex, OverlapError, Overlaps, RangeSet};
+pub use inversions::{count_inversions, min_adjacent_swaps_to, Fenwick};
+pub use iterate::{
+    find_cycle, find_cycle_by_key, fixed_point, iterate, nth_iterate, reduce_adjacent, Cycle,
+};
+pub use json::JsonLite;
+pub use lazy_grid::LazyGrid;
+pub use life::{LifeGrid, LifeRule};
+pub use math::{
+    crt, egcd, first_aligned_time, first_aligned_time_sieve, gcd, lcm, lcm_all, mod_inverse,
+    mod_pow, solve_nxn, Field,
+};
+pub use memo::{memoized, Memo};
+pub use multipeek::{multipeek, MultiPeek};
+pub use nested::{biodiversity, step_flat, NestedGrids};
+pub use ocr::ocr;
+pub use ordering::{infer_order, is_consistent, OrderError};
+pub use parallel::{par_map_indexed, par_map_lines, parallel_chunks, parallel_map_reduce};
+pub use path_tree::PathTree;
+pub use permutation::Permutation;
+pub use probe::{count_hitting_velocities, simulate_probe};
+pub use ranking::{dense_rank_by_key, rank_by_key, sort_by_cached_key_desc, total_winnings};
+pub use recipes::{recipe_scores_after, recipes_before_pattern};
+pub use records::{records, Record};
+pub use rect::{positions_in_rect, Rect};
+pub use rng::Pcg32;
+pub use rockfall::RockFall;
+pub use rotation::Rot3;
+pub use router::{LineRouter, RouteError};
+pub use rpg::{
+    cheapest_winning_loadout, fight, fight_trace, most_expensive_losing_loadout,
+    spell_fight_min_mana, Combatant, Item, ItemShop, Spell, SpellFight,
+};
+pub use runner::{
+    both_parts, with_param, BothParts, Day, DayResult, InputKind, InputLayout, Mode, PartOutcome,
+    ResolvedInput, RunError, Runner, Solution, Summary, SummaryRow, WithParam,
+};
+pub use schedule::schedule_with_workers;
+pub use schematics::{count_fitting_pairs, split_locks_and_keys};
+pub use search::{astar, bfs01, dijkstra, dijkstra_all, dijkstra_layered, dijkstra_layered_by};
+pub use segments::{count_easy_digits, decode_output, deduce_segment_mapping, segments_to_bits};
+pub use sensitivity::{diff_lines, probe_sensitivity, DiffOp, MutationKind};
+pub use sparse::{count_cells_with_at_least, rasterize_segments, SparseGrid};
+pub use spiral::{accumulate_spiral, spiral};
+pub use stats::{best_alignment_cost, RunningMedian, RunningStats};
+pub use tree::{Tree, TreeError};
+pub use trie::{count_compositions, Trie};
+pub use turns::{min_reading_order, sort_reading_order, TurnOrderSim, TurnResult};
+pub use union_find::{transitive_closure_edges, transitive_closure_indexed, UnionFind};
+pub use vec2::{parse_vec2, Vec2Ext};
+pub use vec3::{all_int_triples, coord3, coords3, Vec3Ext};
+pub use voxels::VoxelSet;
+pub use workflow::{RuleChain, RuleChains, Verdict};
 
 pub fn int(s: &str) -> i64 {
     s.trim().parse().expect("failed to parse as int")
 }
 
 pub fn ints(s: &str) -> Vec<i64> {
-    s.trim()
-        .split(" ")
-        .filter(|s| !s.is_empty())
-        .map(int)
-        .collect()
+    s.split_whitespace().map(int).collect()
+}
+
+/// Every integer in the string, ignoring whatever surrounds it. A `-` directly in front of
+/// digits is taken as the sign, so `x=-12..-3` gives `[-12, -3]`.
+pub fn all_ints(s: &str) -> Vec<i64> {
+    scan_ints(s, true)
+}
+
+/// Like [`all_ints`], but minus signs are treated as separators.
+pub fn all_uints(s: &str) -> Vec<u64> {
+    scan_ints(s, false)
+}
+
+/// Exactly `N` integers as found by [`all_ints`].
+pub fn ints_n<const N: usize>(s: &str) -> [i64; N] {
+    let found = all_ints(s);
+    found
+        .as_slice()
+        .try_into()
+        .unwrap_or_else(|_| panic!("expected {N} ints but found {}: {s:?}", found.len()))
+}
+
+fn scan_ints<T: std::str::FromStr>(s: &str, signed: bool) -> Vec<T> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let negative =
+            signed && bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        out.push(
+            s[start..i]
+                .parse()
+                .unwrap_or_else(|_| panic!("integer {:?} out of range", &s[start..i])),
+        );
+    }
+    out
 }
 
 pub fn transitive_closure<I: IntoIterator<Item = T>, T, F: FnMut(&T, &T) -> bool>(
@@ -35,7 +245,8 @@ pub fn transitive_closure<I: IntoIterator<Item = T>, T, F: FnMut(&T, &T) -> bool
             None => sets.push(vec![item]),
             Some((&first, others)) => {
                 sets[first].push(item);
-                for &other in others {
+                // from the back so that the remaining indices stay valid
+                for &other in others.iter().rev() {
                     let mut other = sets.remove(other);
                     sets[first].append(&mut other);
                 }
@@ -44,3 +255,34 @@ pub fn transitive_closure<I: IntoIterator<Item = T>, T, F: FnMut(&T, &T) -> bool
     }
     sets
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ints_with_whitespace() {
+        assert_eq!(ints(" 3\t-4   5 "), [3, -4, 5]);
+        assert_eq!(ints(""), []);
+    }
+
+    #[test]
+    fn ints_in_text() {
+        assert_eq!(all_ints("p=0,4 v=3,-3"), [0, 4, 3, -3]);
+        assert_eq!(all_ints("Card 1: 41 48 | 83 86"), [1, 41, 48, 83, 86]);
+        assert_eq!(all_ints("x=-12..-3, y=7a-b 9-"), [-12, -3, 7, 9]);
+        assert_eq!(all_ints("a-1-2"), [-1, -2]);
+        assert_eq!(all_uints("x=-12..-3"), [12, 3]);
+        assert_eq!(all_ints("no digits - here"), []);
+        assert_eq!(all_uints(""), []);
+        // unsigned values use the whole range of u64
+        assert_eq!(all_uints("18446744073709551615,9"), [u64::MAX, 9]);
+        assert_eq!(ints_n::<4>("p=0,4 v=3,-3"), [0, 4, 3, -3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 3 ints but found 2")]
+    fn ints_n_count_mismatch() {
+        ints_n::<3>("1,2");
+    }
+}
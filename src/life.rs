@@ -0,0 +1,284 @@
+use vecm::Vec2i;
+
+use crate::Grid;
+
+/// The rule of a life-like automaton: bit `n` of `birth` lets a dead cell with `n` alive
+/// neighbors come alive, bit `n` of `survive` keeps an alive one alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    pub birth: u16,
+    pub survive: u16,
+}
+impl LifeRule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub const CONWAY: Self = Self {
+        birth: 1 << 3,
+        survive: 1 << 2 | 1 << 3,
+    };
+
+    /// Parses the `B3/S23` notation. Panics on anything else.
+    pub fn parse(s: &str) -> Self {
+        let counts = |part: &str, prefix: char| {
+            let digits = part
+                .strip_prefix(prefix)
+                .unwrap_or_else(|| panic!("expected {prefix} counts in rule {s:?}"));
+            digits.chars().fold(0u16, |mask, c| match c.to_digit(10) {
+                Some(n @ 0..=8) => mask | 1 << n,
+                _ => panic!("invalid neighbor count {c:?} in rule {s:?}"),
+            })
+        };
+        let (birth, survive) = s
+            .trim()
+            .split_once('/')
+            .unwrap_or_else(|| panic!("missing '/' in rule {s:?}"));
+        Self {
+            birth: counts(birth, 'B'),
+            survive: counts(survive, 'S'),
+        }
+    }
+
+    /// Whether a cell is alive in the next generation.
+    pub fn next_state(self, alive: bool, neighbors: usize) -> bool {
+        let mask = if alive { self.survive } else { self.birth };
+        neighbors < 16 && mask >> neighbors & 1 == 1
+    }
+}
+
+/// A boolean grid stored as rows of `u64` words for bit-parallel automaton steps, bit `x % 64`
+/// of word `x / 64` holding column `x`. Everything outside of the grid is dead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LifeGrid {
+    width: usize,
+    height: usize,
+    words: usize,
+    rows: Vec<u64>,
+}
+impl LifeGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            words,
+            rows: vec![0; words * height],
+        }
+    }
+
+    pub fn from_grid(grid: &Grid<bool>) -> Self {
+        let mut life = Self::new(grid.width(), grid.height());
+        for (pos, &alive) in grid.iter() {
+            life.set(pos, alive);
+        }
+        life
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        Grid::from_fn(self.width, self.height, |pos| self.get(pos))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn bit_of(&self, pos: Vec2i) -> Option<(usize, u32)> {
+        let (x, y) = (usize::try_from(pos.x).ok()?, usize::try_from(pos.y).ok()?);
+        (x < self.width && y < self.height).then_some((y * self.words + x / 64, x as u32 % 64))
+    }
+
+    /// Whether the cell is alive, false outside of the grid.
+    pub fn get(&self, pos: Vec2i) -> bool {
+        self.bit_of(pos)
+            .is_some_and(|(word, bit)| self.rows[word] >> bit & 1 == 1)
+    }
+
+    /// Panics outside of the grid.
+    pub fn set(&mut self, pos: Vec2i, alive: bool) {
+        let (word, bit) = self
+            .bit_of(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"));
+        if alive {
+            self.rows[word] |= 1 << bit;
+        } else {
+            self.rows[word] &= !(1 << bit);
+        }
+    }
+
+    pub fn count_alive(&self) -> usize {
+        self.rows.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// One generation of Conway's Game of Life.
+    #[must_use]
+    pub fn step_life(&self) -> Self {
+        self.step_rule(LifeRule::CONWAY)
+    }
+
+    /// One generation of any life-like rule, 64 cells at a time. The eight neighbor words of
+    /// each word are summed into four bit planes of a counter, then every count the rule lists
+    /// is matched against the planes.
+    #[must_use]
+    pub fn step_rule(&self, rule: LifeRule) -> Self {
+        let mut next = Self::new(self.width, self.height);
+        let row = |y: Option<usize>| match y {
+            Some(y) if y < self.height => &self.rows[y * self.words..(y + 1) * self.words],
+            _ => &[][..],
+        };
+        let match_counts = |planes: &[u64; 4], mask: u16| {
+            (0..9)
+                .filter(|n| mask >> n & 1 == 1)
+                .map(|n| {
+                    planes
+                        .iter()
+                        .enumerate()
+                        .fold(u64::MAX, |acc, (i, &plane)| {
+                            acc & if n >> i & 1 == 1 { plane } else { !plane }
+                        })
+                })
+                .fold(0, |acc, m| acc | m)
+        };
+        let word = |r: &[u64], k: Option<usize>| k.and_then(|k| r.get(k)).copied().unwrap_or(0);
+        for y in 0..self.height {
+            let rows = [row(y.checked_sub(1)), row(Some(y)), row(Some(y + 1))];
+            for k in 0..self.words {
+                let mut planes = [0u64; 4];
+                for (i, r) in rows.iter().enumerate() {
+                    let (prev, mid, after) = (
+                        word(r, k.checked_sub(1)),
+                        word(r, Some(k)),
+                        word(r, Some(k + 1)),
+                    );
+                    // the neighbors to the west and east of every bit, carrying across words
+                    let west = mid << 1 | prev >> 63;
+                    let east = mid >> 1 | after << 63;
+                    let center = if i == 1 { 0 } else { mid };
+                    for neighbors in [west, center, east] {
+                        // ripple carry of one bit into the counter
+                        let mut carry = neighbors;
+                        for plane in &mut planes {
+                            let overflow = *plane & carry;
+                            *plane ^= carry;
+                            carry = overflow;
+                        }
+                    }
+                }
+                let alive = rows[1][k];
+                let mut cells = alive & match_counts(&planes, rule.survive)
+                    | !alive & match_counts(&planes, rule.birth);
+                if k == self.words - 1 && !self.width.is_multiple_of(64) {
+                    cells &= (1 << (self.width % 64)) - 1;
+                }
+                next.rows[y * self.words + k] = cells;
+            }
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    /// The per-cell reference stepper.
+    fn step_cells(grid: &Grid<bool>, rule: LifeRule) -> Grid<bool> {
+        Grid::from_fn(grid.width(), grid.height(), |pos| {
+            let neighbors = grid.neighbor_positions8(pos).filter(|&n| grid[n]).count();
+            rule.next_state(grid[pos], neighbors)
+        })
+    }
+
+    fn alive_cells(life: &LifeGrid) -> Vec<(i32, i32)> {
+        life.to_grid()
+            .iter()
+            .filter(|(_, &alive)| alive)
+            .map(|(pos, _)| (pos.x, pos.y))
+            .collect()
+    }
+
+    fn with_cells(width: usize, height: usize, cells: &[(i32, i32)]) -> LifeGrid {
+        let mut life = LifeGrid::new(width, height);
+        for &(x, y) in cells {
+            life.set(Vec2i::new(x, y), true);
+        }
+        life
+    }
+
+    #[test]
+    fn blinkers_across_words() {
+        for x in [0, 62, 63, 126] {
+            let life = with_cells(130, 3, &[(x, 1), (x + 1, 1), (x + 2, 1)]);
+            let next = life.step_life();
+            assert_eq!(
+                next,
+                with_cells(130, 3, &[(x + 1, 0), (x + 1, 1), (x + 1, 2)])
+            );
+            assert_eq!(next.step_life(), life);
+        }
+        // cut off by the edge a blinker dies out like any pair
+        let edge = with_cells(64, 3, &[(62, 1), (63, 1)]);
+        assert_eq!(edge.step_life().count_alive(), 0);
+    }
+
+    #[test]
+    fn glider_crosses_words() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let start = 58;
+        let mut life = with_cells(140, 20, &glider.map(|(x, y)| (x + start, y)));
+        for moved in 1..=12 {
+            for _ in 0..4 {
+                life = life.step_life();
+            }
+            let mut expected = glider.map(|(x, y)| (x + start + moved, y + moved));
+            expected.sort_by_key(|&(x, y)| (y, x));
+            assert_eq!(alive_cells(&life), expected);
+        }
+    }
+
+    #[test]
+    fn rule_masks() {
+        assert_eq!(LifeRule::parse("B3/S23"), LifeRule::CONWAY);
+        let highlife = LifeRule::parse("B36/S23");
+        assert_eq!(highlife.birth, 1 << 3 | 1 << 6);
+        let seeds = LifeRule::parse("B2/S");
+        assert_eq!(seeds.survive, 0);
+        // under seeds every cell dies and a lone pair gives birth to two pairs
+        let pair = with_cells(70, 4, &[(63, 1), (64, 1)]);
+        assert_eq!(
+            alive_cells(&pair.step_rule(seeds)),
+            [(63, 0), (64, 0), (63, 2), (64, 2)]
+        );
+        let mut rng = Pcg32::new(305);
+        for rule in [highlife, seeds, LifeRule::parse("B1357/S02468")] {
+            let grid = Grid::from_fn(90, 30, |_| rng.chance(0.3));
+            let (mut life, mut cells) = (LifeGrid::from_grid(&grid), grid);
+            for _ in 0..10 {
+                life = life.step_rule(rule);
+                cells = step_cells(&cells, rule);
+                assert_eq!(life.to_grid(), cells);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid neighbor count '9' in rule \"B39/S23\"")]
+    fn invalid_rule() {
+        LifeRule::parse("B39/S23");
+    }
+
+    #[test]
+    fn matches_cell_stepper() {
+        let mut rng = Pcg32::new(42);
+        let grid = Grid::from_fn(200, 200, |_| rng.chance(0.35));
+        let (mut life, mut cells) = (LifeGrid::from_grid(&grid), grid);
+        for _ in 0..50 {
+            life = life.step_life();
+            cells = step_cells(&cells, LifeRule::CONWAY);
+            assert_eq!(life.to_grid(), cells);
+        }
+        assert_eq!(life.count_alive(), cells.count(&true));
+    }
+}
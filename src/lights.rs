@@ -0,0 +1,95 @@
+use vecm::Vec2i;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightAction {
+    On,
+    Off,
+    Toggle,
+}
+
+/// A command like `turn on 0,0 through 999,999` with both corners inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LightCommand {
+    pub action: LightAction,
+    pub from: Vec2i,
+    pub to: Vec2i,
+}
+impl LightCommand {
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let (action, rest) = [
+            ("turn on ", LightAction::On),
+            ("turn off ", LightAction::Off),
+            ("toggle ", LightAction::Toggle),
+        ]
+        .into_iter()
+        .find_map(|(prefix, action)| Some((action, line.strip_prefix(prefix)?)))
+        .unwrap_or_else(|| panic!("unknown light command {line:?}"));
+        let corner = |s: &str| {
+            let (x, y) = s
+                .trim()
+                .split_once(',')
+                .unwrap_or_else(|| panic!("invalid corner {s:?} in {line:?}"));
+            Vec2i::new(crate::int(x) as i32, crate::int(y) as i32)
+        };
+        let (from, to) = rest
+            .split_once(" through ")
+            .unwrap_or_else(|| panic!("missing 'through' in {line:?}"));
+        Self {
+            action,
+            from: corner(from),
+            to: corner(to),
+        }
+    }
+
+    pub fn parse_all(s: &str) -> Vec<Self> {
+        s.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    use super::*;
+
+    const COMMANDS: &str = "turn on 0,0 through 999,999
+toggle 0,0 through 999,0
+turn off 499,499 through 500,500";
+
+    #[test]
+    fn switch_lights() {
+        let mut lights = Grid::from_nested(vec![vec![false; 1000]; 1000]);
+        let mut brightness = Grid::from_nested(vec![vec![0u32; 1000]; 1000]);
+        for cmd in LightCommand::parse_all(COMMANDS) {
+            lights.apply_rect(cmd.from, cmd.to, |on| {
+                *on = match cmd.action {
+                    LightAction::On => true,
+                    LightAction::Off => false,
+                    LightAction::Toggle => !*on,
+                }
+            });
+            brightness.apply_rect(cmd.from, cmd.to, |b| match cmd.action {
+                LightAction::On => *b += 1,
+                LightAction::Off => *b = b.saturating_sub(1),
+                LightAction::Toggle => *b += 2,
+            });
+        }
+        assert_eq!(lights.rows().flatten().filter(|&&on| on).count(), 998_996);
+        assert_eq!(brightness.rows().flatten().sum::<u32>(), 1_001_996);
+    }
+
+    #[test]
+    fn corners_in_any_order() {
+        let cmd = LightCommand::parse("toggle 3,5 through 1,4");
+        let positions: Vec<Vec2i> = crate::rect::positions_in_rect(cmd.from, cmd.to).collect();
+        assert_eq!(positions.len(), 6);
+        assert_eq!(positions[0], Vec2i::new(1, 4));
+        let mut grid = Grid::from([[0; 3]; 3]);
+        grid.apply_rect(Vec2i::new(5, 5), Vec2i::new(1, 1), |c| *c += 1);
+        assert_eq!(grid, Grid::from([[0, 0, 0], [0, 1, 1], [0, 1, 1]]));
+    }
+}
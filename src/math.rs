@@ -0,0 +1,232 @@
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a / gcd(a, b) * b
+}
+
+/// The least common multiple of all values, 1 for none.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> u64 {
+    values.into_iter().fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m` in `0..m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    assert!(m > 0, "modulus has to be positive");
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp mod m` in `0..m`.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    assert!(m > 0, "modulus has to be positive");
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Solves the system `x = residue (mod modulus)` for all `(residue, modulus)` pairs and
+/// returns `(x, lcm of the moduli)` with `x` in `0..lcm`. Moduli don't have to be coprime,
+/// `None` is returned if the congruences contradict each other.
+pub fn crt(residues: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(r, n) in residues {
+        assert!(n > 0, "modulus has to be positive");
+        let (r, n) = ((r as i128).rem_euclid(n as i128), n as i128);
+        let g = gcd(m as u64, n as u64) as i128;
+        if (r - x) % g != 0 {
+            return None;
+        }
+        // x + m * k = r (mod n)  =>  k = (r - x) / g * (m / g)^-1 (mod n / g)
+        let step = n / g;
+        let inv = mod_inverse((m / g % step) as i64, step as i64).unwrap() as i128;
+        let k = ((r - x) / g).rem_euclid(step) * inv % step;
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+    }
+    let m = i64::try_from(m).expect("combined modulus overflows i64");
+    Some((x as i64, m))
+}
+
+/// The first time `t` at which every `(positions, start, delay)` disc is at position 0 when it's
+/// reached, at `t + delay`. `None` if the discs never line up.
+pub fn first_aligned_time(discs: &[(u64, u64, u64)]) -> Option<u64> {
+    let residues: Vec<(i64, i64)> = discs
+        .iter()
+        .map(|&(positions, start, delay)| {
+            let p = positions as i64;
+            (-((start + delay) as i64 % p), p)
+        })
+        .collect();
+    crt(&residues).map(|(t, _)| t as u64)
+}
+
+/// Same as [`first_aligned_time`] but by sieving: adds the discs one at a time and steps by the
+/// period of the ones aligned so far until the next one is aligned too.
+pub fn first_aligned_time_sieve(discs: &[(u64, u64, u64)]) -> Option<u64> {
+    let (mut t, mut step) = (0, 1);
+    for &(positions, start, delay) in discs {
+        // the alignment repeats after `positions` steps at the latest
+        t = (0..positions)
+            .map(|k| t + k * step)
+            .find(|t| (start + t + delay) % positions == 0)?;
+        step = lcm(step, positions);
+    }
+    Some(t)
+}
+
+/// The numbers [`solve_nxn`] can eliminate over: `f64` for speed or [`crate::Frac`] for exact
+/// results.
+pub trait Field:
+    Copy
+    + PartialEq
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Div<Output = Self>
+{
+    fn zero() -> Self;
+    fn is_zero(&self) -> bool;
+    /// The size used to pick the pivot.
+    fn magnitude(&self) -> f64;
+}
+
+impl Field for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn is_zero(&self) -> bool {
+        self.abs() < 1e-12
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+/// Solves `a * x = b` for a square `a` by Gaussian elimination with partial pivoting, `None`
+/// if `a` is singular.
+pub fn solve_nxn<T: Field>(a: &[Vec<T>], b: &[T]) -> Option<Vec<T>> {
+    let n = b.len();
+    assert!(
+        a.len() == n && a.iter().all(|row| row.len() == n),
+        "expected a {n}x{n} matrix"
+    );
+    let mut m: Vec<Vec<T>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &b)| row.iter().copied().chain([b]).collect())
+        .collect();
+    for col in 0..n {
+        let pivot =
+            (col..n).max_by(|&i, &j| m[i][col].magnitude().total_cmp(&m[j][col].magnitude()))?;
+        if m[pivot][col].is_zero() {
+            return None;
+        }
+        m.swap(col, pivot);
+        let pivot_row = m[col].clone();
+        for (i, row) in m.iter_mut().enumerate() {
+            if i == col || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col] / pivot_row[col];
+            for (cell, &p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *cell = *cell - factor * p;
+            }
+        }
+    }
+    Some((0..n).map(|i| m[i][n] / m[i][i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm_all([2, 3, 4, 5]), 60);
+        assert_eq!(lcm_all([]), 1);
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(7, 20201227 - 2, 20201227) * 7 % 20201227, 1);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+    }
+
+    #[test]
+    fn bus_schedule() {
+        let residues: Vec<(i64, i64)> = "7,13,x,x,59,x,31,19"
+            .split(',')
+            .enumerate()
+            .filter_map(|(i, id)| Some((-(i as i64), id.parse().ok()?)))
+            .collect();
+        assert_eq!(crt(&residues), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn non_coprime() {
+        assert_eq!(crt(&[(2, 6), (5, 9)]), Some((14, 18)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        // moduli near i64::MAX / 2 need 128-bit intermediates
+        let big = (1i64 << 61) - 1;
+        assert_eq!(crt(&[(5, big), (1, 3)]), Some((5 + 2 * big, 3 * big)));
+    }
+
+    #[test]
+    fn aligned_discs() {
+        let discs = [(5, 4, 1), (2, 1, 2)];
+        assert_eq!(first_aligned_time(&discs), Some(5));
+        assert_eq!(first_aligned_time_sieve(&discs), Some(5));
+        let more = [(5, 4, 1), (2, 1, 2), (11, 0, 3)];
+        assert_eq!(first_aligned_time(&more), Some(85));
+        assert_eq!(first_aligned_time_sieve(&more), Some(85));
+        // odd times for the first disc, even ones for the second
+        let never = [(2, 0, 1), (4, 0, 2)];
+        assert_eq!(first_aligned_time(&never), None);
+        assert_eq!(first_aligned_time_sieve(&never), None);
+        assert_eq!(first_aligned_time(&[(6, 1, 1), (4, 0, 2)]), Some(10));
+        assert_eq!(first_aligned_time_sieve(&[(6, 1, 1), (4, 0, 2)]), Some(10));
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A cache for recursive functions. The computation gets the cache passed back in, so it can
+/// recurse through [`Memo::get_or_compute`] without borrowing it twice.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+}
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+        }
+    }
+}
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached value for `key`, or the result of `f` which is cached before returning it.
+    pub fn get_or_compute(&mut self, key: K, f: impl FnOnce(&mut Memo<K, V>) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// The number of cached keys.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+fn call<K: Eq + Hash + Clone, V: Clone>(
+    memo: &mut Memo<K, V>,
+    f: &impl Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    key: K,
+) -> V {
+    memo.get_or_compute(key.clone(), |memo| f(&mut |k| call(memo, f, k), key))
+}
+
+/// Turns a recurrence into a cached function. `f` gets a function to recurse with and the key,
+/// the cache lives as long as the returned function.
+pub fn memoized<K: Eq + Hash + Clone, V: Clone>(
+    f: impl Fn(&mut dyn FnMut(K) -> V, K) -> V,
+) -> impl FnMut(K) -> V {
+    let mut memo = Memo::new();
+    move |key| call(&mut memo, &f, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn fibonacci_once_per_key() {
+        let calls = Cell::new(0);
+        let mut fib = memoized(|fib, n: u64| {
+            calls.set(calls.get() + 1);
+            if n < 2 {
+                n
+            } else {
+                fib(n - 1) + fib(n - 2)
+            }
+        });
+        assert_eq!(fib(90), 2880067194370816120);
+        assert_eq!(calls.get(), 91);
+        assert_eq!(fib(50), 12586269025);
+        assert_eq!(calls.get(), 91);
+
+        fn fib_memo(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+            memo.get_or_compute(n, |memo| {
+                if n < 2 {
+                    n
+                } else {
+                    fib_memo(memo, n - 1) + fib_memo(memo, n - 2)
+                }
+            })
+        }
+        let mut memo = Memo::new();
+        assert_eq!(fib_memo(&mut memo, 30), 832040);
+        assert_eq!(memo.len(), 31);
+        assert_eq!(memo.get(&10), Some(&55));
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    /// The ways to fill in the `?` springs so that the damaged `#` runs match `groups`.
+    fn arrangements(springs: &str, groups: &[usize]) -> u64 {
+        let springs = springs.as_bytes();
+        let mut count = memoized(|count, (i, g): (usize, usize)| {
+            if i >= springs.len() {
+                return (g == groups.len()) as u64;
+            }
+            let mut ways = 0;
+            if springs[i] != b'#' {
+                ways += count((i + 1, g));
+            }
+            if let Some(&len) = groups.get(g) {
+                let end = i + len;
+                let fits = end <= springs.len()
+                    && springs[i..end].iter().all(|&c| c != b'.')
+                    && springs.get(end) != Some(&b'#');
+                if springs[i] != b'.' && fits {
+                    ways += count((end + 1, g + 1));
+                }
+            }
+            ways
+        });
+        count((0, 0))
+    }
+
+    #[test]
+    fn spring_arrangements() {
+        assert_eq!(arrangements("???.###", &[1, 1, 3]), 1);
+        assert_eq!(arrangements(".??..??...?##.", &[1, 1, 3]), 4);
+        assert_eq!(arrangements("?###????????", &[3, 2, 1]), 10);
+        let unfolded = ["?###????????"; 5].join("?");
+        assert_eq!(arrangements(&unfolded, &[3, 2, 1].repeat(5)), 506250);
+    }
+}
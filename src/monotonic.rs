@@ -0,0 +1,130 @@
+/// For every index `i`, the closest `j < i` with `matches(&values[j], &values[i])`. `matches`
+/// has to be transitive for the stack pruning to be correct.
+pub fn prev_indices_by<T>(values: &[T], matches: impl Fn(&T, &T) -> bool) -> Vec<Option<usize>> {
+    let mut stack: Vec<usize> = Vec::new();
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            while stack
+                .last()
+                .is_some_and(|&top| !matches(&values[top], value))
+            {
+                stack.pop();
+            }
+            let found = stack.last().copied();
+            stack.push(i);
+            found
+        })
+        .collect()
+}
+
+/// For every index `i`, the closest `j > i` with `matches(&values[j], &values[i])`.
+pub fn next_indices_by<T>(values: &[T], matches: impl Fn(&T, &T) -> bool) -> Vec<Option<usize>> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut result = vec![None; values.len()];
+    for (i, value) in values.iter().enumerate().rev() {
+        while stack
+            .last()
+            .is_some_and(|&top| !matches(&values[top], value))
+        {
+            stack.pop();
+        }
+        result[i] = stack.last().copied();
+        stack.push(i);
+    }
+    result
+}
+
+/// For every index, the closest later index holding a strictly greater value. Equal values
+/// don't count, so in `[2, 2, 2]` no element has a greater successor. The same strict tie
+/// behavior applies to the other `_greater` and `_smaller` variants.
+pub fn next_greater_indices<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    next_indices_by(values, |other, value| other > value)
+}
+
+pub fn prev_greater_indices<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    prev_indices_by(values, |other, value| other > value)
+}
+
+pub fn next_smaller_indices<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    next_indices_by(values, |other, value| other < value)
+}
+
+pub fn prev_smaller_indices<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    prev_indices_by(values, |other, value| other < value)
+}
+
+/// The area of the largest rectangle fitting under a histogram of bars with width 1.
+pub fn largest_rectangle_in_histogram(heights: &[u64]) -> u64 {
+    let prev = prev_smaller_indices(heights);
+    let next = next_smaller_indices(heights);
+    heights
+        .iter()
+        .enumerate()
+        .map(|(i, &height)| {
+            let left = prev[i].map_or(0, |j| j + 1);
+            let right = next[i].unwrap_or(heights.len());
+            height * (right - left) as u64
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_next(values: &[i32], f: impl Fn(i32, i32) -> bool) -> Vec<Option<usize>> {
+        (0..values.len())
+            .map(|i| (i + 1..values.len()).find(|&j| f(values[j], values[i])))
+            .collect()
+    }
+
+    fn brute_prev(values: &[i32], f: impl Fn(i32, i32) -> bool) -> Vec<Option<usize>> {
+        (0..values.len())
+            .map(|i| (0..i).rev().find(|&j| f(values[j], values[i])))
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let inputs: [&[i32]; 6] = [
+            &[],
+            &[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5],
+            &[2, 2, 2, 2],
+            &[1, 2, 3, 4, 5],
+            &[5, 4, 3, 2, 1],
+            &[1, 3, 3, 2, 3, 1],
+        ];
+        for values in inputs {
+            assert_eq!(
+                next_greater_indices(values),
+                brute_next(values, |a, b| a > b)
+            );
+            assert_eq!(
+                prev_greater_indices(values),
+                brute_prev(values, |a, b| a > b)
+            );
+            assert_eq!(
+                next_smaller_indices(values),
+                brute_next(values, |a, b| a < b)
+            );
+            assert_eq!(
+                prev_smaller_indices(values),
+                brute_prev(values, |a, b| a < b)
+            );
+        }
+        assert_eq!(next_greater_indices(&[2, 2, 2]), [None, None, None]);
+        assert_eq!(next_greater_indices(&[1, 2, 3]), [Some(1), Some(2), None]);
+        assert_eq!(prev_greater_indices(&[3, 2, 1]), [None, Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn histogram() {
+        assert_eq!(largest_rectangle_in_histogram(&[2, 1, 5, 6, 2, 3]), 10);
+        assert_eq!(largest_rectangle_in_histogram(&[3, 3, 3]), 9);
+        assert_eq!(largest_rectangle_in_histogram(&[1, 2, 3, 4, 5]), 9);
+        assert_eq!(largest_rectangle_in_histogram(&[]), 0);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+/// An iterator adapter with arbitrary lookahead. Peeked items are buffered until they are
+/// taken.
+pub struct MultiPeek<I: Iterator> {
+    iter: I,
+    buf: VecDeque<I::Item>,
+}
+
+pub fn multipeek<I: IntoIterator>(it: I) -> MultiPeek<I::IntoIter> {
+    MultiPeek {
+        iter: it.into_iter(),
+        buf: VecDeque::new(),
+    }
+}
+
+impl<I: Iterator> MultiPeek<I> {
+    /// Buffers up to `n` items, returns whether that many are available.
+    fn fill(&mut self, n: usize) -> bool {
+        while self.buf.len() < n {
+            match self.iter.next() {
+                Some(item) => self.buf.push_back(item),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// The item `n` positions ahead, `peek_n(0)` being the next one.
+    pub fn peek_n(&mut self, n: usize) -> Option<&I::Item> {
+        self.fill(n + 1);
+        self.buf.get(n)
+    }
+
+    pub fn peek(&mut self) -> Option<&I::Item> {
+        self.peek_n(0)
+    }
+
+    /// The next `n` items, or fewer if the iterator ends before that.
+    pub fn peek_slice(&mut self, n: usize) -> &[I::Item] {
+        self.fill(n);
+        let n = n.min(self.buf.len());
+        &self.buf.make_contiguous()[..n]
+    }
+
+    pub fn take_if(&mut self, pred: impl FnOnce(&I::Item) -> bool) -> Option<I::Item> {
+        if pred(self.peek()?) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Takes items while `pred` holds. Unlike [`Iterator::take_while`], the first item that
+    /// doesn't match stays in the iterator.
+    pub fn take_while_buffered(&mut self, mut pred: impl FnMut(&I::Item) -> bool) -> Vec<I::Item> {
+        let mut taken = Vec::new();
+        while let Some(item) = self.take_if(&mut pred) {
+            taken.push(item);
+        }
+        taken
+    }
+}
+
+impl<I: Iterator> Iterator for MultiPeek<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.pop_front().or_else(|| self.iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.iter.size_hint();
+        let n = self.buf.len();
+        (lo.saturating_add(n), hi.and_then(|hi| hi.checked_add(n)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::multipeek;
+
+    #[test]
+    fn lookahead_past_end() {
+        let mut it = multipeek([1, 2, 3]);
+        assert_eq!(it.peek_n(2), Some(&3));
+        assert_eq!(it.peek_n(3), None);
+        assert_eq!(it.peek_slice(10), &[1, 2, 3]);
+        assert_eq!(it.collect::<Vec<_>>(), [1, 2, 3]);
+        let mut empty = multipeek(std::iter::empty::<u8>());
+        assert_eq!(empty.peek_slice(2), &[] as &[u8]);
+        assert_eq!(empty.take_if(|_| true), None);
+    }
+
+    #[test]
+    fn interleaved() {
+        let mut it = multipeek("abcdef".chars());
+        assert_eq!(it.peek_n(1), Some(&'b'));
+        assert_eq!(it.next(), Some('a'));
+        assert_eq!(it.peek_slice(3), &['b', 'c', 'd']);
+        assert_eq!(it.take_if(|&c| c == 'x'), None);
+        assert_eq!(it.take_if(|&c| c == 'b'), Some('b'));
+        assert_eq!(it.peek(), Some(&'c'));
+        assert_eq!(it.collect::<String>(), "cdef");
+    }
+
+    #[test]
+    fn take_while_keeps_mismatch() {
+        let s = "123abc";
+        let mut std_iter = s.chars();
+        let std_digits: String = std_iter.by_ref().take_while(char::is_ascii_digit).collect();
+        let mut it = multipeek(s.chars());
+        let digits: String = it
+            .take_while_buffered(char::is_ascii_digit)
+            .into_iter()
+            .collect();
+        assert_eq!(digits, std_digits);
+        // std's version swallowed the 'a'
+        assert_eq!(std_iter.collect::<String>(), "bc");
+        assert_eq!(it.collect::<String>(), "abc");
+    }
+}
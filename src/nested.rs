@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use vecm::Vec2i;
+
+use crate::{grid::DIRS4, Connectivity, Grid};
+
+/// Square grids nested into each other through their center cell, which is never used itself.
+/// Level `l + 1` sits inside the center of level `l`, so the cells around the center of a level
+/// neighbor the whole facing edge of the next level, and the edge cells of a level neighbor the
+/// cells around the center of the previous level. Levels that were never created behave as if
+/// they were filled with `empty`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedGrids<T> {
+    size: usize,
+    empty: T,
+    levels: BTreeMap<i32, Grid<T>>,
+}
+impl<T: Clone + PartialEq> NestedGrids<T> {
+    /// Starts with `grid` as level 0, its size has to be odd so that there is a center cell.
+    pub fn new(grid: Grid<T>, empty: T) -> Self {
+        let size = grid.width();
+        assert!(
+            grid.height() == size && size % 2 == 1 && size >= 3,
+            "nested grids have to be square with an odd size of at least 3, got {}x{}",
+            grid.width(),
+            grid.height()
+        );
+        Self {
+            size,
+            empty,
+            levels: BTreeMap::from([(0, grid)]),
+        }
+    }
+
+    fn center(&self) -> Vec2i {
+        Vec2i::new(self.size as i32 / 2, self.size as i32 / 2)
+    }
+
+    /// The cell at `pos` on `level`, or `default` for levels that don't exist yet.
+    pub fn get<'a>(&'a self, level: i32, pos: Vec2i, default: &'a T) -> &'a T {
+        self.levels.get(&level).map_or(default, |grid| &grid[pos])
+    }
+
+    /// The lowest and highest existing level.
+    pub fn levels(&self) -> (i32, i32) {
+        let first = *self.levels.keys().next().unwrap();
+        let last = *self.levels.keys().next_back().unwrap();
+        (first, last)
+    }
+
+    fn neighbors(&self, level: i32, pos: Vec2i) -> Vec<(i32, Vec2i)> {
+        let center = self.center();
+        let last = self.size as i32 - 1;
+        let mut out = Vec::with_capacity(8);
+        for (dx, dy) in DIRS4 {
+            let next = pos + Vec2i::new(dx, dy);
+            if next == center {
+                // the whole edge of the inner level that faces `pos`
+                out.extend((0..self.size as i32).map(|i| {
+                    let inner = match (dx, dy) {
+                        (1, 0) => Vec2i::new(0, i),
+                        (-1, 0) => Vec2i::new(last, i),
+                        (0, 1) => Vec2i::new(i, 0),
+                        _ => Vec2i::new(i, last),
+                    };
+                    (level + 1, inner)
+                }));
+            } else if (0..=last).contains(&next.x) && (0..=last).contains(&next.y) {
+                out.push((level, next));
+            } else {
+                out.push((level - 1, center + Vec2i::new(dx, dy)));
+            }
+        }
+        out
+    }
+
+    /// The number of neighbors of `pos` on `level` for which `pred` holds, following the
+    /// adjacency through the center and the edges.
+    pub fn neighbor_count_recursive(
+        &self,
+        level: i32,
+        pos: Vec2i,
+        pred: impl Fn(&T) -> bool,
+    ) -> usize {
+        self.neighbors(level, pos)
+            .into_iter()
+            .filter(|&(l, p)| pred(self.get(l, p, &self.empty)))
+            .count()
+    }
+
+    /// Applies `rule(cell, number of alive neighbors)` to every cell of every level at once.
+    /// The levels just outside of the existing ones are evaluated too and kept if any of their
+    /// cells ends up different from `empty`.
+    pub fn step(&mut self, alive: impl Fn(&T) -> bool, rule: impl Fn(&T, usize) -> T) {
+        let (first, last) = self.levels();
+        let center = self.center();
+        let mut next = BTreeMap::new();
+        for level in first - 1..=last + 1 {
+            let grid = Grid::from_fn(self.size, self.size, |pos| {
+                let cell = self.get(level, pos, &self.empty);
+                if pos == center {
+                    return cell.clone();
+                }
+                rule(cell, self.neighbor_count_recursive(level, pos, &alive))
+            });
+            let existing = (first..=last).contains(&level);
+            if existing || grid.values().any(|cell| *cell != self.empty) {
+                next.insert(level, grid);
+            }
+        }
+        self.levels = next;
+    }
+
+    /// The number of cells on all levels for which `pred` holds, ignoring the centers.
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        let center = self.center();
+        self.levels
+            .values()
+            .flat_map(|grid| grid.iter())
+            .filter(|&(pos, cell)| pos != center && pred(cell))
+            .count()
+    }
+}
+
+/// The flat counterpart of [`NestedGrids::step`]: applies `rule(cell, number of alive
+/// orthogonal neighbors)` to every cell at once, cells outside of the grid count as dead.
+pub fn step_flat<T: Clone>(
+    grid: &Grid<T>,
+    alive: impl Fn(&T) -> bool,
+    rule: impl Fn(&T, usize) -> T,
+) -> Grid<T> {
+    Grid::from_fn(grid.width(), grid.height(), |pos| {
+        let count = grid
+            .neighbor_positions(pos, Connectivity::Four)
+            .filter(|&n| alive(&grid[n]))
+            .count();
+        rule(&grid[pos], count)
+    })
+}
+
+/// The sum of `2^i` over the row-major indices `i` of all set cells. Panics for grids of more
+/// than 64 cells, whose ratings don't fit into a `u64`.
+pub fn biodiversity(grid: &Grid<bool>) -> u64 {
+    let cells = grid.width() * grid.height();
+    assert!(cells <= 64, "{cells} cells don't fit into a u64 rating");
+    grid.values()
+        .enumerate()
+        .filter(|&(_, &bug)| bug)
+        .map(|(i, _)| 1 << i)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_cycle;
+
+    const ERIS: &str = "....#
+#..#.
+#..##
+..#..
+#....";
+
+    fn bugs() -> Grid<bool> {
+        Grid::from_nested(
+            ERIS.lines()
+                .map(|l| l.chars().map(|c| c == '#').collect())
+                .collect(),
+        )
+    }
+
+    fn rule(&bug: &bool, neighbors: usize) -> bool {
+        neighbors == 1 || (!bug && neighbors == 2)
+    }
+
+    #[test]
+    fn flat_biodiversity() {
+        let cycle = find_cycle(bugs(), |grid| step_flat(grid, |&b| b, rule));
+        assert_eq!(biodiversity(&cycle.states[cycle.start]), 2129920);
+    }
+
+    #[test]
+    #[should_panic(expected = "65 cells don't fit into a u64 rating")]
+    fn biodiversity_overflow() {
+        assert_eq!(biodiversity(&Grid::new(8, 8, true)), u64::MAX);
+        biodiversity(&Grid::new(5, 13, false));
+    }
+
+    #[test]
+    fn recursive_bugs() {
+        let mut grids = NestedGrids::new(bugs(), false);
+        assert_eq!(
+            grids.neighbor_count_recursive(0, Vec2i::new(3, 3), |&b| b),
+            2
+        );
+        // next to the center, with the empty inner edge
+        assert_eq!(grids.neighbors(0, Vec2i::new(2, 1)).len(), 8);
+        for _ in 0..10 {
+            grids.step(|&b| b, rule);
+        }
+        assert_eq!(grids.count(|&b| b), 99);
+        assert_eq!(grids.levels(), (-5, 5));
+        assert!(!grids.get(6, Vec2i::new(0, 0), &false));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::Grid;
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 6;
+
+/// The letters of the 4x6 font used by the puzzles rendering text, row by row.
+const LETTERS: &[(char, [&str; HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads text rendered in the 4x6 letter font with one column of spacing between letters.
+/// Unknown glyphs are returned as `?`.
+pub fn ocr(grid: &Grid<bool>) -> String {
+    assert_eq!(
+        grid.height(),
+        HEIGHT,
+        "letters have to be {HEIGHT} cells high"
+    );
+    (0..grid.width().div_ceil(WIDTH + 1))
+        .map(|i| {
+            let x0 = i * (WIDTH + 1);
+            LETTERS
+                .iter()
+                .find(|(_, rows)| {
+                    rows.iter().enumerate().all(|(y, row)| {
+                        row.bytes().enumerate().all(|(x, c)| {
+                            let lit = x0 + x < grid.width() && grid[(x0 + x, y)];
+                            lit == (c == b'#')
+                        })
+                    })
+                })
+                .map_or('?', |&(c, _)| c)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    use super::ocr;
+
+    #[test]
+    fn read_letters() {
+        let text = "\
+#..#..###.###.
+#..#...#..#..#
+####...#..#..#
+#..#...#..###.
+#..#...#..#...
+#..#..###.#...";
+        let grid = Grid::from_str_chars(text).map(|c| c == '#');
+        assert_eq!(ocr(&grid), "HIP");
+    }
+}
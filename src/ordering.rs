@@ -0,0 +1,143 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError<T> {
+    /// The observations contradict each other. Contains the items that couldn't be ordered
+    /// because of it, in order of their first appearance.
+    Cycle(Vec<T>),
+    /// Nothing decides which one of the two items comes first.
+    Ambiguous(T, T),
+}
+impl<T: Debug> Display for OrderError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cycle(items) => write!(f, "the observed orders of {items:?} contain a cycle"),
+            Self::Ambiguous(a, b) => write!(f, "the order of {a:?} and {b:?} isn't determined"),
+        }
+    }
+}
+impl<T: Debug> std::error::Error for OrderError<T> {}
+
+/// An order of all items that agrees with every observation, where each observation lists
+/// some items in order. Of the items that could come next, the one that appeared first in the
+/// observations is taken. With `require_unique`, an order that isn't the only possible one is
+/// an [`OrderError::Ambiguous`] error, unless the observations also contain a cycle, which is
+/// reported first.
+pub fn infer_order<T: Eq + Hash + Clone>(
+    observations: &[Vec<T>],
+    require_unique: bool,
+) -> Result<Vec<T>, OrderError<T>> {
+    let mut items: Vec<T> = Vec::new();
+    let mut index: HashMap<T, usize> = HashMap::new();
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for observation in observations {
+        let mut prev = None;
+        for item in observation {
+            let i = *index.entry(item.clone()).or_insert_with(|| {
+                items.push(item.clone());
+                items.len() - 1
+            });
+            if let Some(prev) = prev {
+                edges.insert((prev, i));
+            }
+            prev = Some(i);
+        }
+    }
+    let mut after = vec![Vec::new(); items.len()];
+    let mut before_count = vec![0; items.len()];
+    for &(a, b) in &edges {
+        after[a].push(b);
+        before_count[b] += 1;
+    }
+    let mut ready: BTreeSet<usize> = (0..items.len()).filter(|&i| before_count[i] == 0).collect();
+    let mut order = Vec::with_capacity(items.len());
+    let mut ambiguous = None;
+    while let Some(i) = ready.pop_first() {
+        if let Some(&other) = ready.first() {
+            ambiguous.get_or_insert((i, other));
+        }
+        order.push(i);
+        for &next in &after[i] {
+            before_count[next] -= 1;
+            if before_count[next] == 0 {
+                ready.insert(next);
+            }
+        }
+    }
+    if order.len() < items.len() {
+        let left = (0..items.len()).filter(|&i| before_count[i] > 0);
+        return Err(OrderError::Cycle(left.map(|i| items[i].clone()).collect()));
+    }
+    if let Some((a, b)) = ambiguous.filter(|_| require_unique) {
+        return Err(OrderError::Ambiguous(items[a].clone(), items[b].clone()));
+    }
+    Ok(order.into_iter().map(|i| items[i].clone()).collect())
+}
+
+/// Whether some order agrees with all observations.
+pub fn is_consistent<T: Eq + Hash + Clone>(observations: &[Vec<T>]) -> bool {
+    infer_order(observations, false).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(observations: &[&str]) -> Vec<Vec<char>> {
+        observations.iter().map(|s| s.chars().collect()).collect()
+    }
+
+    #[test]
+    fn unique_order() {
+        // login attempts, each revealing three digits of the passcode in order
+        let observations = chars(&["316", "629", "731", "290", "160"]);
+        let order: String = infer_order(&observations, true)
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(order, "7316290");
+        assert!(is_consistent(&observations));
+    }
+
+    #[test]
+    fn ambiguous_order() {
+        let observations = chars(&["abd", "acd"]);
+        assert_eq!(
+            infer_order(&observations, true),
+            Err(OrderError::Ambiguous('b', 'c'))
+        );
+        // without the flag, the earlier seen item wins the tie every time
+        for _ in 0..5 {
+            assert_eq!(
+                infer_order(&observations, false),
+                Ok(vec!['a', 'b', 'c', 'd'])
+            );
+        }
+        let reversed = chars(&["acd", "abd"]);
+        assert_eq!(infer_order(&reversed, false), Ok(vec!['a', 'c', 'b', 'd']));
+        assert_eq!(infer_order::<char>(&[], true), Ok(vec![]));
+    }
+
+    #[test]
+    fn contradiction() {
+        let observations = chars(&["xab", "bc", "ca"]);
+        let err = infer_order(&observations, false).unwrap_err();
+        assert_eq!(err, OrderError::Cycle(vec!['a', 'b', 'c']));
+        assert_eq!(
+            err.to_string(),
+            "the observed orders of ['a', 'b', 'c'] contain a cycle"
+        );
+        assert!(!is_consistent(&observations));
+        assert!(!is_consistent(&chars(&["aa"])));
+        // the cycle wins over an ambiguity that comes before it
+        let observations = chars(&["ab", "ac", "de", "ed"]);
+        assert_eq!(
+            infer_order(&observations, true),
+            Err(OrderError::Cycle(vec!['d', 'e']))
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    ops::Range,
+    panic,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// The largest chunk handed to a thread at once, so huge search ranges still short-circuit
+/// quickly.
+const MAX_CHUNK: u64 = 1 << 16;
+
+fn thread_count(threads: usize) -> usize {
+    if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    }
+}
+
+/// Splits `range` into consecutive chunks and runs `work` on them from `threads` threads, `0`
+/// meaning one per available core. Returns the result of the first chunk, in range order,
+/// for which `work` returns `Some`, so the outcome doesn't depend on scheduling. Once a result
+/// is found no chunks after it are started.
+pub fn parallel_chunks<R: Send>(
+    range: Range<u64>,
+    threads: usize,
+    work: impl Fn(Range<u64>) -> Option<R> + Sync,
+) -> Option<R> {
+    let threads = thread_count(threads);
+    let len = range.end.saturating_sub(range.start);
+    let chunk_len = len.div_ceil(threads as u64 * 8).clamp(1, MAX_CHUNK);
+    let chunk_count = len.div_ceil(chunk_len);
+    let next_chunk = AtomicU64::new(0);
+    // the smallest chunk index that found a result, chunks after it are skipped
+    let found = AtomicU64::new(u64::MAX);
+    let best: Mutex<Option<(u64, R)>> = Mutex::new(None);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                if chunk >= chunk_count || chunk > found.load(Ordering::Relaxed) {
+                    break;
+                }
+                let start = range.start + chunk * chunk_len;
+                let end = start.saturating_add(chunk_len).min(range.end);
+                if let Some(result) = work(start..end) {
+                    found.fetch_min(chunk, Ordering::Relaxed);
+                    let mut best = best.lock().unwrap();
+                    if best.as_ref().is_none_or(|&(other, _)| chunk < other) {
+                        *best = Some((chunk, result));
+                    }
+                }
+            });
+        }
+    });
+    best.into_inner().unwrap().map(|(_, result)| result)
+}
+
+/// Maps every value of `range` and combines the results with `reduce`, splitting the range
+/// into one contiguous part per thread, with no more threads than values. Parts are combined
+/// in range order, so `reduce` only has to be associative. Returns `None` for an empty range.
+pub fn parallel_map_reduce<R: Send>(
+    range: Range<u64>,
+    threads: usize,
+    map: impl Fn(u64) -> R + Sync,
+    reduce: impl Fn(R, R) -> R + Sync,
+) -> Option<R> {
+    let len = range.end.saturating_sub(range.start);
+    let threads = (thread_count(threads) as u64).min(len.max(1));
+    let part_len = len.div_ceil(threads).max(1);
+    let (map, reduce) = (&map, &reduce);
+    let parts: Vec<Option<R>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let start = range.start.saturating_add(i * part_len).min(range.end);
+                let end = start.saturating_add(part_len).min(range.end);
+                scope.spawn(move || (start..end).map(map).reduce(reduce))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    parts.into_iter().flatten().reduce(reduce)
+}
+
+/// Maps every item with its index from `threads` threads, `0` meaning one per available core.
+/// Threads take the next unmapped item whenever they are done, and the results come back in
+/// item order however long each one takes. If `f` panics, the remaining items are skipped and
+/// the panic is resumed once every thread has finished.
+pub fn par_map_indexed<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(usize, &T) -> R + Sync,
+) -> Vec<R> {
+    let threads = thread_count(threads).min(items.len().max(1));
+    let next_item = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let joined: Vec<thread::Result<Vec<(usize, R)>>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    // marks the map as failed if `f` unwinds out of this thread
+                    struct Guard<'a>(&'a AtomicBool);
+                    impl Drop for Guard<'_> {
+                        fn drop(&mut self) {
+                            if thread::panicking() {
+                                self.0.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                    let _guard = Guard(&failed);
+                    let mut done = Vec::new();
+                    while !failed.load(Ordering::Relaxed) {
+                        let i = next_item.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else { break };
+                        done.push((i, f(i, item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join()).collect()
+    });
+    let mut results: Vec<Option<R>> = (0..items.len()).map(|_| None).collect();
+    for done in joined {
+        match done {
+            Ok(done) => {
+                for (i, result) in done {
+                    results[i] = Some(result);
+                }
+            }
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+/// [`par_map_indexed`] over the lines of `input`, for days where every line is an expensive
+/// independent computation.
+pub fn par_map_lines<R: Send>(input: &str, threads: usize, f: impl Fn(&str) -> R + Sync) -> Vec<R> {
+    let lines: Vec<&str> = input.lines().collect();
+    par_map_indexed(&lines, threads, |_, line| f(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    fn is_hit(x: u64) -> bool {
+        x.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 50 == 0
+    }
+
+    #[test]
+    fn first_hit_is_deterministic() {
+        let sequential = (0..1_000_000).find(|&x| is_hit(x));
+        assert!(sequential.is_some());
+        for threads in [1, 2, 3, 8] {
+            for _ in 0..5 {
+                let found = parallel_chunks(0..1_000_000, threads, |chunk| {
+                    chunk.into_iter().find(|&x| is_hit(x))
+                });
+                assert_eq!(found, sequential);
+            }
+        }
+        let none = parallel_chunks(0..1000, 4, |chunk| chunk.into_iter().find(|&x| x > 5000));
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn short_circuits() {
+        let chunks = AtomicU64::new(0);
+        let found = parallel_chunks(10..u64::MAX, 4, |chunk| {
+            chunks.fetch_add(1, Ordering::Relaxed);
+            chunk.into_iter().find(|&x| x >= 300_000)
+        });
+        assert_eq!(found, Some(300_000));
+        assert!(chunks.load(Ordering::Relaxed) < 100);
+    }
+
+    #[test]
+    fn map_reduce_matches_sequential() {
+        let sequential: u64 = (0..10_007u64).map(|x| x * x % 1_000).sum();
+        for threads in [1, 4, 7, 64] {
+            let sum = parallel_map_reduce(0..10_007, threads, |x| x * x % 1_000, |a, b| a + b);
+            assert_eq!(sum, Some(sequential));
+        }
+        // combining in range order keeps non-commutative reductions intact
+        let digits = parallel_map_reduce(0..10, 3, |x| x.to_string(), |a, b| a + &b);
+        assert_eq!(digits.as_deref(), Some("0123456789"));
+        assert_eq!(parallel_map_reduce(5..5, 3, |x| x, |a, b| a + b), None);
+        // more threads than values only spawns one per value
+        assert_eq!(
+            parallel_map_reduce(0..3, 1_000, |x| x, |a, b| a + b),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn map_keeps_item_order() {
+        // the first lines take the longest, so they finish last
+        let input: String = (0..8).map(|i| format!("{i}\n")).collect();
+        let mapped = par_map_lines(&input, 8, |line| {
+            let i: u64 = line.parse().unwrap();
+            thread::sleep(Duration::from_millis((8 - i) * 15));
+            i * 10
+        });
+        assert_eq!(mapped, [0, 10, 20, 30, 40, 50, 60, 70]);
+
+        let items: Vec<u64> = (0..1_000).map(|x| x * 7 % 13).collect();
+        let sequential: Vec<u64> = items
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| i as u64 * x)
+            .collect();
+        for threads in [0, 1, 3, 64] {
+            let mapped = par_map_indexed(&items, threads, |i, &x| i as u64 * x);
+            assert_eq!(mapped, sequential);
+        }
+        assert!(par_map_indexed(&[] as &[u8], 4, |_, &x| x).is_empty());
+        assert!(par_map_lines("", 0, str::len).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "bad line 13")]
+    fn map_propagates_panics() {
+        let input: String = (0..100).map(|i| format!("{i}\n")).collect();
+        par_map_lines(&input, 4, |line| {
+            assert_ne!(line, "13", "bad line {line}");
+            line.len()
+        });
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+struct Dir {
+    name: String,
+    parent: Option<usize>,
+    children: HashMap<String, usize>,
+    file_size: u64,
+    listed: bool,
+}
+
+/// A directory tree built by walking it with `cd`-like operations. Only file sizes are kept.
+#[derive(Debug, Clone)]
+pub struct PathTree {
+    dirs: Vec<Dir>,
+    cwd: usize,
+}
+impl Default for PathTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl PathTree {
+    /// A tree containing only the root, which is also the current directory.
+    pub fn new() -> Self {
+        Self {
+            dirs: vec![Dir {
+                name: String::new(),
+                parent: None,
+                children: HashMap::new(),
+                file_size: 0,
+                listed: false,
+            }],
+            cwd: 0,
+        }
+    }
+
+    /// Enters the subdirectory `name`, creating it if needed.
+    pub fn push_dir(&mut self, name: &str) {
+        let next = self.dirs.len();
+        let child = *self.dirs[self.cwd]
+            .children
+            .entry(name.to_owned())
+            .or_insert(next);
+        if child == next {
+            self.dirs.push(Dir {
+                name: name.to_owned(),
+                parent: Some(self.cwd),
+                children: HashMap::new(),
+                file_size: 0,
+                listed: false,
+            });
+        }
+        self.cwd = child;
+    }
+
+    /// Goes to the parent directory, returns false at the root.
+    pub fn pop_dir(&mut self) -> bool {
+        match self.dirs[self.cwd].parent {
+            Some(parent) => {
+                self.cwd = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn go_to_root(&mut self) {
+        self.cwd = 0;
+    }
+
+    pub fn add_file(&mut self, size: u64) {
+        self.dirs[self.cwd].file_size += size;
+    }
+
+    fn path(&self, mut dir: usize) -> String {
+        if dir == 0 {
+            return "/".to_owned();
+        }
+        let mut parts = Vec::new();
+        while let Some(parent) = self.dirs[dir].parent {
+            parts.push(self.dirs[dir].name.as_str());
+            dir = parent;
+        }
+        parts.iter().rev().map(|p| format!("/{p}")).collect()
+    }
+
+    /// The total size of every directory including its subdirectories, by full path, in the
+    /// order the directories were created. Children are always created after their parent, so
+    /// one reverse pass accumulates all sizes.
+    pub fn dir_sizes(&self) -> Vec<(String, u64)> {
+        let mut totals: Vec<u64> = self.dirs.iter().map(|d| d.file_size).collect();
+        for i in (1..self.dirs.len()).rev() {
+            let parent = self.dirs[i].parent.unwrap();
+            totals[parent] += totals[i];
+        }
+        totals
+            .into_iter()
+            .enumerate()
+            .map(|(i, total)| (self.path(i), total))
+            .collect()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.dir_sizes()[0].1
+    }
+
+    /// The size of the smallest directory with a total size of at least `needed`.
+    pub fn smallest_dir_at_least(&self, needed: u64) -> Option<u64> {
+        self.dir_sizes()
+            .into_iter()
+            .map(|(_, size)| size)
+            .filter(|&size| size >= needed)
+            .min()
+    }
+
+    /// Replays a terminal log of `$ cd` and `$ ls` commands. Directories listed twice only
+    /// count their files once.
+    pub fn parse_terminal_log(s: &str) -> Self {
+        let mut tree = Self::new();
+        let mut counting = false;
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(command) = line.strip_prefix("$ ") {
+                counting = false;
+                match command.split_once(' ') {
+                    Some(("cd", "/")) => tree.go_to_root(),
+                    Some(("cd", "..")) => {
+                        tree.pop_dir();
+                    }
+                    Some(("cd", name)) => tree.push_dir(name),
+                    None if command == "ls" => {
+                        let dir = &mut tree.dirs[tree.cwd];
+                        counting = !dir.listed;
+                        dir.listed = true;
+                    }
+                    _ => panic!("unknown command {line:?}"),
+                }
+            } else if line.starts_with("dir ") {
+                continue;
+            } else {
+                let (size, _) = line
+                    .split_once(' ')
+                    .unwrap_or_else(|| panic!("invalid listing {line:?}"));
+                let size = size
+                    .parse()
+                    .unwrap_or_else(|_| panic!("invalid file size in {line:?}"));
+                if counting {
+                    tree.add_file(size);
+                }
+            }
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn no_space_left() {
+        let tree = PathTree::parse_terminal_log(LOG);
+        let sizes: HashMap<String, u64> = tree.dir_sizes().into_iter().collect();
+        assert_eq!(sizes["/a/e"], 584);
+        assert_eq!(sizes["/a"], 94853);
+        assert_eq!(sizes["/d"], 24933642);
+        assert_eq!(sizes["/"], 48381165);
+        let small: u64 = sizes.values().filter(|&&s| s <= 100_000).sum();
+        assert_eq!(small, 95437);
+        let needed = 30_000_000 - (70_000_000 - tree.total_size());
+        assert_eq!(tree.smallest_dir_at_least(needed), Some(24933642));
+    }
+
+    #[test]
+    fn repeated_listing() {
+        let log = format!("{LOG}\n$ cd /\n$ ls\n14848514 b.txt\n8504156 c.dat");
+        let tree = PathTree::parse_terminal_log(&log);
+        assert_eq!(tree.total_size(), 48381165);
+        let mut manual = PathTree::new();
+        manual.push_dir("x");
+        manual.add_file(5);
+        assert!(manual.pop_dir());
+        assert!(!manual.pop_dir());
+        manual.push_dir("x");
+        manual.add_file(1);
+        assert_eq!(manual.dir_sizes(), [("/".into(), 6), ("/x".into(), 6)]);
+    }
+}
@@ -0,0 +1,257 @@
+/// A permutation of `0..n` acting on positions: applying it to a slice puts the element at
+/// index `map[i]` at index `i`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    map: Vec<usize>,
+}
+impl Permutation {
+    pub fn identity(n: usize) -> Self {
+        Self {
+            map: (0..n).collect(),
+        }
+    }
+
+    /// Panics if `map` isn't a permutation of `0..map.len()`.
+    pub fn from_mapping(map: Vec<usize>) -> Self {
+        let mut seen = vec![false; map.len()];
+        for &i in &map {
+            assert!(
+                i < map.len() && !std::mem::replace(&mut seen[i], true),
+                "{map:?} isn't a permutation"
+            );
+        }
+        Self { map }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> usize {
+        self.map[i]
+    }
+
+    pub fn mapping(&self) -> &[usize] {
+        &self.map
+    }
+
+    /// The permutation applying `other` first and then `self`.
+    #[must_use]
+    pub fn compose(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len(), "permutation sizes differ");
+        Self {
+            map: self.map.iter().map(|&i| other.map[i]).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let mut map = vec![0; self.len()];
+        for (i, &j) in self.map.iter().enumerate() {
+            map[j] = i;
+        }
+        Self { map }
+    }
+
+    /// Applies the permutation `n` times, in linear time using the cycle decomposition.
+    #[must_use]
+    pub fn pow(&self, n: u64) -> Self {
+        let mut map = vec![0; self.len()];
+        for cycle in self.cycles() {
+            let shift = (n % cycle.len() as u64) as usize;
+            for (k, &i) in cycle.iter().enumerate() {
+                map[i] = cycle[(k + shift) % cycle.len()];
+            }
+        }
+        Self { map }
+    }
+
+    pub fn apply_to_slice<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        assert_eq!(
+            self.len(),
+            items.len(),
+            "permutation and slice sizes differ"
+        );
+        self.map.iter().map(|&i| items[i].clone()).collect()
+    }
+
+    /// All cycles including fixed points, each following `i -> get(i)` from its smallest
+    /// element.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.len() {
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                cycle.push(i);
+                i = self.map[i];
+            }
+            if !cycle.is_empty() {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DanceMove {
+    /// Moves the last `n` elements to the front.
+    Spin(usize),
+    /// Swaps the elements at two positions.
+    Exchange(usize, usize),
+    /// Swaps two labels wherever they are.
+    Partner(usize, usize),
+}
+impl DanceMove {
+    /// Parses comma separated moves like `s1,x3/4,pe/b`, where partners are lowercase letters.
+    pub fn parse_all(s: &str) -> Vec<Self> {
+        let pair = |s: &str, f: &dyn Fn(&str) -> usize| {
+            let (a, b) = s
+                .split_once('/')
+                .unwrap_or_else(|| panic!("missing '/' in dance move {s:?}"));
+            (f(a), f(b))
+        };
+        let number = |s: &str| -> usize { s.parse().expect("invalid position in dance move") };
+        let label = |s: &str| (s.as_bytes()[0] - b'a') as usize;
+        s.trim()
+            .split(',')
+            .map(|m| match m.split_at(1) {
+                ("s", n) => Self::Spin(number(n)),
+                ("x", rest) => {
+                    let (a, b) = pair(rest, &number);
+                    Self::Exchange(a, b)
+                }
+                ("p", rest) => {
+                    let (a, b) = pair(rest, &label);
+                    Self::Partner(a, b)
+                }
+                _ => panic!("invalid dance move {m:?}"),
+            })
+            .collect()
+    }
+}
+
+/// A sequence of dance moves split into the positional part (spins and exchanges) and the
+/// relabeling part (partner swaps). Both commute, so repeating the dance is just repeating
+/// both parts independently.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dance {
+    pub positions: Permutation,
+    /// Maps every label to the label it is replaced by.
+    pub labels: Permutation,
+}
+impl Dance {
+    pub fn from_moves(n: usize, moves: &[DanceMove]) -> Self {
+        let mut positions: Vec<usize> = (0..n).collect();
+        let mut labels: Vec<usize> = (0..n).collect();
+        for &m in moves {
+            match m {
+                DanceMove::Spin(k) => positions.rotate_right(k % n),
+                DanceMove::Exchange(a, b) => positions.swap(a, b),
+                DanceMove::Partner(a, b) => {
+                    for label in &mut labels {
+                        if *label == a {
+                            *label = b;
+                        } else if *label == b {
+                            *label = a;
+                        }
+                    }
+                }
+            }
+        }
+        Self {
+            positions: Permutation::from_mapping(positions),
+            labels: Permutation::from_mapping(labels),
+        }
+    }
+
+    #[must_use]
+    pub fn pow(&self, n: u64) -> Self {
+        Self {
+            positions: self.positions.pow(n),
+            labels: self.labels.pow(n),
+        }
+    }
+
+    /// The order of the labels `0..n` after the dance.
+    pub fn apply(&self) -> Vec<usize> {
+        let order = self
+            .positions
+            .apply_to_slice(&(0..self.positions.len()).collect::<Vec<_>>());
+        order
+            .into_iter()
+            .map(|label| self.labels.get(label))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(labels: &[usize]) -> String {
+        labels.iter().map(|&l| (b'a' + l as u8) as char).collect()
+    }
+
+    #[test]
+    fn sample_dance() {
+        let moves = DanceMove::parse_all("s1,x3/4,pe/b");
+        let dance = Dance::from_moves(5, &moves);
+        assert_eq!(letters(&dance.apply()), "baedc");
+        assert_eq!(letters(&dance.pow(2).apply()), "ceadb");
+
+        // brute force: the dance repeats the start order after some rounds
+        let mut order: Vec<usize> = (0..5).collect();
+        let mut period = 0;
+        loop {
+            let positions = dance.positions.apply_to_slice(&order);
+            order = positions.into_iter().map(|l| dance.labels.get(l)).collect();
+            period += 1;
+            if order == (0..5).collect::<Vec<_>>() {
+                break;
+            }
+        }
+        let billion = 1_000_000_000;
+        assert_eq!(
+            dance.pow(billion).apply(),
+            dance.pow(billion % period).apply()
+        );
+    }
+
+    #[test]
+    fn group_properties() {
+        let p = Permutation::from_mapping(vec![2, 0, 1, 4, 3, 5]);
+        let id = Permutation::identity(6);
+        assert_eq!(p.compose(&p.inverse()), id);
+        assert_eq!(p.pow(6), id);
+        assert_eq!(p.pow(5), p.inverse());
+        assert_eq!(p.pow(2), p.compose(&p));
+        assert_eq!(p.cycles(), [vec![0, 2, 1], vec![3, 4], vec![5]]);
+        let mut rebuilt = vec![0; 6];
+        for cycle in p.cycles() {
+            for (k, &i) in cycle.iter().enumerate() {
+                rebuilt[i] = cycle[(k + 1) % cycle.len()];
+            }
+        }
+        assert_eq!(Permutation::from_mapping(rebuilt), p);
+        let q = Permutation::from_mapping(vec![1, 2, 3, 4, 5, 0]);
+        let items = ['a', 'b', 'c', 'd', 'e', 'f'];
+        assert_eq!(
+            p.compose(&q).apply_to_slice(&items),
+            p.apply_to_slice(&q.apply_to_slice(&items))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't a permutation")]
+    fn invalid_mapping() {
+        Permutation::from_mapping(vec![0, 0, 1]);
+    }
+}
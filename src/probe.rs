@@ -0,0 +1,78 @@
+use vecm::Vec2i;
+
+use crate::Rect;
+
+/// Fires a probe with the initial velocity, applying drag towards zero on x and gravity on y
+/// after each step. Returns the highest y reached if the probe is inside the target after any
+/// step.
+pub fn simulate_probe(vel: Vec2i, target: Rect) -> Option<i32> {
+    let (mut pos, mut vel) = (Vec2i::new(0, 0), vel);
+    let mut max_y = 0;
+    loop {
+        pos += vel;
+        vel.x -= vel.x.signum();
+        vel.y -= 1;
+        max_y = max_y.max(pos.y);
+        if target.contains(pos) {
+            return Some(max_y);
+        }
+        let below = vel.y < 0 && pos.y < target.min.y;
+        let x_stuck = vel.x == 0 && !(target.min.x..=target.max.x).contains(&pos.x);
+        let x_past = (vel.x > 0 && pos.x > target.max.x) || (vel.x < 0 && pos.x < target.min.x);
+        if below || x_stuck || x_past {
+            return None;
+        }
+    }
+}
+
+/// Tries every initial velocity that could possibly hit the target and returns the best max
+/// height and the number of hitting velocities. The x range covers zero through the far side
+/// of the target (anything faster overshoots after one step) and the y range is bounded by the
+/// target's distance from the origin, since a probe falling back down passes y = 0 with the
+/// negated initial velocity. `None` if no velocity hits, which only happens for an empty
+/// target since any target cell can be hit in a single step.
+pub fn count_hitting_velocities(target: Rect) -> Option<(i32, usize)> {
+    let x_range = target.min.x.min(0)..=target.max.x.max(0);
+    let y_limit = target.min.y.abs().max(target.max.y.abs());
+    let y_range = target.min.y.min(0)..=y_limit;
+    let mut best = None;
+    let mut count = 0;
+    for vx in x_range {
+        for vy in y_range.clone() {
+            if let Some(height) = simulate_probe(Vec2i::new(vx, vy), target) {
+                best = best.max(Some(height));
+                count += 1;
+            }
+        }
+    }
+    best.map(|best| (best, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::*;
+
+    #[test]
+    fn trick_shot() {
+        let target = Rect::new(Vec2i::new(20, -10), Vec2i::new(30, -5));
+        assert_eq!(simulate_probe(Vec2i::new(6, 9), target), Some(45));
+        assert_eq!(simulate_probe(Vec2i::new(17, -4), target), None);
+        assert_eq!(count_hitting_velocities(target), Some((45, 112)));
+    }
+
+    #[test]
+    fn unhittable_target() {
+        let empty = Rect {
+            min: Vec2i::new(3, -2),
+            max: Vec2i::new(2, -4),
+        };
+        assert_eq!(count_hitting_velocities(empty), None);
+        let cell = Rect::new(Vec2i::new(-7, 4), Vec2i::new(-7, 4));
+        assert_eq!(
+            count_hitting_velocities(cell).map(|(_, n)| n > 0),
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+
+/// Sorts by descending key, computing the key once per element. Elements with equal keys keep
+/// their relative order.
+pub fn sort_by_cached_key_desc<T, K: Ord>(v: &mut [T], mut key: impl FnMut(&T) -> K) {
+    v.sort_by_cached_key(|x| Reverse(key(x)));
+}
+
+/// Sorted keys with the index of their element, ties ordered by index.
+fn sorted_keys<T, K: Ord>(
+    it: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> Vec<(K, usize)> {
+    let mut keys: Vec<(K, usize)> = it
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| (key(&x), i))
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// The 1-based rank of every element in ascending key order. Equal keys share the smallest rank
+/// of their group and the following ranks are skipped ("1224" ranking), so ranks only depend on
+/// the keys and not on the input order.
+pub fn rank_by_key<T, K: Ord>(
+    it: impl IntoIterator<Item = T>,
+    key: impl FnMut(&T) -> K,
+) -> Vec<usize> {
+    let keys = sorted_keys(it, key);
+    let mut ranks = vec![0; keys.len()];
+    for (pos, (k, i)) in keys.iter().enumerate() {
+        ranks[*i] = if pos > 0 && keys[pos - 1].0 == *k {
+            ranks[keys[pos - 1].1]
+        } else {
+            pos + 1
+        };
+    }
+    ranks
+}
+
+/// Like [`rank_by_key`] but without gaps after ties ("1223" ranking).
+pub fn dense_rank_by_key<T, K: Ord>(
+    it: impl IntoIterator<Item = T>,
+    key: impl FnMut(&T) -> K,
+) -> Vec<usize> {
+    let keys = sorted_keys(it, key);
+    let mut ranks = vec![0; keys.len()];
+    let mut rank = 0;
+    for (pos, (k, i)) in keys.iter().enumerate() {
+        if pos == 0 || keys[pos - 1].0 != *k {
+            rank += 1;
+        }
+        ranks[*i] = rank;
+    }
+    ranks
+}
+
+/// Sums `rank * bid` over all `(rank, bid)` pairs.
+pub fn total_winnings(ranked: impl IntoIterator<Item = (usize, i64)>) -> i64 {
+    ranked
+        .into_iter()
+        .map(|(rank, bid)| rank as i64 * bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    const HANDS: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    fn hand_key(hand: &str, jokers: bool) -> (Vec<usize>, Vec<usize>) {
+        let order = if jokers {
+            "J23456789TQKA"
+        } else {
+            "23456789TJQKA"
+        };
+        let values: Vec<usize> = hand.chars().map(|c| order.find(c).unwrap()).collect();
+        let mut counts = [0; 13];
+        let mut wild = 0;
+        for c in hand.chars() {
+            if jokers && c == 'J' {
+                wild += 1;
+            } else {
+                counts[order.find(c).unwrap()] += 1;
+            }
+        }
+        let mut groups: Vec<usize> = counts.into_iter().filter(|&c| c > 0).collect();
+        sort_by_cached_key_desc(&mut groups, |&c| c);
+        if groups.is_empty() {
+            groups.push(0);
+        }
+        groups[0] += wild;
+        (groups, values)
+    }
+
+    fn winnings(jokers: bool) -> i64 {
+        let hands: Vec<(&str, i64)> = HANDS
+            .lines()
+            .map(|l| {
+                let (hand, bid) = l.split_once(' ').unwrap();
+                (hand, bid.parse().unwrap())
+            })
+            .collect();
+        let ranks = rank_by_key(&hands, |(hand, _)| hand_key(hand, jokers));
+        total_winnings(ranks.into_iter().zip(hands.iter().map(|&(_, bid)| bid)))
+    }
+
+    #[test]
+    fn camel_cards() {
+        assert_eq!(winnings(false), 6440);
+        assert_eq!(winnings(true), 5905);
+    }
+
+    #[test]
+    fn tie_handling() {
+        let keys = [30, 10, 20, 10, 30, 40];
+        assert_eq!(rank_by_key(keys, |&k| k), [4, 1, 3, 1, 4, 6]);
+        assert_eq!(dense_rank_by_key(keys, |&k| k), [3, 1, 2, 1, 3, 4]);
+        let mut v = vec![(1, 'a'), (3, 'b'), (1, 'c'), (3, 'd')];
+        sort_by_cached_key_desc(&mut v, |&(k, _)| k);
+        assert_eq!(v, [(3, 'b'), (3, 'd'), (1, 'a'), (1, 'c')]);
+        assert!(rank_by_key(Vec::<u8>::new(), |&k| k).is_empty());
+    }
+
+    #[test]
+    fn ranks_ignore_input_order() {
+        let mut rng = Pcg32::new(5);
+        for _ in 0..100 {
+            let n = rng.index(20) + 1;
+            let mut items: Vec<(u32, usize)> = (0..n).map(|id| (rng.below(5), id)).collect();
+            let rank_of = |items: &[(u32, usize)], dense: bool| {
+                let ranks = if dense {
+                    dense_rank_by_key(items, |(k, _)| *k)
+                } else {
+                    rank_by_key(items, |(k, _)| *k)
+                };
+                let mut by_id = vec![0; items.len()];
+                for (&(_, id), rank) in items.iter().zip(ranks) {
+                    by_id[id] = rank;
+                }
+                by_id
+            };
+            let (expected, expected_dense) = (rank_of(&items, false), rank_of(&items, true));
+            for (i, &(k, id)) in items.iter().enumerate() {
+                let smaller = items.iter().filter(|&&(other, _)| other < k).count();
+                assert_eq!(expected[id], smaller + 1, "item {i}");
+            }
+            rng.shuffle(&mut items);
+            assert_eq!(rank_of(&items, false), expected);
+            assert_eq!(rank_of(&items, true), expected_dense);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+/// The growing scoreboard of the two-elf recipe puzzle. Each round both elves' current scores
+/// are summed, the digits of the sum are appended and each elf moves forward by one plus its
+/// current score.
+struct Scoreboard {
+    scores: Vec<u8>,
+    elves: [usize; 2],
+}
+impl Scoreboard {
+    fn new() -> Self {
+        Self {
+            scores: vec![3, 7],
+            elves: [0, 1],
+        }
+    }
+
+    /// Runs one round and returns the number of appended digits (1 or 2).
+    fn step(&mut self) -> usize {
+        let [a, b] = self.elves.map(|elf| self.scores[elf]);
+        let sum = a + b;
+        let appended = if sum >= 10 {
+            self.scores.extend([1, sum - 10]);
+            2
+        } else {
+            self.scores.push(sum);
+            1
+        };
+        let len = self.scores.len();
+        for elf in &mut self.elves {
+            *elf = (*elf + 1 + self.scores[*elf] as usize) % len;
+        }
+        appended
+    }
+}
+
+/// The `count` scores following the first `skip` recipes, as a string of digits.
+pub fn recipe_scores_after(skip: usize, count: usize) -> String {
+    let mut board = Scoreboard::new();
+    board.scores.reserve(skip + count + 1);
+    while board.scores.len() < skip + count {
+        board.step();
+    }
+    board.scores[skip..skip + count]
+        .iter()
+        .map(|&d| (b'0' + d) as char)
+        .collect()
+}
+
+/// The number of recipes to the left of the first occurrence of the digit `pattern`.
+pub fn recipes_before_pattern(pattern: &[u8]) -> usize {
+    assert!(!pattern.is_empty(), "pattern can't be empty");
+    let mut board = Scoreboard::new();
+    let ends_with_pattern = |scores: &[u8], len: usize| {
+        len >= pattern.len() && &scores[len - pattern.len()..len] == pattern
+    };
+    if let Some(end) = (pattern.len()..=2).find(|&len| ends_with_pattern(&board.scores, len)) {
+        return end - pattern.len();
+    }
+    loop {
+        let appended = board.step();
+        let len = board.scores.len();
+        // a two digit sum can complete the pattern with either digit
+        for end in len - appended + 1..=len {
+            if ends_with_pattern(&board.scores, end) {
+                return end - pattern.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_after() {
+        assert_eq!(recipe_scores_after(9, 10), "5158916779");
+        assert_eq!(recipe_scores_after(5, 10), "0124515891");
+        assert_eq!(recipe_scores_after(18, 10), "9251071085");
+        assert_eq!(recipe_scores_after(2018, 10), "5941429882");
+    }
+
+    #[test]
+    fn before_pattern() {
+        assert_eq!(recipes_before_pattern(&[5, 1, 5, 8, 9]), 9);
+        assert_eq!(recipes_before_pattern(&[0, 1, 2, 4, 5]), 5);
+        assert_eq!(recipes_before_pattern(&[9, 2, 5, 1, 0]), 18);
+        assert_eq!(recipes_before_pattern(&[5, 9, 4, 1, 4]), 2018);
+        assert_eq!(recipes_before_pattern(&[3, 7]), 0);
+        assert_eq!(recipes_before_pattern(&[7, 1]), 1);
+    }
+}
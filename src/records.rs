@@ -0,0 +1,131 @@
+/// Splits delimited lines into the header fields of the first line and the fields of every
+/// following non-empty line. Quoting isn't supported, so a delimiter inside the last column
+/// shows up as a field count mismatch and panics instead of silently shifting fields.
+pub fn records(s: &str, delim: char) -> (Vec<&str>, Vec<Vec<&str>>) {
+    let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().expect("records need a header line");
+    let headers: Vec<&str> = header.split(delim).map(str::trim).collect();
+    let rows = lines
+        .enumerate()
+        .map(|(i, line)| {
+            let fields: Vec<&str> = line.split(delim).map(str::trim).collect();
+            assert!(
+                fields.len() == headers.len(),
+                "row {} has {} fields but the header has {} (delimiters inside fields aren't \
+                 supported): {line:?}",
+                i + 1,
+                fields.len(),
+                headers.len(),
+            );
+            fields
+        })
+        .collect();
+    (headers, rows)
+}
+
+/// A row that can be read by column name. `row` is 1-based and only used in error messages.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    headers: &'a [&'a str],
+    fields: &'a [&'a str],
+    row: usize,
+}
+impl<'a> Record<'a> {
+    /// Wraps all rows returned by [`records`].
+    pub fn all(headers: &'a [&'a str], rows: &'a [Vec<&'a str>]) -> Vec<Self> {
+        rows.iter()
+            .enumerate()
+            .map(|(i, fields)| Self {
+                headers,
+                fields,
+                row: i + 1,
+            })
+            .collect()
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn get(&self, name: &str) -> &'a str {
+        let i = self
+            .headers
+            .iter()
+            .position(|&h| h == name)
+            .unwrap_or_else(|| panic!("no column named {name:?}, columns are {:?}", self.headers));
+        self.fields[i]
+    }
+
+    pub fn get_int(&self, name: &str) -> i64 {
+        let value = self.get(name);
+        value.parse().unwrap_or_else(|_| {
+            panic!(
+                "row {}: column {name:?} has non-integer value {value:?}",
+                self.row
+            )
+        })
+    }
+
+    /// `(column name, value)` pairs in column order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.headers
+            .iter()
+            .copied()
+            .zip(self.fields.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "name;speed;duration;rest
+Comet;14;10;127
+
+Dancer; 16 ;11;162
+Vixen;fast;3;1";
+
+    #[test]
+    fn lookup_by_name() {
+        let (headers, rows) = records(TABLE, ';');
+        assert_eq!(headers, ["name", "speed", "duration", "rest"]);
+        let records = Record::all(&headers, &rows);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].get("name"), "Dancer");
+        assert_eq!(records[1].get_int("speed"), 16);
+        let distance: i64 = records[..2]
+            .iter()
+            .map(|r| r.get_int("speed") * r.get_int("duration"))
+            .sum();
+        assert_eq!(distance, 140 + 176);
+        assert_eq!(
+            records[0].iter().collect::<Vec<_>>(),
+            [
+                ("name", "Comet"),
+                ("speed", "14"),
+                ("duration", "10"),
+                ("rest", "127")
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no column named \"distance\"")]
+    fn missing_column() {
+        let (headers, rows) = records(TABLE, ';');
+        Record::all(&headers, &rows)[0].get("distance");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 3: column \"speed\" has non-integer value \"fast\"")]
+    fn int_error_names_row() {
+        let (headers, rows) = records(TABLE, ';');
+        Record::all(&headers, &rows)[2].get_int("speed");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2 has 3 fields but the header has 2")]
+    fn delimiter_in_last_column() {
+        records("id,note\n1,fine\n2,not, fine", ',');
+    }
+}
@@ -0,0 +1,59 @@
+use vecm::Vec2i;
+
+/// An axis-aligned rectangle of integer positions, both corners inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Vec2i,
+    pub max: Vec2i,
+}
+impl Rect {
+    /// Creates the rectangle spanned by two opposite corners given in any order.
+    pub fn new(a: Vec2i, b: Vec2i) -> Self {
+        Self {
+            min: Vec2i::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Vec2i::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn from_size(origin: Vec2i, width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "rect can't be empty");
+        Self {
+            min: origin,
+            max: Vec2i::new(origin.x + width as i32 - 1, origin.y + height as i32 - 1),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, pos: Vec2i) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+    }
+
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let min = Vec2i::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Vec2i::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        (min.x <= max.x && min.y <= max.y).then_some(Rect { min, max })
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2i> {
+        let Rect { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Vec2i::new(x, y)))
+    }
+}
+
+/// All positions in the rectangle spanned by the inclusive corners `a` and `b`, in row-major
+/// order. The corners can be given in any order.
+pub fn positions_in_rect(a: Vec2i, b: Vec2i) -> impl Iterator<Item = Vec2i> {
+    Rect::new(a, b).positions()
+}
@@ -0,0 +1,201 @@
+use crate::{find_cycle_by_key, Shape};
+
+const WIDTH: usize = 7;
+const FULL: u8 = (1 << WIDTH) - 1;
+
+/// The rocks in the order they fall.
+pub const ROCKS: [&str; 5] = [
+    "####",
+    ".#.\n###\n.#.",
+    "..#\n..#\n###",
+    "#\n#\n#\n#",
+    "##\n##",
+];
+
+/// Rows of a shape as bit masks from the bottom up, bit `x` for column `x`.
+fn row_masks(shape: &Shape<char>) -> Vec<u8> {
+    let mut rows = vec![0; shape.height()];
+    for (pos, _) in shape.cells() {
+        rows[shape.height() - 1 - pos.y as usize] |= 1 << pos.x;
+    }
+    rows
+}
+
+/// Rocks pushed by a repeating pattern of jets while falling into a chamber that is seven
+/// units wide. Each rock appears two units from the left wall and three above the highest rock.
+#[derive(Debug, Clone)]
+pub struct RockFall {
+    /// Occupied cells of each row from the bottom up.
+    rows: Vec<u8>,
+    rocks: Vec<Vec<u8>>,
+    /// `true` for a jet that pushes to the right.
+    jets: Vec<bool>,
+    rock: usize,
+    jet: usize,
+    dropped: u64,
+}
+impl RockFall {
+    /// Jets are given as `<` and `>`, other characters are ignored.
+    pub fn new(jets: &str) -> Self {
+        let jets: Vec<bool> = jets
+            .chars()
+            .filter_map(|c| match c {
+                '<' => Some(false),
+                '>' => Some(true),
+                _ => None,
+            })
+            .collect();
+        assert!(!jets.is_empty(), "there are no jets");
+        Self {
+            rows: Vec::new(),
+            rocks: ROCKS
+                .iter()
+                .map(|rock| row_masks(&Shape::from_pattern(rock, '.')))
+                .collect(),
+            jets,
+            rock: 0,
+            jet: 0,
+            dropped: 0,
+        }
+    }
+
+    fn fits(&self, rock: &[u8], x: isize, y: usize) -> bool {
+        x >= 0
+            && rock.iter().enumerate().all(|(i, &mask)| {
+                let shifted = (mask as u16) << x;
+                shifted <= FULL as u16
+                    && self
+                        .rows
+                        .get(y + i)
+                        .is_none_or(|&row| row & shifted as u8 == 0)
+            })
+    }
+
+    /// Drops the next rock until it comes to rest.
+    pub fn step(&mut self) {
+        let rock = std::mem::take(&mut self.rocks[self.rock]);
+        let (mut x, mut y) = (2, self.rows.len() + 3);
+        loop {
+            let dx = if self.jets[self.jet] { 1 } else { -1 };
+            self.jet = (self.jet + 1) % self.jets.len();
+            if self.fits(&rock, x + dx, y) {
+                x += dx;
+            }
+            if y == 0 || !self.fits(&rock, x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+        for (i, &mask) in rock.iter().enumerate() {
+            if y + i == self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + i] |= mask << x;
+        }
+        self.rocks[self.rock] = rock;
+        self.rock = (self.rock + 1) % self.rocks.len();
+        self.dropped += 1;
+    }
+
+    /// The height of the tower.
+    pub fn height(&self) -> u64 {
+        self.rows.len() as u64
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// The cells that a falling rock could still reach from above, as row masks from the top
+    /// down. Everything below them can't influence the future, so together with the rock and
+    /// jet index this determines all further rocks. Column heights alone aren't enough since
+    /// rocks can be pushed under overhangs.
+    pub fn surface(&self) -> Vec<u8> {
+        let mut surface = Vec::new();
+        let mut above = FULL;
+        for &row in self.rows.iter().rev() {
+            let mut reach = above & !row;
+            loop {
+                let spread = (reach | reach << 1 | reach >> 1) & !row & FULL;
+                if spread == reach {
+                    break;
+                }
+                reach = spread;
+            }
+            if reach == 0 {
+                break;
+            }
+            surface.push(reach);
+            above = reach;
+        }
+        surface
+    }
+
+    /// The height of the tower after `n` rocks in total, skipping repetitions of the
+    /// `(rock, jet, surface)` state.
+    pub fn height_after(&self, n: u64) -> u64 {
+        assert!(
+            n >= self.dropped,
+            "{} rocks have already fallen",
+            self.dropped
+        );
+        let mut sim = self.clone();
+        let key = |sim: &RockFall| (sim.rock, sim.jet, sim.surface());
+        let initial = (key(&sim), sim.height());
+        let cycle = find_cycle_by_key(
+            initial,
+            |_| {
+                sim.step();
+                (key(&sim), sim.height())
+            },
+            |(key, _)| key.clone(),
+        );
+        let steps = n - self.dropped;
+        let (start, length) = (cycle.start as u64, cycle.length as u64);
+        if steps < start + length {
+            return cycle.states[steps as usize].1;
+        }
+        // `sim` has stepped once past the end of the first repetition
+        let per_cycle = sim.height() - cycle.states[cycle.start].1;
+        let rest = cycle
+            .state_at((start + (steps - start) % length) as usize)
+            .1;
+        rest + (steps - start) / length * per_cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JETS: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn tower_heights() {
+        let mut fall = RockFall::new(JETS);
+        for _ in 0..2022 {
+            fall.step();
+        }
+        assert_eq!(fall.height(), 3068);
+        let fresh = RockFall::new(JETS);
+        assert_eq!(fresh.height_after(2022), 3068);
+        assert_eq!(fresh.height_after(1_000_000_000_000), 1514285714288);
+        assert_eq!(fresh.height_after(0), 0);
+    }
+
+    #[test]
+    fn first_rocks() {
+        let mut fall = RockFall::new(JETS);
+        fall.step();
+        assert_eq!(fall.rows, [0b0111100]);
+        fall.step();
+        assert_eq!(fall.height(), 4);
+        // the plus sits on the bar, so only the corners of the bar's row stay reachable
+        assert_eq!(fall.surface(), [0b1110111, 0b1100011, 0b1110111, 0b1000011]);
+        let mut continued = fall.clone();
+        for _ in 0..100 {
+            continued.step();
+        }
+        assert_eq!(fall.height_after(102), continued.height());
+    }
+}
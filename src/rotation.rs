@@ -0,0 +1,318 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use vecm::Vec3i;
+
+/// One of the 24 axis-aligned rotations in 3D, stored as a signed permutation of the axes:
+/// component `i` of the result is `signs[i] * v[axes[i]]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rot3 {
+    axes: [u8; 3],
+    signs: [i8; 3],
+}
+impl Rot3 {
+    pub const IDENTITY: Self = Self {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn all() -> [Self; 24] {
+        const PERMS: [([u8; 3], i8); 6] = [
+            ([0, 1, 2], 1),
+            ([0, 2, 1], -1),
+            ([1, 0, 2], -1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([2, 1, 0], -1),
+        ];
+        let mut all = [Self::IDENTITY; 24];
+        let mut i = 0;
+        for (axes, parity) in PERMS {
+            for bits in 0..8 {
+                let signs = [0, 1, 2].map(|b| if bits & (1 << b) == 0 { 1 } else { -1 });
+                // only keep proper rotations, i.e. determinant 1
+                if parity * signs[0] * signs[1] * signs[2] == 1 {
+                    all[i] = Self { axes, signs };
+                    i += 1;
+                }
+            }
+        }
+        debug_assert_eq!(i, 24);
+        all
+    }
+
+    pub fn apply(self, v: Vec3i) -> Vec3i {
+        let c = [v.x, v.y, v.z];
+        let [x, y, z] = [0, 1, 2].map(|i| self.signs[i] as i32 * c[self.axes[i] as usize]);
+        Vec3i::new(x, y, z)
+    }
+
+    /// The rotation applying `other` first and then `self`.
+    #[must_use]
+    pub fn compose(self, other: Self) -> Self {
+        let axes = [0, 1, 2].map(|i| other.axes[self.axes[i] as usize]);
+        let signs = [0, 1, 2].map(|i| self.signs[i] * other.signs[self.axes[i] as usize]);
+        Self { axes, signs }
+    }
+
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        let mut inv = Self::IDENTITY;
+        for i in 0..3 {
+            let j = self.axes[i] as usize;
+            inv.axes[j] = i as u8;
+            inv.signs[j] = self.signs[i];
+        }
+        inv
+    }
+}
+
+/// Finds a rotation and translation mapping at least `min_overlap` candidate points onto
+/// reference points. For each rotation all pairwise differences are counted in a histogram,
+/// a translation that occurs often enough is the offset.
+pub fn align(
+    reference: &HashSet<Vec3i>,
+    candidate: &[Vec3i],
+    min_overlap: usize,
+) -> Option<(Rot3, Vec3i)> {
+    let mut histogram: HashMap<Vec3i, usize> = HashMap::new();
+    for rot in Rot3::all() {
+        histogram.clear();
+        for &c in candidate {
+            let c = rot.apply(c);
+            for &r in reference {
+                let count = histogram.entry(r - c).or_default();
+                *count += 1;
+                if *count >= min_overlap {
+                    return Some((rot, r - c));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Aligns all scanners relative to the first one, requiring 12 shared beacons between
+/// overlapping scanners. Returns all distinct beacons and the scanner positions in the first
+/// scanner's coordinates. Panics if some scanner can't be aligned.
+pub fn assemble(scanners: Vec<Vec<Vec3i>>) -> (HashSet<Vec3i>, Vec<Vec3i>) {
+    const MIN_OVERLAP: usize = 12;
+    let n = scanners.len();
+    let mut aligned: Vec<Option<HashSet<Vec3i>>> = vec![None; n];
+    let mut positions = vec![Vec3i::new(0, 0, 0); n];
+    aligned[0] = Some(scanners[0].iter().copied().collect());
+    let mut queue = VecDeque::from([0]);
+    while let Some(known) = queue.pop_front() {
+        for i in 0..n {
+            if aligned[i].is_some() {
+                continue;
+            }
+            let reference = aligned[known].as_ref().unwrap();
+            if let Some((rot, offset)) = align(reference, &scanners[i], MIN_OVERLAP) {
+                positions[i] = offset;
+                aligned[i] = Some(scanners[i].iter().map(|&b| rot.apply(b) + offset).collect());
+                queue.push_back(i);
+            }
+        }
+    }
+    let mut beacons = HashSet::new();
+    for (i, set) in aligned.into_iter().enumerate() {
+        beacons.extend(set.unwrap_or_else(|| panic!("scanner {i} couldn't be aligned")));
+    }
+    (beacons, positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec3i;
+
+    use super::*;
+
+    #[test]
+    fn rotations_form_a_group() {
+        let all = Rot3::all();
+        let distinct: HashSet<Rot3> = all.iter().copied().collect();
+        assert_eq!(distinct.len(), 24);
+        let v = Vec3i::new(1, 2, 3);
+        for a in all {
+            assert_eq!(a.compose(a.inverse()), Rot3::IDENTITY);
+            assert_eq!(a.inverse().apply(a.apply(v)), v);
+            for b in all {
+                assert!(distinct.contains(&a.compose(b)));
+                assert_eq!(a.compose(b).apply(v), a.apply(b.apply(v)));
+            }
+        }
+    }
+
+    const SAMPLE: &str = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14";
+
+    #[test]
+    fn sample_scanners() {
+        let scanners = SAMPLE
+            .split("\n\n")
+            .map(|block| {
+                block
+                    .lines()
+                    .skip(1)
+                    .map(|line| {
+                        let [x, y, z] = line
+                            .split(',')
+                            .map(|c| c.parse().unwrap())
+                            .collect::<Vec<i32>>()[..]
+                        else {
+                            panic!()
+                        };
+                        Vec3i::new(x, y, z)
+                    })
+                    .collect()
+            })
+            .collect();
+        let (beacons, positions) = assemble(scanners);
+        assert_eq!(beacons.len(), 79);
+        assert_eq!(positions[1], Vec3i::new(68, -1246, -43));
+        let largest = positions
+            .iter()
+            .flat_map(|a| {
+                positions
+                    .iter()
+                    .map(move |b| (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs())
+            })
+            .max();
+        assert_eq!(largest, Some(3621));
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+/// No pattern of a [`LineRouter`] matched a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteError {
+    pub line: String,
+    /// The 1-based line number when routing a whole input.
+    pub line_number: Option<usize>,
+    /// Every registered pattern, in the order they were tried.
+    pub patterns: Vec<String>,
+}
+impl Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no pattern matches ")?;
+        if let Some(n) = self.line_number {
+            write!(f, "line {n} ")?;
+        }
+        write!(f, "{:?}, tried {:?}", self.line, self.patterns)
+    }
+}
+impl std::error::Error for RouteError {}
+
+/// Turns the captures of a pattern into a value.
+type Parser<'a, T> = Box<dyn Fn(&[&str]) -> T + 'a>;
+
+struct Route<'a, T> {
+    pattern: String,
+    parse: Parser<'a, T>,
+}
+
+/// Dispatches lines in one of several formats to the parser of the first matching pattern.
+///
+/// A pattern without placeholders matches every line starting with it and passes the rest of
+/// the line as the only capture. Otherwise the whole line has to match, with every `{}`
+/// capturing the non-empty text up to the first following occurrence of the literal after it,
+/// so `mem[{}] = {}` captures `8` and `11` from `mem[8] = 11`.
+pub struct LineRouter<'a, T> {
+    routes: Vec<Route<'a, T>>,
+}
+impl<T> Default for LineRouter<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<'a, T> LineRouter<'a, T> {
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Adds a pattern, tried after all earlier ones. Panics if two placeholders are adjacent
+    /// since there would be no literal to tell their captures apart.
+    pub fn rule(mut self, pattern: &str, parse: impl Fn(&[&str]) -> T + 'a) -> Self {
+        assert!(
+            !pattern.contains("{}{}"),
+            "adjacent placeholders in pattern {pattern:?}"
+        );
+        self.routes.push(Route {
+            pattern: pattern.to_owned(),
+            parse: Box::new(parse),
+        });
+        self
+    }
+
+    pub fn route(&self, line: &str) -> Result<T, RouteError> {
+        self.routes
+            .iter()
+            .find_map(|route| Some((route.parse)(&captures(&route.pattern, line)?)))
+            .ok_or_else(|| RouteError {
+                line: line.to_owned(),
+                line_number: None,
+                patterns: self.routes.iter().map(|r| r.pattern.clone()).collect(),
+            })
+    }
+
+    /// Routes every line of the input, stopping at the first one nothing matches.
+    pub fn route_all(&self, input: &str) -> Result<Vec<T>, RouteError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                self.route(line).map_err(|err| RouteError {
+                    line_number: Some(i + 1),
+                    ..err
+                })
+            })
+            .collect()
+    }
+}
+
+fn captures<'l>(pattern: &str, line: &'l str) -> Option<Vec<&'l str>> {
+    let mut literals = pattern.split("{}");
+    let mut rest = line.strip_prefix(literals.next().unwrap())?;
+    let literals: Vec<&str> = literals.collect();
+    if literals.is_empty() {
+        return Some(vec![rest]);
+    }
+    let mut captures = Vec::with_capacity(literals.len());
+    for (i, literal) in literals.iter().enumerate() {
+        let end = if i + 1 == literals.len() {
+            rest.strip_suffix(literal)?.len()
+        } else {
+            rest.match_indices(literal).find(|&(at, _)| at > 0)?.0
+        };
+        if end == 0 {
+            return None;
+        }
+        captures.push(&rest[..end]);
+        rest = &rest[end + literal.len()..];
+    }
+    Some(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::int;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Docking {
+        Mask(String),
+        Mem(u64, u64),
+    }
+
+    fn docking() -> LineRouter<'static, Docking> {
+        LineRouter::new()
+            .rule("mask = {}", |c| Docking::Mask(c[0].to_owned()))
+            .rule("mem[{}] = {}", |c| {
+                Docking::Mem(int(c[0]) as u64, int(c[1]) as u64)
+            })
+    }
+
+    #[test]
+    fn docking_program() {
+        let program =
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = 101\nmem[8] = 0";
+        assert_eq!(
+            docking().route_all(program),
+            Ok(vec![
+                Docking::Mask("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".to_owned()),
+                Docking::Mem(8, 11),
+                Docking::Mem(7, 101),
+                Docking::Mem(8, 0),
+            ])
+        );
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Light {
+        On([i64; 4]),
+        Off([i64; 4]),
+        Toggle([i64; 4]),
+    }
+
+    #[test]
+    fn light_commands() {
+        let corners = |c: &[&str]| [c[0], c[1], c[2], c[3]].map(int);
+        let router = LineRouter::new()
+            .rule("turn on {},{} through {},{}", |c| Light::On(corners(c)))
+            .rule("turn off {},{} through {},{}", |c| Light::Off(corners(c)))
+            .rule("toggle {},{} through {},{}", |c| Light::Toggle(corners(c)));
+        let commands = "turn on 0,0 through 999,999\ntoggle 0,0 through 999,0\nturn off 499,499 through 500,500\n";
+        assert_eq!(
+            router.route_all(commands),
+            Ok(vec![
+                Light::On([0, 0, 999, 999]),
+                Light::Toggle([0, 0, 999, 0]),
+                Light::Off([499, 499, 500, 500]),
+            ])
+        );
+        // a prefix pattern passes the rest of the line, earlier rules win
+        let router = LineRouter::new()
+            .rule("turn on ", |c| c[0].len())
+            .rule("turn {}", |_| 0);
+        assert_eq!(router.route("turn on 1,2"), Ok(3));
+        assert_eq!(router.route("turn off 1,2"), Ok(0));
+    }
+
+    #[test]
+    fn no_match_error() {
+        let router = docking();
+        let err = router.route_all("mask = 01X\nmem[] = 3\n").unwrap_err();
+        assert_eq!(
+            err,
+            RouteError {
+                line: "mem[] = 3".to_owned(),
+                line_number: Some(2),
+                patterns: vec!["mask = {}".to_owned(), "mem[{}] = {}".to_owned()],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            r#"no pattern matches line 2 "mem[] = 3", tried ["mask = {}", "mem[{}] = {}"]"#
+        );
+        assert_eq!(
+            router.route("mem[4] = ").unwrap_err().to_string(),
+            r#"no pattern matches "mem[4] = ", tried ["mask = {}", "mem[{}] = {}"]"#
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use crate::dijkstra;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Combatant {
+    pub hp: i64,
+    pub damage: i64,
+    pub armor: i64,
+}
+impl Combatant {
+    /// The damage of one attack against `defender`, always at least 1.
+    pub fn hit(&self, defender: &Combatant) -> i64 {
+        (self.damage - defender.armor).max(1)
+    }
+
+    /// The number of attacks it takes to bring `defender` down to 0 hit points.
+    pub fn attacks_to_defeat(&self, defender: &Combatant) -> i64 {
+        (defender.hp.max(0) + self.hit(defender) - 1) / self.hit(defender)
+    }
+}
+
+/// Whether the player wins when the two take turns attacking, the player first.
+pub fn fight(player: Combatant, boss: Combatant) -> bool {
+    player.attacks_to_defeat(&boss) <= boss.attacks_to_defeat(&player)
+}
+
+/// The hit points left to the one attacked after every attack of a [`fight`], starting with
+/// the player's first attack against the boss.
+pub fn fight_trace(mut player: Combatant, mut boss: Combatant) -> Vec<i64> {
+    let mut trace = Vec::new();
+    loop {
+        boss.hp -= player.hit(&boss);
+        trace.push(boss.hp.max(0));
+        if boss.hp <= 0 {
+            return trace;
+        }
+        player.hp -= boss.hit(&player);
+        trace.push(player.hp.max(0));
+        if player.hp <= 0 {
+            return trace;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Item {
+    pub name: String,
+    pub cost: u64,
+    pub damage: i64,
+    pub armor: i64,
+}
+
+/// The shop of the fight with items: a loadout is exactly one weapon, at most one armor and at
+/// most two different rings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemShop {
+    pub weapons: Vec<Item>,
+    pub armor: Vec<Item>,
+    pub rings: Vec<Item>,
+}
+impl ItemShop {
+    /// Parses the shop listing with sections like `Weapons:    Cost  Damage  Armor` followed by
+    /// lines like `Dagger        8     4       0`. Names may contain spaces.
+    pub fn parse(s: &str) -> Self {
+        let mut sections: Vec<Vec<Item>> = Vec::new();
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            if line.contains(':') {
+                sections.push(Vec::new());
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let [.., cost, damage, armor] = words[..] else {
+                panic!("expected an item with cost, damage and armor but found {line:?}");
+            };
+            let number = |n: &str| {
+                n.parse::<i64>()
+                    .unwrap_or_else(|_| panic!("invalid number {n:?} in {line:?}"))
+            };
+            let item = Item {
+                name: words[..words.len() - 3].join(" "),
+                cost: number(cost) as u64,
+                damage: number(damage),
+                armor: number(armor),
+            };
+            sections
+                .last_mut()
+                .unwrap_or_else(|| panic!("item {line:?} before the first section"))
+                .push(item);
+        }
+        let [weapons, armor, rings]: [Vec<Item>; 3] = sections
+            .try_into()
+            .unwrap_or_else(|s: Vec<_>| panic!("expected 3 sections but found {}", s.len()));
+        Self {
+            weapons,
+            armor,
+            rings,
+        }
+    }
+
+    /// Every allowed loadout as its cost and the player with `hp` hit points wearing it.
+    pub fn loadouts(&self, hp: i64) -> Vec<(u64, Combatant)> {
+        let armor: Vec<Option<&Item>> = std::iter::once(None)
+            .chain(self.armor.iter().map(Some))
+            .collect();
+        let mut rings: Vec<Vec<&Item>> = vec![vec![]];
+        for (i, a) in self.rings.iter().enumerate() {
+            rings.push(vec![a]);
+            rings.extend(self.rings[i + 1..].iter().map(|b| vec![a, b]));
+        }
+        let mut loadouts = Vec::new();
+        for weapon in &self.weapons {
+            for a in &armor {
+                for r in &rings {
+                    let items: Vec<&Item> = std::iter::once(weapon)
+                        .chain(*a)
+                        .chain(r.iter().copied())
+                        .collect();
+                    let player = Combatant {
+                        hp,
+                        damage: items.iter().map(|item| item.damage).sum(),
+                        armor: items.iter().map(|item| item.armor).sum(),
+                    };
+                    let cost = items.iter().map(|item| item.cost).sum();
+                    loadouts.push((cost, player));
+                }
+            }
+        }
+        loadouts
+    }
+}
+
+/// The least gold to spend on a loadout that wins against `boss`, `None` if none does.
+pub fn cheapest_winning_loadout(shop: &ItemShop, player_hp: i64, boss: Combatant) -> Option<u64> {
+    shop.loadouts(player_hp)
+        .into_iter()
+        .filter(|&(_, player)| fight(player, boss))
+        .map(|(cost, _)| cost)
+        .min()
+}
+
+/// The most gold to spend on a loadout that still loses against `boss`.
+pub fn most_expensive_losing_loadout(
+    shop: &ItemShop,
+    player_hp: i64,
+    boss: Combatant,
+) -> Option<u64> {
+    shop.loadouts(player_hp)
+        .into_iter()
+        .filter(|&(_, player)| !fight(player, boss))
+        .map(|(cost, _)| cost)
+        .max()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Spell {
+    MagicMissile,
+    Drain,
+    Shield,
+    Poison,
+    Recharge,
+}
+impl Spell {
+    pub const ALL: [Spell; 5] = [
+        Spell::MagicMissile,
+        Spell::Drain,
+        Spell::Shield,
+        Spell::Poison,
+        Spell::Recharge,
+    ];
+
+    pub fn cost(self) -> i64 {
+        match self {
+            Spell::MagicMissile => 53,
+            Spell::Drain => 73,
+            Spell::Shield => 113,
+            Spell::Poison => 173,
+            Spell::Recharge => 229,
+        }
+    }
+}
+
+/// The fight with spells at the start of a player turn. Shield, Poison and Recharge are effects
+/// lasting 6, 6 and 5 turns that can't be cast again while they are active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpellFight {
+    pub player_hp: i64,
+    pub mana: i64,
+    pub boss_hp: i64,
+    pub boss_damage: i64,
+    /// The turns left of shield, poison and recharge.
+    pub timers: [u8; 3],
+}
+impl SpellFight {
+    pub fn new(player_hp: i64, mana: i64, boss: Combatant) -> Self {
+        Self {
+            player_hp,
+            mana,
+            boss_hp: boss.hp,
+            boss_damage: boss.damage,
+            timers: [0; 3],
+        }
+    }
+
+    pub fn won(&self) -> bool {
+        self.boss_hp <= 0
+    }
+
+    /// The start of a turn: every active effect applies once and its timer counts down.
+    /// Returns the player's armor for this turn.
+    fn apply_effects(&mut self) -> i64 {
+        let armor = if self.timers[0] > 0 { 7 } else { 0 };
+        if self.timers[1] > 0 {
+            self.boss_hp -= 3;
+        }
+        if self.timers[2] > 0 {
+            self.mana += 101;
+        }
+        for timer in &mut self.timers {
+            *timer = timer.saturating_sub(1);
+        }
+        armor
+    }
+
+    /// Casts `spell` and lets the boss take its turn, returns the new state with the mana spent.
+    /// `None` if the spell can't be cast or the player dies. The fight stops as soon as the boss
+    /// is defeated, without casting anything if the effects already do it. In hard mode, the
+    /// player loses a hit point at the start of every own turn.
+    pub fn round(&self, spell: Spell, hard_mode: bool) -> Option<(SpellFight, u64)> {
+        let mut next = *self;
+        if hard_mode {
+            next.player_hp -= 1;
+            if next.player_hp <= 0 {
+                return None;
+            }
+        }
+        next.apply_effects();
+        if next.won() {
+            return Some((next, 0));
+        }
+        if spell.cost() > next.mana {
+            return None;
+        }
+        next.mana -= spell.cost();
+        match spell {
+            Spell::MagicMissile => next.boss_hp -= 4,
+            Spell::Drain => {
+                next.boss_hp -= 2;
+                next.player_hp += 2;
+            }
+            timed => {
+                let (i, turns) = match timed {
+                    Spell::Shield => (0, 6),
+                    Spell::Poison => (1, 6),
+                    _ => (2, 5),
+                };
+                if next.timers[i] > 0 {
+                    return None;
+                }
+                next.timers[i] = turns;
+            }
+        }
+        let spent = spell.cost() as u64;
+        if next.won() {
+            return Some((next, spent));
+        }
+        let armor = next.apply_effects();
+        if next.won() {
+            return Some((next, spent));
+        }
+        next.player_hp -= (next.boss_damage - armor).max(1);
+        (next.player_hp > 0).then_some((next, spent))
+    }
+}
+
+/// The least mana to spend on spells to win the fight, `None` if it can't be won.
+pub fn spell_fight_min_mana(state: SpellFight, hard_mode: bool) -> Option<u64> {
+    let successors = |s: &SpellFight| {
+        Spell::ALL
+            .iter()
+            .filter_map(|&spell| s.round(spell, hard_mode))
+            .collect()
+    };
+    dijkstra(state, successors, SpellFight::won).map(|(mana, _)| mana)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOP: &str = "Weapons:    Cost  Damage  Armor
+Dagger        8     4       0
+Shortsword   10     5       0
+Warhammer    25     6       0
+Longsword    40     7       0
+Greataxe     74     8       0
+
+Armor:      Cost  Damage  Armor
+Leather      13     0       1
+Chainmail    31     0       2
+Splintmail   53     0       3
+Bandedmail   75     0       4
+Platemail   102     0       5
+
+Rings:      Cost  Damage  Armor
+Damage +1    25     1       0
+Damage +2    50     2       0
+Damage +3   100     3       0
+Defense +1   20     0       1
+Defense +2   40     0       2
+Defense +3   80     0       3";
+
+    #[test]
+    fn item_fight() {
+        let player = Combatant {
+            hp: 8,
+            damage: 5,
+            armor: 5,
+        };
+        let boss = Combatant {
+            hp: 12,
+            damage: 7,
+            armor: 2,
+        };
+        assert_eq!(fight_trace(player, boss), [9, 6, 6, 4, 3, 2, 0]);
+        assert!(fight(player, boss));
+        assert!(!fight(Combatant { hp: 6, ..player }, boss));
+
+        let shop = ItemShop::parse(SHOP);
+        assert_eq!(shop.rings[3].name, "Defense +1");
+        assert_eq!(shop.loadouts(100).len(), 5 * 6 * 22);
+        let boss = Combatant {
+            hp: 104,
+            damage: 8,
+            armor: 1,
+        };
+        assert_eq!(cheapest_winning_loadout(&shop, 100, boss), Some(78));
+        assert_eq!(most_expensive_losing_loadout(&shop, 100, boss), Some(148));
+    }
+
+    #[test]
+    fn spell_fights() {
+        let boss = Combatant {
+            hp: 13,
+            damage: 8,
+            armor: 0,
+        };
+        let start = SpellFight::new(10, 250, boss);
+        let end = [Spell::Poison, Spell::MagicMissile]
+            .iter()
+            .try_fold(start, |s, &spell| Some(s.round(spell, false)?.0))
+            .unwrap();
+        assert!(end.won());
+        assert_eq!((end.player_hp, end.mana), (2, 24));
+        assert_eq!(spell_fight_min_mana(start, false), Some(226));
+
+        let start = SpellFight::new(10, 250, Combatant { hp: 14, ..boss });
+        let spells = [
+            Spell::Recharge,
+            Spell::Shield,
+            Spell::Drain,
+            Spell::Poison,
+            Spell::MagicMissile,
+        ];
+        let end = spells
+            .iter()
+            .try_fold(start, |s, &spell| Some(s.round(spell, false)?.0))
+            .unwrap();
+        assert!(end.won());
+        assert_eq!((end.player_hp, end.mana), (1, 114));
+        assert_eq!(spell_fight_min_mana(start, false), Some(641));
+        // shield can't be renewed while it is active
+        let (shielded, spent) = start.round(Spell::Shield, false).unwrap();
+        assert_eq!(spent, 113);
+        assert_eq!(shielded.round(Spell::Shield, false), None);
+    }
+
+    #[test]
+    fn hard_mode() {
+        let boss = Combatant {
+            hp: 51,
+            damage: 9,
+            armor: 0,
+        };
+        let start = SpellFight::new(50, 500, boss);
+        assert_eq!(spell_fight_min_mana(start, false), Some(900));
+        assert_eq!(spell_fight_min_mana(start, true), Some(1216));
+    }
+}
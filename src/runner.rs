@@ -0,0 +1,1561 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::{self, Answers, Registry, VerifyReport},
+    color, fmt_duration, Color, JsonLite,
+};
+
+/// The solution of one day. Pairs of functions `(part1, part2)` taking the input and returning
+/// anything printable implement it too.
+pub trait Day {
+    fn part1(&self, input: &str) -> String;
+    fn part2(&self, input: &str) -> String;
+}
+impl<F, G, A, B> Day for (F, G)
+where
+    F: Fn(&str) -> A,
+    G: Fn(&str) -> B,
+    A: Display,
+    B: Display,
+{
+    fn part1(&self, input: &str) -> String {
+        self.0(input).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        self.1(input).to_string()
+    }
+}
+
+/// A day that parses its input once for both parts, the runner times the parsing on its own.
+/// Every [`Day`] is a solution that hands both parts the unparsed input.
+pub trait Solution {
+    type Parsed: 'static;
+
+    fn parse(&self, input: &str) -> Self::Parsed;
+    fn part1(&self, parsed: &Self::Parsed) -> impl Display;
+    fn part2(&self, parsed: &Self::Parsed) -> impl Display;
+
+    /// For a day whose second part reads the input differently, the parsing for part 2 only.
+    /// Its time then counts towards part 2.
+    fn parse_part2(&self, input: &str) -> Option<Self::Parsed> {
+        let _ = input;
+        None
+    }
+
+    /// Whether there is any parsing to time, false for plain [`Day`]s.
+    fn timed_parse(&self) -> bool {
+        true
+    }
+
+    /// The plain [`Day`] this is, which the runner solves on the input directly instead of
+    /// copying it into `Parsed` first.
+    #[doc(hidden)]
+    fn as_day(&self) -> Option<&dyn Day> {
+        None
+    }
+}
+impl<D: Day> Solution for D {
+    type Parsed = String;
+
+    fn parse(&self, input: &str) -> String {
+        input.to_owned()
+    }
+
+    fn part1(&self, input: &String) -> impl Display {
+        Day::part1(self, input)
+    }
+
+    fn part2(&self, input: &String) -> impl Display {
+        Day::part2(self, input)
+    }
+
+    fn timed_parse(&self) -> bool {
+        false
+    }
+
+    fn as_day(&self) -> Option<&dyn Day> {
+        Some(self)
+    }
+}
+
+/// Which part a [`with_param`] day is solving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Part1,
+    Part2,
+}
+
+/// The [`Solution`] of [`both_parts`].
+pub struct BothParts<Parse, Score1, Score2> {
+    parse: Parse,
+    score1: Score1,
+    score2: Score2,
+}
+impl<P, A, B, Parse, Score1, Score2> Solution for BothParts<Parse, Score1, Score2>
+where
+    P: 'static,
+    A: Display,
+    B: Display,
+    Parse: Fn(&str) -> P,
+    Score1: Fn(&P) -> A,
+    Score2: Fn(&P) -> B,
+{
+    type Parsed = P;
+
+    fn parse(&self, input: &str) -> P {
+        (self.parse)(input)
+    }
+
+    fn part1(&self, parsed: &P) -> impl Display {
+        (self.score1)(parsed)
+    }
+
+    fn part2(&self, parsed: &P) -> impl Display {
+        (self.score2)(parsed)
+    }
+}
+
+/// A day whose parts only differ in how they score the same parsed input, e.g.
+/// `both_parts(parse, |rounds| score(rounds, false), |rounds| score(rounds, true))`.
+pub fn both_parts<P: 'static, A: Display, B: Display>(
+    parse: impl Fn(&str) -> P,
+    score1: impl Fn(&P) -> A,
+    score2: impl Fn(&P) -> B,
+) -> BothParts<impl Fn(&str) -> P, impl Fn(&P) -> A, impl Fn(&P) -> B> {
+    BothParts {
+        parse,
+        score1,
+        score2,
+    }
+}
+
+/// The [`Solution`] of [`with_param`].
+pub struct WithParam<Parse, Solve> {
+    parse: Parse,
+    solve: Solve,
+}
+impl<P, A, Parse, Solve> Solution for WithParam<Parse, Solve>
+where
+    P: 'static,
+    A: Display,
+    Parse: Fn(&str) -> P,
+    Solve: Fn(&P, Mode) -> A,
+{
+    type Parsed = P;
+
+    fn parse(&self, input: &str) -> P {
+        (self.parse)(input)
+    }
+
+    fn part1(&self, parsed: &P) -> impl Display {
+        (self.solve)(parsed, Mode::Part1)
+    }
+
+    fn part2(&self, parsed: &P) -> impl Display {
+        (self.solve)(parsed, Mode::Part2)
+    }
+}
+
+/// A day solved by one function that gets told which part it is solving.
+pub fn with_param<P: 'static, A: Display>(
+    parse: impl Fn(&str) -> P,
+    solve: impl Fn(&P, Mode) -> A,
+) -> WithParam<impl Fn(&str) -> P, impl Fn(&P, Mode) -> A> {
+    WithParam { parse, solve }
+}
+
+/// A [`Solution`] with its parsed type erased, so that the runner can keep it boxed.
+trait ErasedSolution {
+    fn parse_any(&self, input: &str) -> Box<dyn Any>;
+    fn run_part(&self, part: u8, input: &str, parsed: &dyn Any) -> String;
+    fn has_parse_time(&self) -> bool;
+}
+impl<S: Solution> ErasedSolution for S {
+    fn parse_any(&self, input: &str) -> Box<dyn Any> {
+        if self.as_day().is_some() {
+            return Box::new(());
+        }
+        Box::new(Solution::parse(self, input))
+    }
+
+    fn run_part(&self, part: u8, input: &str, parsed: &dyn Any) -> String {
+        if let Some(day) = self.as_day() {
+            return if part == 1 {
+                day.part1(input)
+            } else {
+                day.part2(input)
+            };
+        }
+        let parsed = parsed.downcast_ref::<S::Parsed>().unwrap();
+        if part == 1 {
+            return Solution::part1(self, parsed).to_string();
+        }
+        match self.parse_part2(input) {
+            Some(own) => Solution::part2(self, &own).to_string(),
+            None => Solution::part2(self, parsed).to_string(),
+        }
+    }
+
+    fn has_parse_time(&self) -> bool {
+        Solution::timed_parse(self)
+    }
+}
+
+/// Registers days on a new [`Runner`], e.g. `days![1 => (day01::part1, day01::part2), 7 =>
+/// Day07]`.
+///
+/// A day can be gated behind a feature of the solutions crate with `17 => Day17; feature =
+/// "day17"`, next to `#[cfg(feature = "day17")] mod day17;`. Without the feature, the solution
+/// isn't compiled and running the day fails with [`RunError::NotCompiled`]. An `all-days`
+/// feature enabling every day feature belongs in the same manifest.
+#[macro_export]
+macro_rules! days {
+    (@day $runner:ident, $day:expr, $solution:expr) => {
+        $runner.day($day, $solution)
+    };
+    (@day $runner:ident, $day:expr, $solution:expr, $feature:literal) => {{
+        #[cfg(feature = $feature)]
+        let runner = $runner.day($day, $solution);
+        #[cfg(not(feature = $feature))]
+        let runner = $runner.not_compiled($day, $feature);
+        runner
+    }};
+    ($($day:expr => $solution:expr $(; feature = $feature:literal)?),* $(,)?) => {{
+        let runner = $crate::runner::Runner::new();
+        $(let runner = $crate::days!(@day runner, $day, $solution $(, $feature)?);)*
+        runner
+    }};
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Usage(String),
+    UnknownDay(u32),
+    /// The day is registered behind a feature that isn't enabled.
+    NotCompiled {
+        day: u32,
+        feature: String,
+    },
+    /// None of the candidate files could be read.
+    MissingInput(Vec<PathBuf>),
+    /// The answers next to a fixture aren't an object with `part1` and `part2`.
+    BadSidecar {
+        path: PathBuf,
+        message: String,
+    },
+    ExampleMismatch {
+        day: u32,
+        part: u8,
+        expected: String,
+        actual: String,
+    },
+    /// An answer on a fixture differs from its sidecar.
+    FixtureMismatch {
+        day: u32,
+        part: u8,
+        expected: String,
+        actual: String,
+    },
+    /// Part 0 is the parsing of a [`Solution`].
+    Panicked {
+        day: u32,
+        part: u8,
+        message: String,
+    },
+    /// Some of the days run with `--all` failed, they are marked in the printed summary.
+    Failed {
+        failed: usize,
+        total: usize,
+    },
+}
+impl RunError {
+    /// The error without the day and part, for a cell of the summary table.
+    fn short(&self) -> String {
+        match self {
+            Self::ExampleMismatch {
+                expected, actual, ..
+            }
+            | Self::FixtureMismatch {
+                expected, actual, ..
+            } => format!("expected {expected}, got {actual}"),
+            Self::Panicked { message, .. } => {
+                format!("panicked: {}", message.lines().next().unwrap_or(""))
+            }
+            other => other.to_string(),
+        }
+    }
+}
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(msg) => write!(f, "{msg}\n{USAGE}"),
+            Self::UnknownDay(day) => write!(f, "day {day} isn't registered"),
+            Self::NotCompiled { day, feature } => {
+                write!(f, "day {day} not compiled in — enable feature {feature}")
+            }
+            Self::MissingInput(paths) => {
+                write!(f, "can't read the input, looked for")?;
+                for (i, path) in paths.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{sep}{}", path.display())?;
+                }
+                Ok(())
+            }
+            Self::BadSidecar { path, message } => {
+                write!(f, "invalid answers in {}: {message}", path.display())
+            }
+            Self::ExampleMismatch {
+                day,
+                part,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "day {day} part {part} on the example: expected {expected}, got {actual}"
+            ),
+            Self::FixtureMismatch {
+                day,
+                part,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "day {day} part {part} on the fixture: expected {expected}, got {actual}"
+            ),
+            Self::Panicked {
+                day,
+                part: 0,
+                message,
+            } => write!(f, "day {day} panicked while parsing: {message}"),
+            Self::Panicked { day, part, message } => {
+                write!(f, "day {day} part {part} panicked: {message}")
+            }
+            Self::Failed { failed, total } => write!(f, "{failed} of {total} days failed"),
+        }
+    }
+}
+impl std::error::Error for RunError {}
+
+const USAGE: &str =
+    "usage: [example] <day> | [example] --all, with --sample [N] or AOCH_INPUT=sample[N]";
+
+/// Which input file of a day to run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// `dayNN.txt`
+    Real,
+    /// `dayNN_example.txt`, checked against the example answers.
+    Example,
+    /// `dayNN.sample.txt` for sample 1 (or `dayNN.sample1.txt`), `dayNN.sampleN.txt` otherwise.
+    Sample(u32),
+}
+impl InputKind {
+    /// Reads `AOCH_INPUT`, which can be `sample` or `sampleN`.
+    fn from_env() -> Result<Option<Self>, RunError> {
+        let Ok(value) = std::env::var("AOCH_INPUT") else {
+            return Ok(None);
+        };
+        match value.strip_prefix("sample") {
+            Some("") => Ok(Some(Self::Sample(1))),
+            Some(n) => n.parse().map(|n| Some(Self::Sample(n))).map_err(|_| {
+                RunError::Usage(format!("invalid sample number in AOCH_INPUT={value:?}"))
+            }),
+            None if value == "real" => Ok(Some(Self::Real)),
+            None => Err(RunError::Usage(format!("unknown AOCH_INPUT={value:?}"))),
+        }
+    }
+}
+
+/// The answers of one day with the time each part took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayResult {
+    pub day: u32,
+    /// The file the input was read from, `None` for [`Runner::run_str`].
+    pub input: Option<PathBuf>,
+    /// Whether the input is a bundled fixture because the real one is missing.
+    pub sample: bool,
+    /// The time the shared parsing of a [`Solution`] took.
+    pub parse: Option<Duration>,
+    pub part1: (String, Duration),
+    pub part2: (String, Duration),
+}
+impl Display for DayResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let colored = color::enabled();
+        let input = match &self.input {
+            Some(path) => path.display().to_string(),
+            None => "string input".to_owned(),
+        };
+        write!(
+            f,
+            "{} {}",
+            color::bold(format!("day {}", self.day)).when(colored),
+            Color::DEFAULT.paint(format!("({input})")).when(colored)
+        )?;
+        if self.sample {
+            write!(
+                f,
+                " {}",
+                Color::Yellow.paint("running on SAMPLE").when(colored)
+            )?;
+        }
+        writeln!(f)?;
+        if let Some(time) = self.parse {
+            writeln!(
+                f,
+                "  parse:  {}",
+                Color::DEFAULT.paint(fmt_duration(time)).when(colored)
+            )?;
+        }
+        for (part, (answer, time)) in [(1, &self.part1), (2, &self.part2)] {
+            writeln!(
+                f,
+                "  part {part}: {}  {}",
+                Color::Green.paint_bold(answer).when(colored),
+                Color::DEFAULT.paint(fmt_duration(*time)).when(colored)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The answer and time of one part, or why there is none.
+pub type PartOutcome = Result<(String, Duration), RunError>;
+
+#[derive(Debug)]
+pub struct SummaryRow {
+    pub day: u32,
+    /// Whether the day ran on a fixture instead of the real input.
+    pub sample: bool,
+    pub parse: Option<Duration>,
+    /// An error for the whole day if it couldn't run at all.
+    pub parts: Result<[PartOutcome; 2], RunError>,
+}
+impl SummaryRow {
+    pub fn failed(&self) -> bool {
+        !matches!(&self.parts, Ok([Ok(_), Ok(_)]))
+    }
+}
+
+/// The results of [`Runner::run_all`], displayed as a table with failures in red.
+#[derive(Debug)]
+pub struct Summary {
+    pub rows: Vec<SummaryRow>,
+}
+impl Summary {
+    pub fn failed_count(&self) -> usize {
+        self.rows.iter().filter(|row| row.failed()).count()
+    }
+
+    /// The days that completed without failures.
+    pub fn into_results(self) -> Vec<DayResult> {
+        self.rows
+            .into_iter()
+            .filter_map(|row| match row.parts {
+                Ok([Ok(part1), Ok(part2)]) => Some(DayResult {
+                    day: row.day,
+                    input: None,
+                    sample: row.sample,
+                    parse: row.parse,
+                    part1,
+                    part2,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Error cells of the summary are cut to this many characters.
+const MAX_ERROR_LEN: usize = 40;
+
+fn truncate(s: String) -> String {
+    if s.chars().count() <= MAX_ERROR_LEN {
+        return s;
+    }
+    s.chars().take(MAX_ERROR_LEN - 1).chain(['…']).collect()
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let colored = color::enabled();
+        // the text of every cell and whether it is a failure
+        let cell = |outcome: &PartOutcome| match outcome {
+            Ok((answer, time)) => [(answer.clone(), false), (fmt_duration(*time), false)],
+            Err(err) => [(truncate(err.short()), true), (String::new(), false)],
+        };
+        let header = ["day", "part 1", "time", "part 2", "time"].map(|h| (h.to_owned(), false));
+        let rows: Vec<[(String, bool); 5]> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let day = if row.sample {
+                    (format!("{} SAMPLE", row.day), false)
+                } else {
+                    (row.day.to_string(), false)
+                };
+                let empty = || (String::new(), false);
+                match &row.parts {
+                    Ok([part1, part2]) => {
+                        let ([a, b], [c, d]) = (cell(part1), cell(part2));
+                        [day, a, b, c, d]
+                    }
+                    Err(err) => [
+                        day,
+                        (truncate(err.short()), true),
+                        empty(),
+                        empty(),
+                        empty(),
+                    ],
+                }
+            })
+            .collect();
+        let mut widths = [0; 5];
+        for row in std::iter::once(&header).chain(&rows) {
+            for (width, (text, _)) in widths.iter_mut().zip(row) {
+                *width = (*width).max(text.chars().count());
+            }
+        }
+        let header = format_row(&header, &widths, colored);
+        writeln!(f, "{}", color::bold(header).when(colored))?;
+        for row in &rows {
+            writeln!(f, "{}", format_row(row, &widths, colored))?;
+        }
+        Ok(())
+    }
+}
+
+/// Pads the cells to their column widths, with the day right-aligned.
+fn format_row(row: &[(String, bool); 5], widths: &[usize; 5], colored: bool) -> String {
+    let mut line = String::new();
+    for (i, ((text, failed), &width)) in row.iter().zip(widths).enumerate() {
+        let padded = if i == 0 {
+            format!("{text:>width$}")
+        } else {
+            format!("  {text:<width$}")
+        };
+        line += &Color::Red
+            .paint(padded)
+            .when(colored && *failed)
+            .to_string();
+    }
+    line.trim_end().to_owned()
+}
+
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "unknown panic payload".to_owned(),
+        },
+    }
+}
+
+struct Entry {
+    solution: Box<dyn ErasedSolution>,
+    example: Option<Answers>,
+    answers: Option<Answers>,
+}
+
+/// The registered days and where their inputs are, usually created with [`days!`].
+pub struct Runner {
+    days: BTreeMap<u32, Entry>,
+    /// The days left out by [`days!`] with the feature that would compile them.
+    not_compiled: BTreeMap<u32, String>,
+    layout: InputLayout,
+}
+
+/// Where the inputs of the days are found, shared by the [`Runner`] and
+/// [`answers::verify_all`] so that both resolve them the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputLayout {
+    input_dir: PathBuf,
+    fixture_dir: Option<PathBuf>,
+}
+impl Default for InputLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl InputLayout {
+    /// Inputs are read from `input/` relative to the working directory, without fixtures.
+    pub fn new() -> Self {
+        Self {
+            input_dir: PathBuf::from("input"),
+            fixture_dir: None,
+        }
+    }
+
+    pub fn input_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.input_dir = dir.into();
+        self
+    }
+
+    /// See [`Runner::fixture_dir`].
+    pub fn fixture_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.fixture_dir = Some(dir.into());
+        self
+    }
+
+    /// The files that are tried in order for an input of `day` in the input directory.
+    pub fn input_paths(&self, day: u32, kind: InputKind) -> Vec<PathBuf> {
+        let names = match kind {
+            InputKind::Real => vec![format!("day{day:02}.txt")],
+            InputKind::Example => vec![format!("day{day:02}_example.txt")],
+            InputKind::Sample(1) => vec![
+                format!("day{day:02}.sample.txt"),
+                format!("day{day:02}.sample1.txt"),
+            ],
+            InputKind::Sample(n) => vec![format!("day{day:02}.sample{n}.txt")],
+        };
+        names.into_iter().map(|n| self.input_dir.join(n)).collect()
+    }
+
+    /// The first existing file of [`InputLayout::input_paths`], falling back to the fixture
+    /// for a missing real input. Missing all is a [`RunError::MissingInput`] with every path
+    /// tried.
+    pub fn resolve(&self, day: u32, kind: InputKind) -> Result<ResolvedInput, RunError> {
+        let mut paths: Vec<(PathBuf, bool)> = self
+            .input_paths(day, kind)
+            .into_iter()
+            .map(|path| (path, false))
+            .collect();
+        if let (InputKind::Real, Some(dir)) = (kind, &self.fixture_dir) {
+            paths.push((dir.join(format!("day{day:02}.txt")), true));
+        }
+        paths
+            .iter()
+            .find_map(|(path, sample)| {
+                Some(ResolvedInput {
+                    path: path.clone(),
+                    text: fs::read_to_string(path).ok()?,
+                    sample: *sample,
+                })
+            })
+            .ok_or_else(|| RunError::MissingInput(paths.into_iter().map(|(p, _)| p).collect()))
+    }
+
+    /// The expected answers of the fixture of `day`, from a sidecar like `{"part1": 142,
+    /// "part2": "abc"}`. `None` without a fixture directory or sidecar, a sidecar that exists
+    /// but can't be read is a [`RunError::BadSidecar`]. The year isn't known and left at 0.
+    pub fn expected_answers_for_fixture(&self, day: u32) -> Result<Option<Answers>, RunError> {
+        let Some(dir) = &self.fixture_dir else {
+            return Ok(None);
+        };
+        let path = dir.join(format!("day{day:02}.answers.json"));
+        let error = |message: String| RunError::BadSidecar {
+            path: path.clone(),
+            message,
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(error(err.to_string())),
+        };
+        let JsonLite::Object(fields) = JsonLite::parse(&text).map_err(|e| error(e.to_string()))?
+        else {
+            return Err(error("expected an object".to_owned()));
+        };
+        let mut answers = Answers {
+            year: 0,
+            day,
+            part1: None,
+            part2: None,
+        };
+        for (key, value) in fields {
+            let answer = match value {
+                JsonLite::Num(n) => n.to_string(),
+                JsonLite::Str(s) => s,
+                other => return Err(error(format!("answer {key:?} is {other:?}"))),
+            };
+            match key.as_str() {
+                "part1" => answers.part1 = Some(answer),
+                "part2" => answers.part2 = Some(answer),
+                _ => return Err(error(format!("unknown key {key:?}"))),
+            }
+        }
+        Ok(Some(answers))
+    }
+}
+
+/// The input of a day found by [`Runner::input_or_fixture`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedInput {
+    pub path: PathBuf,
+    pub text: String,
+    /// Whether this is the fixture because the real input is missing.
+    pub sample: bool,
+}
+
+/// An input read for a run with the answers to check it against.
+struct Loaded {
+    input: ResolvedInput,
+    expected: Option<Answers>,
+}
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Runner {
+    /// Inputs are read from `input/` relative to the working directory, which is the crate root
+    /// under `cargo run`.
+    pub fn new() -> Self {
+        Self {
+            days: BTreeMap::new(),
+            not_compiled: BTreeMap::new(),
+            layout: InputLayout::new(),
+        }
+    }
+
+    pub fn input_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.layout = self.layout.input_dir(dir);
+        self
+    }
+
+    /// Registers a [`Solution`], which includes every [`Day`].
+    pub fn day(mut self, day: u32, solution: impl Solution + 'static) -> Self {
+        self.days.insert(
+            day,
+            Entry {
+                solution: Box::new(solution),
+                example: None,
+                answers: None,
+            },
+        );
+        self
+    }
+
+    /// Where sample fixtures `dayNN.txt` are bundled, usually `fixtures/`, for sharing the
+    /// solutions without the real inputs. Real inputs that are missing fall back to them, with
+    /// the answers in `dayNN.answers.json` checked if there are any.
+    pub fn fixture_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.layout = self.layout.fixture_dir(dir);
+        self
+    }
+
+    pub fn layout(&self) -> &InputLayout {
+        &self.layout
+    }
+
+    /// Records that `day` exists but was left out of the build, running it then asks for
+    /// `feature`. Used by [`days!`] for gated days.
+    pub fn not_compiled(mut self, day: u32, feature: &str) -> Self {
+        self.not_compiled.insert(day, feature.to_owned());
+        self
+    }
+
+    /// The registered days that were compiled in, in order.
+    pub fn compiled_days(&self) -> Vec<u32> {
+        self.days.keys().copied().collect()
+    }
+
+    fn entry(&self, day: u32) -> Result<&Entry, RunError> {
+        self.days
+            .get(&day)
+            .ok_or_else(|| match self.not_compiled.get(&day) {
+                Some(feature) => RunError::NotCompiled {
+                    day,
+                    feature: feature.clone(),
+                },
+                None => RunError::UnknownDay(day),
+            })
+    }
+
+    /// The expected answers for the example of an already registered day, created with
+    /// [`answers!`](crate::answers!). Parts without an answer aren't checked, and neither are
+    /// days that weren't compiled in.
+    pub fn example_answers(mut self, answers: Answers) -> Self {
+        if self.not_compiled.contains_key(&answers.day) {
+            return self;
+        }
+        let entry = self
+            .days
+            .get_mut(&answers.day)
+            .unwrap_or_else(|| panic!("day {} has to be registered first", answers.day));
+        entry.example = Some(answers);
+        self
+    }
+
+    /// The known answers for the real input of an already registered day, checked by
+    /// [`Runner::verify_all`]. Like [`Runner::example_answers`], days that weren't compiled in
+    /// are skipped.
+    pub fn answers(mut self, answers: Answers) -> Self {
+        if self.not_compiled.contains_key(&answers.day) {
+            return self;
+        }
+        let entry = self
+            .days
+            .get_mut(&answers.day)
+            .unwrap_or_else(|| panic!("day {} has to be registered first", answers.day));
+        entry.answers = Some(answers);
+        self
+    }
+
+    /// The compiled days with known [`Runner::answers`] for [`answers::verify_all`], so that
+    /// the checked days always match the ones [`days!`] registered.
+    pub fn registry(&self) -> Registry<'_> {
+        let mut registry = Registry::new();
+        for entry in self.days.values() {
+            let Some(answers) = &entry.answers else {
+                continue;
+            };
+            registry.add(answers.clone(), move |input: &str| {
+                let parsed = entry.solution.parse_any(input);
+                (
+                    entry.solution.run_part(1, input, &*parsed),
+                    entry.solution.run_part(2, input, &*parsed),
+                )
+            });
+        }
+        registry
+    }
+
+    /// Checks every day of [`Runner::registry`] on its real input, or on the fixture with its
+    /// answers if the real input is missing.
+    pub fn verify_all(&self) -> VerifyReport {
+        answers::verify_all(&self.layout, &self.registry())
+    }
+
+    /// The files that are tried in order for an input of `day` in the input directory.
+    pub fn input_paths(&self, day: u32, kind: InputKind) -> Vec<PathBuf> {
+        self.layout.input_paths(day, kind)
+    }
+
+    /// The real input of `day`, or the fixture if there is none and a fixture directory is set.
+    /// Missing both is a [`RunError::MissingInput`] with every path tried.
+    pub fn input_or_fixture(&self, day: u32) -> Result<ResolvedInput, RunError> {
+        self.resolve(day, InputKind::Real)
+    }
+
+    /// See [`InputLayout::expected_answers_for_fixture`].
+    pub fn expected_answers_for_fixture(&self, day: u32) -> Result<Option<Answers>, RunError> {
+        self.layout.expected_answers_for_fixture(day)
+    }
+
+    fn resolve(&self, day: u32, kind: InputKind) -> Result<ResolvedInput, RunError> {
+        self.entry(day)?;
+        self.layout.resolve(day, kind)
+    }
+
+    /// The input with the example answers for [`InputKind::Example`] or the fixture answers
+    /// when it fell back to the fixture.
+    fn load(&self, day: u32, kind: InputKind) -> Result<Loaded, RunError> {
+        let input = self.resolve(day, kind)?;
+        let expected = if input.sample {
+            self.expected_answers_for_fixture(day)?
+        } else if kind == InputKind::Example {
+            self.entry(day)?.example.clone()
+        } else {
+            None
+        };
+        Ok(Loaded { input, expected })
+    }
+
+    /// Parses the input and runs both parts with their panics caught, so that the second part
+    /// still runs if the first one panics. Returns the parsing time for solutions that parse.
+    /// Answers that differ from `expected` are errors, of the fixture if `sample` is set.
+    fn run_parts(
+        &self,
+        day: u32,
+        input: &str,
+        expected: Option<&Answers>,
+        sample: bool,
+    ) -> Result<(Option<Duration>, [PartOutcome; 2]), RunError> {
+        let entry = self.entry(day)?;
+        let start = Instant::now();
+        let parsed = panic::catch_unwind(AssertUnwindSafe(|| entry.solution.parse_any(input)))
+            .map_err(|payload| RunError::Panicked {
+                day,
+                part: 0,
+                message: panic_message(payload),
+            })?;
+        let parse_time = entry.solution.has_parse_time().then(|| start.elapsed());
+        let run_part = |part: u8| {
+            let start = Instant::now();
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+                entry.solution.run_part(part, input, &*parsed)
+            }));
+            let time = start.elapsed();
+            let answer = answer.map_err(|payload| RunError::Panicked {
+                day,
+                part,
+                message: panic_message(payload),
+            })?;
+            let expected =
+                expected.and_then(|a| if part == 1 { &a.part1 } else { &a.part2 }.as_ref());
+            if let Some(expected) = expected.filter(|e| **e != answer) {
+                let (expected, actual) = (expected.clone(), answer);
+                return Err(if sample {
+                    RunError::FixtureMismatch {
+                        day,
+                        part,
+                        expected,
+                        actual,
+                    }
+                } else {
+                    RunError::ExampleMismatch {
+                        day,
+                        part,
+                        expected,
+                        actual,
+                    }
+                });
+            }
+            Ok((answer, time))
+        };
+        Ok((parse_time, [run_part(1), run_part(2)]))
+    }
+
+    /// Runs both parts of `day` on the first existing file of [`Runner::input_paths`], checking
+    /// the example answers for [`InputKind::Example`]. A panic in a part is returned as
+    /// [`RunError::Panicked`].
+    pub fn run(&self, day: u32, kind: InputKind) -> Result<DayResult, RunError> {
+        let Loaded { input, expected } = self.load(day, kind)?;
+        let (parse, [part1, part2]) =
+            self.run_parts(day, &input.text, expected.as_ref(), input.sample)?;
+        Ok(DayResult {
+            day,
+            input: Some(input.path),
+            sample: input.sample,
+            parse,
+            part1: part1?,
+            part2: part2?,
+        })
+    }
+
+    /// Runs both parts of `day` on `input` directly, without touching the file system.
+    pub fn run_str(&self, day: u32, input: &str) -> Result<DayResult, RunError> {
+        let (parse, [part1, part2]) = self.run_parts(day, input, None, false)?;
+        Ok(DayResult {
+            day,
+            input: None,
+            sample: false,
+            parse,
+            part1: part1?,
+            part2: part2?,
+        })
+    }
+
+    /// Runs every registered day, continuing after failures instead of stopping at the first
+    /// one. Panics are caught per part, including those that a day's worker threads forward
+    /// to it when they are joined.
+    pub fn run_all(&self, kind: InputKind) -> Summary {
+        let rows = self
+            .days
+            .keys()
+            .map(|&day| {
+                let loaded = self.load(day, kind);
+                let sample = loaded.as_ref().is_ok_and(|l| l.input.sample);
+                let outcome = loaded.and_then(|Loaded { input, expected }| {
+                    self.run_parts(day, &input.text, expected.as_ref(), input.sample)
+                });
+                let (parse, parts) = match outcome {
+                    Ok((parse, parts)) => (parse, Ok(parts)),
+                    Err(err) => (None, Err(err)),
+                };
+                SummaryRow {
+                    day,
+                    sample,
+                    parse,
+                    parts,
+                }
+            })
+            .collect();
+        Summary { rows }
+    }
+
+    /// Runs the days selected by `args` (without the program name) and prints their results,
+    /// or a [`Summary`] for `--all`.
+    pub fn run_args(
+        &self,
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Vec<DayResult>, RunError> {
+        let mut args: Vec<String> = args.into_iter().collect();
+        let mut kind = InputKind::from_env()?.unwrap_or(InputKind::Real);
+        if args.first().is_some_and(|a| a == "example") {
+            args.remove(0);
+            kind = InputKind::Example;
+        }
+        if let Some(i) = args.iter().position(|a| a == "--sample") {
+            if kind == InputKind::Example {
+                return Err(RunError::Usage(
+                    "example and --sample select different inputs".to_owned(),
+                ));
+            }
+            args.remove(i);
+            let n = match args.get(i).map(|n| n.parse()) {
+                Some(Ok(n)) => {
+                    args.remove(i);
+                    n
+                }
+                _ => 1,
+            };
+            kind = InputKind::Sample(n);
+        }
+        let days: Vec<u32> = match args.as_slice() {
+            [all] if all == "--all" => {
+                let summary = self.run_all(kind);
+                print!("{summary}");
+                let failed = summary.failed_count();
+                if failed > 0 {
+                    return Err(RunError::Failed {
+                        failed,
+                        total: summary.rows.len(),
+                    });
+                }
+                return Ok(summary.into_results());
+            }
+            [day] => vec![day
+                .parse()
+                .map_err(|_| RunError::Usage(format!("invalid day {day:?}")))?],
+            _ => return Err(RunError::Usage("expected a day or --all".to_owned())),
+        };
+        days.into_iter()
+            .map(|day| {
+                let result = self.run(day, kind)?;
+                print!("{result}");
+                Ok(result)
+            })
+            .collect()
+    }
+
+    /// The entry point for a binary: runs the days given on the command line and exits with an
+    /// error message and a nonzero status if something is missing or failed.
+    pub fn run_cli(&self) {
+        if let Err(err) = self.run_args(std::env::args().skip(1)) {
+            eprintln!(
+                "{} {err}",
+                Color::Red.paint_bold("error:").when(color::enabled())
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, path::Path, rc::Rc};
+
+    use super::*;
+    use crate::answers;
+
+    fn count_lines(input: &str) -> usize {
+        input.lines().count()
+    }
+
+    fn runner(dir: &Path) -> Runner {
+        days![
+            1 => (count_lines, |input: &str| input.len()),
+            2 => (|_: &str| "a", |_: &str| 'b'),
+        ]
+        .input_dir(dir)
+        .example_answers(answers!(2024, 1, part1 = 2))
+    }
+
+    #[test]
+    fn runs_days() {
+        let dir = std::env::temp_dir().join(format!("aoch-runner-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "a\nb\nc\n").unwrap();
+        fs::write(dir.join("day01_example.txt"), "a\nb\n").unwrap();
+        let runner = runner(&dir);
+        let result = runner.run_args(["1".to_owned()]).unwrap();
+        assert_eq!(result[0].part1.0, "3");
+        assert_eq!(result[0].part2.0, "6");
+        let example = runner.run(1, InputKind::Example).unwrap();
+        assert_eq!(example.part1.0, "2");
+        assert_eq!(example.input, Some(dir.join("day01_example.txt")));
+
+        fs::write(dir.join("day01_example.txt"), "a\n").unwrap();
+        let err = runner.run(1, InputKind::Example).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 part 1 on the example: expected 2, got 1"
+        );
+        let err = runner.run_args(["--all".to_owned()]).unwrap_err();
+        assert!(matches!(
+            err,
+            RunError::Failed {
+                failed: 1,
+                total: 2
+            }
+        ));
+        let summary = runner.run_all(InputKind::Real);
+        assert!(matches!(
+            &summary.rows[1].parts,
+            Err(RunError::MissingInput(paths)) if *paths == [dir.join("day02.txt")]
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn friendly_errors() {
+        let runner = runner(Path::new("/nonexistent"));
+        let args = |args: &[&str]| runner.run_args(args.iter().map(|a| a.to_string()));
+        assert!(matches!(args(&["3"]), Err(RunError::UnknownDay(3))));
+        assert!(matches!(args(&["example", "x"]), Err(RunError::Usage(_))));
+        assert!(matches!(args(&[]), Err(RunError::Usage(_))));
+        let err = args(&["example", "2", "--sample"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("example and --sample select different inputs\n"));
+        let err = args(&["example", "2"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "can't read the input, looked for /nonexistent/day02_example.txt"
+        );
+        let err = args(&["2", "--sample"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "can't read the input, looked for /nonexistent/day02.sample.txt, \
+             /nonexistent/day02.sample1.txt"
+        );
+    }
+
+    #[test]
+    fn sample_files() {
+        let dir = std::env::temp_dir().join(format!("aoch-samples-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.sample1.txt"), "a\n").unwrap();
+        fs::write(dir.join("day01.sample2.txt"), "a\nb\n").unwrap();
+        let runner = runner(&dir);
+        let first = runner.run(1, InputKind::Sample(1)).unwrap();
+        assert_eq!(first.input, Some(dir.join("day01.sample1.txt")));
+        fs::write(dir.join("day01.sample.txt"), "").unwrap();
+        let first = runner.run(1, InputKind::Sample(1)).unwrap();
+        assert_eq!(first.input, Some(dir.join("day01.sample.txt")));
+        let args = ["1", "--sample", "2"].map(String::from);
+        let second = runner.run_args(args).unwrap();
+        assert_eq!(second[0].part1.0, "2");
+        assert!(second[0].to_string().contains("day01.sample2.txt"));
+        assert!(matches!(
+            runner.run(1, InputKind::Sample(3)),
+            Err(RunError::MissingInput(paths)) if paths == [dir.join("day01.sample3.txt")]
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn feature_gated_days() {
+        // `fetch` stands in for a day feature, it is off in a plain `cargo test`
+        let runner = days![
+            1 => (count_lines, count_lines),
+            2 => (count_lines, |input: &str| input.len()); feature = "fetch",
+            3 => (|_: &str| 3, |_: &str| 3); feature = "fetch",
+            4 => (|_: &str| 4, |_: &str| 4),
+        ]
+        .example_answers(answers!(2024, 2, part1 = 1))
+        .answers(answers!(2024, 1, part1 = 2))
+        .answers(answers!(2024, 2, part2 = 3));
+        let verified: Vec<u32> = runner
+            .verify_all()
+            .days
+            .iter()
+            .map(|&(_, day, _)| day)
+            .collect();
+        let result = runner.run_str(1, "a\nb\n").unwrap();
+        assert_eq!(result.part1.0, "2");
+        if cfg!(feature = "fetch") {
+            assert_eq!(runner.compiled_days(), [1, 2, 3, 4]);
+            assert_eq!(verified, [1, 2]);
+            assert_eq!(runner.run_str(2, "abc").unwrap().part2.0, "3");
+        } else {
+            assert_eq!(runner.compiled_days(), [1, 4]);
+            // the registry for verifying leaves out the same days
+            assert_eq!(verified, [1]);
+            let err = runner.run_str(3, "").unwrap_err();
+            assert!(
+                matches!(&err, RunError::NotCompiled { day: 3, feature } if feature == "fetch")
+            );
+            assert_eq!(
+                err.to_string(),
+                "day 3 not compiled in — enable feature fetch"
+            );
+            let err = runner.run_args(["2".to_owned()]).unwrap_err();
+            assert!(matches!(err, RunError::NotCompiled { day: 2, .. }));
+            assert!(runner
+                .run_all(InputKind::Real)
+                .rows
+                .iter()
+                .all(|row| row.day != 2));
+        }
+        assert!(matches!(
+            runner.run_str(5, ""),
+            Err(RunError::UnknownDay(5))
+        ));
+        assert_eq!(days![].compiled_days(), []);
+        let gated_only = days![9 => (count_lines, count_lines); feature = "fetch"];
+        assert_eq!(
+            gated_only.compiled_days().len(),
+            cfg!(feature = "fetch") as usize
+        );
+    }
+
+    /// The hook for checking the real inputs after refactoring shared helpers, run with
+    /// `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn verify_inputs() {
+        let report = runner(Path::new("input"))
+            .answers(answers!(2024, 1, part1 = 3, part2 = 6))
+            .verify_all();
+        print!("{report}");
+        assert!(report.all_passed());
+    }
+
+    #[test]
+    fn fixture_fallback() {
+        let dir = std::env::temp_dir().join(format!("aoch-fixtures-{}", std::process::id()));
+        let (inputs, fixtures) = (dir.join("input"), dir.join("fixtures"));
+        fs::create_dir_all(&inputs).unwrap();
+        fs::create_dir_all(&fixtures).unwrap();
+        fs::write(fixtures.join("day01.txt"), "a\nb\n").unwrap();
+        fs::write(
+            fixtures.join("day01.answers.json"),
+            r#"{"part1": 2, "part2": "4"}"#,
+        )
+        .unwrap();
+        let runner = runner(&inputs).fixture_dir(&fixtures);
+
+        let resolved = runner.input_or_fixture(1).unwrap();
+        assert_eq!(resolved.path, fixtures.join("day01.txt"));
+        assert!(resolved.sample);
+        let result = runner.run(1, InputKind::Real).unwrap();
+        assert!(result.sample);
+        assert!(result.to_string().contains("running on SAMPLE"));
+        let summary = runner.run_all(InputKind::Real);
+        assert!(summary.rows[0].sample && !summary.rows[0].failed());
+        assert!(summary
+            .to_string()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("1 SAMPLE"));
+        assert!(summary.into_results()[0].sample);
+        // the sidecar answers are checked
+        fs::write(fixtures.join("day01.answers.json"), r#"{"part2": 5}"#).unwrap();
+        let err = runner.run(1, InputKind::Real).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 part 2 on the fixture: expected 5, got 4"
+        );
+
+        // the real input wins, and it isn't checked against the fixture answers
+        fs::write(inputs.join("day01.txt"), "a\n").unwrap();
+        let resolved = runner.input_or_fixture(1).unwrap();
+        assert_eq!((resolved.text.as_str(), resolved.sample), ("a\n", false));
+        assert!(!runner.run(1, InputKind::Real).unwrap().sample);
+        // fixtures only stand in for real inputs
+        assert!(matches!(
+            runner.run(1, InputKind::Sample(1)),
+            Err(RunError::MissingInput(paths)) if paths.len() == 2
+        ));
+
+        let err = runner.input_or_fixture(2).unwrap_err();
+        assert!(matches!(
+            &err,
+            RunError::MissingInput(paths)
+                if *paths == [inputs.join("day02.txt"), fixtures.join("day02.txt")]
+        ));
+        assert_eq!(runner.expected_answers_for_fixture(2).unwrap(), None);
+        assert_eq!(
+            runner
+                .expected_answers_for_fixture(1)
+                .unwrap()
+                .unwrap()
+                .part2
+                .as_deref(),
+            Some("5")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sidecar_errors() {
+        let dir = std::env::temp_dir().join(format!("aoch-sidecars-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let runner = runner(Path::new("/nonexistent")).fixture_dir(&dir);
+        let sidecar = dir.join("day01.answers.json");
+        let error = |json: &str| {
+            fs::write(&sidecar, json).unwrap();
+            let err = runner.expected_answers_for_fixture(1).unwrap_err();
+            assert!(matches!(&err, RunError::BadSidecar { path, .. } if *path == sidecar));
+            err.to_string()
+        };
+        assert!(error(r#"{"part1": "#).starts_with(&format!(
+            "invalid answers in {}: at byte",
+            sidecar.display()
+        )));
+        assert!(error("[1, 2]").ends_with("expected an object"));
+        assert!(error(r#"{"part3": 1}"#).ends_with(r#"unknown key "part3""#));
+        assert!(error(r#"{"part1": true}"#).ends_with(r#"answer "part1" is Bool(true)"#));
+        fs::write(&sidecar, r#"{"part1": -3}"#).unwrap();
+        let answers = runner.expected_answers_for_fixture(1).unwrap().unwrap();
+        assert_eq!(
+            (answers.part1.as_deref(), answers.part2),
+            (Some("-3"), None)
+        );
+        assert_eq!(Runner::new().expected_answers_for_fixture(1).unwrap(), None);
+        // a sidecar that exists but can't be read isn't treated as missing
+        fs::remove_file(&sidecar).unwrap();
+        fs::create_dir(&sidecar).unwrap();
+        assert!(matches!(
+            runner.expected_answers_for_fixture(1),
+            Err(RunError::BadSidecar { path, .. }) if path == sidecar
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Sums the digits in part 1 and multiplies them in part 2.
+    struct Digits {
+        parses: Rc<Cell<u32>>,
+        /// Part 2 reads the digits in reverse and takes the first one instead.
+        reversed_part2: bool,
+    }
+    impl Solution for Digits {
+        type Parsed = Vec<u32>;
+
+        fn parse(&self, input: &str) -> Vec<u32> {
+            self.parses.set(self.parses.get() + 1);
+            input
+                .trim()
+                .chars()
+                .map(|c| c.to_digit(10).expect("not a digit"))
+                .collect()
+        }
+
+        fn part1(&self, digits: &Vec<u32>) -> impl Display {
+            digits.iter().sum::<u32>()
+        }
+
+        fn part2(&self, digits: &Vec<u32>) -> impl Display {
+            if self.reversed_part2 {
+                digits[0]
+            } else {
+                digits.iter().product()
+            }
+        }
+
+        fn parse_part2(&self, input: &str) -> Option<Vec<u32>> {
+            let reversed: String = input.chars().rev().collect();
+            self.reversed_part2.then(|| self.parse(&reversed))
+        }
+    }
+
+    #[test]
+    fn parses_once() {
+        let parses = Rc::new(Cell::new(0));
+        let digits = |reversed_part2| Digits {
+            parses: Rc::clone(&parses),
+            reversed_part2,
+        };
+        let runner = days![
+            1 => digits(false),
+            2 => digits(true),
+            3 => (count_lines, count_lines),
+        ];
+        let result = runner.run_str(1, "1234\n").unwrap();
+        assert_eq!(
+            (result.part1.0.as_str(), result.part2.0.as_str()),
+            ("10", "24")
+        );
+        assert_eq!(parses.get(), 1);
+        assert!(result.parse.is_some());
+        assert!(result.to_string().contains("  parse:  "));
+        let result = runner.run_str(2, "1234").unwrap();
+        assert_eq!(
+            (result.part1.0.as_str(), result.part2.0.as_str()),
+            ("10", "4")
+        );
+        assert_eq!(parses.get(), 3);
+        // plain days have no parsing of their own
+        let result = runner.run_str(3, "a\nb").unwrap();
+        assert_eq!(result.parse, None);
+        assert!(!result.to_string().contains("parse"));
+        // and get the input itself rather than a copy of it
+        let seen = Rc::new(Cell::new(0));
+        let seen_by_day = Rc::clone(&seen);
+        let part1 = move |input: &str| {
+            seen_by_day.set(input.as_ptr() as usize);
+            input.len()
+        };
+        let plain = days![1 => (part1, count_lines)];
+        let input = "a\nb";
+        plain.run_str(1, input).unwrap();
+        assert_eq!(seen.get(), input.as_ptr() as usize);
+
+        let err = runner.run_str(1, "12x").unwrap_err();
+        assert!(matches!(
+            err,
+            RunError::Panicked {
+                day: 1,
+                part: 0,
+                ..
+            }
+        ));
+        assert_eq!(err.to_string(), "day 1 panicked while parsing: not a digit");
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Shape {
+        Rock,
+        Paper,
+        Scissors,
+    }
+    impl Shape {
+        fn score(self, other: Self) -> u32 {
+            let outcome = match (self as u32 + 3 - other as u32) % 3 {
+                0 => 3,
+                1 => 6,
+                _ => 0,
+            };
+            self as u32 + 1 + outcome
+        }
+    }
+
+    fn strategy(input: &str) -> Vec<(Shape, u8)> {
+        input
+            .lines()
+            .map(|line| {
+                let (elf, me) = line.split_once(' ').unwrap();
+                let shape = [Shape::Rock, Shape::Paper, Shape::Scissors]
+                    [(elf.as_bytes()[0] - b'A') as usize];
+                (shape, me.as_bytes()[0] - b'X')
+            })
+            .collect()
+    }
+
+    /// The second column is my shape in part 1 and the outcome in part 2.
+    fn rps_score(rounds: &[(Shape, u8)], mode: Mode) -> u32 {
+        let shapes = [Shape::Rock, Shape::Paper, Shape::Scissors];
+        rounds
+            .iter()
+            .map(|&(elf, column)| {
+                let me = match mode {
+                    Mode::Part1 => shapes[column as usize],
+                    Mode::Part2 => shapes[(elf as usize + column as usize + 2) % 3],
+                };
+                me.score(elf)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn combinators() {
+        // (parses, part 1 runs, part 2 runs) of day 2
+        let calls = Rc::new(Cell::new((0, 0, 0)));
+        let count = |calls: &Rc<Cell<(u32, u32, u32)>>, add: (u32, u32, u32)| {
+            let (a, b, c) = calls.get();
+            calls.set((a + add.0, b + add.1, c + add.2));
+        };
+        let (parse_calls, part1_calls, part2_calls) =
+            (Rc::clone(&calls), Rc::clone(&calls), Rc::clone(&calls));
+        let runner = days![
+            2 => both_parts(
+                move |input: &str| {
+                    count(&parse_calls, (1, 0, 0));
+                    strategy(input)
+                },
+                move |rounds| {
+                    count(&part1_calls, (0, 1, 0));
+                    rps_score(rounds, Mode::Part1)
+                },
+                move |rounds| {
+                    count(&part2_calls, (0, 0, 1));
+                    rps_score(rounds, Mode::Part2)
+                },
+            ),
+            3 => with_param(strategy, |rounds, mode| rps_score(rounds, mode)),
+            4 => (count_lines, count_lines),
+        ];
+        let guide = "A Y\nB X\nC Z\n";
+        for day in [2, 3] {
+            let result = runner.run_str(day, guide).unwrap();
+            assert_eq!(
+                (result.part1.0.as_str(), result.part2.0.as_str()),
+                ("15", "12")
+            );
+            assert!(result.parse.is_some());
+        }
+        // the shared parse runs once and every part once per run
+        assert_eq!(calls.get(), (1, 1, 1));
+        runner.run_str(2, guide).unwrap();
+        assert_eq!(calls.get(), (2, 2, 2));
+        assert_eq!(runner.run_str(4, guide).unwrap().part2.0, "3");
+    }
+
+    #[test]
+    fn string_input() {
+        let runner = runner(Path::new("/nonexistent"));
+        let result = runner.run_str(1, "x\ny\n").unwrap();
+        assert_eq!(
+            (result.part1.0.as_str(), result.part2.0.as_str()),
+            ("2", "4")
+        );
+        assert_eq!(result.input, None);
+        assert!(result.to_string().starts_with("day 1 (string input)"));
+        assert!(matches!(
+            runner.run_str(9, ""),
+            Err(RunError::UnknownDay(9))
+        ));
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let dir = std::env::temp_dir().join(format!("aoch-panics-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for day in [1, 2] {
+            fs::write(dir.join(format!("day{day:02}.txt")), "a\nb\n").unwrap();
+        }
+        let runner = days![
+            1 => (count_lines, |input: &str| input.len()),
+            2 => (
+                |_: &str| -> u32 { panic!("index out of bounds: the len is 3 but the index is 7 in day 2") },
+                |input: &str| {
+                    // a panic in a worker thread reaches the day when the thread is joined
+                    std::thread::scope(|s| s.spawn(|| input.parse::<u32>().unwrap()).join())
+                        .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+                }
+            ),
+        ]
+        .input_dir(&dir);
+        let summary = runner.run_all(InputKind::Real);
+        assert_eq!(summary.failed_count(), 1);
+        assert!(!summary.rows[0].failed());
+        let Ok([part1, part2]) = &summary.rows[1].parts else {
+            panic!("day 2 should have run");
+        };
+        assert!(matches!(
+            part1,
+            Err(RunError::Panicked { day: 2, part: 1, message }) if message.starts_with("index out")
+        ));
+        assert!(matches!(part2, Err(RunError::Panicked { part: 2, .. })));
+        let table = summary.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("day  part 1"));
+        assert!(lines[1].starts_with("  1  2"));
+        assert!(lines[2].contains("panicked: index out of bounds: the len …"));
+        assert!(lines[2].contains("panicked: called `Result::unwrap()` on …"));
+
+        assert_eq!(summary.into_results().len(), 1);
+        let err = runner.run_args(["--all".to_owned()]).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 days failed");
+        assert!(matches!(
+            runner.run(2, InputKind::Real),
+            Err(RunError::Panicked { part: 1, .. })
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
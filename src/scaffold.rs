@@ -0,0 +1,162 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The template used when the project has no `templates/day.rs.tmpl`. `{{day}}` is replaced by
+/// the zero-padded day, `{{day_number}}` by the plain one and `{{year}}` by the year. The day
+/// runs through the [`Runner`](crate::runner::Runner) on `input/dayNN.txt`, downloading it first
+/// if the solutions crate has a `fetch` feature enabling `aoch/fetch`.
+pub const DEFAULT_TEMPLATE: &str = r#"use aoch::{answers, days, Runner};
+
+fn parse(input: &str) -> Vec<&str> {
+    input.lines().collect()
+}
+
+fn part1(input: &str) -> i64 {
+    let _lines = parse(input);
+    0
+}
+
+fn part2(input: &str) -> i64 {
+    let _lines = parse(input);
+    0
+}
+
+fn runner() -> Runner {
+    days![{{day_number}} => (part1, part2)].answers(answers!({{year}}, {{day_number}}))
+}
+
+fn main() {
+    #[cfg(feature = "fetch")]
+    if let Err(err) = aoch::fetch::fetch_input({{year}}, {{day_number}}) {
+        eprintln!("can't fetch input/day{{day}}.txt: {err}");
+    }
+    runner().run_cli();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 0);
+        assert_eq!(part2(EXAMPLE), 0);
+    }
+
+    /// Checks the answers given to `runner`, run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn verify() {
+        let report = runner().verify_all();
+        print!("{report}");
+        assert!(report.all_passed());
+    }
+}
+"#;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The target file exists and isn't empty.
+    AlreadyExists(PathBuf),
+    Io(io::Error),
+}
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyExists(path) => {
+                write!(
+                    f,
+                    "{} already exists, refusing to overwrite it",
+                    path.display()
+                )
+            }
+            Self::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+impl std::error::Error for ScaffoldError {}
+impl From<io::Error> for ScaffoldError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+pub fn render_template(template: &str, day: u32, year: u32) -> String {
+    template
+        .replace("{{day}}", &format!("{day:02}"))
+        .replace("{{day_number}}", &day.to_string())
+        .replace("{{year}}", &year.to_string())
+}
+
+/// Creates `src/bin/dayNN.rs` below the project `root` from `templates/day.rs.tmpl` or the
+/// [`DEFAULT_TEMPLATE`] and returns its path.
+pub fn scaffold(root: &Path, day: u32, year: u32) -> Result<PathBuf, ScaffoldError> {
+    let template = match fs::read_to_string(root.join("templates/day.rs.tmpl")) {
+        Ok(template) => template,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => DEFAULT_TEMPLATE.to_owned(),
+        Err(err) => return Err(err.into()),
+    };
+    let bin_dir = root.join("src/bin");
+    let path = bin_dir.join(format!("day{day:02}.rs"));
+    if fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+        return Err(ScaffoldError::AlreadyExists(path));
+    }
+    fs::create_dir_all(&bin_dir)?;
+    fs::write(&path, render_template(&template, day, year))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The default template rendered for 2024 day 7, included to check that it compiles.
+    #[allow(dead_code)]
+    mod day07 {
+        include!("scaffold/day07.rs");
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("aoch-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn generates_day() {
+        let root = temp_root("default");
+        let path = scaffold(&root, 7, 2024).unwrap();
+        assert_eq!(path, root.join("src/bin/day07.rs"));
+        let code = fs::read_to_string(&path).unwrap();
+        assert_eq!(code, include_str!("scaffold/day07.rs"));
+
+        assert!(matches!(
+            scaffold(&root, 7, 2024),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        fs::write(&path, "").unwrap();
+        assert!(scaffold(&root, 7, 2024).is_ok());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn custom_template() {
+        let root = temp_root("custom");
+        fs::create_dir_all(root.join("templates")).unwrap();
+        fs::write(
+            root.join("templates/day.rs.tmpl"),
+            "// {{year}} day {{day}}\n",
+        )
+        .unwrap();
+        let path = scaffold(&root, 12, 2022).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "// 2022 day 12\n");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
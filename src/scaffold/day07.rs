@@ -0,0 +1,49 @@
+use aoch::{answers, days, Runner};
+
+fn parse(input: &str) -> Vec<&str> {
+    input.lines().collect()
+}
+
+fn part1(input: &str) -> i64 {
+    let _lines = parse(input);
+    0
+}
+
+fn part2(input: &str) -> i64 {
+    let _lines = parse(input);
+    0
+}
+
+fn runner() -> Runner {
+    days![7 => (part1, part2)].answers(answers!(2024, 7))
+}
+
+fn main() {
+    #[cfg(feature = "fetch")]
+    if let Err(err) = aoch::fetch::fetch_input(2024, 7) {
+        eprintln!("can't fetch input/day07.txt: {err}");
+    }
+    runner().run_cli();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 0);
+        assert_eq!(part2(EXAMPLE), 0);
+    }
+
+    /// Checks the answers given to `runner`, run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn verify() {
+        let report = runner().verify_all();
+        print!("{report}");
+        assert!(report.all_passed());
+    }
+}
@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+
+/// Runs the tasks `0..n_tasks` on `workers` parallel workers, where `(a, b)` in `deps` means
+/// that `b` can only start once `a` is done. Whenever a worker is idle it takes the available
+/// task that comes first by `tie_break`. Returns the time at which everything is done and the
+/// `(task, start, end)` assignments in the order they were made.
+pub fn schedule_with_workers(
+    deps: &[(usize, usize)],
+    n_tasks: usize,
+    workers: usize,
+    duration: impl Fn(usize) -> u64,
+    tie_break: impl Fn(usize, usize) -> Ordering,
+) -> (u64, Vec<(usize, u64, u64)>) {
+    assert!(workers > 0, "tasks can't be scheduled without workers");
+    let mut blocked_by = vec![0; n_tasks];
+    let mut dependents = vec![Vec::new(); n_tasks];
+    for &(before, after) in deps {
+        blocked_by[after] += 1;
+        dependents[before].push(after);
+    }
+    let mut available: Vec<usize> = (0..n_tasks).filter(|&t| blocked_by[t] == 0).collect();
+    // (end, task) of the tasks that are being worked on
+    let mut running: Vec<(u64, usize)> = Vec::with_capacity(workers);
+    let mut assignments = Vec::with_capacity(n_tasks);
+    let mut time = 0;
+    while assignments.len() < n_tasks || !running.is_empty() {
+        available.sort_by(|&a, &b| tie_break(a, b));
+        let free = (workers - running.len()).min(available.len());
+        for task in available.drain(..free) {
+            let end = time + duration(task);
+            running.push((end, task));
+            assignments.push((task, time, end));
+        }
+        let Some(&(next, _)) = running.iter().min() else {
+            panic!("the dependencies of the remaining tasks contain a cycle");
+        };
+        time = next;
+        running.retain(|&(end, task)| {
+            if end != time {
+                return true;
+            }
+            for &after in &dependents[task] {
+                blocked_by[after] -= 1;
+                if blocked_by[after] == 0 {
+                    available.push(after);
+                }
+            }
+            false
+        });
+    }
+    (time, assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEPS: &str = "CA CF AB AD BE DE FE";
+
+    fn deps() -> Vec<(usize, usize)> {
+        STEPS
+            .split_whitespace()
+            .map(|s| {
+                let [a, b] = [0, 1].map(|i| (s.as_bytes()[i] - b'A') as usize);
+                (a, b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_workers() {
+        let (time, assignments) =
+            schedule_with_workers(&deps(), 6, 2, |t| t as u64 + 1, |a, b| a.cmp(&b));
+        assert_eq!(time, 15);
+        assert_eq!(assignments[0], (2, 0, 3));
+        assert_eq!(assignments.last(), Some(&(4, 10, 15)));
+    }
+
+    #[test]
+    fn sequential_order() {
+        let (time, assignments) = schedule_with_workers(&deps(), 6, 1, |_| 1, |a, b| a.cmp(&b));
+        assert_eq!(time, 6);
+        let order: String = assignments
+            .iter()
+            .map(|&(t, _, _)| (b'A' + t as u8) as char)
+            .collect();
+        assert_eq!(order, "CABDFE");
+    }
+
+    #[test]
+    #[should_panic(expected = "contain a cycle")]
+    fn cycle() {
+        schedule_with_workers(&[(0, 1), (1, 0)], 2, 1, |_| 1, |a, b| a.cmp(&b));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Grid, Side};
+
+/// Splits blank-line separated 5-pin schematics into lock and key pin heights. Locks have their
+/// top row filled, keys their bottom row, and the filled base row doesn't count as a pin.
+pub fn split_locks_and_keys(s: &str) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut locks = Vec::new();
+    let mut keys = Vec::new();
+    for block in s.split("\n\n").filter(|b| !b.trim().is_empty()) {
+        let grid = Grid::from_str_chars(block.trim()).map(|c| c == '#');
+        let top_full = grid.rows().next().is_some_and(|row| row.iter().all(|&c| c));
+        let (side, out) = if top_full {
+            (Side::T, &mut locks)
+        } else {
+            (Side::B, &mut keys)
+        };
+        out.push(
+            grid.column_heights_from(side)
+                .into_iter()
+                .map(|h| h.saturating_sub(1))
+                .collect(),
+        );
+    }
+    (locks, keys)
+}
+
+/// Counts the lock and key pairs whose heights add up to at most `max` in every column.
+pub fn count_fitting_pairs(locks: &[Vec<usize>], keys: &[Vec<usize>], max: usize) -> usize {
+    locks
+        .iter()
+        .flat_map(|lock| keys.iter().map(move |key| (lock, key)))
+        .filter(|(lock, key)| lock.iter().zip(key.iter()).all(|(l, k)| l + k <= max))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMATICS: &str = "#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####";
+
+    #[test]
+    fn locks_and_keys() {
+        let (locks, keys) = split_locks_and_keys(SCHEMATICS);
+        assert_eq!(locks, [vec![0, 5, 3, 4, 3], vec![1, 2, 0, 5, 3]]);
+        assert_eq!(
+            keys,
+            [
+                vec![5, 0, 2, 1, 3],
+                vec![4, 3, 4, 0, 2],
+                vec![3, 0, 2, 0, 1]
+            ]
+        );
+        assert_eq!(count_fitting_pairs(&locks, &keys, 5), 3);
+    }
+}
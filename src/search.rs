@@ -0,0 +1,400 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use vecm::Vec2i;
+
+use crate::{grid::DIRS4, Grid};
+
+/// Interned search states with their best known cost and predecessor.
+struct States<S> {
+    states: Vec<S>,
+    ids: HashMap<S, usize>,
+    best: Vec<u64>,
+    prev: Vec<usize>,
+}
+impl<S: Eq + Hash + Clone> States<S> {
+    fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            ids: HashMap::new(),
+            best: Vec::new(),
+            prev: Vec::new(),
+        }
+    }
+
+    /// Records `cost` for `state` if it is an improvement, returning the state's id.
+    fn relax(&mut self, state: &S, cost: u64, prev: usize) -> Option<usize> {
+        match self.ids.get(state) {
+            Some(&id) if self.best[id] <= cost => None,
+            Some(&id) => {
+                self.best[id] = cost;
+                self.prev[id] = prev;
+                Some(id)
+            }
+            None => {
+                let id = self.states.len();
+                self.states.push(state.clone());
+                self.ids.insert(state.clone(), id);
+                self.best.push(cost);
+                self.prev.push(prev);
+                Some(id)
+            }
+        }
+    }
+
+    fn path_to(&self, mut id: usize) -> Vec<S> {
+        let mut path = vec![self.states[id].clone()];
+        while self.prev[id] != usize::MAX {
+            id = self.prev[id];
+            path.push(self.states[id].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// A* over arbitrary states. `heuristic` has to be admissible (never overestimate the remaining
+/// cost) for the result to be optimal. Returns the cost of the cheapest goal and the states on
+/// the way there, including the start and the goal.
+pub fn astar<S: Eq + Hash + Clone>(
+    start: S,
+    mut successors: impl FnMut(&S) -> Vec<(S, u64)>,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(u64, Vec<S>)> {
+    let mut states = States::new();
+    let mut heap = BinaryHeap::new();
+    let start_id = states.relax(&start, 0, usize::MAX).unwrap();
+    heap.push(Reverse((heuristic(&start), 0, start_id)));
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        // stale entry, a cheaper way to this state was found after it was pushed
+        if cost > states.best[id] {
+            continue;
+        }
+        if is_goal(&states.states[id]) {
+            return Some((cost, states.path_to(id)));
+        }
+        for (next, step) in successors(&states.states[id]) {
+            let next_cost = cost + step;
+            if let Some(next_id) = states.relax(&next, next_cost, id) {
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, next_id)));
+            }
+        }
+    }
+    None
+}
+
+/// [`astar`] without a heuristic.
+pub fn dijkstra<S: Eq + Hash + Clone>(
+    start: S,
+    successors: impl FnMut(&S) -> Vec<(S, u64)>,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(u64, Vec<S>)> {
+    astar(start, successors, |_| 0, is_goal)
+}
+
+/// The cheapest cost of every state reachable from `start`.
+pub fn dijkstra_all<S: Eq + Hash + Clone>(
+    start: S,
+    mut successors: impl FnMut(&S) -> Vec<(S, u64)>,
+) -> HashMap<S, u64> {
+    let mut states = States::new();
+    let mut heap = BinaryHeap::new();
+    states.relax(&start, 0, usize::MAX);
+    heap.push(Reverse((0, 0)));
+    while let Some(Reverse((cost, id))) = heap.pop() {
+        if cost > states.best[id] {
+            continue;
+        }
+        for (next, step) in successors(&states.states[id]) {
+            let next_cost = cost + step;
+            if let Some(next_id) = states.relax(&next, next_cost, id) {
+                heap.push(Reverse((next_cost, next_id)));
+            }
+        }
+    }
+    states
+        .ids
+        .into_iter()
+        .map(|(s, id)| (s, states.best[id]))
+        .collect()
+}
+
+/// Shortest distance to a goal when every edge costs either 0 or 1, `true` marking the edges
+/// with cost 1. Zero-cost successors go to the front of the deque, so states still come out in
+/// order of their cost like in [`dijkstra`], without the heap.
+pub fn bfs01<S: Eq + Hash + Clone>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> Vec<(S, bool)>,
+    is_goal: impl Fn(&S) -> bool,
+) -> Option<u64> {
+    let mut best: HashMap<S, u64> = HashMap::new();
+    let mut deque = VecDeque::new();
+    best.insert(start.clone(), 0);
+    deque.push_back((start, 0));
+    let mut last_cost = 0;
+    while let Some((state, cost)) = deque.pop_front() {
+        debug_assert!(cost >= last_cost, "0-1 BFS popped states out of cost order");
+        last_cost = cost;
+        if cost > best[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some(cost);
+        }
+        for (next, costs_one) in neighbors(&state) {
+            let next_cost = cost + costs_one as u64;
+            if best.get(&next).is_some_and(|&b| b <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            if costs_one {
+                deque.push_back((next, next_cost));
+            } else {
+                deque.push_front((next, next_cost));
+            }
+        }
+    }
+    None
+}
+
+/// The cheapest way from `start` to `goal` through `(position, layer)` states of a grid with
+/// `L` layers, like the tool held while climbing. A step to a neighbor keeps the layer and costs
+/// `move_cost`, changing to another layer in place costs `switch_cost`. Only states where
+/// `allowed(pos, cell, layer)` holds are entered.
+pub fn dijkstra_layered<T, const L: usize>(
+    grid: &Grid<T>,
+    start: (Vec2i, usize),
+    goal: (Vec2i, usize),
+    move_cost: u64,
+    switch_cost: u64,
+    allowed: impl Fn(Vec2i, &T, usize) -> bool,
+) -> Option<u64> {
+    dijkstra_layered_by::<T, L>(
+        grid,
+        start,
+        goal,
+        move_cost,
+        |_, _, _, _| Some(switch_cost),
+        allowed,
+    )
+}
+
+/// Like [`dijkstra_layered`], with `switch(pos, cell, from, to)` giving the cost of changing
+/// layers at a position or `None` if that isn't possible there, for example picking up a key
+/// only on the cell it lies on.
+pub fn dijkstra_layered_by<T, const L: usize>(
+    grid: &Grid<T>,
+    start: (Vec2i, usize),
+    goal: (Vec2i, usize),
+    move_cost: u64,
+    switch: impl Fn(Vec2i, &T, usize, usize) -> Option<u64>,
+    allowed: impl Fn(Vec2i, &T, usize) -> bool,
+) -> Option<u64> {
+    assert!(
+        start.1 < L && goal.1 < L,
+        "layers have to be below {L}, got {} and {}",
+        start.1,
+        goal.1
+    );
+    let enterable =
+        |(pos, layer): (Vec2i, usize)| grid.get(pos).is_some_and(|cell| allowed(pos, cell, layer));
+    if !enterable(start) {
+        return None;
+    }
+    let successors = |&(pos, layer): &(Vec2i, usize)| {
+        let cell = &grid[pos];
+        let moves = DIRS4
+            .iter()
+            .map(|&(dx, dy)| (pos + Vec2i::new(dx, dy), layer))
+            .filter(|&next| enterable(next))
+            .map(|next| (next, move_cost));
+        let switches = (0..L)
+            .filter(|&to| to != layer && allowed(pos, cell, to))
+            .filter_map(|to| Some(((pos, to), switch(pos, cell, layer, to)?)));
+        moves.chain(switches).collect()
+    };
+    dijkstra(start, successors, |&state| state == goal).map(|(cost, _)| cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    /// `a -> d` directly is cheapest at the first step but the detour through `b` and `c` wins.
+    fn edges(node: &char) -> Vec<(char, u64)> {
+        match node {
+            'a' => vec![('d', 1), ('b', 2)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('e', 1)],
+            'd' => vec![('e', 10)],
+            'f' => vec![('a', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn greedy_is_not_optimal() {
+        assert_eq!(
+            dijkstra('a', edges, |&n| n == 'e'),
+            Some((5, vec!['a', 'b', 'c', 'e']))
+        );
+        let costs = dijkstra_all('a', edges);
+        assert_eq!(costs.len(), 5);
+        assert_eq!((costs[&'d'], costs[&'e']), (1, 5));
+        assert_eq!(dijkstra('a', edges, |&n| n == 'a'), Some((0, vec!['a'])));
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(dijkstra('a', edges, |&n| n == 'f'), None);
+        assert_eq!(astar('b', edges, |_| 0, |&n| n == 'a'), None);
+    }
+
+    #[test]
+    fn astar_on_line() {
+        // states are integers, moving by +-1 or jumping by +5 for a cost of 3
+        let successors = |&n: &i64| vec![(n + 1, 1), (n - 1, 1), (n + 5, 3)];
+        let goal = 23;
+        let heuristic = |&n: &i64| ((goal - n).max(0) as u64 * 3).div_ceil(5);
+        let (cost, path) = astar(0, successors, heuristic, |&n| n == goal).unwrap();
+        let (reference, _) = dijkstra(0, successors, |&n| n == goal).unwrap();
+        assert_eq!(cost, reference);
+        assert_eq!(cost, 15);
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&goal));
+    }
+
+    /// A random graph on `n` nodes where every node has a few edges of weight 0 or 1.
+    fn random_graph(rng: &mut Pcg32, n: usize, degree: usize) -> Vec<Vec<(usize, bool)>> {
+        (0..n)
+            .map(|_| {
+                (0..degree)
+                    .map(|_| (rng.index(n), rng.chance(0.5)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bfs01_matches_dijkstra() {
+        let mut rng = Pcg32::new(5);
+        for _ in 0..50 {
+            let n = rng.index(30) + 1;
+            let graph = random_graph(&mut rng, n, 3);
+            let goal = rng.index(n);
+            let expected = dijkstra(
+                0,
+                |&v| graph[v].iter().map(|&(w, one)| (w, one as u64)).collect(),
+                |&v| v == goal,
+            )
+            .map(|(cost, _)| cost);
+            assert_eq!(bfs01(0, |&v| graph[v].clone(), |&v| v == goal), expected);
+        }
+    }
+
+    #[test]
+    #[ignore = "timing comparison on a large graph"]
+    fn bfs01_beats_dijkstra() {
+        let mut rng = Pcg32::new(11);
+        let graph = random_graph(&mut rng, 200_000, 4);
+        let time = std::time::Instant::now();
+        let fast = bfs01(0, |&v| graph[v].clone(), |_| false);
+        let fast_time = time.elapsed();
+        let time = std::time::Instant::now();
+        let slow = dijkstra(
+            0,
+            |&v| graph[v].iter().map(|&(w, one)| (w, one as u64)).collect(),
+            |_| false,
+        );
+        let slow_time = time.elapsed();
+        assert_eq!(fast, None);
+        assert_eq!(slow, None);
+        assert!(fast_time < slow_time, "{fast_time:?} vs {slow_time:?}");
+    }
+
+    #[test]
+    fn cave_rescue() {
+        let (depth, target) = (510, Vec2i::new(10, 10));
+        let mut erosion = crate::lazy_grid::erosion_levels(depth, target);
+        let cave = erosion
+            .to_grid(crate::Rect::new(Vec2i::new(0, 0), Vec2i::new(30, 30)))
+            .map(|level| (level % 3) as u8);
+        // tools are neither, torch and climbing gear, each one unusable in the region type
+        // with the same number: rocky, wet and narrow
+        const TORCH: usize = 1;
+        let minutes = dijkstra_layered::<u8, 3>(
+            &cave,
+            (Vec2i::new(0, 0), TORCH),
+            (target, TORCH),
+            1,
+            7,
+            |_, &region, tool| region as usize != tool,
+        );
+        assert_eq!(minutes, Some(45));
+    }
+
+    #[test]
+    fn key_layers_match_expanded_graph() {
+        let mut rng = Pcg32::new(290);
+        for _ in 0..40 {
+            let (w, h) = (rng.index(8) + 2, rng.index(8) + 2);
+            let mut grid = Grid::from_fn(w, h, |_| match rng.below(10) {
+                0 | 1 => b'#',
+                2 => b'D',
+                _ => b'.',
+            });
+            let key = Vec2i::new(rng.index(w) as i32, rng.index(h) as i32);
+            grid[Vec2i::new(0, 0)] = b'.';
+            grid[key] = b'k';
+            let goal = Vec2i::new(w as i32 - 1, h as i32 - 1);
+            let allowed =
+                |_, &cell: &u8, has_key: usize| cell != b'#' && (cell != b'D' || has_key == 1);
+            let pick_up = |_, &cell: &u8, from: usize, _| (cell == b'k' && from == 0).then_some(0);
+            let layered = (0..2)
+                .filter_map(|layer| {
+                    dijkstra_layered_by::<u8, 2>(
+                        &grid,
+                        (Vec2i::new(0, 0), 0),
+                        (goal, layer),
+                        1,
+                        pick_up,
+                        allowed,
+                    )
+                })
+                .min();
+
+            // every (cell, layer) as a node of its own, with the edges listed up front
+            let node =
+                |pos: Vec2i, layer: usize| layer * w * h + pos.y as usize * w + pos.x as usize;
+            let mut edges = vec![Vec::new(); 2 * w * h];
+            for (pos, &cell) in grid.iter() {
+                for layer in 0..2 {
+                    if !allowed(pos, &cell, layer) {
+                        continue;
+                    }
+                    for (dx, dy) in DIRS4 {
+                        let next = pos + Vec2i::new(dx, dy);
+                        if grid.get(next).is_some_and(|c| allowed(next, c, layer)) {
+                            edges[node(pos, layer)].push((node(next, layer), 1));
+                        }
+                    }
+                }
+                if cell == b'k' {
+                    edges[node(pos, 0)].push((node(pos, 1), 0));
+                }
+            }
+            let expanded = dijkstra(
+                node(Vec2i::new(0, 0), 0),
+                |&n| edges[n].clone(),
+                |&n| n % (w * h) == node(goal, 0),
+            )
+            .map(|(cost, _)| cost);
+            assert_eq!(layered, expanded);
+        }
+    }
+}
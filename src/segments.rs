@@ -0,0 +1,126 @@
+/// Converts a pattern of the segments `a` to `g` into a bitmask, `a` being the lowest bit.
+pub fn segments_to_bits(s: &str) -> u8 {
+    s.trim().bytes().fold(0, |bits, b| {
+        assert!(
+            (b'a'..=b'g').contains(&b),
+            "invalid segment {:?} in {s:?}",
+            b as char
+        );
+        bits | 1 << (b - b'a')
+    })
+}
+
+/// Parses `<10 patterns> | <outputs>`.
+pub fn parse_entry(line: &str) -> ([u8; 10], Vec<u8>) {
+    let (patterns, outputs) = line
+        .split_once('|')
+        .unwrap_or_else(|| panic!("missing '|' in {line:?}"));
+    let patterns: Vec<u8> = patterns.split_whitespace().map(segments_to_bits).collect();
+    let patterns = patterns.try_into().unwrap_or_else(|p: Vec<u8>| {
+        panic!("expected 10 patterns but got {} in {line:?}", p.len())
+    });
+    let outputs = outputs.split_whitespace().map(segments_to_bits).collect();
+    (patterns, outputs)
+}
+
+/// Whether `pattern` has a unique number of segments, so it shows a 1, 4, 7 or 8.
+pub fn is_easy_digit(pattern: u8) -> bool {
+    matches!(pattern.count_ones(), 2 | 3 | 4 | 7)
+}
+
+pub fn count_easy_digits(outputs: &[u8]) -> usize {
+    outputs.iter().filter(|&&p| is_easy_digit(p)).count()
+}
+
+/// For each digit 0 to 9 the scrambled pattern showing it. Panics if the patterns aren't the
+/// ten digits of a seven-segment display under some wiring.
+pub fn deduce_segment_mapping(patterns: &[u8; 10]) -> [u8; 10] {
+    let find = |digit: usize, pred: &dyn Fn(u8) -> bool| {
+        let mut matching = patterns.iter().filter(|&&p| pred(p));
+        match (matching.next(), matching.next()) {
+            (Some(&p), None) => p,
+            _ => panic!("can't identify digit {digit} in {patterns:?}"),
+        }
+    };
+    let contains = |a: u8, b: u8| a & b == b;
+    let len = |p: u8| p.count_ones();
+    let one = find(1, &|p| len(p) == 2);
+    let four = find(4, &|p| len(p) == 4);
+    let seven = find(7, &|p| len(p) == 3);
+    let eight = find(8, &|p| len(p) == 7);
+    let nine = find(9, &|p| len(p) == 6 && contains(p, four));
+    let zero = find(0, &|p| {
+        len(p) == 6 && !contains(p, four) && contains(p, one)
+    });
+    let six = find(6, &|p| len(p) == 6 && !contains(p, one));
+    let three = find(3, &|p| len(p) == 5 && contains(p, one));
+    let five = find(5, &|p| len(p) == 5 && !contains(p, one) && contains(six, p));
+    let two = find(2, &|p| {
+        len(p) == 5 && !contains(p, one) && !contains(six, p)
+    });
+    [zero, one, two, three, four, five, six, seven, eight, nine]
+}
+
+/// The number shown by `outputs`, most significant digit first.
+pub fn decode_output(patterns: &[u8; 10], outputs: &[u8]) -> u64 {
+    let digits = deduce_segment_mapping(patterns);
+    outputs.iter().fold(0, |n, &output| {
+        let digit = digits
+            .iter()
+            .position(|&p| p == output)
+            .unwrap_or_else(|| panic!("output {output:#09b} isn't one of the patterns"));
+        n * 10 + digit as u64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str =
+        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+
+    #[test]
+    fn single_entry() {
+        let (patterns, outputs) = parse_entry(
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf",
+        );
+        assert_eq!(segments_to_bits("ab"), 0b11);
+        assert_eq!(deduce_segment_mapping(&patterns)[1], segments_to_bits("ab"));
+        assert_eq!(decode_output(&patterns, &outputs), 5353);
+    }
+
+    #[test]
+    fn sample_entries() {
+        let entries: Vec<_> = SAMPLE.lines().map(parse_entry).collect();
+        let easy: usize = entries.iter().map(|(_, out)| count_easy_digits(out)).sum();
+        assert_eq!(easy, 26);
+        let sum: u64 = entries.iter().map(|(p, out)| decode_output(p, out)).sum();
+        assert_eq!(sum, 61229);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid segment 'h'")]
+    fn invalid_segment() {
+        segments_to_bits("abh");
+    }
+
+    #[test]
+    #[should_panic(expected = "can't identify digit 4")]
+    fn invalid_patterns() {
+        let mut patterns = parse_entry(SAMPLE.lines().next().unwrap()).0;
+        // replace the 4 by a second 7
+        let four = patterns.iter().position(|p| p.count_ones() == 4).unwrap();
+        patterns[four] = segments_to_bits("bed");
+        deduce_segment_mapping(&patterns);
+    }
+}
@@ -0,0 +1,220 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::runner::panic_message;
+
+/// One step of a line diff from [`diff_lines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    /// A line both inputs share.
+    Equal(&'a str),
+    /// A line only in the first input.
+    Delete(&'a str),
+    /// A line only in the second input.
+    Insert(&'a str),
+}
+
+/// A minimal edit script turning the lines of `a` into the lines of `b`, keeping a longest
+/// common subsequence of lines. Where several scripts are minimal, deletions tend to come
+/// before insertions. Uses Hirschberg's divide and conquer, so only rows of common subsequence
+/// lengths are kept instead of the whole table.
+pub fn diff_lines<'a>(a: &'a str, b: &'a str) -> Vec<DiffOp<'a>> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    diff_into(&a, &b, &mut ops);
+    ops
+}
+
+/// `lens[j]` is the length of the longest common subsequence of `a` and `b[..j]`.
+fn lcs_lengths(a: &[&str], b: &[&str]) -> Vec<usize> {
+    let mut lens = vec![0; b.len() + 1];
+    for x in a {
+        let mut diagonal = 0;
+        for j in 1..=b.len() {
+            let above = lens[j];
+            lens[j] = if *x == b[j - 1] {
+                diagonal + 1
+            } else {
+                above.max(lens[j - 1])
+            };
+            diagonal = above;
+        }
+    }
+    lens
+}
+
+fn diff_into<'a>(a: &[&'a str], b: &[&'a str], ops: &mut Vec<DiffOp<'a>>) {
+    match a {
+        [] => ops.extend(b.iter().map(|line| DiffOp::Insert(line))),
+        _ if b.is_empty() => ops.extend(a.iter().map(|line| DiffOp::Delete(line))),
+        [line] => match b.iter().position(|l| l == line) {
+            Some(j) => {
+                ops.extend(b[..j].iter().map(|line| DiffOp::Insert(line)));
+                ops.push(DiffOp::Equal(line));
+                ops.extend(b[j + 1..].iter().map(|line| DiffOp::Insert(line)));
+            }
+            None => {
+                ops.push(DiffOp::Delete(line));
+                ops.extend(b.iter().map(|line| DiffOp::Insert(line)));
+            }
+        },
+        _ => {
+            let mid = a.len() / 2;
+            let front = lcs_lengths(&a[..mid], b);
+            let rev = |lines: &[&'a str]| -> Vec<&'a str> { lines.iter().rev().copied().collect() };
+            let back = lcs_lengths(&rev(&a[mid..]), &rev(b));
+            // the first split of b with the longest total, so deletions come first
+            let split = (0..=b.len())
+                .rev()
+                .max_by_key(|&j| front[j] + back[b.len() - j])
+                .unwrap();
+            diff_into(&a[..mid], &b[..split], ops);
+            diff_into(&a[mid..], &b[split..], ops);
+        }
+    }
+}
+
+/// A family of input mutations for [`probe_sensitivity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    /// Removes line `i`.
+    DropLine,
+    /// Swaps line `i` with line `i + 1`.
+    SwapAdjacent,
+    /// Replaces line `i` with an empty line.
+    BlankLine,
+}
+
+/// Applies every mutation of the kind to the input and returns the line index of each one that
+/// changes the answer of `solve`, along with the changed answer. Lines the solution never
+/// depends on don't show up, which helps to find the part of the input a parser gets wrong.
+/// A mutation that makes `solve` panic is reported with the panic message, while a panic on the
+/// unchanged input is passed on.
+pub fn probe_sensitivity<A: PartialEq>(
+    input: &str,
+    solve: impl Fn(&str) -> A,
+    mutate: MutationKind,
+) -> Vec<(usize, Result<A, String>)> {
+    let lines: Vec<&str> = input.lines().collect();
+    let newline = if input.ends_with('\n') { "\n" } else { "" };
+    let baseline = solve(input);
+    let count = match mutate {
+        MutationKind::SwapAdjacent => lines.len().saturating_sub(1),
+        MutationKind::DropLine | MutationKind::BlankLine => lines.len(),
+    };
+    (0..count)
+        .filter_map(|i| {
+            let mut mutated = lines.clone();
+            match mutate {
+                MutationKind::DropLine => {
+                    mutated.remove(i);
+                }
+                MutationKind::SwapAdjacent => mutated.swap(i, i + 1),
+                MutationKind::BlankLine => mutated[i] = "",
+            }
+            let mutated = mutated.join("\n") + newline;
+            let answer =
+                panic::catch_unwind(AssertUnwindSafe(|| solve(&mutated))).map_err(panic_message);
+            (answer.as_ref() != Ok(&baseline)).then_some((i, answer))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use DiffOp::*;
+
+    #[test]
+    fn edit_scripts() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nc\nd\ne"),
+            [Equal("a"), Delete("b"), Equal("c"), Equal("d"), Insert("e")]
+        );
+        assert_eq!(
+            diff_lines("x\ny", "y\nx"),
+            [Delete("x"), Equal("y"), Insert("x")]
+        );
+        assert_eq!(
+            diff_lines("a\nb", "c"),
+            [Delete("a"), Delete("b"), Insert("c")]
+        );
+        assert_eq!(diff_lines("", "a\n"), [Insert("a")]);
+        assert_eq!(diff_lines("same\n", "same"), [Equal("same")]);
+        // the script is minimal, the classic ABCABBA to CBABAC needs five edits
+        let ops = diff_lines("a\nb\nc\na\nb\nb\na", "c\nb\na\nb\na\nc");
+        assert_eq!(ops.iter().filter(|op| !matches!(op, Equal(_))).count(), 5);
+        let rebuilt: Vec<&str> = ops
+            .iter()
+            .filter_map(|op| match op {
+                Equal(line) | Insert(line) => Some(*line),
+                Delete(_) => None,
+            })
+            .collect();
+        assert_eq!(rebuilt, ["c", "b", "a", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn minimal_on_random_inputs() {
+        let mut rng = crate::Pcg32::new(302);
+        for _ in 0..200 {
+            let mut lines = |len: usize| -> Vec<&str> {
+                (0..rng.index(len))
+                    .map(|_| ["a", "b", "c"][rng.index(3)])
+                    .collect()
+            };
+            let (a, b) = (lines(12), lines(12));
+            // the common subsequence length from the full table
+            let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    table[i + 1][j + 1] = if a[i] == b[j] {
+                        table[i][j] + 1
+                    } else {
+                        table[i][j + 1].max(table[i + 1][j])
+                    };
+                }
+            }
+            let (a_text, b_text) = (a.join("\n"), b.join("\n"));
+            let ops = diff_lines(&a_text, &b_text);
+            let equal = ops.iter().filter(|op| matches!(op, Equal(_))).count();
+            assert_eq!(equal, table[a.len()][b.len()], "{a:?} {b:?}");
+            assert_eq!(ops.len(), a.len() + b.len() - equal);
+        }
+    }
+
+    /// Sums the first three lines, so only they matter.
+    fn first_half(input: &str) -> i64 {
+        input
+            .lines()
+            .take(3)
+            .map(|l| l.parse::<i64>().expect("not a number"))
+            .sum()
+    }
+
+    #[test]
+    fn finds_read_lines() {
+        let input = "1\n20\n300\n4000\n50000\n600000\n";
+        assert_eq!(
+            probe_sensitivity(input, first_half, MutationKind::DropLine),
+            [(0, Ok(4320)), (1, Ok(4301)), (2, Ok(4021))]
+        );
+        // blanking a summed line makes the parser panic, which is reported for that line only
+        let not_a_number = || Err("not a number: ParseIntError { kind: Empty }".to_owned());
+        assert_eq!(
+            probe_sensitivity(input, first_half, MutationKind::BlankLine),
+            [
+                (0, not_a_number()),
+                (1, not_a_number()),
+                (2, not_a_number())
+            ]
+        );
+        // reordering within the summed lines doesn't matter, only across the boundary
+        assert_eq!(
+            probe_sensitivity(input, first_half, MutationKind::SwapAdjacent),
+            [(2, Ok(4021))]
+        );
+        assert!(probe_sensitivity("", first_half, MutationKind::SwapAdjacent).is_empty());
+    }
+}
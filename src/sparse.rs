@@ -0,0 +1,326 @@
+use std::{collections::HashMap, fmt::Display};
+
+use vecm::Vec2i;
+
+use crate::{
+    geometry::line_between,
+    grid::{DIRS4, DIRS8},
+    Grid, Rect,
+};
+
+/// An unbounded grid storing only the cells that were set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2i, T>,
+}
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, pos: Vec2i) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2i) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Vec2i, value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Vec2i) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn get_or_insert_with(&mut self, pos: Vec2i, f: impl FnOnce() -> T) -> &mut T {
+        self.cells.entry(pos).or_insert_with(f)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The 4 orthogonal neighbors of `pos`, set or not since the grid has no edges.
+    pub fn neighbor_positions4(&self, pos: Vec2i) -> impl Iterator<Item = Vec2i> {
+        DIRS4
+            .into_iter()
+            .map(move |(dx, dy)| pos + Vec2i::new(dx, dy))
+    }
+
+    /// The 8 neighbors of `pos` including diagonals, set or not.
+    pub fn neighbor_positions8(&self, pos: Vec2i) -> impl Iterator<Item = Vec2i> {
+        DIRS8
+            .into_iter()
+            .map(move |(dx, dy)| pos + Vec2i::new(dx, dy))
+    }
+
+    /// The set cells among the 8 neighbors of `pos`.
+    pub fn neighbors8(&self, pos: Vec2i) -> impl Iterator<Item = (Vec2i, &T)> {
+        DIRS8.into_iter().filter_map(move |(dx, dy)| {
+            let p = pos + Vec2i::new(dx, dy);
+            self.cells.get(&p).map(|v| (p, v))
+        })
+    }
+
+    /// The smallest rectangle containing all set cells.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut positions = self.cells.keys();
+        let first = *positions.next()?;
+        Some(positions.fold(Rect::new(first, first), |r, &p| {
+            Rect::new(
+                Vec2i::new(r.min.x.min(p.x), r.min.y.min(p.y)),
+                Vec2i::new(r.max.x.max(p.x), r.max.y.max(p.y)),
+            )
+        }))
+    }
+
+    /// The set cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2i, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// The positions of the set cells in arbitrary order.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2i> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// A dense grid over the bounding box with `default` in the missing cells, and the sparse
+    /// position of its `(0, 0)` cell. An empty sparse grid becomes a single `default` cell at the
+    /// origin.
+    pub fn to_grid(&self, default: T) -> (Grid<T>, Vec2i)
+    where
+        T: Clone,
+    {
+        let Some(bounds) = self.bounds() else {
+            return (Grid::new(1, 1, default), Vec2i::new(0, 0));
+        };
+        let size = bounds.max - bounds.min + Vec2i::new(1, 1);
+        let grid = Grid::from_fn(size.x as usize, size.y as usize, |pos| {
+            self.cells
+                .get(&(pos + bounds.min))
+                .unwrap_or(&default)
+                .clone()
+        });
+        (grid, bounds.min)
+    }
+}
+impl<T> FromIterator<(Vec2i, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec2i, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Draws the bounding box row by row without separators, missing cells are shown as `.`.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(bounds) = self.bounds() else {
+            return Ok(());
+        };
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                match self.cells.get(&Vec2i::new(x, y)) {
+                    Some(cell) => write!(f, "{cell}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Keeps only the cells for which `is_empty` doesn't hold, at the same positions.
+    pub fn to_sparse(self, is_empty: impl Fn(&T) -> bool) -> SparseGrid<T> {
+        self.into_iter()
+            .filter(|(_, cell)| !is_empty(cell))
+            .collect()
+    }
+}
+
+/// Counts how many of the `(start, end)` segments cover each cell, skipping diagonal ones
+/// unless `include_diagonals` is set. Segments have to be horizontal, vertical or 45° diagonal.
+/// Counts into a dense buffer when the bounding box isn't much larger than the covered cells.
+pub fn rasterize_segments(segments: &[(Vec2i, Vec2i)], include_diagonals: bool) -> SparseGrid<u32> {
+    let segments: Vec<(Vec2i, Vec2i)> = segments
+        .iter()
+        .copied()
+        .filter(|(a, b)| include_diagonals || a.x == b.x || a.y == b.y)
+        .collect();
+    let Some(bounds) = segments
+        .iter()
+        .map(|&(a, b)| Rect::new(a, b))
+        .reduce(|r, s| {
+            Rect::new(
+                Vec2i::new(r.min.x.min(s.min.x), r.min.y.min(s.min.y)),
+                Vec2i::new(r.max.x.max(s.max.x), r.max.y.max(s.max.y)),
+            )
+        })
+    else {
+        return SparseGrid::new();
+    };
+    let covered: usize = segments
+        .iter()
+        .map(|(a, b)| (b.x - a.x).abs().max((b.y - a.y).abs()) as usize + 1)
+        .sum();
+    if bounds.area() <= 4 * covered {
+        rasterize_dense(&segments, bounds)
+    } else {
+        rasterize_sparse(&segments)
+    }
+}
+
+fn rasterize_sparse(segments: &[(Vec2i, Vec2i)]) -> SparseGrid<u32> {
+    let mut counts = SparseGrid::new();
+    for &(a, b) in segments {
+        for p in line_between(a, b) {
+            *counts.get_or_insert_with(p, || 0) += 1;
+        }
+    }
+    counts
+}
+
+fn rasterize_dense(segments: &[(Vec2i, Vec2i)], bounds: Rect) -> SparseGrid<u32> {
+    let mut counts = Grid::new(bounds.width(), bounds.height(), 0);
+    for &(a, b) in segments {
+        for p in line_between(a, b) {
+            counts[p - bounds.min] += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count != 0)
+        .map(|(p, count)| (p + bounds.min, count))
+        .collect()
+}
+
+/// The number of cells with a count of at least `n`.
+pub fn count_cells_with_at_least(grid: &SparseGrid<u32>, n: u32) -> usize {
+    grid.iter().filter(|&(_, &count)| count >= n).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::*;
+
+    #[test]
+    fn neighbors_and_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        grid.insert(Vec2i::new(0, 0), 1);
+        grid.insert(Vec2i::new(1, 1), 2);
+        grid.insert(Vec2i::new(-3, 0), 3);
+        let sum: i32 = grid.neighbors8(Vec2i::new(0, 1)).map(|(_, v)| v).sum();
+        assert_eq!(sum, 3);
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Vec2i::new(-3, 0), Vec2i::new(1, 1)))
+        );
+        assert_eq!(grid.remove(Vec2i::new(0, 0)), Some(1));
+        assert_eq!(grid.len(), 2);
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let mut sand = SparseGrid::new();
+        for x in -2..=0 {
+            sand.insert(Vec2i::new(x, -5), '#');
+        }
+        *sand.get_or_insert_with(Vec2i::new(-1, -6), || 'o') = '+';
+        assert_eq!(sand.get_or_insert_with(Vec2i::new(-1, -6), || 'x'), &'+');
+        assert_eq!(sand.to_string(), ".+.\n###\n");
+        let (grid, origin) = sand.to_grid('.');
+        assert_eq!(origin, Vec2i::new(-2, -6));
+        assert_eq!(grid, Grid::from_str_chars(".+.\n###"));
+        let mut back = grid.to_sparse(|&c| c == '.');
+        assert_eq!(back.len(), 4);
+        assert_eq!(back.remove(Vec2i::new(1, 0)), Some('+'));
+        assert_eq!(back.neighbor_positions8(Vec2i::new(1, 0)).count(), 8);
+        let mut open: Vec<Vec2i> = back
+            .neighbor_positions4(Vec2i::new(1, 0))
+            .filter(|&p| back.get(p).is_none())
+            .collect();
+        open.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(
+            open,
+            [Vec2i::new(1, -1), Vec2i::new(0, 0), Vec2i::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let grid: SparseGrid<char> = SparseGrid::new();
+        assert_eq!(grid.to_string(), "");
+        assert_eq!(grid.positions().count(), 0);
+        let (dense, origin) = grid.to_grid('.');
+        assert_eq!((dense.width(), dense.height()), (1, 1));
+        assert_eq!(origin, Vec2i::new(0, 0));
+    }
+
+    const VENTS: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    fn vents() -> Vec<(Vec2i, Vec2i)> {
+        let point = |p: &str| {
+            let (x, y) = p.split_once(',').unwrap();
+            Vec2i::new(x.parse().unwrap(), y.parse().unwrap())
+        };
+        VENTS
+            .lines()
+            .map(|l| {
+                let (a, b) = l.split_once(" -> ").unwrap();
+                (point(a), point(b))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vent_overlaps() {
+        let orthogonal = rasterize_segments(&vents(), false);
+        assert_eq!(count_cells_with_at_least(&orthogonal, 2), 5);
+        let all = rasterize_segments(&vents(), true);
+        assert_eq!(count_cells_with_at_least(&all, 2), 12);
+        assert_eq!(all.get(Vec2i::new(4, 4)), Some(&3));
+        assert!(rasterize_segments(&[], true).is_empty());
+    }
+
+    #[test]
+    fn dense_matches_sparse() {
+        let mut segments = vents();
+        segments.push((Vec2i::new(-1000, -1000), Vec2i::new(-1000, -998)));
+        let bounds = Rect::new(Vec2i::new(-1000, -1000), Vec2i::new(9, 9));
+        assert_eq!(
+            rasterize_dense(&segments, bounds),
+            rasterize_sparse(&segments)
+        );
+        assert_eq!(
+            rasterize_segments(&segments, true),
+            rasterize_sparse(&segments)
+        );
+    }
+}
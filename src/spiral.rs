@@ -0,0 +1,96 @@
+use vecm::Vec2i;
+
+use crate::SparseGrid;
+
+/// The positions of the square spiral starting at the origin and going right, then up
+/// (negative y), left and down with legs of length 1, 1, 2, 2, 3, 3, ...
+pub fn spiral() -> impl Iterator<Item = Vec2i> {
+    const DIRS: [(i32, i32); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
+    let mut pos = Vec2i::new(0, 0);
+    let (mut leg, mut leg_len, mut taken) = (0, 1, 0);
+    std::iter::once(pos).chain(std::iter::from_fn(move || {
+        let (dx, dy) = DIRS[leg % 4];
+        pos += Vec2i::new(dx, dy);
+        taken += 1;
+        if taken == leg_len {
+            taken = 0;
+            leg += 1;
+            if leg % 2 == 0 {
+                leg_len += 1;
+            }
+        }
+        Some(pos)
+    }))
+}
+
+/// Fills the spiral cell by cell with `f(pos, filled)`, which can look at all cells filled so
+/// far. Returns the first cell whose value satisfies `stop`.
+pub fn accumulate_spiral(
+    mut f: impl FnMut(Vec2i, &SparseGrid<u64>) -> u64,
+    stop: impl Fn(u64) -> bool,
+) -> (Vec2i, u64) {
+    let mut grid = SparseGrid::new();
+    for pos in spiral() {
+        let value = f(pos, &grid);
+        if stop(value) {
+            return (pos, value);
+        }
+        grid.insert(pos, value);
+    }
+    unreachable!("the spiral is infinite")
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::*;
+
+    fn neighbor_sum(pos: Vec2i, grid: &SparseGrid<u64>) -> u64 {
+        if pos == Vec2i::new(0, 0) {
+            return 1;
+        }
+        grid.neighbors8(pos).map(|(_, v)| v).sum()
+    }
+
+    #[test]
+    fn spiral_positions() {
+        let first: Vec<Vec2i> = spiral().take(10).collect();
+        let expected = [
+            (0, 0),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+        ];
+        assert_eq!(first, expected.map(|(x, y)| Vec2i::new(x, y)));
+        for (square, steps) in [(1, 0), (12, 3), (23, 2), (1024, 31)] {
+            let pos = spiral().nth(square - 1).unwrap();
+            assert_eq!(pos.x.abs() + pos.y.abs(), steps);
+        }
+    }
+
+    #[test]
+    fn neighbor_sums() {
+        let mut values = Vec::new();
+        accumulate_spiral(
+            |pos, grid| {
+                let v = neighbor_sum(pos, grid);
+                values.push(v);
+                v
+            },
+            |v| v > 57,
+        );
+        assert_eq!(values, [1, 1, 2, 4, 5, 10, 11, 23, 25, 26, 54, 57, 59]);
+        assert_eq!(accumulate_spiral(neighbor_sum, |v| v > 747).1, 806);
+        assert_eq!(
+            accumulate_spiral(neighbor_sum, |v| v > 58),
+            (Vec2i::new(2, -2), 59)
+        );
+    }
+}
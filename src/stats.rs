@@ -0,0 +1,240 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// The median of a multiset with insertions and removals, using a max-heap for the lower half
+/// and a min-heap for the upper half. Removed values are deleted lazily once they reach the top
+/// of their heap.
+#[derive(Debug, Clone, Default)]
+pub struct RunningMedian {
+    low: BinaryHeap<i64>,
+    high: BinaryHeap<Reverse<i64>>,
+    /// Live sizes of the heaps, not counting values waiting for deletion.
+    low_len: usize,
+    high_len: usize,
+    delayed: HashMap<i64, usize>,
+    counts: HashMap<i64, usize>,
+}
+impl RunningMedian {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.low_len + self.high_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn take_delayed(delayed: &mut HashMap<i64, usize>, value: i64) -> bool {
+        match delayed.get_mut(&value) {
+            Some(n) => {
+                *n -= 1;
+                if *n == 0 {
+                    delayed.remove(&value);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn prune(&mut self) {
+        while let Some(&top) = self.low.peek() {
+            if !Self::take_delayed(&mut self.delayed, top) {
+                break;
+            }
+            self.low.pop();
+        }
+        while let Some(&Reverse(top)) = self.high.peek() {
+            if !Self::take_delayed(&mut self.delayed, top) {
+                break;
+            }
+            self.high.pop();
+        }
+    }
+
+    /// Keeps `low_len` equal to `high_len` or one larger.
+    fn rebalance(&mut self) {
+        self.prune();
+        if self.low_len > self.high_len + 1 {
+            let v = self.low.pop().unwrap();
+            self.high.push(Reverse(v));
+            self.low_len -= 1;
+            self.high_len += 1;
+        } else if self.high_len > self.low_len {
+            let Reverse(v) = self.high.pop().unwrap();
+            self.low.push(v);
+            self.high_len -= 1;
+            self.low_len += 1;
+        }
+        self.prune();
+    }
+
+    pub fn insert(&mut self, value: i64) {
+        *self.counts.entry(value).or_insert(0) += 1;
+        if self.low.peek().is_none_or(|&top| value <= top) {
+            self.low.push(value);
+            self.low_len += 1;
+        } else {
+            self.high.push(Reverse(value));
+            self.high_len += 1;
+        }
+        self.rebalance();
+    }
+
+    /// Removes one occurrence of `value`, returns false if there is none.
+    pub fn remove(&mut self, value: i64) -> bool {
+        let Some(count) = self.counts.get_mut(&value) else {
+            return false;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(&value);
+        }
+        *self.delayed.entry(value).or_insert(0) += 1;
+        // the top of `low` is live after pruning and everything in `high` is at least as large
+        if self.low.peek().is_some_and(|&top| value <= top) {
+            self.low_len -= 1;
+        } else {
+            self.high_len -= 1;
+        }
+        self.rebalance();
+        true
+    }
+
+    /// The lower of the two middle values for an even count.
+    pub fn median_low(&self) -> Option<i64> {
+        (!self.is_empty()).then(|| *self.low.peek().unwrap())
+    }
+
+    /// The upper of the two middle values for an even count.
+    pub fn median_high(&self) -> Option<i64> {
+        if self.low_len == self.high_len {
+            self.high.peek().map(|&Reverse(v)| v)
+        } else {
+            self.median_low()
+        }
+    }
+
+    /// The mean of the two middle values for an even count.
+    pub fn median(&self) -> Option<f64> {
+        Some((self.median_low()? as f64 + self.median_high()? as f64) / 2.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    pub count: usize,
+    pub sum: i128,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: i64) {
+        self.count += 1;
+        self.sum += value as i128;
+        self.min = Some(self.min.map_or(value, |m| m.min(value)));
+        self.max = Some(self.max.map_or(value, |m| m.max(value)));
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+    }
+}
+impl FromIterator<i64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = i64>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        for value in iter {
+            stats.push(value);
+        }
+        stats
+    }
+}
+
+/// The meeting point minimizing the summed `cost(distance)` over all positions, with that total.
+/// `cost` has to be convex like the linear (where the median is optimal) or triangular cost,
+/// which makes the total convex as well, so a binary search on its slope finds the smallest
+/// optimal point.
+pub fn best_alignment_cost(positions: &[i64], cost: impl Fn(i64) -> i64) -> (i64, i64) {
+    assert!(!positions.is_empty(), "no positions to align");
+    let total = |at: i64| -> i64 { positions.iter().map(|&p| cost((p - at).abs())).sum() };
+    let (mut lo, mut hi) = (
+        *positions.iter().min().unwrap(),
+        *positions.iter().max().unwrap(),
+    );
+    // the smallest point where the total stops decreasing
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if total(mid) <= total(mid + 1) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    (lo, total(lo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    #[test]
+    fn crab_alignment() {
+        let crabs = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        assert_eq!(best_alignment_cost(&crabs, |d| d), (2, 37));
+        assert_eq!(best_alignment_cost(&crabs, |d| d * (d + 1) / 2), (5, 168));
+        let mut median = RunningMedian::new();
+        crabs.iter().for_each(|&c| median.insert(c));
+        assert_eq!(median.median_low(), Some(2));
+        assert_eq!(median.median(), Some(2.0));
+    }
+
+    #[test]
+    fn median_with_removals() {
+        let mut rng = Pcg32::new(8);
+        let mut median = RunningMedian::new();
+        let mut reference: Vec<i64> = Vec::new();
+        for _ in 0..2000 {
+            if !reference.is_empty() && rng.chance(0.4) {
+                let v = reference.remove(rng.index(reference.len()));
+                assert!(median.remove(v));
+            } else {
+                let v = rng.below(50) as i64 - 25;
+                median.insert(v);
+                reference.push(v);
+            }
+            reference.sort_unstable();
+            let n = reference.len();
+            assert_eq!(median.len(), n);
+            if n == 0 {
+                assert_eq!(median.median(), None);
+                continue;
+            }
+            let (low, high) = (reference[(n - 1) / 2], reference[n / 2]);
+            assert_eq!(
+                (median.median_low(), median.median_high()),
+                (Some(low), Some(high))
+            );
+            assert_eq!(median.median(), Some((low + high) as f64 / 2.0));
+        }
+        assert!(!median.remove(1000));
+    }
+
+    #[test]
+    fn stats() {
+        let stats: RunningStats = [3, -1, 10, 4].into_iter().collect();
+        assert_eq!((stats.count, stats.sum), (4, 16));
+        assert_eq!((stats.min, stats.max), (Some(-1), Some(10)));
+        assert_eq!(stats.mean(), Some(4.0));
+        assert_eq!(RunningStats::new().mean(), None);
+    }
+}
@@ -0,0 +1,159 @@
+/// A scrambling operation on a string of letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringOp {
+    SwapPositions(usize, usize),
+    SwapLetters(u8, u8),
+    RotateLeft(usize),
+    RotateRight(usize),
+    /// Rotates right by one plus the letter's index, and once more if the index is at least 4.
+    RotateByLetter(u8),
+    ReversePositions(usize, usize),
+    MovePosition(usize, usize),
+}
+impl StringOp {
+    /// Parses sentences like `swap position 4 with position 0` or
+    /// `rotate based on position of letter b`.
+    pub fn parse(line: &str) -> Self {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let num = |i: usize| -> usize {
+            words[i]
+                .parse()
+                .unwrap_or_else(|_| panic!("expected a number in {line:?}"))
+        };
+        let letter = |i: usize| words[i].as_bytes()[0];
+        match words[..] {
+            ["swap", "position", ..] => Self::SwapPositions(num(2), num(5)),
+            ["swap", "letter", ..] => Self::SwapLetters(letter(2), letter(5)),
+            ["rotate", "left", ..] => Self::RotateLeft(num(2)),
+            ["rotate", "right", ..] => Self::RotateRight(num(2)),
+            ["rotate", "based", ..] => Self::RotateByLetter(letter(6)),
+            ["reverse", "positions", ..] => Self::ReversePositions(num(2), num(4)),
+            ["move", "position", ..] => Self::MovePosition(num(2), num(5)),
+            _ => panic!("unknown string operation {line:?}"),
+        }
+    }
+
+    pub fn parse_all(s: &str) -> Vec<Self> {
+        s.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    fn position(s: &[u8], letter: u8) -> usize {
+        s.iter()
+            .position(|&c| c == letter)
+            .unwrap_or_else(|| panic!("letter {:?} not found", letter as char))
+    }
+
+    fn apply_to(self, s: &mut [u8]) {
+        let len = s.len();
+        match self {
+            Self::SwapPositions(a, b) => s.swap(a, b),
+            Self::SwapLetters(a, b) => {
+                let (a, b) = (Self::position(s, a), Self::position(s, b));
+                s.swap(a, b);
+            }
+            Self::RotateLeft(n) => s.rotate_left(n % len),
+            Self::RotateRight(n) => s.rotate_right(n % len),
+            Self::RotateByLetter(letter) => {
+                let i = Self::position(s, letter);
+                s.rotate_right((1 + i + (i >= 4) as usize) % len);
+            }
+            Self::ReversePositions(a, b) => s[a..=b].reverse(),
+            Self::MovePosition(from, to) if from < to => s[from..=to].rotate_left(1),
+            Self::MovePosition(from, to) => s[to..=from].rotate_right(1),
+        }
+    }
+
+    fn invert_on(self, s: &mut [u8]) {
+        let len = s.len();
+        match self {
+            Self::SwapPositions(..) | Self::SwapLetters(..) | Self::ReversePositions(..) => {
+                self.apply_to(s)
+            }
+            Self::RotateLeft(n) => Self::RotateRight(n).apply_to(s),
+            Self::RotateRight(n) => Self::RotateLeft(n).apply_to(s),
+            Self::MovePosition(from, to) => Self::MovePosition(to, from).apply_to(s),
+            Self::RotateByLetter(_) => {
+                // not injective for every length, so try all sources and take the first that
+                // maps to `s` (unique for the length 8 used by the puzzle)
+                let target = s.to_vec();
+                let source = (0..len)
+                    .map(|k| {
+                        let mut candidate = target.clone();
+                        candidate.rotate_left(k);
+                        candidate
+                    })
+                    .find(|candidate| {
+                        let mut scrambled = candidate.clone();
+                        self.apply_to(&mut scrambled);
+                        scrambled == target
+                    })
+                    .expect("rotation by letter has no inverse for this string");
+                s.copy_from_slice(&source);
+            }
+        }
+    }
+}
+
+pub fn apply(s: &str, ops: &[StringOp]) -> String {
+    let mut bytes = s.as_bytes().to_vec();
+    for op in ops {
+        op.apply_to(&mut bytes);
+    }
+    String::from_utf8(bytes).expect("scrambling only works on ASCII strings")
+}
+
+/// Finds the string that [`apply`] turns into `s`.
+pub fn invert(s: &str, ops: &[StringOp]) -> String {
+    let mut bytes = s.as_bytes().to_vec();
+    for op in ops.iter().rev() {
+        op.invert_on(&mut bytes);
+    }
+    String::from_utf8(bytes).expect("scrambling only works on ASCII strings")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    use super::*;
+
+    const SAMPLE: &str = "swap position 4 with position 0
+swap letter d with letter b
+reverse positions 0 through 4
+rotate left 1 step
+move position 1 to position 4
+move position 3 to position 0
+rotate based on position of letter b
+rotate based on position of letter d";
+
+    #[test]
+    fn sample_steps() {
+        let ops = StringOp::parse_all(SAMPLE);
+        let steps = [
+            "ebcda", "edcba", "abcde", "bcdea", "bdeac", "abdec", "ecabd", "decab",
+        ];
+        let mut s = "abcde".to_owned();
+        for (op, expected) in ops.iter().zip(steps) {
+            s = apply(&s, std::slice::from_ref(op));
+            assert_eq!(s, expected, "after {op:?}");
+        }
+        assert_eq!(apply("abcde", &ops), "decab");
+    }
+
+    #[test]
+    fn invert_roundtrip() {
+        let mut rng = Pcg32::new(21);
+        let mut ops = StringOp::parse_all(SAMPLE);
+        ops.extend([StringOp::RotateRight(11), StringOp::MovePosition(7, 2)]);
+        for _ in 0..100 {
+            let mut letters = *b"abcdefgh";
+            rng.shuffle(&mut letters);
+            let s = String::from_utf8(letters.to_vec()).unwrap();
+            assert_eq!(invert(&apply(&s, &ops), &ops), s);
+            assert_eq!(apply(&invert(&s, &ops), &ops), s);
+        }
+    }
+}
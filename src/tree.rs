@@ -0,0 +1,263 @@
+use std::fmt::Display;
+
+use crate::{Interner, Sym};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    Empty,
+    /// A node was listed as the child of two different parents.
+    MultipleParents(String),
+    /// A node lies on a cycle and so doesn't reach the root.
+    Cycle(String),
+    /// More than one node has no parent.
+    Forest {
+        roots: Vec<String>,
+    },
+}
+impl Display for TreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the tree has no nodes"),
+            Self::MultipleParents(node) => write!(f, "node {node:?} has multiple parents"),
+            Self::Cycle(node) => write!(f, "node {node:?} is part of a cycle"),
+            Self::Forest { roots } => write!(f, "expected a single root but found {roots:?}"),
+        }
+    }
+}
+impl std::error::Error for TreeError {}
+
+/// A rooted tree over interned node names.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    names: Interner,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    root: usize,
+}
+impl Tree {
+    pub fn from_pairs<'a>(
+        pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, TreeError> {
+        let mut names = Interner::new();
+        let mut parents: Vec<Option<usize>> = Vec::new();
+        let name = |names: &Interner, id: usize| names.resolve(Sym::from_index(id)).to_owned();
+        for (parent, child) in pairs {
+            let [parent, child] = [parent, child].map(|node| names.intern(node).index());
+            parents.resize(names.len(), None);
+            if parents[child].is_some_and(|p| p != parent) {
+                return Err(TreeError::MultipleParents(name(&names, child)));
+            }
+            parents[child] = Some(parent);
+        }
+        let roots: Vec<usize> = (0..names.len()).filter(|&i| parents[i].is_none()).collect();
+        let root = match roots[..] {
+            [] if names.is_empty() => return Err(TreeError::Empty),
+            [] => return Err(TreeError::Cycle(name(&names, 0))),
+            [root] => root,
+            _ => {
+                return Err(TreeError::Forest {
+                    roots: roots.iter().map(|&i| name(&names, i)).collect(),
+                })
+            }
+        };
+        let mut children = vec![Vec::new(); names.len()];
+        for (child, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                children[*parent].push(child);
+            }
+        }
+        let mut depths = vec![usize::MAX; names.len()];
+        depths[root] = 0;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            for &child in &children[node] {
+                depths[child] = depths[node] + 1;
+                stack.push(child);
+            }
+        }
+        if let Some(unreached) = depths.iter().position(|&d| d == usize::MAX) {
+            return Err(TreeError::Cycle(name(&names, unreached)));
+        }
+        Ok(Self {
+            names,
+            parents,
+            depths,
+            root,
+        })
+    }
+
+    /// Parses `PARENT)CHILD` lines.
+    pub fn parse_orbits(s: &str) -> Result<Self, TreeError> {
+        Self::from_pairs(s.lines().filter(|l| !l.trim().is_empty()).map(|l| {
+            l.trim()
+                .split_once(')')
+                .unwrap_or_else(|| panic!("invalid orbit {l:?}"))
+        }))
+    }
+
+    fn id(&self, node: &str) -> Option<usize> {
+        Some(self.names.get(node)?.index())
+    }
+
+    fn name(&self, id: usize) -> &str {
+        self.names.resolve(Sym::from_index(id))
+    }
+
+    /// The symbol of `node`, usable as a cheap key and with the `*_sym` methods.
+    pub fn sym(&self, node: &str) -> Option<Sym> {
+        self.names.get(node)
+    }
+
+    pub fn resolve(&self, sym: Sym) -> &str {
+        self.names.resolve(sym)
+    }
+
+    pub fn parent_sym(&self, node: Sym) -> Option<Sym> {
+        self.parents[node.index()].map(Sym::from_index)
+    }
+
+    pub fn depth_sym(&self, node: Sym) -> usize {
+        self.depths[node.index()]
+    }
+
+    pub fn root(&self) -> &str {
+        self.name(self.root)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn parent(&self, node: &str) -> Option<&str> {
+        Some(self.name(self.parents[self.id(node)?]?))
+    }
+
+    /// The number of edges between `node` and the root.
+    pub fn depth(&self, node: &str) -> Option<usize> {
+        Some(self.depths[self.id(node)?])
+    }
+
+    pub fn total_depth(&self) -> usize {
+        self.depths.iter().sum()
+    }
+
+    /// `node` followed by its ancestors, ending with the root.
+    pub fn path_to_root(&self, node: &str) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = self.id(node);
+        while let Some(i) = current {
+            path.push(self.name(i));
+            current = self.parents[i];
+        }
+        path
+    }
+
+    fn lca_id(&self, a: usize, b: usize) -> usize {
+        let (mut a, mut b) = (a, b);
+        while self.depths[a] > self.depths[b] {
+            a = self.parents[a].unwrap();
+        }
+        while self.depths[b] > self.depths[a] {
+            b = self.parents[b].unwrap();
+        }
+        while a != b {
+            a = self.parents[a].unwrap();
+            b = self.parents[b].unwrap();
+        }
+        a
+    }
+
+    pub fn lowest_common_ancestor(&self, a: &str, b: &str) -> Option<&str> {
+        Some(self.name(self.lca_id(self.id(a)?, self.id(b)?)))
+    }
+
+    /// The number of edges on the path between `a` and `b`.
+    pub fn distance(&self, a: &str, b: &str) -> Option<usize> {
+        let (a, b) = (self.id(a)?, self.id(b)?);
+        let lca = self.lca_id(a, b);
+        Some(self.depths[a] + self.depths[b] - 2 * self.depths[lca])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORBITS: &str = "COM)B
+B)C
+C)D
+D)E
+E)F
+B)G
+G)H
+D)I
+E)J
+J)K
+K)L";
+
+    #[test]
+    fn orbit_map() {
+        let tree = Tree::parse_orbits(ORBITS).unwrap();
+        assert_eq!(tree.total_depth(), 42);
+        assert_eq!(tree.root(), "COM");
+        assert_eq!(tree.depth("L"), Some(7));
+        assert_eq!(tree.path_to_root("D"), ["D", "C", "B", "COM"]);
+        let d = tree.sym("D").unwrap();
+        assert_eq!(tree.resolve(tree.parent_sym(d).unwrap()), "C");
+        assert_eq!(tree.depth_sym(d), 3);
+
+        let transfers = Tree::parse_orbits(&format!("{ORBITS}\nK)YOU\nI)SAN")).unwrap();
+        let (you, san) = (
+            transfers.parent("YOU").unwrap(),
+            transfers.parent("SAN").unwrap(),
+        );
+        assert_eq!(transfers.distance(you, san), Some(4));
+    }
+
+    #[test]
+    fn lca() {
+        let tree = Tree::from_pairs([
+            ("r", "a"),
+            ("r", "b"),
+            ("a", "c"),
+            ("a", "d"),
+            ("d", "e"),
+            ("b", "f"),
+        ])
+        .unwrap();
+        assert_eq!(tree.lowest_common_ancestor("c", "e"), Some("a"));
+        assert_eq!(tree.lowest_common_ancestor("e", "f"), Some("r"));
+        assert_eq!(tree.lowest_common_ancestor("d", "e"), Some("d"));
+        assert_eq!(tree.lowest_common_ancestor("c", "c"), Some("c"));
+        assert_eq!(tree.lowest_common_ancestor("c", "x"), None);
+        assert_eq!(tree.distance("e", "f"), Some(5));
+        assert_eq!(tree.distance("r", "r"), Some(0));
+    }
+
+    #[test]
+    fn invalid_trees() {
+        assert_eq!(
+            Tree::from_pairs([("a", "b"), ("c", "d")]).unwrap_err(),
+            TreeError::Forest {
+                roots: vec!["a".into(), "c".into()]
+            }
+        );
+        assert_eq!(
+            Tree::from_pairs([("r", "a"), ("b", "c"), ("c", "b")]).unwrap_err(),
+            TreeError::Cycle("b".into())
+        );
+        assert_eq!(
+            Tree::from_pairs([("a", "b"), ("b", "a")]).unwrap_err(),
+            TreeError::Cycle("a".into())
+        );
+        assert_eq!(
+            Tree::from_pairs([("a", "c"), ("b", "c")]).unwrap_err(),
+            TreeError::MultipleParents("c".into())
+        );
+        assert_eq!(Tree::from_pairs([]).unwrap_err(), TreeError::Empty);
+    }
+}
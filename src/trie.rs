@@ -0,0 +1,200 @@
+use crate::ByteMap;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: ByteMap<usize>,
+    terminal: bool,
+}
+
+/// A prefix tree over the bytes of its words. Everything works on UTF-8 bytes, so lengths and
+/// positions are byte offsets and non-ASCII words only match at char boundaries if the haystack
+/// is valid UTF-8 as well.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    len: usize,
+}
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Trie {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            len: 0,
+        }
+    }
+
+    fn child(&self, node: usize, b: u8) -> Option<usize> {
+        self.nodes[node].children.get(b).copied()
+    }
+
+    fn walk(&self, s: &[u8]) -> Option<usize> {
+        s.iter().try_fold(0, |node, &b| self.child(node, b))
+    }
+
+    /// Adds a word, returns false if it was already present.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = 0;
+        for &b in word.as_bytes() {
+            node = match self.child(node, b) {
+                Some(next) => next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(b, next);
+                    next
+                }
+            };
+        }
+        let new = !self.nodes[node].terminal;
+        self.nodes[node].terminal = true;
+        self.len += new as usize;
+        new
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.walk(word.as_bytes())
+            .is_some_and(|node| self.nodes[node].terminal)
+    }
+
+    /// Whether some word starts with `prefix`.
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        self.walk(prefix.as_bytes()).is_some()
+    }
+
+    /// The lengths of all words occurring in `haystack` at `pos`, shortest first.
+    pub fn matches_at<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        pos: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let mut node = Some(0);
+        let mut len = 0;
+        let root_terminal = self.nodes[0].terminal;
+        std::iter::once(0)
+            .filter(move |_| root_terminal)
+            .chain(std::iter::from_fn(move || loop {
+                let &b = haystack.get(pos + len)?;
+                node = self.child(node?, b);
+                len += 1;
+                if self.nodes[node?].terminal {
+                    return Some(len);
+                }
+            }))
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of nodes including the root, one per distinct prefix.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for word in iter {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+/// The number of ways `target` can be written as a concatenation of dictionary words.
+pub fn count_compositions(target: &str, words: &Trie) -> u64 {
+    let bytes = target.as_bytes();
+    // ways[i]: compositions of the first i bytes
+    let mut ways = vec![0u64; bytes.len() + 1];
+    ways[0] = 1;
+    for start in 0..bytes.len() {
+        if ways[start] == 0 {
+            continue;
+        }
+        for len in words.matches_at(bytes, start).filter(|&len| len > 0) {
+            ways[start + len] += ways[start];
+        }
+    }
+    ways[bytes.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pcg32;
+
+    #[test]
+    fn towels() {
+        let towels: Trie = "r, wr, b, g, bwu, rb, gb, br".split(", ").collect();
+        let designs = [
+            "brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb",
+        ];
+        let ways: Vec<u64> = designs
+            .iter()
+            .map(|d| count_compositions(d, &towels))
+            .collect();
+        assert_eq!(ways.iter().filter(|&&w| w > 0).count(), 6);
+        assert_eq!(ways.iter().sum::<u64>(), 16);
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let mut trie = Trie::new();
+        for word in ["abc", "ab", "abd", "a", "b", "ab"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.len(), 5);
+        // root, a, ab, abc, abd, b
+        assert_eq!(trie.node_count(), 6);
+        assert!(trie.contains("ab") && !trie.contains("abcd") && !trie.contains(""));
+        assert!(trie.is_prefix("ab") && trie.is_prefix("") && !trie.is_prefix("c"));
+        assert_eq!(trie.matches_at(b"xabcab", 1).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(trie.matches_at(b"ab", 2).count(), 0);
+
+        let unicode: Trie = ["ä", "äb"].into_iter().collect();
+        assert_eq!("äb".len(), 3);
+        assert_eq!(
+            unicode.matches_at("äbc".as_bytes(), 0).collect::<Vec<_>>(),
+            [2, 3]
+        );
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = Pcg32::new(257);
+        let random_word = |rng: &mut Pcg32, max: usize| -> String {
+            let len = rng.index(max) + 1;
+            (0..len)
+                .map(|_| (b'a' + rng.below(3) as u8) as char)
+                .collect()
+        };
+        for _ in 0..50 {
+            let words: Vec<String> = (0..rng.index(12) + 1)
+                .map(|_| random_word(&mut rng, 4))
+                .collect();
+            let trie: Trie = words.iter().map(String::as_str).collect();
+            let haystack = random_word(&mut rng, 30);
+            let bytes = haystack.as_bytes();
+            for pos in 0..=bytes.len() {
+                let mut expected: Vec<usize> = words
+                    .iter()
+                    .filter(|w| bytes[pos..].starts_with(w.as_bytes()))
+                    .map(|w| w.len())
+                    .collect();
+                expected.sort_unstable();
+                expected.dedup();
+                assert_eq!(trie.matches_at(bytes, pos).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use vecm::Vec2i;
+
+/// Sort key for reading order: top to bottom, then left to right.
+pub fn reading_order_key(pos: Vec2i) -> (i32, i32) {
+    (pos.y, pos.x)
+}
+
+pub fn sort_reading_order(positions: &mut [Vec2i]) {
+    positions.sort_unstable_by_key(|&p| reading_order_key(p));
+}
+
+/// The first position in reading order.
+pub fn min_reading_order(it: impl IntoIterator<Item = Vec2i>) -> Option<Vec2i> {
+    it.into_iter().min_by_key(|&p| reading_order_key(p))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnResult {
+    Acted,
+    /// The unit found no targets left, which ends the combat before its turn.
+    NoTargets,
+}
+
+/// Round-based simulation where every round all living units take a turn in the reading order
+/// of their positions at the start of the round. Units that die during a round are skipped,
+/// they stay in `units` so indices remain stable.
+pub struct TurnOrderSim<U> {
+    pub units: Vec<U>,
+    /// Rounds that ran to completion, combat ending mid-round doesn't count that round.
+    pub full_rounds: usize,
+    pos: fn(&U) -> Vec2i,
+    alive: fn(&U) -> bool,
+}
+impl<U> TurnOrderSim<U> {
+    pub fn new(units: Vec<U>, pos: fn(&U) -> Vec2i, alive: fn(&U) -> bool) -> Self {
+        Self {
+            units,
+            full_rounds: 0,
+            pos,
+            alive,
+        }
+    }
+
+    /// Indices of the living units in reading order.
+    pub fn turn_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.units.len())
+            .filter(|&i| (self.alive)(&self.units[i]))
+            .collect();
+        order.sort_by_key(|&i| reading_order_key((self.pos)(&self.units[i])));
+        order
+    }
+
+    /// Runs one round, `act(units, i)` takes the turn of unit `i`. Returns false if combat
+    /// ended during the round.
+    pub fn round(&mut self, mut act: impl FnMut(&mut [U], usize) -> TurnResult) -> bool {
+        for i in self.turn_order() {
+            if !(self.alive)(&self.units[i]) {
+                continue;
+            }
+            if act(&mut self.units, i) == TurnResult::NoTargets {
+                return false;
+            }
+        }
+        self.full_rounds += 1;
+        true
+    }
+
+    /// Runs rounds until combat ends, returns the number of full rounds.
+    pub fn run(&mut self, mut act: impl FnMut(&mut [U], usize) -> TurnResult) -> usize {
+        while self.round(&mut act) {}
+        self.full_rounds
+    }
+
+    pub fn living(&self) -> impl Iterator<Item = &U> {
+        self.units.iter().filter(|u| (self.alive)(u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecm::Vec2i;
+
+    use super::*;
+    use crate::Grid;
+
+    #[derive(Debug, Clone)]
+    struct Unit {
+        pos: Vec2i,
+        elf: bool,
+        hp: i32,
+    }
+
+    fn combat(s: &str) -> (usize, i32) {
+        let mut grid = Grid::from_str_bytes(s);
+        let units = grid
+            .positions()
+            .filter(|&p| matches!(grid[p], b'E' | b'G'))
+            .map(|pos| Unit {
+                pos,
+                elf: grid[pos] == b'E',
+                hp: 200,
+            })
+            .collect();
+        for pos in grid.positions() {
+            if grid[pos] != b'#' {
+                grid[pos] = b'.';
+            }
+        }
+        let mut sim = TurnOrderSim::new(units, |u| u.pos, |u| u.hp > 0);
+        let rounds = sim.run(|units, i| {
+            let me = units[i].clone();
+            let is_enemy = |u: &Unit| u.hp > 0 && u.elf != me.elf;
+            if !units.iter().any(is_enemy) {
+                return TurnResult::NoTargets;
+            }
+            let occupied: Vec<Vec2i> = units.iter().filter(|u| u.hp > 0).map(|u| u.pos).collect();
+            let enemy_adjacent = |p: Vec2i| {
+                units
+                    .iter()
+                    .any(|u| is_enemy(u) && grid.neighbor_positions4(p).any(|n| n == u.pos))
+            };
+            let mut pos = me.pos;
+            if !enemy_adjacent(pos) {
+                let passable = |p: Vec2i, &c: &u8| c == b'.' && !occupied.contains(&p);
+                if let Some(step) = grid.first_step_towards(pos, enemy_adjacent, passable) {
+                    pos = step;
+                    units[i].pos = step;
+                }
+            }
+            let target = (0..units.len())
+                .filter(|&j| {
+                    is_enemy(&units[j]) && grid.neighbor_positions4(pos).any(|n| n == units[j].pos)
+                })
+                .min_by_key(|&j| (units[j].hp, reading_order_key(units[j].pos)));
+            if let Some(j) = target {
+                units[j].hp -= 3;
+            }
+            TurnResult::Acted
+        });
+        (rounds, sim.living().map(|u| u.hp).sum())
+    }
+
+    #[test]
+    fn reading_order() {
+        let mut v = vec![
+            Vec2i::new(2, 1),
+            Vec2i::new(0, 2),
+            Vec2i::new(1, 1),
+            Vec2i::new(5, 0),
+        ];
+        assert_eq!(min_reading_order(v.iter().copied()), Some(Vec2i::new(5, 0)));
+        sort_reading_order(&mut v);
+        assert_eq!(
+            v,
+            [
+                Vec2i::new(5, 0),
+                Vec2i::new(1, 1),
+                Vec2i::new(2, 1),
+                Vec2i::new(0, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn beverage_bandits() {
+        let first = "#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######";
+        assert_eq!(combat(first), (47, 590));
+        let second = "#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######";
+        let (rounds, hp) = combat(second);
+        assert_eq!(rounds * hp as usize, 36334);
+        let third = "#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######";
+        let (rounds, hp) = combat(third);
+        assert_eq!(rounds * hp as usize, 39514);
+    }
+}
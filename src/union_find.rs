@@ -0,0 +1,175 @@
+/// Disjoint sets over `0..n` with path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    count: usize,
+}
+impl UnionFind {
+    /// Every element starts in its own set.
+    pub fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            count: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The representative of the set containing `i`.
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    /// Merges the sets of `a` and `b`, returns whether they were separate before.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (low, high) = if self.ranks[a] < self.ranks[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[low] = high;
+        if self.ranks[low] == self.ranks[high] {
+            self.ranks[high] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    /// All sets with their elements in ascending order, ordered by their smallest element.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+        for i in 0..self.len() {
+            let root = self.find(i);
+            if index_of_root[root] == usize::MAX {
+                index_of_root[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[index_of_root[root]].push(i);
+        }
+        components
+    }
+}
+
+/// Like [`transitive_closure`](crate::transitive_closure) but returns groups of indices into
+/// `items`, ordered as by [`UnionFind::components`]. Still calls `relation` for every pair that
+/// isn't known to be connected yet, use [`transitive_closure_edges`] if the candidate pairs can
+/// be found faster.
+pub fn transitive_closure_indexed<T>(
+    items: &[T],
+    mut relation: impl FnMut(&T, &T) -> bool,
+) -> Vec<Vec<usize>> {
+    let mut sets = UnionFind::new(items.len());
+    for j in 0..items.len() {
+        for i in 0..j {
+            if !sets.same(i, j) && relation(&items[i], &items[j]) {
+                sets.union(i, j);
+            }
+        }
+    }
+    sets.components()
+}
+
+/// The groups of `0..n` connected by the index pairs in `edges`.
+pub fn transitive_closure_edges(
+    n: usize,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Vec<Vec<usize>> {
+    let mut sets = UnionFind::new(n);
+    for (a, b) in edges {
+        sets.union(a, b);
+    }
+    sets.components()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transitive_closure, Pcg32};
+
+    #[test]
+    fn union_and_components() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(4, 1));
+        assert!(sets.union(1, 5));
+        assert!(!sets.union(5, 4));
+        assert!(sets.union(0, 3));
+        assert_eq!(sets.component_count(), 3);
+        assert!(sets.same(4, 5));
+        assert!(!sets.same(0, 1));
+        assert_eq!(sets.components(), [vec![0, 3], vec![1, 4, 5], vec![2]]);
+    }
+
+    #[test]
+    fn modulus_groups() {
+        let groups = transitive_closure_edges(10_000, (7..10_000).map(|i| (i - 7, i)));
+        assert_eq!(groups.len(), 7);
+        assert!(groups
+            .iter()
+            .enumerate()
+            .all(|(r, g)| g.len() >= 1428 && g.iter().all(|i| i % 7 == r)));
+        let items: Vec<u32> = (0..2000).collect();
+        assert_eq!(
+            transitive_closure_indexed(&items, |a, b| a % 7 == b % 7),
+            groups
+                .iter()
+                .map(|g| g.iter().copied().filter(|&i| i < 2000).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn matches_set_merging() {
+        let mut rng = Pcg32::new(8);
+        for _ in 0..200 {
+            // points in a plane, so that one point can join up to four groups at once
+            let items: Vec<(i32, i32)> = (0..rng.index(40))
+                .map(|_| (rng.below(12) as i32, rng.below(12) as i32))
+                .collect();
+            let close = |a: &(i32, i32), b: &(i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs() <= 2;
+            let mut expected: Vec<Vec<(i32, i32)>> =
+                transitive_closure(items.iter().copied(), close)
+                    .into_iter()
+                    .map(|mut set| {
+                        set.sort();
+                        set
+                    })
+                    .collect();
+            expected.sort();
+            let mut groups: Vec<Vec<(i32, i32)>> = transitive_closure_indexed(&items, close)
+                .into_iter()
+                .map(|g| {
+                    let mut set: Vec<(i32, i32)> = g.into_iter().map(|i| items[i]).collect();
+                    set.sort();
+                    set
+                })
+                .collect();
+            groups.sort();
+            assert_eq!(groups, expected, "{items:?}");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use vecm::{PolyVec2, Vec2i};
+
+use crate::grid::{DIRS4, DIRS8};
+
+/// Everyday operations on integer vectors. Rotations use grid coordinates where +y points
+/// down, so clockwise turns up `(0, -1)` into right `(1, 0)` as it looks on screen.
+pub trait Vec2Ext: Sized + Copy {
+    fn manhattan(self, other: Self) -> i64;
+    fn chebyshev(self, other: Self) -> i64;
+    #[must_use]
+    fn rot_cw(self) -> Self;
+    #[must_use]
+    fn rot_ccw(self) -> Self;
+    /// The sign of both components, a step towards `self` from the origin.
+    #[must_use]
+    fn signum(self) -> Self;
+    /// The 4 neighbors in [`DIRS4`] order, not limited to any bounds.
+    fn neighbors4(self) -> [Self; 4];
+    /// The 8 neighbors in [`DIRS8`] order, not limited to any bounds.
+    fn neighbors8(self) -> [Self; 8];
+}
+
+macro_rules! impl_vec2_ext {
+    ($($t:ty),*) => {$(
+        impl Vec2Ext for PolyVec2<$t> {
+            fn manhattan(self, other: Self) -> i64 {
+                (self.x as i64 - other.x as i64).abs() + (self.y as i64 - other.y as i64).abs()
+            }
+
+            fn chebyshev(self, other: Self) -> i64 {
+                (self.x as i64 - other.x as i64)
+                    .abs()
+                    .max((self.y as i64 - other.y as i64).abs())
+            }
+
+            fn rot_cw(self) -> Self {
+                Self::new(-self.y, self.x)
+            }
+
+            fn rot_ccw(self) -> Self {
+                Self::new(self.y, -self.x)
+            }
+
+            fn signum(self) -> Self {
+                Self::new(self.x.signum(), self.y.signum())
+            }
+
+            fn neighbors4(self) -> [Self; 4] {
+                DIRS4.map(|(x, y)| Self::new(self.x + x as $t, self.y + y as $t))
+            }
+
+            fn neighbors8(self) -> [Self; 8] {
+                DIRS8.map(|(x, y)| Self::new(self.x + x as $t, self.y + y as $t))
+            }
+        }
+    )*};
+}
+impl_vec2_ext!(i32, i64);
+
+/// The first two integers in `s` as a position, for formats like `498,4` or `x=10, y=-20`.
+/// Panics if there are fewer than two.
+pub fn parse_vec2(s: &str) -> Vec2i {
+    match crate::all_ints(s)[..] {
+        [x, y, ..] => Vec2i::new(x as i32, y as i32),
+        _ => panic!("expected two integers in {s:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotations() {
+        let up = Vec2i::new(0, -1);
+        assert_eq!(up.rot_cw(), Vec2i::new(1, 0));
+        assert_eq!(up.rot_cw().rot_cw(), Vec2i::new(0, 1));
+        assert_eq!(up.rot_ccw(), Vec2i::new(-1, 0));
+        let v = PolyVec2::new(3i64, -7);
+        assert_eq!(v.rot_cw().rot_ccw(), v);
+        assert_eq!(v.rot_cw().rot_cw().rot_cw().rot_cw(), v);
+        assert_eq!(v.signum(), PolyVec2::new(1, -1));
+    }
+
+    #[test]
+    fn distances_and_neighbors() {
+        let (a, b) = (Vec2i::new(-3, 4), Vec2i::new(2, -8));
+        assert_eq!(a.manhattan(b), 17);
+        assert_eq!(b.manhattan(a), 17);
+        assert_eq!(a.chebyshev(b), 12);
+        let n = Vec2i::new(0, 0).neighbors4();
+        assert_eq!(n[0], Vec2i::new(0, -1));
+        assert!(n.iter().all(|p| p.manhattan(Vec2i::new(0, 0)) == 1));
+        let n8 = PolyVec2::new(i64::MAX - 1, 5).neighbors8();
+        assert!(n8
+            .iter()
+            .all(|p| p.chebyshev(PolyVec2::new(i64::MAX - 1, 5)) == 1));
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse_vec2("498,4"), Vec2i::new(498, 4));
+        assert_eq!(
+            parse_vec2("Sensor at x=10, y=-20: closest beacon is at x=1, y=2"),
+            Vec2i::new(10, -20)
+        );
+        assert_eq!(parse_vec2("  p=<-3, 7>  "), Vec2i::new(-3, 7));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected two integers")]
+    fn too_few_ints() {
+        parse_vec2("x=5");
+    }
+}
@@ -0,0 +1,164 @@
+use vecm::{PolyVec3, Vec3i};
+
+/// The 3D counterpart of [`Vec2Ext`](crate::Vec2Ext).
+pub trait Vec3Ext: Sized + Copy {
+    fn manhattan(self, other: Self) -> i64;
+    fn chebyshev(self, other: Self) -> i64;
+    /// The sign of every component.
+    #[must_use]
+    fn signum(self) -> Self;
+    /// The face neighbors, in the order -x, +x, -y, +y, -z, +z.
+    fn neighbors6(self) -> [Self; 6];
+    /// All neighbors including edges and corners, ordered by the offset `(dx, dy, dz)`.
+    fn neighbors26(self) -> [Self; 26];
+}
+
+const OFFSETS6: [(i32, i32, i32); 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+const OFFSETS26: [(i32, i32, i32); 26] = {
+    let mut offsets = [(0, 0, 0); 26];
+    let mut i = 0;
+    let mut n = 0;
+    while n < 27 {
+        let offset = (n / 9 - 1, n / 3 % 3 - 1, n % 3 - 1);
+        if n != 13 {
+            offsets[i] = offset;
+            i += 1;
+        }
+        n += 1;
+    }
+    offsets
+};
+
+macro_rules! impl_vec3_ext {
+    ($($t:ty),*) => {$(
+        impl Vec3Ext for PolyVec3<$t> {
+            fn manhattan(self, other: Self) -> i64 {
+                (self.x as i64 - other.x as i64).abs()
+                    + (self.y as i64 - other.y as i64).abs()
+                    + (self.z as i64 - other.z as i64).abs()
+            }
+
+            fn chebyshev(self, other: Self) -> i64 {
+                (self.x as i64 - other.x as i64)
+                    .abs()
+                    .max((self.y as i64 - other.y as i64).abs())
+                    .max((self.z as i64 - other.z as i64).abs())
+            }
+
+            fn signum(self) -> Self {
+                Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+            }
+
+            fn neighbors6(self) -> [Self; 6] {
+                OFFSETS6.map(|(x, y, z)| {
+                    Self::new(self.x + x as $t, self.y + y as $t, self.z + z as $t)
+                })
+            }
+
+            fn neighbors26(self) -> [Self; 26] {
+                OFFSETS26.map(|(x, y, z)| {
+                    Self::new(self.x + x as $t, self.y + y as $t, self.z + z as $t)
+                })
+            }
+        }
+    )*};
+}
+impl_vec3_ext!(i32, i64);
+
+/// Parses `x,y,z` with optional spaces around the numbers.
+pub fn coord3(s: &str) -> Vec3i {
+    let parts: Vec<i32> = s
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("expected x,y,z but found {s:?}"))
+        })
+        .collect();
+    match parts[..] {
+        [x, y, z] => Vec3i::new(x, y, z),
+        _ => panic!("expected x,y,z but found {s:?}"),
+    }
+}
+
+/// A [`coord3`] per non-empty line.
+pub fn coords3(s: &str) -> Vec<Vec3i> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(coord3)
+        .collect()
+}
+
+/// The integers of every line as found by [`all_ints`](crate::all_ints), in groups of three.
+/// Panics if a line has a count that isn't a multiple of three.
+pub fn all_int_triples(s: &str) -> Vec<[i64; 3]> {
+    let mut triples = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let ints = crate::all_ints(line);
+        assert!(
+            ints.len().is_multiple_of(3),
+            "line {} has {} integers, which doesn't split into triples: {line:?}",
+            i + 1,
+            ints.len()
+        );
+        triples.extend(ints.chunks(3).map(|c| [c[0], c[1], c[2]]));
+    }
+    triples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        assert_eq!(coord3("1,-2, 3"), Vec3i::new(1, -2, 3));
+        assert_eq!(
+            coords3("2,2,2\n\n -1 , 0,5\n"),
+            [Vec3i::new(2, 2, 2), Vec3i::new(-1, 0, 5)]
+        );
+        let bots = "pos=<0,0,0>, r=4\npos=<1,-3,1>, r=1\npos=<-12,3,-1>, r=1";
+        let positions: Vec<[i64; 3]> = bots
+            .lines()
+            .map(|line| all_int_triples(line.split_once(", ").unwrap().0)[0])
+            .collect();
+        assert_eq!(positions, [[0, 0, 0], [1, -3, 1], [-12, 3, -1]]);
+        assert_eq!(all_int_triples("1 2 3 -4 5 6"), [[1, 2, 3], [-4, 5, 6]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2 has 4 integers")]
+    fn incomplete_triple() {
+        all_int_triples("pos=<1,3,1>\npos=<1,3,1>, r=1");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected x,y,z")]
+    fn two_components() {
+        coord3("1,2");
+    }
+
+    #[test]
+    fn distances_and_neighbors() {
+        let (a, b) = (Vec3i::new(-1, 4, 0), Vec3i::new(2, -3, -5));
+        assert_eq!(a.manhattan(b), 15);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!((b - a).signum(), Vec3i::new(1, -1, -1));
+        let origin = PolyVec3::new(0i64, 0, 0);
+        assert!(origin.neighbors6().iter().all(|n| n.manhattan(origin) == 1));
+        let all = origin.neighbors26();
+        assert_eq!(all[0], PolyVec3::new(-1, -1, -1));
+        assert_eq!(all[25], PolyVec3::new(1, 1, 1));
+        assert!(all.iter().all(|n| n.chebyshev(origin) == 1));
+        let distinct: std::collections::HashSet<_> = all.iter().map(|n| (n.x, n.y, n.z)).collect();
+        assert_eq!(distinct.len(), 26);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use vecm::Vec3i;
+
+use crate::Vec3Ext;
+
+/// A sparse set of unit cubes at integer coordinates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoxelSet {
+    voxels: HashSet<Vec3i>,
+}
+impl VoxelSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The set of the coordinates, for example as parsed by [`coords3`](crate::coords3).
+    pub fn from_coords(coords: impl IntoIterator<Item = Vec3i>) -> Self {
+        Self {
+            voxels: coords.into_iter().collect(),
+        }
+    }
+
+    /// Returns whether the voxel is new.
+    pub fn insert(&mut self, voxel: Vec3i) -> bool {
+        self.voxels.insert(voxel)
+    }
+
+    pub fn contains(&self, voxel: Vec3i) -> bool {
+        self.voxels.contains(&voxel)
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec3i> + '_ {
+        self.voxels.iter().copied()
+    }
+
+    /// The smallest and largest coordinate on every axis, `None` for an empty set.
+    pub fn bounds(&self) -> Option<(Vec3i, Vec3i)> {
+        let mut voxels = self.iter();
+        let first = voxels.next()?;
+        Some(voxels.fold((first, first), |(min, max), v| {
+            (
+                Vec3i::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+                Vec3i::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+            )
+        }))
+    }
+
+    /// The number of faces not shared with another voxel, including the ones facing enclosed
+    /// air pockets.
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .flat_map(|v| v.neighbors6())
+            .filter(|&n| !self.contains(n))
+            .count()
+    }
+
+    /// The number of faces reachable from the outside. Air is flooded from a corner of the
+    /// bounding box padded by one on every side, so the flood can get around the whole shape,
+    /// and only faces it touches count.
+    pub fn exterior_surface_area(&self) -> usize {
+        let Some((min, max)) = self.bounds() else {
+            return 0;
+        };
+        let (min, max) = (min - Vec3i::new(1, 1, 1), max + Vec3i::new(1, 1, 1));
+        let inside = |v: Vec3i| {
+            (min.x..=max.x).contains(&v.x)
+                && (min.y..=max.y).contains(&v.y)
+                && (min.z..=max.z).contains(&v.z)
+        };
+        let mut air = HashSet::from([min]);
+        let mut queue = vec![min];
+        let mut faces = 0;
+        while let Some(v) = queue.pop() {
+            for n in v.neighbors6() {
+                if self.contains(n) {
+                    faces += 1;
+                } else if inside(n) && air.insert(n) {
+                    queue.push(n);
+                }
+            }
+        }
+        faces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords3;
+
+    const DROPLET: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+    #[test]
+    fn lava_droplet() {
+        let droplet = VoxelSet::from_coords(coords3(DROPLET));
+        assert_eq!(droplet.len(), 13);
+        assert_eq!(droplet.surface_area(), 64);
+        assert_eq!(droplet.exterior_surface_area(), 58);
+        assert_eq!(
+            droplet.bounds(),
+            Some((Vec3i::new(1, 1, 1), Vec3i::new(3, 3, 6)))
+        );
+    }
+
+    #[test]
+    fn single_cube() {
+        let mut cube = VoxelSet::new();
+        assert_eq!(cube.exterior_surface_area(), 0);
+        assert_eq!(cube.bounds(), None);
+        assert!(cube.insert(Vec3i::new(-4, 0, 7)));
+        assert!(!cube.insert(Vec3i::new(-4, 0, 7)));
+        assert_eq!(cube.surface_area(), 6);
+        assert_eq!(cube.exterior_surface_area(), 6);
+        // two cubes touching only along an edge share no face
+        cube.insert(Vec3i::new(-3, 1, 7));
+        assert_eq!(cube.surface_area(), 12);
+        assert_eq!(cube.exterior_surface_area(), 12);
+    }
+
+    #[test]
+    fn hollow_shell() {
+        let shell = VoxelSet::from_coords(
+            (0..27)
+                .map(|i| Vec3i::new(i % 3, i / 3 % 3, i / 9))
+                .filter(|&v| v != Vec3i::new(1, 1, 1)),
+        );
+        assert!(!shell.contains(Vec3i::new(1, 1, 1)));
+        assert_eq!(shell.surface_area(), 6 * 9 + 6);
+        assert_eq!(shell.exterior_surface_area(), 6 * 9);
+    }
+}
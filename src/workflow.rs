@@ -0,0 +1,289 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    Accept,
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    Verdict(Verdict),
+    Chain(String),
+}
+impl Target {
+    fn parse(s: &str) -> Self {
+        match s {
+            "A" => Self::Verdict(Verdict::Accept),
+            "R" => Self::Verdict(Verdict::Reject),
+            name => Self::Chain(name.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmp {
+    Lt,
+    Gt,
+}
+impl Cmp {
+    pub fn holds(self, a: i64, b: i64) -> bool {
+        match self {
+            Self::Lt => a < b,
+            Self::Gt => a > b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub field: String,
+    pub cmp: Cmp,
+    pub value: i64,
+    pub target: Target,
+}
+
+/// A named chain of rules like `px{a<2006:qkq,m>2090:A,rfg}`. Rules are checked in order and
+/// the first matching one decides the target, otherwise the fallback is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleChain {
+    pub name: String,
+    pub rules: Vec<Rule>,
+    pub fallback: Target,
+}
+impl RuleChain {
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        let (name, rest) = s
+            .split_once('{')
+            .unwrap_or_else(|| panic!("missing '{{' in rule chain {s:?}"));
+        let body = rest
+            .strip_suffix('}')
+            .unwrap_or_else(|| panic!("missing '}}' in rule chain {s:?}"));
+        let mut parts: Vec<&str> = body.split(',').collect();
+        let fallback = Target::parse(parts.pop().expect("empty rule chain"));
+        let rules = parts
+            .into_iter()
+            .map(|rule| {
+                let (cond, target) = rule
+                    .split_once(':')
+                    .unwrap_or_else(|| panic!("missing ':' in rule {rule:?}"));
+                let (i, cmp) = cond
+                    .char_indices()
+                    .find_map(|(i, c)| match c {
+                        '<' => Some((i, Cmp::Lt)),
+                        '>' => Some((i, Cmp::Gt)),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| panic!("missing comparison in rule {rule:?}"));
+                Rule {
+                    field: cond[..i].to_owned(),
+                    cmp,
+                    value: crate::int(&cond[i + 1..]),
+                    target: Target::parse(target),
+                }
+            })
+            .collect();
+        Self {
+            name: name.to_owned(),
+            rules,
+            fallback,
+        }
+    }
+
+    /// Returns the target the item is routed to. Missing fields never match a rule.
+    pub fn route(&self, item: &HashMap<String, i64>) -> &Target {
+        self.rules
+            .iter()
+            .find(|rule| {
+                item.get(&rule.field)
+                    .is_some_and(|&v| rule.cmp.holds(v, rule.value))
+            })
+            .map_or(&self.fallback, |rule| &rule.target)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleChains {
+    chains: HashMap<String, RuleChain>,
+}
+impl RuleChains {
+    /// Parses one chain per line, stopping at the first blank line.
+    pub fn parse(s: &str) -> Self {
+        Self {
+            chains: s
+                .lines()
+                .take_while(|line| !line.trim().is_empty())
+                .map(RuleChain::parse)
+                .map(|chain| (chain.name.clone(), chain))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> &RuleChain {
+        self.chains
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown rule chain {name:?}"))
+    }
+
+    pub fn evaluate(&self, start: &str, item: &HashMap<String, i64>) -> Verdict {
+        let mut chain = self.get(start);
+        loop {
+            match chain.route(item) {
+                Target::Verdict(verdict) => return *verdict,
+                Target::Chain(next) => chain = self.get(next),
+            }
+        }
+    }
+
+    /// Propagates the field ranges symbolically through the chains starting at `start` and
+    /// returns the number of accepted combinations. Fields without a range never match a rule.
+    pub fn accepted_combinations(
+        &self,
+        start: &str,
+        ranges: HashMap<String, RangeInclusive<i64>>,
+    ) -> u64 {
+        self.accepted_ranges(start, ranges).iter().map(volume).sum()
+    }
+
+    /// Returns disjoint range boxes that are accepted when starting at `start`.
+    pub fn accepted_ranges(
+        &self,
+        start: &str,
+        ranges: HashMap<String, RangeInclusive<i64>>,
+    ) -> Vec<HashMap<String, RangeInclusive<i64>>> {
+        let mut accepted = Vec::new();
+        let mut queue = vec![(Target::Chain(start.to_owned()), ranges)];
+        while let Some((target, mut ranges)) = queue.pop() {
+            let chain = match target {
+                Target::Verdict(Verdict::Accept) => {
+                    accepted.push(ranges);
+                    continue;
+                }
+                Target::Verdict(Verdict::Reject) => continue,
+                Target::Chain(name) => self.get(&name),
+            };
+            let mut remaining = true;
+            for rule in &chain.rules {
+                let Some(range) = ranges.get(&rule.field) else {
+                    continue;
+                };
+                let (pass, fail) = split_range(range, rule.cmp, rule.value);
+                if let Some(pass) = pass {
+                    let mut passing = ranges.clone();
+                    passing.insert(rule.field.clone(), pass);
+                    queue.push((rule.target.clone(), passing));
+                }
+                match fail {
+                    Some(fail) => {
+                        ranges.insert(rule.field.clone(), fail);
+                    }
+                    None => {
+                        remaining = false;
+                        break;
+                    }
+                }
+            }
+            if remaining {
+                queue.push((chain.fallback.clone(), ranges));
+            }
+        }
+        accepted
+    }
+}
+
+/// Splits a range into the part satisfying `x <cmp> value` and the part that doesn't.
+pub fn split_range(
+    range: &RangeInclusive<i64>,
+    cmp: Cmp,
+    value: i64,
+) -> (Option<RangeInclusive<i64>>, Option<RangeInclusive<i64>>) {
+    let (start, end) = (*range.start(), *range.end());
+    let non_empty = |a: i64, b: i64| (a <= b).then_some(a..=b);
+    match cmp {
+        Cmp::Lt => (
+            non_empty(start, end.min(value - 1)),
+            non_empty(start.max(value), end),
+        ),
+        Cmp::Gt => (
+            non_empty(start.max(value + 1), end),
+            non_empty(start, end.min(value)),
+        ),
+    }
+}
+
+/// Parses an item of the form `{x=787,m=2655,a=1222,s=2876}`.
+pub fn parse_item(s: &str) -> HashMap<String, i64> {
+    let s = s.trim();
+    s.strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or_else(|| panic!("item {s:?} isn't enclosed in braces"))
+        .split(',')
+        .map(|field| {
+            let (name, value) = field
+                .split_once('=')
+                .unwrap_or_else(|| panic!("missing '=' in field {field:?}"));
+            (name.trim().to_owned(), crate::int(value))
+        })
+        .collect()
+}
+
+fn volume(ranges: &HashMap<String, RangeInclusive<i64>>) -> u64 {
+    ranges
+        .values()
+        .map(|r| (r.end() - r.start() + 1).max(0) as u64)
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn sample_ratings() {
+        let chains = RuleChains::parse(SAMPLE);
+        let (_, items) = SAMPLE.split_once("\n\n").unwrap();
+        let sum: i64 = items
+            .lines()
+            .map(parse_item)
+            .filter(|item| chains.evaluate("in", item) == Verdict::Accept)
+            .map(|item| item.values().sum::<i64>())
+            .sum();
+        assert_eq!(sum, 19114);
+    }
+
+    #[test]
+    fn sample_combinations() {
+        let chains = RuleChains::parse(SAMPLE);
+        let ranges = "xmas".chars().map(|c| (c.to_string(), 1..=4000)).collect();
+        assert_eq!(chains.accepted_combinations("in", ranges), 167409079868000);
+    }
+
+    #[test]
+    fn split_at_bounds() {
+        assert_eq!(
+            split_range(&(1..=10), Cmp::Lt, 5),
+            (Some(1..=4), Some(5..=10))
+        );
+        assert_eq!(split_range(&(1..=10), Cmp::Gt, 10), (None, Some(1..=10)));
+        assert_eq!(split_range(&(1..=10), Cmp::Lt, 1), (None, Some(1..=10)));
+    }
+}